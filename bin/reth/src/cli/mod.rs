@@ -6,8 +6,8 @@ use crate::{
         LogArgs,
     },
     commands::{
-        config_cmd, db, debug_cmd, dump_genesis, import, init_cmd, node, node::NoArgs, p2p,
-        recover, stage, test_vectors,
+        config_cmd, db, debug_cmd, dump_genesis, export, import, import_receipts, init_cmd, node,
+        node::NoArgs, p2p, recover, stage, test_vectors, txpool,
     },
     version::{LONG_VERSION, SHORT_VERSION},
 };
@@ -146,6 +146,10 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             }
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ImportReceipts(command) => {
+                runner.run_blocking_until_ctrl_c(command.execute())
+            }
+            Commands::Export(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::DumpGenesis(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Stage(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
@@ -154,6 +158,7 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             Commands::Config(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::Debug(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Recover(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
+            Commands::TxPool(command) => runner.run_until_ctrl_c(command.execute()),
         }
     }
 
@@ -179,6 +184,12 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(import::ImportCommand),
+    /// This imports RLP encoded receipts from a file into static files.
+    #[command(name = "import-receipts")]
+    ImportReceipts(import_receipts::ImportReceiptsCommand),
+    /// This exports canonical blocks and receipts to RLP or Era1 files.
+    #[command(name = "export")]
+    Export(export::ExportCommand),
     /// Dumps genesis block JSON configuration to stdout.
     DumpGenesis(dump_genesis::DumpGenesisCommand),
     /// Database debugging utilities
@@ -202,6 +213,9 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Scripts for node recovery
     #[command(name = "recover")]
     Recover(recover::Command),
+    /// Transaction pool utilities
+    #[command(name = "txpool")]
+    TxPool(txpool::Command),
 }
 
 #[cfg(test)]
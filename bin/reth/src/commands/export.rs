@@ -0,0 +1,438 @@
+//! Command that exports canonical chain data to files.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+    version::SHORT_VERSION,
+};
+use alloy_rlp::Encodable;
+use clap::{Parser, ValueEnum};
+use eyre::Context;
+use reth_db::open_db_read_only;
+use reth_downloaders::{
+    era1::{
+        era1_file_name, era1_files, Era1Block, Era1Reader, Era1Receipts, Era1Writer, MAX_EPOCH_SIZE,
+    },
+    receipt_file_codec::BlockReceipts,
+};
+use reth_primitives::{BlockNumber, ChainSpec, ForkCondition, Hardfork, Receipt, SealedBlock};
+use reth_provider::{BlockReader, ProviderFactory};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, info};
+
+/// Interval of blocks after which export progress is logged.
+const LOG_INTERVAL: u64 = 100_000;
+
+/// The format of exported chain data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// RLP encoded blocks, one after another, as read by `reth import`.
+    Rlp,
+    /// Era1 archives, one per epoch of 8192 blocks.
+    ///
+    /// Only complete pre-merge epochs can be exported. The intermediate state roots of
+    /// pre-Byzantium receipts are not stored, so these receipts are taken from existing archives.
+    Era1,
+}
+
+/// Exports canonical blocks and receipts from the database to files.
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(flatten)]
+    db: DatabaseArgs,
+
+    /// The first block to export.
+    ///
+    /// Defaults to the genesis block for the RLP format and for the Era1 format with
+    /// --pre-byzantium-receipts, otherwise to the first epoch that starts at or after the
+    /// Byzantium fork. Must be the first block of an epoch for the Era1 format.
+    #[arg(long, value_name = "BLOCK", verbatim_doc_comment)]
+    from: Option<BlockNumber>,
+
+    /// The last block to export, inclusive.
+    ///
+    /// Defaults to the highest block in the database for the RLP format, and to the end of the
+    /// last complete pre-merge epoch in the database for the Era1 format. Must be the last block
+    /// of an epoch for the Era1 format.
+    #[arg(long, value_name = "BLOCK", verbatim_doc_comment)]
+    to: Option<BlockNumber>,
+
+    /// The format to export to.
+    ///
+    /// Era1 archives can only be exported for complete pre-merge epochs. Epochs before the
+    /// Byzantium fork require --pre-byzantium-receipts.
+    #[arg(long, value_enum, verbatim_doc_comment, default_value_t = ExportFormat::Rlp)]
+    format: ExportFormat,
+
+    /// Directory of Era1 archives to take the receipts of pre-Byzantium blocks from, e.g. the
+    /// archives the chain was imported from.
+    ///
+    /// The intermediate state roots of pre-Byzantium receipts are not stored, so their receipts
+    /// are copied from the archive of the same epoch after checking them against the receipts
+    /// root of the block. Only used by the Era1 format.
+    #[arg(long, value_name = "DIR", verbatim_doc_comment)]
+    pre_byzantium_receipts: Option<PathBuf>,
+
+    /// Also export receipts to the given file.
+    ///
+    /// Only used by the RLP format, Era1 archives always contain receipts.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    receipts: Option<PathBuf>,
+
+    /// The path to export to.
+    ///
+    /// This is a file for the RLP format and a directory for the Era1 format.
+    #[arg(value_name = "EXPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl ExportCommand {
+    /// Execute `export` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        if self.format == ExportFormat::Era1 && self.receipts.is_some() {
+            eyre::bail!("receipts are always part of Era1 archives");
+        }
+        if self.format == ExportFormat::Rlp && self.pre_byzantium_receipts.is_some() {
+            eyre::bail!("pre-Byzantium receipts are only used by the Era1 format");
+        }
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = open_db_read_only(&db_path, self.db.database_args())?;
+        let provider_factory =
+            ProviderFactory::new(db, self.chain.clone(), data_dir.static_files_path())?;
+        let provider = provider_factory.provider()?;
+
+        let last_block = provider.last_block_number()?;
+        let range = match self.format {
+            ExportFormat::Rlp => self.from.unwrap_or_default()..=self.to.unwrap_or(last_block),
+            ExportFormat::Era1 => self.era1_range(last_block)?,
+        };
+        if range.is_empty() {
+            eyre::bail!("invalid block range {}..={}", range.start(), range.end());
+        }
+
+        info!(target: "reth::cli",
+            from = range.start(),
+            to = range.end(),
+            format = ?self.format,
+            "Exporting chain"
+        );
+        match self.format {
+            ExportFormat::Rlp => self.export_rlp(&provider, range)?,
+            ExportFormat::Era1 => self.export_era1(&provider, range)?,
+        }
+
+        info!(target: "reth::cli", path = ?self.path, "Chain exported");
+        Ok(())
+    }
+
+    /// Writes the blocks of the range, and optionally their receipts, as RLP.
+    fn export_rlp<P: BlockReader>(
+        &self,
+        provider: &P,
+        range: RangeInclusive<BlockNumber>,
+    ) -> eyre::Result<()> {
+        let mut blocks_file = create_file(&self.path)?;
+        let mut receipts_file = self.receipts.as_deref().map(create_file).transpose()?;
+
+        let mut buf = Vec::new();
+        for number in range {
+            let block = sealed_block(provider, number)?;
+
+            buf.clear();
+            block.unseal().encode(&mut buf);
+            blocks_file.write_all(&buf)?;
+
+            if let Some(receipts_file) = &mut receipts_file {
+                let receipts = BlockReceipts {
+                    number,
+                    receipts: block_receipts(provider, number)?
+                        .into_iter()
+                        .map(Receipt::with_bloom)
+                        .collect(),
+                };
+
+                buf.clear();
+                receipts.encode(&mut buf);
+                receipts_file.write_all(&buf)?;
+            }
+
+            if number % LOG_INTERVAL == 0 {
+                info!(target: "reth::cli", block = number, "Exported blocks");
+            }
+        }
+
+        blocks_file.flush()?;
+        if let Some(mut receipts_file) = receipts_file {
+            receipts_file.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the range of complete epochs to export as Era1 archives.
+    ///
+    /// Without `--to`, the range ends with the last complete epoch up to the given block, the
+    /// export stops at the merge.
+    fn era1_range(&self, last_block: BlockNumber) -> eyre::Result<RangeInclusive<BlockNumber>> {
+        let epoch_size = MAX_EPOCH_SIZE as u64;
+
+        let from = match self.from {
+            Some(from) => from,
+            None if self.pre_byzantium_receipts.is_some() => 0,
+            // receipts before byzantium contain intermediate state roots, which are not stored
+            None => match self.chain.fork(Hardfork::Byzantium) {
+                ForkCondition::Block(block) => block.div_ceil(epoch_size) * epoch_size,
+                ForkCondition::Never => eyre::bail!("Byzantium is not activated on this chain"),
+                _ => 0,
+            },
+        };
+        if from % epoch_size != 0 {
+            eyre::bail!("Era1 export must start at the first block of an epoch, got {from}");
+        }
+        if !self.chain.is_byzantium_active_at_block(from) && self.pre_byzantium_receipts.is_none() {
+            eyre::bail!(
+                "the intermediate state roots of pre-Byzantium receipts are not stored, but block \
+                 {from} is before Byzantium; use --pre-byzantium-receipts to take them from \
+                 existing Era1 archives"
+            );
+        }
+
+        let to = match self.to {
+            Some(to) => to,
+            None => match ((last_block + 1) / epoch_size).checked_sub(1) {
+                Some(epoch) => (epoch + 1) * epoch_size - 1,
+                None => eyre::bail!("the database contains no complete epoch"),
+            },
+        };
+        if (to + 1) % epoch_size != 0 {
+            eyre::bail!("Era1 export must end at the last block of an epoch, got {to}");
+        }
+
+        Ok(from..=to)
+    }
+
+    /// Writes the blocks of the range as Era1 archives, one per epoch.
+    ///
+    /// The range must consist of complete epochs. The export stops before the first epoch that
+    /// contains a post-merge block, which is an error if the end of the range was given
+    /// explicitly.
+    fn export_era1<P: BlockReader>(
+        &self,
+        provider: &P,
+        range: RangeInclusive<BlockNumber>,
+    ) -> eyre::Result<()> {
+        std::fs::create_dir_all(&self.path)
+            .wrap_err_with(|| format!("Could not create export directory {:?}", self.path))?;
+
+        let network = self.chain.chain.to_string();
+        let epoch_size = MAX_EPOCH_SIZE as u64;
+        let receipt_archives = match &self.pre_byzantium_receipts {
+            Some(dir) => era1_files(dir)
+                .wrap_err_with(|| format!("Could not read Era1 directory {dir:?}"))?,
+            None => Vec::new(),
+        };
+
+        let mut start = *range.start();
+        while start <= *range.end() {
+            let epoch = start / epoch_size;
+            let end = (epoch + 1) * epoch_size - 1;
+
+            // post-merge blocks have no difficulty, so this is not a complete pre-merge epoch
+            let last_header = provider
+                .header_by_number(end)?
+                .ok_or_else(|| eyre::eyre!("block {end} not found"))?;
+            if last_header.is_zero_difficulty() {
+                if self.to.is_some() {
+                    eyre::bail!("Era1 epoch {epoch} contains post-merge blocks");
+                }
+                info!(target: "reth::cli", epoch, "Stopping Era1 export at the merge");
+                break
+            }
+
+            // the final file name contains the accumulator root, which is known once all blocks
+            // are written
+            let tmp_path = self.path.join(format!("{network}-{epoch:05}.era1.tmp"));
+            let mut writer = Era1Writer::create(&tmp_path)?;
+
+            // receipts of pre-Byzantium blocks are taken from an existing archive of the epoch
+            let mut archived_receipts = if self.chain.is_byzantium_active_at_block(start) {
+                Vec::new().into_iter()
+            } else {
+                archived_receipts(&receipt_archives, start)?.into_iter()
+            };
+
+            for number in start..=end {
+                let (header, body) = sealed_block(provider, number)?.split_header_body();
+                let total_difficulty = provider
+                    .header_td_by_number(number)?
+                    .ok_or_else(|| eyre::eyre!("total difficulty of block {number} not found"))?;
+
+                if self.chain.is_byzantium_active_at_block(number) {
+                    writer.append(&Era1Block {
+                        receipts_root: header.receipts_root,
+                        header,
+                        body,
+                        receipts: Some(block_receipts(provider, number)?),
+                        total_difficulty,
+                    })?;
+                    continue
+                }
+
+                let receipts = archived_receipts
+                    .find(|receipts| receipts.number == number)
+                    .ok_or_else(|| eyre::eyre!("receipts of block {number} not archived"))?;
+                if receipts.receipts_root != header.receipts_root {
+                    eyre::bail!(
+                        "archived receipts of block {number} don't match its receipts root {}",
+                        header.receipts_root
+                    );
+                }
+                writer.append_with_encoded_receipts(
+                    &Era1Block {
+                        receipts_root: header.receipts_root,
+                        header,
+                        body,
+                        receipts: None,
+                        total_difficulty,
+                    },
+                    &receipts.encoded,
+                )?;
+            }
+
+            let (_, accumulator_root) = writer.finish()?;
+            let path = self.path.join(era1_file_name(&network, epoch, accumulator_root));
+            std::fs::rename(&tmp_path, &path)?;
+
+            debug!(target: "reth::cli", ?path, start, end, %accumulator_root, "Exported Era1 epoch");
+            info!(target: "reth::cli", epoch, block = end, "Exported blocks");
+
+            start = end + 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the canonical block with the given number.
+fn sealed_block<P: BlockReader>(provider: &P, number: BlockNumber) -> eyre::Result<SealedBlock> {
+    let hash = provider
+        .block_hash(number)?
+        .ok_or_else(|| eyre::eyre!("canonical hash of block {number} not found"))?;
+    let block =
+        provider.block_by_number(number)?.ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+
+    Ok(block.seal(hash))
+}
+
+/// Returns the receipts of the block with the given number.
+fn block_receipts<P: BlockReader>(provider: &P, number: BlockNumber) -> eyre::Result<Vec<Receipt>> {
+    provider.receipts_by_block(number.into())?.ok_or_else(|| {
+        eyre::eyre!("receipts of block {number} not found, they may have been pruned")
+    })
+}
+
+/// Reads the receipts of the epoch starting at the given block from the archive that contains it.
+fn archived_receipts(archives: &[PathBuf], start: BlockNumber) -> eyre::Result<Vec<Era1Receipts>> {
+    for path in archives {
+        let range = Era1Reader::open(path)?.read_block_range()?;
+        if range.is_some_and(|range| *range.start() == start) {
+            debug!(target: "reth::cli", ?path, start, "Reading archived receipts");
+            return Ok(Era1Reader::open(path)?.read_receipts()?)
+        }
+    }
+    eyre::bail!(
+        "no Era1 archive with pre-Byzantium receipts of the epoch starting at block {start}"
+    )
+}
+
+fn create_file(path: &Path) -> eyre::Result<BufWriter<File>> {
+    let file = File::create(path).wrap_err_with(|| format!("Could not create file {path:?}"))?;
+    Ok(BufWriter::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_export_command() {
+        let args: ExportCommand =
+            ExportCommand::parse_from(["reth", "--from", "10", "--to", "20", "blocks.rlp"]);
+        assert_eq!((args.from, args.to, args.format), (Some(10), Some(20), ExportFormat::Rlp));
+
+        let args: ExportCommand =
+            ExportCommand::parse_from(["reth", "--format", "era1", "era1-files"]);
+        assert_eq!(args.format, ExportFormat::Era1);
+        assert_eq!(args.path, PathBuf::from("era1-files"));
+    }
+
+    #[test]
+    fn era1_range_covers_complete_epochs() {
+        let args: ExportCommand =
+            ExportCommand::parse_from(["reth", "--chain", "mainnet", "--format", "era1", "out"]);
+        // the first complete epoch after byzantium at block 4_370_000 is epoch 534
+        assert_eq!(args.era1_range(4_383_000).unwrap(), 534 * 8192..=534 * 8192 + 8191);
+        assert!(args.era1_range(4_380_000).unwrap().is_empty());
+
+        let args: ExportCommand = ExportCommand::parse_from([
+            "reth", "--chain", "mainnet", "--format", "era1", "--from", "4370000", "out",
+        ]);
+        assert!(args.era1_range(4_383_000).is_err());
+
+        let args: ExportCommand = ExportCommand::parse_from([
+            "reth", "--chain", "mainnet", "--format", "era1", "--from", "0", "out",
+        ]);
+        assert!(args.era1_range(4_383_000).is_err());
+
+        // pre-byzantium epochs are exported with archived receipts
+        let args: ExportCommand = ExportCommand::parse_from([
+            "reth",
+            "--chain",
+            "mainnet",
+            "--format",
+            "era1",
+            "--pre-byzantium-receipts",
+            "archives",
+            "out",
+        ]);
+        assert_eq!(args.era1_range(8191).unwrap(), 0..=8191);
+    }
+}
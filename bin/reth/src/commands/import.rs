@@ -13,9 +13,10 @@ use eyre::Context;
 use futures::{Stream, StreamExt};
 use reth_beacon_consensus::BeaconConsensus;
 use reth_config::{config::EtlConfig, Config};
-use reth_db::{database::Database, init_db};
+use reth_db::{database::Database, init_db, DatabaseEnv};
 use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
+    era1::{era1_files, Era1Reader},
     file_client::{ChunkedFileReader, FileClient, DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE},
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
@@ -30,17 +31,27 @@ use reth_interfaces::{
 use reth_node_core::init::init_genesis;
 use reth_node_ethereum::EthEvmConfig;
 use reth_node_events::node::NodeEvent;
-use reth_primitives::{stage::StageId, ChainSpec, PruneModes, B256};
-use reth_provider::{HeaderSyncMode, ProviderFactory, StageCheckpointReader};
+use reth_primitives::{
+    stage::StageId, BlockNumber, ChainSpec, PruneCheckpoint, PruneMode, PruneModes, PruneSegment,
+    Receipt, StaticFileSegment, B256,
+};
+use reth_provider::{
+    providers::StaticFileWriter, BlockReader, HeaderProvider, HeaderSyncMode, ProviderError,
+    ProviderFactory, PruneCheckpointWriter, StageCheckpointReader,
+};
 use reth_stages::{
     prelude::*,
     stages::{ExecutionStage, ExecutionStageThresholds, SenderRecoveryStage},
     Pipeline, StageSet,
 };
 use reth_static_file::StaticFileProducer;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 use tokio::sync::watch;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Stages that require state.
 const STATE_STAGES: &[StageId] = &[
@@ -95,15 +106,39 @@ pub struct ImportCommand {
     #[arg(long, value_name = "CHUNK_LEN", verbatim_doc_comment)]
     chunk_len: Option<u64>,
 
+    /// Import Era1 archives from the given directory instead of a block file.
+    ///
+    /// Archives are imported in the order of their file names. Every epoch is verified against
+    /// its accumulator root before it is imported. Receipts are written directly to static files
+    /// if they are not produced by the execution stage. Pre-Byzantium receipts cannot be
+    /// represented and are marked as pruned instead.
+    #[arg(
+        long,
+        value_name = "ERA1_DIR",
+        verbatim_doc_comment,
+        conflicts_with_all = ["paths", "chunk_len"]
+    )]
+    era1: Option<PathBuf>,
+
+    /// File with trusted epoch accumulator roots to verify Era1 archives against.
+    ///
+    /// Contains one hex encoded root per line, where the line number is the epoch index.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment, requires = "era1")]
+    era1_accumulator: Option<PathBuf>,
+
     #[command(flatten)]
     db: DatabaseArgs,
 
-    /// The path to a block file for import.
+    /// The paths to block files, or directories of block files, for import.
     ///
+    /// Files are imported in the given order, files in a directory in the order of their names.
     /// The online stages (headers and bodies) are replaced by a file import, after which the
     /// remaining stages are executed.
-    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
-    path: PathBuf,
+    ///
+    /// Blocks that have already been imported, according to the stage checkpoints, are skipped,
+    /// so an interrupted import can be resumed by running the same command again.
+    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment, required_unless_present = "era1")]
+    paths: Vec<PathBuf>,
 }
 
 impl ImportCommand {
@@ -151,8 +186,24 @@ impl ImportCommand {
         let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
         info!(target: "reth::cli", "Consensus engine initialized");
 
-        // open file
-        let mut reader = ChunkedFileReader::new(&self.path, self.chunk_len).await?;
+        if let Some(era1_dir) = self.era1.clone() {
+            return self.import_era1(&era1_dir, &config, provider_factory, consensus, db).await
+        }
+
+        // resume after the last block that went through all stages
+        let last_imported_block = provider_factory
+            .provider()?
+            .get_stage_checkpoint(StageId::Finish)?
+            .unwrap_or_default()
+            .block_number;
+        if last_imported_block > 0 {
+            info!(target: "reth::cli", last_imported_block, "Resuming import");
+        }
+
+        // open files
+        let mut reader = ChunkedFileReader::from_paths(self.paths.clone(), self.chunk_len)
+            .await?
+            .skip_until(last_imported_block);
 
         while let Some(file_client) = reader.next_chunk().await? {
             // create a new FileClient from chunk read from file
@@ -160,52 +211,198 @@ impl ImportCommand {
                 "Importing chain file chunk"
             );
 
-            // override the tip
-            let tip = file_client.tip().expect("file client has no tip");
-            info!(target: "reth::cli", "Chain file chunk read");
-
-            let (mut pipeline, events) = self
-                .build_import_pipeline(
+            if !self
+                .import_file_client(
                     &config,
                     provider_factory.clone(),
                     &consensus,
-                    Arc::new(file_client),
-                    StaticFileProducer::new(
-                        provider_factory.clone(),
-                        provider_factory.static_file_provider(),
-                        PruneModes::default(),
-                    ),
-                    self.no_state,
+                    file_client,
+                    db.clone(),
                 )
-                .await?;
+                .await?
+            {
+                info!(target: "reth::cli", "Chain file import interrupted");
+                return Ok(())
+            }
+        }
 
-            // override the tip
-            pipeline.set_tip(tip);
-            debug!(target: "reth::cli", ?tip, "Tip manually set");
+        info!(target: "reth::cli", "Chain file imported");
+        Ok(())
+    }
 
+    /// Imports Era1 archives from the given directory, one epoch at a time.
+    async fn import_era1<C>(
+        &self,
+        dir: &Path,
+        config: &Config,
+        provider_factory: ProviderFactory<Arc<DatabaseEnv>>,
+        consensus: Arc<C>,
+        db: Arc<DatabaseEnv>,
+    ) -> eyre::Result<()>
+    where
+        C: Consensus + 'static,
+    {
+        let trusted_roots = self.era1_accumulator.as_deref().map(read_epoch_roots).transpose()?;
+        let files =
+            era1_files(dir).wrap_err_with(|| format!("Could not read era1 directory {dir:?}"))?;
+        info!(target: "reth::cli", files = files.len(), "Importing Era1 archives");
+
+        for path in files {
+            let read_path = path.clone();
+            let Some(block_range) = tokio::task::spawn_blocking(move || {
+                Era1Reader::open(read_path)?.read_block_range()
+            })
+            .await?
+            .wrap_err_with(|| format!("Could not read era1 archive {path:?}"))?
+            else {
+                warn!(target: "reth::cli", ?path, "Skipping empty Era1 archive");
+                continue
+            };
+
+            // resume after the last block that went through all stages, skipping all archives
+            // before the epoch that contains the next block without reading them
             let provider = provider_factory.provider()?;
+            let last_imported = provider
+                .get_stage_checkpoint(StageId::Finish)?
+                .map(|checkpoint| checkpoint.block_number);
+            let next_receipts_block = provider_factory
+                .static_file_provider()
+                .get_highest_static_file_block(StaticFileSegment::Receipts)
+                .map_or(0, |block| block + 1);
+            drop(provider);
+            let imported = last_imported.is_some_and(|last| *block_range.end() <= last);
+            if imported && *block_range.end() < next_receipts_block {
+                debug!(target: "reth::cli", ?path, "Era1 epoch already imported");
+                continue
+            }
 
-            let latest_block_number =
-                provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
-            tokio::spawn(reth_node_events::node::handle_events(
-                None,
-                latest_block_number,
-                events,
-                db.clone(),
-            ));
-
-            // Run pipeline
-            info!(target: "reth::cli", "Starting sync pipeline");
-            tokio::select! {
-                res = pipeline.run() => res?,
-                _ = tokio::signal::ctrl_c() => {},
+            let read_path = path.clone();
+            let epoch =
+                tokio::task::spawn_blocking(move || Era1Reader::open(read_path)?.read_epoch())
+                    .await?
+                    .wrap_err_with(|| format!("Could not read era1 archive {path:?}"))?;
+
+            let (Some(start_block), Some(end_block), Some(index)) =
+                (epoch.start_block(), epoch.end_block(), epoch.epoch())
+            else {
+                warn!(target: "reth::cli", ?path, "Skipping empty Era1 archive");
+                continue
+            };
+
+            if imported {
+                debug!(target: "reth::cli", epoch = index, "Era1 epoch already imported");
+                // the import was interrupted before the receipts of this epoch were written
+                let receipts = epoch
+                    .blocks
+                    .into_iter()
+                    .map(|block| (block.header.number, block.receipts))
+                    .collect();
+                append_receipts(&provider_factory, receipts)?;
+                continue
             }
+
+            // verify total difficulties against the chain we already have
+            let parent_td = match start_block.checked_sub(1) {
+                Some(parent) => {
+                    Some(provider_factory.provider()?.header_td_by_number(parent)?.ok_or_else(
+                        || eyre::eyre!("missing parent block {parent} of Era1 epoch {index}"),
+                    )?)
+                }
+                None => None,
+            };
+
+            epoch.verify(parent_td).wrap_err_with(|| format!("Invalid era1 archive {path:?}"))?;
+            if let Some(roots) = &trusted_roots {
+                let trusted = roots.get(index as usize).ok_or_else(|| {
+                    eyre::eyre!("no trusted accumulator root for Era1 epoch {index}")
+                })?;
+                if *trusted != epoch.accumulator_root {
+                    eyre::bail!(
+                        "Era1 epoch {index} accumulator root {} does not match trusted root {trusted}",
+                        epoch.accumulator_root
+                    );
+                }
+            }
+
+            info!(target: "reth::cli", epoch = index, start_block, end_block, "Importing Era1 epoch");
+
+            let (file_client, receipts) = epoch.into_file_client();
+            if !self
+                .import_file_client(
+                    config,
+                    provider_factory.clone(),
+                    &consensus,
+                    file_client,
+                    db.clone(),
+                )
+                .await?
+            {
+                // the epoch has only been partially imported, so its receipts are written once
+                // the import is resumed
+                info!(target: "reth::cli", epoch = index, "Era1 import interrupted");
+                return Ok(())
+            }
+
+            append_receipts(&provider_factory, receipts)?;
         }
 
-        info!(target: "reth::cli", "Chain file imported");
+        info!(target: "reth::cli", "Era1 archives imported");
         Ok(())
     }
 
+    /// Runs the import pipeline for the blocks of the given [`FileClient`].
+    ///
+    /// Returns `false` if the import was interrupted by the user before the pipeline finished.
+    async fn import_file_client<C>(
+        &self,
+        config: &Config,
+        provider_factory: ProviderFactory<Arc<DatabaseEnv>>,
+        consensus: &Arc<C>,
+        file_client: FileClient,
+        db: Arc<DatabaseEnv>,
+    ) -> eyre::Result<bool>
+    where
+        C: Consensus + 'static,
+    {
+        // override the tip
+        let tip = file_client.tip().expect("file client has no tip");
+        info!(target: "reth::cli", "Chain file chunk read");
+
+        let (mut pipeline, events) = self
+            .build_import_pipeline(
+                config,
+                provider_factory.clone(),
+                consensus,
+                Arc::new(file_client),
+                StaticFileProducer::new(
+                    provider_factory.clone(),
+                    provider_factory.static_file_provider(),
+                    PruneModes::default(),
+                ),
+                self.no_state,
+            )
+            .await?;
+
+        // override the tip
+        pipeline.set_tip(tip);
+        debug!(target: "reth::cli", ?tip, "Tip manually set");
+
+        let provider = provider_factory.provider()?;
+
+        let latest_block_number =
+            provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
+        tokio::spawn(reth_node_events::node::handle_events(None, latest_block_number, events, db));
+
+        // Run pipeline
+        info!(target: "reth::cli", "Starting sync pipeline");
+        tokio::select! {
+            res = pipeline.run() => res?,
+            _ = tokio::signal::ctrl_c() => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
     async fn build_import_pipeline<DB, C>(
         &self,
         config: &Config,
@@ -292,6 +489,102 @@ impl ImportCommand {
     }
 }
 
+/// Writes the receipts of imported blocks directly to the receipts static files.
+///
+/// Receipts of blocks that already have receipts, e.g. because they have been executed, are
+/// skipped. Pre-Byzantium receipts cannot be represented, so the receipts segment is advanced
+/// across those blocks without receipts and the receipts prune checkpoint marks them as pruned.
+/// Nothing is written if the receipts would leave a gap in the static files.
+fn append_receipts<DB: Database>(
+    provider_factory: &ProviderFactory<DB>,
+    receipts: Vec<(BlockNumber, Option<Vec<Receipt>>)>,
+) -> eyre::Result<()> {
+    let static_file_provider = provider_factory.static_file_provider();
+    let next_block = static_file_provider
+        .get_highest_static_file_block(StaticFileSegment::Receipts)
+        .map_or(0, |block| block + 1);
+
+    let receipts = receipts.into_iter().skip_while(|(number, _)| *number < next_block);
+    let mut receipts = receipts.peekable();
+    match receipts.peek() {
+        None => return Ok(()),
+        Some((number, _)) if *number != next_block => {
+            warn!(target: "reth::cli",
+                next_block,
+                first_block = number,
+                "Receipts would leave a gap in static files, skipping"
+            );
+            return Ok(())
+        }
+        _ => {}
+    }
+
+    let provider = provider_factory.provider_rw()?;
+    // legacy receipts can only be skipped while no receipts have been written, since the prune
+    // checkpoint marks all receipts up to the last skipped block as pruned
+    let mut has_receipts =
+        static_file_provider.get_highest_static_file_tx(StaticFileSegment::Receipts).is_some();
+    let mut last_legacy_block = None;
+    let mut writer = static_file_provider.get_writer(next_block, StaticFileSegment::Receipts)?;
+    for (number, receipts) in receipts {
+        let indices = provider
+            .block_body_indices(number)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(number))?;
+
+        let Some(receipts) = receipts else {
+            if has_receipts {
+                eyre::bail!("block {number} has pre-Byzantium receipts after Byzantium receipts");
+            }
+            writer.increment_block(StaticFileSegment::Receipts, number)?;
+            last_legacy_block = Some((number, indices));
+            continue
+        };
+
+        if indices.tx_count != receipts.len() as u64 {
+            eyre::bail!(
+                "block {number} has {} transactions, but {} receipts",
+                indices.tx_count,
+                receipts.len()
+            );
+        }
+        writer.increment_block(StaticFileSegment::Receipts, number)?;
+        for (idx, receipt) in receipts.into_iter().enumerate() {
+            writer.append_receipt(indices.first_tx_num + idx as u64, receipt)?;
+            has_receipts = true;
+        }
+    }
+
+    if let Some((number, indices)) = last_legacy_block {
+        debug!(target: "reth::cli", last_block = number, "Marking pre-Byzantium receipts as pruned");
+        provider.save_prune_checkpoint(
+            PruneSegment::Receipts,
+            PruneCheckpoint {
+                block_number: Some(number),
+                tx_number: indices.next_tx_num().checked_sub(1),
+                prune_mode: PruneMode::Before(number + 1),
+            },
+        )?;
+    }
+    writer.commit()?;
+    provider.commit()?;
+
+    Ok(())
+}
+
+/// Reads trusted epoch accumulator roots, one hex encoded root per line.
+fn read_epoch_roots(path: &Path) -> eyre::Result<Vec<B256>> {
+    let roots = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Could not read accumulator roots {path:?}"))?;
+    roots
+        .lines()
+        .enumerate()
+        .map(|(epoch, line)| {
+            B256::from_str(line.trim())
+                .wrap_err_with(|| format!("Invalid accumulator root for epoch {epoch}"))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,4 +600,23 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn parse_era1_import_command() {
+        let args: ImportCommand = ImportCommand::parse_from(["reth", "--era1", "era1-files"]);
+        assert_eq!(args.era1, Some(PathBuf::from("era1-files")));
+        assert!(args.paths.is_empty());
+
+        assert!(ImportCommand::try_parse_from(["reth", "--era1", "era1-files", "."]).is_err());
+        assert!(ImportCommand::try_parse_from(["reth"]).is_err());
+    }
+
+    #[test]
+    fn parse_multiple_import_paths() {
+        let args: ImportCommand = ImportCommand::parse_from(["reth", "a.rlp", "b.rlp", "dir"]);
+        assert_eq!(
+            args.paths,
+            vec![PathBuf::from("a.rlp"), PathBuf::from("b.rlp"), PathBuf::from("dir")]
+        );
+    }
 }
@@ -0,0 +1,273 @@
+//! Command that imports receipts from a file into static files.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+    version::SHORT_VERSION,
+};
+use clap::{Parser, ValueEnum};
+use reth_db::{database::Database, init_db};
+use reth_downloaders::{
+    file_client::{ChunkedFileReader, FileClientError, DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE},
+    receipt_file_codec::{BlockReceipts, OpGethReceiptFileCodec, ReceiptFileCodec},
+};
+#[cfg(not(feature = "optimism"))]
+use reth_primitives::proofs::calculate_receipt_root;
+#[cfg(feature = "optimism")]
+use reth_primitives::proofs::calculate_receipt_root_optimism;
+use reth_primitives::{ChainSpec, StaticFileSegment};
+#[cfg(feature = "optimism")]
+use reth_provider::ChainSpecProvider;
+use reth_provider::{
+    providers::StaticFileWriter, BlockReader, HeaderProvider, ProviderError, ProviderFactory,
+};
+use std::{path::PathBuf, sync::Arc};
+use tokio_util::codec::Decoder;
+use tracing::{debug, info};
+
+/// The format of a receipts file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReceiptsFormat {
+    /// RLP encoded receipts per block, as written by `reth export --receipts`.
+    Reth,
+    /// RLP encoded receipts per transaction, as exported by op-geth for OP Mainnet blocks below
+    /// Bedrock.
+    OpGeth,
+}
+
+/// Imports receipts from files into static files.
+///
+/// The receipts are validated against the receipts root of the imported headers, so the blocks
+/// must have been imported before, e.g. with `reth import --no-state`.
+#[derive(Debug, Parser)]
+pub struct ImportReceiptsCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(flatten)]
+    db: DatabaseArgs,
+
+    /// Chunk byte length.
+    #[arg(long, value_name = "CHUNK_LEN", verbatim_doc_comment)]
+    chunk_len: Option<u64>,
+
+    /// The format of the receipts files.
+    #[arg(long, value_enum, default_value_t = ReceiptsFormat::Reth)]
+    format: ReceiptsFormat,
+
+    /// The paths to receipts files, or directories of receipts files, for import.
+    ///
+    /// Files are imported in the given order, files in a directory in the order of their names.
+    /// Receipts of blocks that already have receipts in static files are skipped, so an
+    /// interrupted import can be resumed by running the same command again.
+    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment, required = true)]
+    paths: Vec<PathBuf>,
+}
+
+impl ImportReceiptsCommand {
+    /// Execute `import-receipts` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        debug!(target: "reth::cli",
+            chunk_byte_len=self.chunk_len.unwrap_or(DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE),
+            format=?self.format,
+            "Chunking receipts import"
+        );
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = Arc::new(init_db(db_path, self.db.database_args())?);
+        info!(target: "reth::cli", "Database opened");
+        let provider_factory =
+            ProviderFactory::new(db, self.chain.clone(), data_dir.static_files_path())?;
+
+        let mut reader =
+            ChunkedFileReader::from_paths(self.paths.iter().cloned(), self.chunk_len).await?;
+
+        // resume after the last block with receipts
+        if let Some(highest_block) = provider_factory
+            .static_file_provider()
+            .get_highest_static_file_block(StaticFileSegment::Receipts)
+        {
+            info!(target: "reth::cli", highest_block, "Skipping blocks with receipts");
+            reader = reader.skip_until(highest_block);
+        }
+
+        let total_receipts = match self.format {
+            ReceiptsFormat::Reth => {
+                import_receipts::<_, ReceiptFileCodec>(&provider_factory, reader).await?
+            }
+            ReceiptsFormat::OpGeth => {
+                import_receipts::<_, OpGethReceiptFileCodec>(&provider_factory, reader).await?
+            }
+        };
+
+        info!(target: "reth::cli", total_receipts, "Receipts imported");
+        Ok(())
+    }
+}
+
+/// Reads the receipts files in chunks with the decoder `D`, and writes the receipts to static
+/// files. Returns the number of imported receipts.
+///
+/// Stage checkpoints are not touched, the receipts of blocks that are executed later on are
+/// replaced by the execution stage.
+async fn import_receipts<DB, D>(
+    provider_factory: &ProviderFactory<DB>,
+    mut reader: ChunkedFileReader,
+) -> eyre::Result<u64>
+where
+    DB: Database,
+    D: Decoder<Item = BlockReceipts, Error = FileClientError> + Default,
+{
+    let mut total_receipts = 0;
+    // receipts of the last block of a chunk may continue in the next chunk
+    let mut pending: Option<BlockReceipts> = None;
+
+    while let Some(mut chunk) = reader.next_receipts_chunk::<D>().await? {
+        if let Some(mut pending) = pending.take() {
+            if chunk[0].number == pending.number {
+                pending.receipts.append(&mut chunk[0].receipts);
+                chunk[0] = pending;
+            } else {
+                chunk.insert(0, pending);
+            }
+        }
+        pending = chunk.pop();
+
+        total_receipts += write_receipts(provider_factory, chunk)?;
+    }
+
+    if let Some(pending) = pending {
+        total_receipts += write_receipts(provider_factory, vec![pending])?;
+    }
+
+    Ok(total_receipts)
+}
+
+/// Validates the receipts against the receipts roots of the imported headers and appends them
+/// to the receipts static files. Returns the number of written receipts.
+///
+/// Blocks without transactions, which have no receipts in the file, are filled in.
+fn write_receipts<DB: Database>(
+    provider_factory: &ProviderFactory<DB>,
+    blocks: Vec<BlockReceipts>,
+) -> eyre::Result<u64> {
+    let static_file_provider = provider_factory.static_file_provider();
+    let mut next_block = static_file_provider
+        .get_highest_static_file_block(StaticFileSegment::Receipts)
+        .map_or(0, |block| block + 1);
+
+    let provider = provider_factory.provider()?;
+    let mut writer = static_file_provider.get_writer(next_block, StaticFileSegment::Receipts)?;
+    let mut written = 0;
+
+    for BlockReceipts { number, receipts } in blocks {
+        if number < next_block {
+            continue
+        }
+
+        while next_block < number {
+            let indices = provider
+                .block_body_indices(next_block)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(next_block))?;
+            if indices.tx_count != 0 {
+                eyre::bail!("receipts of block {next_block} are missing");
+            }
+            writer.increment_block(StaticFileSegment::Receipts, next_block)?;
+            next_block += 1;
+        }
+
+        let indices = provider
+            .block_body_indices(number)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(number))?;
+        if indices.tx_count != receipts.len() as u64 {
+            eyre::bail!(
+                "block {number} has {} transactions, but {} receipts",
+                indices.tx_count,
+                receipts.len()
+            );
+        }
+
+        let header = provider
+            .header_by_number(number)?
+            .ok_or(ProviderError::HeaderNotFound(number.into()))?;
+        #[cfg(not(feature = "optimism"))]
+        let root = calculate_receipt_root(&receipts);
+        #[cfg(feature = "optimism")]
+        let root = calculate_receipt_root_optimism(
+            &receipts,
+            &provider_factory.chain_spec(),
+            header.timestamp,
+        );
+        if root != header.receipts_root {
+            eyre::bail!(
+                "block {number} receipts root mismatch: got {root}, expected {}",
+                header.receipts_root
+            );
+        }
+
+        writer.increment_block(StaticFileSegment::Receipts, number)?;
+        for (idx, receipt) in receipts.into_iter().enumerate() {
+            writer.append_receipt(indices.first_tx_num + idx as u64, receipt.receipt)?;
+        }
+
+        written += indices.tx_count;
+        next_block += 1;
+    }
+    writer.commit()?;
+
+    debug!(target: "reth::cli", highest_block = next_block.saturating_sub(1), "Wrote receipts");
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_import_receipts_command() {
+        let args: ImportReceiptsCommand =
+            ImportReceiptsCommand::parse_from(["reth", "receipts.rlp"]);
+        assert_eq!(args.format, ReceiptsFormat::Reth);
+
+        let args: ImportReceiptsCommand = ImportReceiptsCommand::parse_from([
+            "reth",
+            "--format",
+            "op-geth",
+            "--chunk-len",
+            "1000",
+            "receipts",
+        ]);
+        assert_eq!(args.format, ReceiptsFormat::OpGeth);
+        assert_eq!(args.chunk_len, Some(1000));
+        assert_eq!(args.paths, vec![PathBuf::from("receipts")]);
+    }
+}
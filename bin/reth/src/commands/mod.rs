@@ -4,7 +4,9 @@ pub mod config_cmd;
 pub mod db;
 pub mod debug_cmd;
 pub mod dump_genesis;
+pub mod export;
 pub mod import;
+pub mod import_receipts;
 
 pub mod init_cmd;
 
@@ -13,3 +15,4 @@ pub mod p2p;
 pub mod recover;
 pub mod stage;
 pub mod test_vectors;
+pub mod txpool;
@@ -4,7 +4,7 @@ use crate::{
     args::{
         utils::{chain_help, genesis_value_parser, parse_socket_address, SUPPORTED_CHAINS},
         DatabaseArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs, PruningArgs,
-        RpcServerArgs, TxPoolArgs,
+        RpcServerArgs, SnapSyncArgs, TxPoolArgs,
     },
     dirs::{DataDirPath, MaybePlatformPath},
 };
@@ -108,6 +108,10 @@ pub struct NodeCommand<Ext: clap::Args + fmt::Debug = NoArgs> {
     #[command(flatten)]
     pub pruning: PruningArgs,
 
+    /// All snap sync related arguments with --snap-sync prefix
+    #[command(flatten)]
+    pub snap_sync: SnapSyncArgs,
+
     /// Additional cli arguments
     #[command(flatten, next_help_heading = "Extension")]
     pub ext: Ext,
@@ -156,6 +160,7 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            snap_sync,
             ext,
         } = self;
 
@@ -173,6 +178,7 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            snap_sync,
         };
 
         // Register the prometheus recorder before creating the database,
@@ -0,0 +1,577 @@
+//! Command that crawls the network and writes an inventory of all reachable nodes.
+
+use super::enr_fork_id;
+use crate::args::{utils::parse_duration_from_secs, DiscoveryArgs};
+use clap::{Parser, ValueEnum};
+use discv5::ListenConfig;
+use futures::{stream::FuturesUnordered, SinkExt, StreamExt};
+use reth_discv4::{DiscoveryUpdate, Discv4, Discv4ConfigBuilder};
+use reth_discv5::{enr::EnrCombinedKeyWrapper, Discv5};
+use reth_ecies::stream::ECIESStream;
+use reth_eth_wire::{
+    DisconnectReason, EthMessage, EthVersion, HelloMessage, ProtocolMessage, Status,
+    UnauthedP2PStream,
+};
+use reth_primitives::{fs, hex, pk2id, ChainSpec, ForkId, Head, NodeRecord, PeerId, B256, U256};
+use secp256k1::SecretKey;
+use serde::Serialize;
+use std::{
+    collections::{hash_map::Entry, BTreeSet, HashMap, VecDeque},
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{net::TcpStream, sync::mpsc};
+use tracing::{debug, info, trace};
+
+type Enr = enr::Enr<SecretKey>;
+
+/// Columns of the CSV inventory, in order.
+const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "ip",
+    "tcp_port",
+    "udp_port",
+    "sources",
+    "enr",
+    "enr_fork_hash",
+    "enr_fork_next",
+    "client_version",
+    "p2p_version",
+    "capabilities",
+    "eth_version",
+    "network_id",
+    "genesis",
+    "head",
+    "total_difficulty",
+    "fork_hash",
+    "fork_next",
+    "error",
+];
+
+/// The arguments for the `reth p2p crawl` command
+///
+/// Every discovered node is dialed once: after the RLPx and `eth` handshakes the connection is
+/// closed again, no requests are made.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// How long to discover new nodes for, in seconds.
+    ///
+    /// Dials that are in progress when the time is up are completed, nodes that were not dialed
+    /// yet are included in the inventory without handshake data.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value = "300",
+        value_parser = parse_duration_from_secs
+    )]
+    duration: Duration,
+
+    /// Maximum number of concurrent dials.
+    #[arg(long, value_name = "COUNT", default_value_t = 64)]
+    max_concurrent_dials: usize,
+
+    /// Timeout of a single dial including all handshakes, in seconds.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value = "10",
+        value_parser = parse_duration_from_secs
+    )]
+    dial_timeout: Duration,
+
+    /// The format of the inventory.
+    #[arg(long, value_enum, default_value_t = InventoryFormat::Json)]
+    format: InventoryFormat,
+
+    /// Path to write the inventory to.
+    ///
+    /// If not set, the inventory is written to stdout.
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+/// The format of the crawl inventory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InventoryFormat {
+    /// A JSON array of nodes.
+    Json,
+    /// One node per line, with a header line.
+    Csv,
+}
+
+impl Command {
+    /// Execute `p2p crawl` command
+    pub async fn execute(
+        &self,
+        chain: Arc<ChainSpec>,
+        discovery: &DiscoveryArgs,
+        secret_key: SecretKey,
+    ) -> eyre::Result<()> {
+        let mut discovered = self.start_discovery(&chain, discovery, secret_key).await?;
+
+        let hello =
+            HelloMessage::builder(pk2id(&secret_key.public_key(secp256k1::SECP256K1))).build();
+        let head = Head {
+            hash: chain.genesis_hash(),
+            difficulty: chain.genesis.difficulty,
+            total_difficulty: chain.genesis.difficulty,
+            timestamp: chain.genesis.timestamp,
+            ..Default::default()
+        };
+        // advertise the latest fork, so that synced nodes don't reject the status before sending
+        // their own
+        let status = Status::spec_builder(&chain, &head).forkid(chain.latest_fork_id()).build();
+
+        let mut nodes = HashMap::<PeerId, CrawledNode>::new();
+        let mut queued = VecDeque::new();
+        let mut dials = FuturesUnordered::new();
+
+        info!(target: "reth::cli", duration = ?self.duration, "Crawling network");
+        let deadline = tokio::time::sleep(self.duration);
+        tokio::pin!(deadline);
+        let mut discovering = true;
+
+        loop {
+            while discovering && dials.len() < self.max_concurrent_dials {
+                let Some(record) = queued.pop_front() else { break };
+                let handshake = dial(record, secret_key, hello.clone(), status);
+                let dial_timeout = self.dial_timeout;
+                dials.push(async move {
+                    let outcome = tokio::time::timeout(dial_timeout, handshake)
+                        .await
+                        .unwrap_or_else(|_| DialOutcome::error("dial timed out"));
+                    (record.id, outcome)
+                });
+            }
+
+            if !discovering && dials.is_empty() {
+                break
+            }
+
+            tokio::select! {
+                _ = &mut deadline, if discovering => {
+                    info!(
+                        target: "reth::cli",
+                        in_flight = dials.len(),
+                        "Discovery finished, completing dials"
+                    );
+                    discovering = false;
+                }
+                node = discovered.recv(), if discovering => {
+                    let Some(node) = node else {
+                        discovering = false;
+                        continue
+                    };
+                    match nodes.entry(node.record.id) {
+                        Entry::Occupied(mut entry) => entry.get_mut().merge(node),
+                        Entry::Vacant(entry) => {
+                            trace!(target: "reth::cli", record = %node.record, "Discovered node");
+                            queued.push_back(node.record);
+                            entry.insert(CrawledNode::new(node));
+                        }
+                    }
+                }
+                Some((id, outcome)) = dials.next(), if !dials.is_empty() => {
+                    debug!(target: "reth::cli", %id, error = ?outcome.error, "Dial finished");
+                    if let Some(node) = nodes.get_mut(&id) {
+                        node.apply(outcome);
+                    }
+                }
+            }
+        }
+
+        for record in queued {
+            if let Some(node) = nodes.get_mut(&record.id) {
+                node.error = Some("not dialed".to_string());
+            }
+        }
+
+        let mut nodes = nodes.into_values().collect::<Vec<_>>();
+        nodes.sort_unstable_by_key(|node| node.id);
+        info!(
+            target: "reth::cli",
+            count = nodes.len(),
+            reachable = nodes.iter().filter(|node| node.client_version.is_some()).count(),
+            "Crawl finished"
+        );
+
+        let inventory = match self.format {
+            InventoryFormat::Json => serde_json::to_string_pretty(&nodes)?,
+            InventoryFormat::Csv => to_csv(&nodes)?,
+        };
+        match &self.output {
+            Some(path) => {
+                fs::write(path, inventory)?;
+                info!(target: "reth::cli", path = %path.display(), "Wrote inventory");
+            }
+            None => println!("{inventory}"),
+        }
+
+        Ok(())
+    }
+
+    /// Starts the enabled discovery services and returns a stream of discovered nodes.
+    async fn start_discovery(
+        &self,
+        chain: &ChainSpec,
+        discovery: &DiscoveryArgs,
+        secret_key: SecretKey,
+    ) -> eyre::Result<mpsc::Receiver<DiscoveredNode>> {
+        if discovery.disable_discovery ||
+            (discovery.disable_discv4_discovery && !discovery.enable_discv5_discovery)
+        {
+            eyre::bail!("Crawling requires discv4 or discv5 discovery to be enabled")
+        }
+
+        let boot_nodes = chain.bootnodes().unwrap_or_default();
+        let (tx, rx) = mpsc::channel(1024);
+
+        if !discovery.disable_discv4_discovery {
+            let socket = SocketAddr::new(discovery.addr, discovery.port);
+            let local = NodeRecord::from_secret_key(socket, &secret_key);
+            let mut config = Discv4ConfigBuilder::default();
+            config
+                .add_boot_nodes(boot_nodes.clone())
+                .lookup_interval(Duration::from_secs(1))
+                .enable_eip868(true);
+            let discv4 = Discv4::spawn(socket, local, secret_key, config.build()).await?;
+            let mut updates = discv4.update_stream().await?;
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                // keep the service alive for as long as the crawl is running
+                let _discv4 = discv4;
+                while let Some(update) = updates.next().await {
+                    for node in DiscoveredNode::from_discv4(update) {
+                        if tx.send(node).await.is_err() {
+                            return
+                        }
+                    }
+                }
+            });
+        }
+
+        if discovery.enable_discv5_discovery {
+            let DiscoveryArgs { discv5_addr, discv5_port, .. } = *discovery;
+            let config = reth_discv5::Config::builder(discovery.port)
+                .discv5_config(
+                    discv5::ConfigBuilder::new(ListenConfig::from(Into::<SocketAddr>::into((
+                        discv5_addr,
+                        discv5_port,
+                    ))))
+                    .build(),
+                )
+                .add_unsigned_boot_nodes(boot_nodes.into_iter())
+                .build();
+            let (discv5, mut events, _) = Discv5::start(&secret_key, config)
+                .await
+                .map_err(|err| eyre::eyre!("failed to start discv5: {err:?}"))?;
+            tokio::spawn(async move {
+                // keep the service alive for as long as the crawl is running
+                let _discv5 = discv5;
+                while let Some(event) = events.recv().await {
+                    let enr = match event {
+                        discv5::Event::Discovered(enr) |
+                        discv5::Event::SessionEstablished(enr, _) => enr,
+                        _ => continue,
+                    };
+                    let Some(node) = DiscoveredNode::from_discv5(EnrCombinedKeyWrapper(enr).into())
+                    else {
+                        continue
+                    };
+                    if tx.send(node).await.is_err() {
+                        return
+                    }
+                }
+            });
+        }
+
+        Ok(rx)
+    }
+}
+
+/// The discovery protocol a node was found with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum DiscoverySource {
+    Discv4,
+    Discv5,
+}
+
+/// A node reported by one of the discovery services.
+#[derive(Debug)]
+struct DiscoveredNode {
+    record: NodeRecord,
+    source: DiscoverySource,
+    enr: Option<Enr>,
+    fork_id: Option<ForkId>,
+}
+
+impl DiscoveredNode {
+    fn from_discv4(update: DiscoveryUpdate) -> Vec<Self> {
+        let node =
+            |record, fork_id| Self { record, source: DiscoverySource::Discv4, enr: None, fork_id };
+        match update {
+            DiscoveryUpdate::Added(record) | DiscoveryUpdate::DiscoveredAtCapacity(record) => {
+                vec![node(record, None)]
+            }
+            DiscoveryUpdate::EnrForkId(record, fork_id) => vec![node(record, Some(fork_id))],
+            DiscoveryUpdate::Removed(_) => Vec::new(),
+            DiscoveryUpdate::Batch(updates) => {
+                updates.into_iter().flat_map(Self::from_discv4).collect()
+            }
+        }
+    }
+
+    fn from_discv5(enr: Enr) -> Option<Self> {
+        let record = NodeRecord {
+            address: enr.ip4().map(IpAddr::from).or_else(|| enr.ip6().map(IpAddr::from))?,
+            tcp_port: enr.tcp4().or_else(|| enr.tcp6())?,
+            udp_port: enr.udp4().or_else(|| enr.udp6())?,
+            id: pk2id(&enr.public_key()),
+        }
+        .into_ipv4_mapped();
+        let fork_id = enr_fork_id(&enr);
+
+        Some(Self { record, source: DiscoverySource::Discv5, enr: Some(enr), fork_id })
+    }
+}
+
+/// The result of dialing a node.
+#[derive(Debug, Default)]
+struct DialOutcome {
+    hello: Option<HelloMessage>,
+    status: Option<Status>,
+    error: Option<String>,
+}
+
+impl DialOutcome {
+    fn error(error: impl Into<String>) -> Self {
+        Self { error: Some(error.into()), ..Default::default() }
+    }
+}
+
+/// Dials the node and performs the RLPx and `eth` handshakes.
+///
+/// The status of the peer is recorded as is and not validated against our own.
+async fn dial(
+    record: NodeRecord,
+    secret_key: SecretKey,
+    hello: HelloMessage,
+    status: Status,
+) -> DialOutcome {
+    let mut outcome = DialOutcome::default();
+    if let Err(err) = handshake(record, secret_key, hello, status, &mut outcome).await {
+        outcome.error = Some(err.to_string());
+    }
+    outcome
+}
+
+async fn handshake(
+    record: NodeRecord,
+    secret_key: SecretKey,
+    hello: HelloMessage,
+    status: Status,
+    outcome: &mut DialOutcome,
+) -> eyre::Result<()> {
+    let stream = TcpStream::connect(record.tcp_addr()).await?;
+    let stream = ECIESStream::connect(stream, secret_key, record.id).await?;
+    let (mut p2p_stream, their_hello) = UnauthedP2PStream::new(stream).handshake(hello).await?;
+    outcome.hello = Some(their_hello);
+
+    let version = match p2p_stream.shared_capabilities().eth() {
+        Ok(eth) => eth.version(),
+        Err(_) => {
+            let _ = p2p_stream.disconnect(DisconnectReason::UselessPeer).await;
+            eyre::bail!("no shared eth capability")
+        }
+    };
+
+    // the status exchange is done manually, because the `EthStream` handshake rejects peers of
+    // other networks before returning their status
+    let status = Status { version, ..status };
+    p2p_stream
+        .send(alloy_rlp::encode(ProtocolMessage::from(EthMessage::Status(status))).into())
+        .await?;
+    let their_msg = p2p_stream
+        .next()
+        .await
+        .ok_or_else(|| eyre::eyre!("connection closed before status was received"))??;
+    let _ = p2p_stream.disconnect(DisconnectReason::ClientQuitting).await;
+
+    let msg =
+        ProtocolMessage::decode_message(EthVersion::try_from(version)?, &mut their_msg.as_ref())?;
+    match msg.message {
+        EthMessage::Status(their_status) => {
+            outcome.status = Some(their_status);
+            Ok(())
+        }
+        _ => eyre::bail!("expected status, got {:?}", msg.message_type),
+    }
+}
+
+/// Everything learned about a single node.
+#[derive(Debug, Serialize)]
+struct CrawledNode {
+    id: PeerId,
+    ip: IpAddr,
+    tcp_port: u16,
+    udp_port: u16,
+    sources: BTreeSet<DiscoverySource>,
+    enr: Option<String>,
+    enr_fork_hash: Option<String>,
+    enr_fork_next: Option<u64>,
+    client_version: Option<String>,
+    p2p_version: Option<u8>,
+    capabilities: Vec<String>,
+    eth_version: Option<u8>,
+    network_id: Option<u64>,
+    genesis: Option<B256>,
+    head: Option<B256>,
+    total_difficulty: Option<U256>,
+    fork_hash: Option<String>,
+    fork_next: Option<u64>,
+    error: Option<String>,
+}
+
+impl CrawledNode {
+    fn new(node: DiscoveredNode) -> Self {
+        let DiscoveredNode { record, source, .. } = node;
+        let mut crawled = Self {
+            id: record.id,
+            ip: record.address,
+            tcp_port: record.tcp_port,
+            udp_port: record.udp_port,
+            sources: BTreeSet::from([source]),
+            enr: None,
+            enr_fork_hash: None,
+            enr_fork_next: None,
+            client_version: None,
+            p2p_version: None,
+            capabilities: Vec::new(),
+            eth_version: None,
+            network_id: None,
+            genesis: None,
+            head: None,
+            total_difficulty: None,
+            fork_hash: None,
+            fork_next: None,
+            error: None,
+        };
+        crawled.merge(node);
+        crawled
+    }
+
+    /// Merges discovery data of a node that was reported again.
+    fn merge(&mut self, node: DiscoveredNode) {
+        self.sources.insert(node.source);
+        if let Some(enr) = node.enr {
+            self.enr = Some(enr.to_base64());
+        }
+        if let Some(fork_id) = node.fork_id {
+            self.enr_fork_hash = Some(hex::encode_prefixed(fork_id.hash.0));
+            self.enr_fork_next = Some(fork_id.next);
+        }
+    }
+
+    /// Records the result of dialing the node.
+    fn apply(&mut self, outcome: DialOutcome) {
+        let DialOutcome { hello, status, error } = outcome;
+        if let Some(hello) = hello {
+            self.client_version = Some(hello.client_version);
+            self.p2p_version = Some(hello.protocol_version as u8);
+            self.capabilities = hello.capabilities.iter().map(ToString::to_string).collect();
+        }
+        if let Some(status) = status {
+            self.eth_version = Some(status.version);
+            self.network_id = Some(status.chain.id());
+            self.genesis = Some(status.genesis);
+            self.head = Some(status.blockhash);
+            self.total_difficulty = Some(status.total_difficulty);
+            self.fork_hash = Some(hex::encode_prefixed(status.forkid.hash.0));
+            self.fork_next = Some(status.forkid.next);
+        }
+        self.error = error;
+    }
+}
+
+/// Renders the nodes as CSV, with [CSV_COLUMNS] as header.
+///
+/// List values are joined with `;`.
+fn to_csv(nodes: &[CrawledNode]) -> eyre::Result<String> {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push('\n');
+    for node in nodes {
+        let value = serde_json::to_value(node)?;
+        let row = CSV_COLUMNS
+            .iter()
+            .map(|column| csv_field(&value[*column]))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+    Ok(csv)
+}
+
+fn csv_field(value: &serde_json::Value) -> String {
+    let field = match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(values) => {
+            values.iter().map(csv_field).collect::<Vec<_>>().join(";")
+        }
+        value => value.to_string(),
+    };
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network::config::rng_secret_key;
+    use reth_primitives::ForkHash;
+
+    #[test]
+    fn escape_csv_fields() {
+        assert_eq!(csv_field(&serde_json::Value::Null), "");
+        assert_eq!(csv_field(&serde_json::json!(30303)), "30303");
+        assert_eq!(csv_field(&serde_json::json!(["eth/67", "eth/68"])), "eth/67;eth/68");
+        assert_eq!(
+            csv_field(&serde_json::json!("Geth/v1.13.14, \"x\"")),
+            "\"Geth/v1.13.14, \"\"x\"\"\""
+        );
+    }
+
+    #[test]
+    fn flatten_discv4_updates() {
+        let record =
+            NodeRecord::from_secret_key("127.0.0.1:30303".parse().unwrap(), &rng_secret_key());
+        let fork_id = ForkId { hash: ForkHash([0xdc, 0xe9, 0x6c, 0x2d]), next: 0 };
+        let nodes = DiscoveredNode::from_discv4(DiscoveryUpdate::Batch(vec![
+            DiscoveryUpdate::Added(record),
+            DiscoveryUpdate::Removed(record.id),
+            DiscoveryUpdate::EnrForkId(record, fork_id),
+        ]));
+        assert_eq!(nodes.len(), 2);
+
+        let mut crawled = CrawledNode::new(nodes.into_iter().next().unwrap());
+        assert_eq!(crawled.enr_fork_hash, None);
+        crawled.merge(DiscoveredNode {
+            record,
+            source: DiscoverySource::Discv5,
+            enr: None,
+            fork_id: Some(fork_id),
+        });
+        assert_eq!(crawled.enr_fork_hash.as_deref(), Some("0xdce96c2d"));
+        assert_eq!(
+            crawled.sources,
+            BTreeSet::from([DiscoverySource::Discv4, DiscoverySource::Discv5])
+        );
+    }
+}
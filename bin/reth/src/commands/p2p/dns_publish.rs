@@ -0,0 +1,177 @@
+//! Command that crawls the network and publishes the discovered nodes as an EIP-1459 DNS tree.
+
+use super::enr_fork_id;
+use crate::args::{get_secret_key, utils::parse_duration_from_secs, DiscoveryArgs};
+use clap::Parser;
+use discv5::ListenConfig;
+use reth_discv4::DEFAULT_DISCOVERY_PORT;
+use reth_discv5::{enr::EnrCombinedKeyWrapper, Discv5};
+use reth_dns_discovery::{publish::DnsTree, tree::LinkEntry};
+use reth_primitives::{fs, ChainSpec, ForkHash};
+use secp256k1::SecretKey;
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, info};
+
+type Enr = enr::Enr<SecretKey>;
+
+/// The arguments for the `reth p2p dns-publish` command
+///
+/// Nodes are crawled via discv5 only: leaves of the tree must be the signed records of the nodes,
+/// which discv4 does not expose for discovered peers.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The domain the tree is published under, e.g. `all.mainnet.example.org`.
+    #[arg(long, value_name = "DOMAIN")]
+    domain: String,
+
+    /// Path to the secret key the tree root is signed with.
+    ///
+    /// If no file exists at the path, a new key is generated and stored there.
+    #[arg(long, value_name = "PATH")]
+    signing_key: PathBuf,
+
+    /// Sequence number of the tree.
+    ///
+    /// Must be increased with every update of a published tree. Defaults to the current unix
+    /// timestamp.
+    #[arg(long, value_name = "SEQ")]
+    sequence: Option<u64>,
+
+    /// How long to crawl the network for nodes, in seconds.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value = "60",
+        value_parser = parse_duration_from_secs
+    )]
+    crawl_duration: Duration,
+
+    /// Links to other trees to include, e.g. `enrtree://<key>@<domain>`.
+    #[arg(long = "link", value_name = "ENRTREE")]
+    links: Vec<LinkEntry>,
+
+    /// Path to write the zone file to.
+    ///
+    /// If not set, the zone file is written to stdout, which also receives the log output unless
+    /// it is silenced with `--quiet`.
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `p2p dns-publish` command
+    pub async fn execute(
+        &self,
+        chain: Arc<ChainSpec>,
+        discovery: &DiscoveryArgs,
+        secret_key: SecretKey,
+    ) -> eyre::Result<()> {
+        let signing_key = get_secret_key(&self.signing_key)?;
+
+        let records = self.crawl(&chain, discovery, secret_key).await?;
+        info!(target: "reth::cli", count = records.len(), "Crawled nodes");
+
+        let sequence = match self.sequence {
+            Some(sequence) => sequence,
+            None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        };
+        let tree = DnsTree::new(sequence, records, self.links.clone(), &signing_key);
+        let zone = tree.to_zone_file(&self.domain);
+
+        match &self.output {
+            Some(path) => {
+                fs::write(path, zone)?;
+                info!(target: "reth::cli", path = %path.display(), "Wrote zone file");
+            }
+            None => print!("{zone}"),
+        }
+        // stdout is reserved for the zone file
+        eprintln!("Tree link: {}", tree.link(self.domain.trim_end_matches('.')));
+
+        Ok(())
+    }
+
+    /// Crawls the network via discv5 and returns the records of all discovered nodes of the chain
+    /// that accept RLPx connections.
+    async fn crawl(
+        &self,
+        chain: &ChainSpec,
+        discovery: &DiscoveryArgs,
+        secret_key: SecretKey,
+    ) -> eyre::Result<Vec<Enr>> {
+        let DiscoveryArgs { discv5_addr, discv5_port, .. } = *discovery;
+        // the crawler doesn't accept RLPx connections, its record advertises the default RLPx port
+        let config = reth_discv5::Config::builder(DEFAULT_DISCOVERY_PORT)
+            .discv5_config(
+                discv5::ConfigBuilder::new(ListenConfig::from(Into::<SocketAddr>::into((
+                    discv5_addr,
+                    discv5_port,
+                ))))
+                .build(),
+            )
+            .add_unsigned_boot_nodes(chain.bootnodes().unwrap_or_default().into_iter())
+            .build();
+        let (discv5, mut events, _) = Discv5::start(&secret_key, config)
+            .await
+            .map_err(|err| eyre::eyre!("failed to start discv5: {err:?}"))?;
+
+        // fork hashes of all forks of the chain, used to filter out nodes of other networks
+        let fork_hashes = chain
+            .hardforks()
+            .keys()
+            .filter_map(|fork| chain.hardfork_fork_id(*fork))
+            .map(|fork_id| fork_id.hash)
+            .collect::<HashSet<_>>();
+
+        let mut records = HashMap::new();
+        let mut on_record = |enr: discv5::Enr| {
+            let enr: Enr = EnrCombinedKeyWrapper(enr).into();
+            if !is_chain_node(&enr, &fork_hashes) {
+                return
+            }
+            let id = enr.node_id();
+            match records.get(&id) {
+                Some(existing) if existing.seq() >= enr.seq() => {}
+                _ => {
+                    records.insert(id, enr);
+                }
+            }
+        };
+
+        info!(target: "reth::cli", duration = ?self.crawl_duration, "Crawling network");
+        let deadline = tokio::time::sleep(self.crawl_duration);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                event = events.recv() => match event {
+                    Some(discv5::Event::Discovered(enr)) |
+                    Some(discv5::Event::SessionEstablished(enr, _)) => on_record(enr),
+                    Some(event) => debug!(target: "reth::cli", ?event, "Ignoring discv5 event"),
+                    None => break,
+                }
+            }
+        }
+
+        for enr in discv5.with_discv5(|discv5| discv5.table_entries_enr()) {
+            on_record(enr);
+        }
+
+        Ok(records.into_values().collect())
+    }
+}
+
+/// Returns true if the node accepts RLPx connections and advertises a fork of the chain in its
+/// `eth` entry.
+fn is_chain_node(enr: &Enr, fork_hashes: &HashSet<ForkHash>) -> bool {
+    if enr.tcp4().is_none() && enr.tcp6().is_none() {
+        return false
+    }
+    enr_fork_id(enr).map_or(false, |fork_id| fork_hashes.contains(&fork_id.hash))
+}
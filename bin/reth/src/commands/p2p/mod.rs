@@ -9,17 +9,22 @@ use crate::{
     dirs::{DataDirPath, MaybePlatformPath},
     utils::get_single_header,
 };
+use alloy_rlp::Decodable;
 use backon::{ConstantBuilder, Retryable};
 use clap::{Parser, Subcommand};
 use discv5::ListenConfig;
 use reth_config::Config;
 use reth_db::create_db;
-use reth_discv4::NatResolver;
+use reth_discv4::{EnrForkIdEntry, NatResolver};
 use reth_interfaces::p2p::bodies::client::BodiesClient;
-use reth_primitives::{BlockHashOrNumber, ChainSpec, NodeRecord};
+use reth_primitives::{BlockHashOrNumber, ChainSpec, ForkId, NodeRecord};
 use reth_provider::ProviderFactory;
+use secp256k1::SecretKey;
 use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
+mod crawl;
+mod dns_publish;
+
 /// `reth p2p` command
 #[derive(Debug, Parser)]
 pub struct Command {
@@ -96,15 +101,34 @@ pub enum Subcommands {
         #[arg(value_parser = hash_or_num_value_parser)]
         id: BlockHashOrNumber,
     },
+    /// Crawl the network and write an inventory of all reachable nodes
+    Crawl(crawl::Command),
+    /// Crawl the network and publish the discovered nodes as an EIP-1459 DNS tree
+    DnsPublish(dns_publish::Command),
 }
 impl Command {
     /// Execute `p2p` command
     pub async fn execute(&self) -> eyre::Result<()> {
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+
+        let default_secret_key_path = data_dir.p2p_secret_path();
+        let secret_key_path = self.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
+        let p2p_secret_key = get_secret_key(&secret_key_path)?;
+
+        match &self.command {
+            Subcommands::Crawl(command) => {
+                return command.execute(self.chain.clone(), &self.discovery, p2p_secret_key).await
+            }
+            Subcommands::DnsPublish(command) => {
+                return command.execute(self.chain.clone(), &self.discovery, p2p_secret_key).await
+            }
+            _ => {}
+        }
+
         let tempdir = tempfile::TempDir::new()?;
         let noop_db = Arc::new(create_db(tempdir.into_path(), self.db.database_args())?);
 
-        // add network name to data dir
-        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
         let config_path = self.config.clone().unwrap_or_else(|| data_dir.config_path());
 
         let mut config: Config = confy::load_path(&config_path).unwrap_or_default();
@@ -119,10 +143,6 @@ impl Command {
 
         config.peers.trusted_nodes_only = self.trusted_only;
 
-        let default_secret_key_path = data_dir.p2p_secret_path();
-        let secret_key_path = self.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
-        let p2p_secret_key = get_secret_key(&secret_key_path)?;
-
         let mut network_config_builder = config
             .network_config(self.nat, None, p2p_secret_key)
             .chain_spec(self.chain.clone())
@@ -196,8 +216,22 @@ impl Command {
                 let body = result.into_iter().next().unwrap();
                 println!("Successfully downloaded body: {body:?}")
             }
+            Subcommands::Crawl(_) | Subcommands::DnsPublish(_) => {
+                unreachable!("handled before the network is started")
+            }
         }
 
         Ok(())
     }
 }
+
+/// Decodes the [ForkId] advertised in the `eth` entry of the record.
+fn enr_fork_id(enr: &enr::Enr<SecretKey>) -> Option<ForkId> {
+    let eth = enr.get(b"eth")?;
+
+    // the entry is `[[hash, next], ...]`, but some clients advertise the bare fork id
+    EnrForkIdEntry::decode(&mut &eth[..])
+        .map(|entry| entry.fork_id)
+        .or_else(|_| ForkId::decode(&mut &eth[..]))
+        .ok()
+}
@@ -12,7 +12,7 @@ use reth_db::{
 use reth_node_core::{args::DatabaseArgs, init::init_genesis};
 use reth_primitives::ChainSpec;
 use reth_provider::{BlockNumReader, HeaderProvider, ProviderError, ProviderFactory};
-use reth_trie::StateRoot;
+use reth_trie_parallel::tx_root::TxParallelStateRoot;
 use std::{fs, sync::Arc};
 use tracing::*;
 
@@ -81,7 +81,9 @@ impl Command {
             entry = storage_trie_cursor.next()?;
         }
 
-        let state_root = StateRoot::from_tx(tx_mut).root()?;
+        // the deletions are not committed yet, but the storage roots are only computed for
+        // existing accounts, whose storage tries are left untouched
+        let state_root = TxParallelStateRoot::from_tx(tx_mut, &factory).root()?;
         if state_root != best_header.state_root {
             eyre::bail!(
                 "Recovery failed. Incorrect state root. Expected: {:?}. Received: {:?}",
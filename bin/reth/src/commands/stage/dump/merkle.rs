@@ -10,8 +10,9 @@ use reth_primitives::{stage::StageCheckpoint, BlockNumber, PruneModes};
 use reth_provider::ProviderFactory;
 use reth_stages::{
     stages::{
-        AccountHashingStage, ExecutionStage, ExecutionStageThresholds, MerkleStage,
-        StorageHashingStage, MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
+        AccountHashingStage, ExecutionStage, ExecutionStageThresholds, MerkleParallelism,
+        MerkleStage, StorageHashingStage, MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
+        MERKLE_STAGE_DEFAULT_PARALLEL_BATCH_SIZE,
     },
     Stage, UnwindInput,
 };
@@ -150,6 +151,10 @@ async fn dry_run<DB: Database>(
     let mut stage = MerkleStage::Execution {
         // Forces updating the root instead of calculating from scratch
         clean_threshold: u64::MAX,
+        parallel: Some(MerkleParallelism {
+            provider: output_provider_factory.clone(),
+            batch_size: MERKLE_STAGE_DEFAULT_PARALLEL_BATCH_SIZE,
+        }),
     };
 
     loop {
@@ -0,0 +1,130 @@
+//! Command that replays a transaction pool recording.
+
+use crate::args::TxPoolArgs;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use human_bytes::human_bytes;
+use reth_node_core::cli::config::RethTransactionPoolConfig;
+use reth_primitives::U256;
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore,
+    replay::{replay, PoolRecording, ReplayValidator, ReplayValidatorConfig},
+    EthPooledTransaction, Pool,
+};
+use std::path::PathBuf;
+use tracing::info;
+
+/// `reth txpool replay` command
+///
+/// Replays a recording captured with `--txpool.record` against a fresh transaction pool,
+/// configured by the transaction pool arguments of this command.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the recording.
+    #[arg(value_name = "PATH")]
+    path: PathBuf,
+
+    /// Balance of senders whose balance isn't known from a recorded block update.
+    ///
+    /// Defaults to an unlimited balance.
+    #[arg(long, value_name = "WEI")]
+    default_balance: Option<U256>,
+
+    /// Reject transactions with a gas limit above this value.
+    #[arg(long, value_name = "GAS")]
+    block_gas_limit: Option<u64>,
+
+    /// Only print every n-th block update.
+    #[arg(long, default_value_t = 1, value_name = "BLOCKS")]
+    report_interval: usize,
+
+    #[command(flatten)]
+    txpool: TxPoolArgs,
+}
+
+impl Command {
+    /// Execute `txpool replay` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let events = PoolRecording::new(self.path.clone()).read()?;
+        info!(target: "reth::cli", path = ?self.path, events = events.len(), "Replaying transaction pool recording");
+
+        let config = self.txpool.pool_config();
+        let validator = ReplayValidator::<EthPooledTransaction>::new(ReplayValidatorConfig {
+            default_balance: self.default_balance.unwrap_or(U256::MAX),
+            block_gas_limit: self.block_gas_limit,
+        });
+        let ordering = config.ordering.ordering();
+        let pool = Pool::new(validator, ordering, InMemoryBlobStore::default(), config);
+
+        let report = replay(&pool, events).await;
+
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header([
+            "Block",
+            "Pending",
+            "BaseFee",
+            "Blob",
+            "Queued",
+            "Size",
+            "Promoted",
+            "Discarded",
+            "Evicted",
+            "Best Txs",
+            "Best Txs/s",
+        ]);
+        for block in report.blocks.iter().step_by(self.report_interval.max(1)) {
+            let size = block.size;
+            let mut row = Row::new();
+            row.add_cell(Cell::new(block.number))
+                .add_cell(Cell::new(size.pending))
+                .add_cell(Cell::new(size.basefee))
+                .add_cell(Cell::new(size.blob))
+                .add_cell(Cell::new(size.queued))
+                .add_cell(Cell::new(human_bytes(
+                    (size.pending_size + size.basefee_size + size.blob_size + size.queued_size)
+                        as f64,
+                )))
+                .add_cell(Cell::new(block.promoted))
+                .add_cell(Cell::new(block.discarded))
+                .add_cell(Cell::new(block.evicted))
+                .add_cell(Cell::new(block.best_transactions))
+                .add_cell(Cell::new(format!("{:.0}", block.best_transactions_per_second())));
+            table.add_row(row);
+        }
+        println!("{table}");
+        println!();
+
+        println!("Replayed {} block updates in {:?}", report.blocks.len(), report.elapsed);
+        println!("Inserted transactions: {}", report.inserted);
+        println!("Rejected transactions: {}", report.rejected);
+        println!("Promoted transactions: {}", report.promoted);
+        println!("Discarded transactions: {}", report.discarded);
+        println!("Evicted transactions: {}", report.evicted);
+        println!("Replaced transactions: {}", report.replaced);
+        println!("Mined transactions: {}", report.mined);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_replay_command() {
+        let cmd = Command::parse_from([
+            "reth",
+            "recording.rlp",
+            "--block-gas-limit",
+            "30000000",
+            "--txpool.pending-max-count",
+            "100",
+        ]);
+        assert_eq!(cmd.path, PathBuf::from("recording.rlp"));
+        assert_eq!(cmd.block_gas_limit, Some(30_000_000));
+        assert_eq!(cmd.txpool.pending_max_count, 100);
+        assert_eq!(cmd.default_balance, None);
+    }
+}
@@ -1,7 +1,9 @@
 //! Configuration files.
 
 use reth_discv4::Discv4Config;
-use reth_network::{NetworkConfigBuilder, PeersConfig, SessionsConfig};
+use reth_network::{
+    bandwidth::ServeLimits, EthRequestsConfig, NetworkConfigBuilder, PeersConfig, SessionsConfig,
+};
 use reth_primitives::PruneModes;
 use secp256k1::SecretKey;
 use serde::{Deserialize, Deserializer, Serialize};
@@ -24,6 +26,10 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for serving `eth` requests of peers.
+    pub eth_requests: EthRequestsConfig,
+    /// Configuration for serving pooled transactions to peers.
+    pub transactions: TransactionsConfig,
 }
 
 impl Config {
@@ -45,10 +51,19 @@ impl Config {
         NetworkConfigBuilder::new(secret_key)
             .sessions_config(self.sessions.clone())
             .peer_config(peer_config)
+            .eth_requests_config(self.eth_requests.clone())
             .discovery(discv4)
     }
 }
 
+/// Configuration for serving pooled transactions to peers.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct TransactionsConfig {
+    /// Limits on the bandwidth used for serving `PooledTransactions` responses.
+    pub serve_limits: ServeLimits,
+}
+
 /// Configuration for each stage in the pipeline.
 #[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
@@ -218,11 +233,15 @@ pub struct MerkleConfig {
     /// The threshold (in number of blocks) for switching from incremental trie building of changes
     /// to whole rebuild.
     pub clean_threshold: u64,
+    /// Whether storage roots are computed in parallel.
+    pub parallel: bool,
+    /// The number of accounts for which storage roots are computed in parallel at once.
+    pub parallel_batch_size: usize,
 }
 
 impl Default for MerkleConfig {
     fn default() -> Self {
-        Self { clean_threshold: 5_000 }
+        Self { clean_threshold: 5_000, parallel: true, parallel_batch_size: 1_000 }
     }
 }
 
@@ -672,6 +691,18 @@ nanos = 0
         let _conf: Config = toml::from_str(alpha_0_0_19).unwrap();
     }
 
+    #[test]
+    fn test_transactions_serve_limits() {
+        let serve_limits = r"#
+[transactions.serve_limits.total]
+bytes_per_sec = 1048576
+burst = 2097152
+#";
+        let conf: Config = toml::from_str(serve_limits).unwrap();
+        assert_eq!(conf.transactions.serve_limits.total.unwrap().bytes_per_sec, 1048576);
+        assert_eq!(conf.transactions.serve_limits.per_peer, None);
+    }
+
     #[test]
     fn test_conf_trust_nodes_only() {
         let trusted_nodes_only = r"#
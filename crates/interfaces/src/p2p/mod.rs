@@ -24,3 +24,6 @@ pub mod error;
 
 /// Priority enum for BlockHeader and BlockBody requests
 pub mod priority;
+
+/// Traits for implementing `snap` protocol clients.
+pub mod snap;
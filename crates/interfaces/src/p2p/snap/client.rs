@@ -0,0 +1,29 @@
+use crate::p2p::{download::DownloadClient, error::PeerRequestResult};
+use futures::Future;
+use std::pin::Pin;
+
+pub use reth_eth_wire_types::snap::{
+    AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+    GetTrieNodes, SlimAccount, StorageData, StorageRanges, TrieNodePathSet, TrieNodes,
+};
+
+/// The future type of `snap` requests.
+pub type SnapFut<T> = Pin<Box<dyn Future<Output = PeerRequestResult<T>> + Send + Sync>>;
+
+/// A client capable of downloading state from peers that support the `snap` protocol.
+///
+/// The request ids of the requests are assigned by the client.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait SnapClient: DownloadClient {
+    /// Fetches a range of accounts of the state trie.
+    fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange>;
+
+    /// Fetches ranges of storage slots of the storage tries of the given accounts.
+    fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges>;
+
+    /// Fetches contract bytecodes by hash.
+    fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes>;
+
+    /// Fetches trie nodes of the state trie or storage tries by path.
+    fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes>;
+}
@@ -0,0 +1,2 @@
+/// Traits for `snap` protocol clients.
+pub mod client;
@@ -1,7 +1,14 @@
 //! Support for banning peers.
 
 use reth_primitives::PeerId;
-use std::{collections::HashMap, net::IpAddr, time::Instant};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr},
+    num::ParseIntError,
+    str::FromStr,
+    time::Instant,
+};
 
 /// Determines whether or not the IP is globally routable.
 /// Should be replaced with [`IpAddr::is_global`](std::net::IpAddr::is_global) once it is stable.
@@ -16,6 +23,103 @@ pub fn is_global(ip: &IpAddr) -> bool {
     }
 }
 
+/// A range of ip addresses, written in CIDR notation, e.g. `10.0.0.0/8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpRange {
+    /// The first address of the range.
+    addr: IpAddr,
+    /// Number of leading bits all addresses of the range share.
+    prefix_len: u8,
+}
+
+impl IpRange {
+    /// Creates the range of all addresses that share the first `prefix_len` bits with `addr`.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, IpRangeError> {
+        let addr = match addr {
+            IpAddr::V4(ip) if prefix_len <= 32 => {
+                let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or_default();
+                IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+            }
+            IpAddr::V6(ip) if prefix_len <= 128 => {
+                let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or_default();
+                IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+            }
+            _ => return Err(IpRangeError::InvalidPrefixLen(prefix_len)),
+        };
+        Ok(Self { addr, prefix_len })
+    }
+
+    /// Returns the first address of the range.
+    pub const fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Returns the number of leading bits all addresses of the range share.
+    pub const fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns true if the ip address is part of the range.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        Self::new(*ip, self.prefix_len).is_ok_and(|range| range.addr == self.addr)
+    }
+}
+
+impl From<IpAddr> for IpRange {
+    /// Returns the range that only contains the given address.
+    fn from(addr: IpAddr) -> Self {
+        let prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        Self { addr, prefix_len }
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = IpRangeError;
+
+    /// Parses a range in CIDR notation, or a single ip address.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((addr, prefix_len)) => Self::new(addr.parse()?, prefix_len.parse()?),
+            None => Ok(Self::from(s.parse::<IpAddr>()?)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for IpRange {
+    /// Serializes the range in CIDR notation.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for IpRange {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Errors when parsing an [`IpRange`].
+#[derive(Debug, thiserror::Error)]
+pub enum IpRangeError {
+    /// The address of the range is invalid.
+    #[error(transparent)]
+    InvalidAddr(#[from] AddrParseError),
+    /// The prefix length is not a number.
+    #[error(transparent)]
+    InvalidPrefix(#[from] ParseIntError),
+    /// The prefix length exceeds the length of the address.
+    #[error("invalid prefix length: {0}")]
+    InvalidPrefixLen(u8),
+}
+
 /// Stores peers that should be taken out of circulation either indefinitely or until a certain
 /// timestamp
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -24,6 +128,8 @@ pub struct BanList {
     banned_ips: HashMap<IpAddr, Option<Instant>>,
     /// A set of [`PeerId`] whose packets get dropped instantly.
     banned_peers: HashMap<PeerId, Option<Instant>>,
+    /// A set of ip ranges whose packets get dropped instantly.
+    banned_ip_ranges: HashMap<IpRange, Option<Instant>>,
 }
 
 impl BanList {
@@ -43,7 +149,7 @@ impl BanList {
         banned_peers: HashMap<PeerId, Option<Instant>>,
         banned_ips: HashMap<IpAddr, Option<Instant>>,
     ) -> Self {
-        Self { banned_ips, banned_peers }
+        Self { banned_ips, banned_peers, banned_ip_ranges: HashMap::new() }
     }
 
     /// Removes all peers that are no longer banned.
@@ -76,10 +182,26 @@ impl BanList {
         evicted
     }
 
+    /// Removes all ip ranges that are no longer banned.
+    pub fn evict_ip_ranges(&mut self, now: Instant) -> Vec<IpRange> {
+        let mut evicted = Vec::new();
+        self.banned_ip_ranges.retain(|range, until| {
+            if let Some(until) = until {
+                if now > *until {
+                    evicted.push(*range);
+                    return false
+                }
+            }
+            true
+        });
+        evicted
+    }
+
     /// Removes all entries that should no longer be banned.
     ///
-    /// Returns the evicted entries.
+    /// Returns the evicted ips and peers, evicted ip ranges are not returned.
     pub fn evict(&mut self, now: Instant) -> (Vec<IpAddr>, Vec<PeerId>) {
+        self.evict_ip_ranges(now);
         let ips = self.evict_ips(now);
         let peers = self.evict_peers(now);
         (ips, peers)
@@ -91,10 +213,12 @@ impl BanList {
         self.is_banned_peer(peer_id) || self.is_banned_ip(ip)
     }
 
-    /// checks the ban list to see if it contains the given ip
+    /// checks the ban list to see if it contains the given ip, either directly or as part of a
+    /// banned ip range
     #[inline]
     pub fn is_banned_ip(&self, ip: &IpAddr) -> bool {
-        self.banned_ips.contains_key(ip)
+        self.banned_ips.contains_key(ip) ||
+            self.banned_ip_ranges.keys().any(|range| range.contains(ip))
     }
 
     /// checks the ban list to see if it contains the given ip
@@ -103,6 +227,33 @@ impl BanList {
         self.banned_peers.contains_key(peer_id)
     }
 
+    /// Returns the timestamp until which the peer is banned.
+    ///
+    /// Returns `None` if the peer is not banned or banned indefinitely.
+    pub fn peer_banned_until(&self, peer_id: &PeerId) -> Option<Instant> {
+        self.banned_peers.get(peer_id).copied().flatten()
+    }
+
+    /// Returns all banned peers, with the timestamp until which they are banned.
+    pub fn banned_peers(&self) -> impl Iterator<Item = (PeerId, Option<Instant>)> + '_ {
+        self.banned_peers.iter().map(|(peer_id, until)| (*peer_id, *until))
+    }
+
+    /// Returns all banned ip addresses, with the timestamp until which they are banned.
+    pub fn banned_ips(&self) -> impl Iterator<Item = (IpAddr, Option<Instant>)> + '_ {
+        self.banned_ips.iter().map(|(ip, until)| (*ip, *until))
+    }
+
+    /// Returns all banned ip ranges, with the timestamp until which they are banned.
+    pub fn banned_ip_ranges(&self) -> impl Iterator<Item = (IpRange, Option<Instant>)> + '_ {
+        self.banned_ip_ranges.iter().map(|(range, until)| (*range, *until))
+    }
+
+    /// Unbans the ip range
+    pub fn unban_ip_range(&mut self, range: &IpRange) {
+        self.banned_ip_ranges.remove(range);
+    }
+
     /// Unbans the ip address
     pub fn unban_ip(&mut self, ip: &IpAddr) {
         self.banned_ips.remove(ip);
@@ -142,6 +293,13 @@ impl BanList {
         self.banned_peers.insert(node_id, until);
     }
 
+    /// Bans the ip range indefinitely or until the given timeout.
+    ///
+    /// Unlike single IPs, ranges of non-global IPs can be banned.
+    pub fn ban_ip_range_with(&mut self, range: IpRange, until: Option<Instant>) {
+        self.banned_ip_ranges.insert(range, until);
+    }
+
     /// Bans the ip indefinitely or until the given timeout.
     ///
     /// This does not ban non-global IPs.
@@ -166,6 +324,18 @@ mod tests {
         assert!(!banlist.is_banned_peer(&peer));
     }
 
+    #[test]
+    fn ban_expiry() {
+        let peer = PeerId::random();
+        let until = Instant::now();
+        let mut banlist = BanList::default();
+        assert_eq!(banlist.peer_banned_until(&peer), None);
+        banlist.ban_peer_until(peer, until);
+        assert_eq!(banlist.peer_banned_until(&peer), Some(until));
+        banlist.ban_peer(peer);
+        assert_eq!(banlist.peer_banned_until(&peer), None);
+    }
+
     #[test]
     fn can_ban_unban_ip() {
         let ip = IpAddr::from([1, 1, 1, 1]);
@@ -176,6 +346,42 @@ mod tests {
         assert!(!banlist.is_banned_ip(&ip));
     }
 
+    #[test]
+    fn can_ban_unban_ip_range() {
+        let range: IpRange = "1.1.0.0/16".parse().unwrap();
+        let mut banlist = BanList::default();
+        banlist.ban_ip_range_with(range, None);
+        assert!(banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 2, 1, 1])));
+        assert_eq!(banlist.banned_ip_ranges().collect::<Vec<_>>(), vec![(range, None)]);
+
+        banlist.ban_ip_range_with(range, Some(Instant::now()));
+        assert_eq!(banlist.evict_ip_ranges(Instant::now()), vec![range]);
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+    }
+
+    #[test]
+    fn parse_ip_range() {
+        let range: IpRange = "10.1.2.3/8".parse().unwrap();
+        assert_eq!(range.addr(), IpAddr::from([10, 0, 0, 0]));
+        assert_eq!(range.to_string(), "10.0.0.0/8");
+        assert!(range.contains(&IpAddr::from([10, 255, 0, 1])));
+        assert!(!range.contains(&IpAddr::from([11, 0, 0, 0])));
+        assert!(!range.contains(&"::1".parse().unwrap()));
+
+        let single: IpRange = "1.1.1.1".parse().unwrap();
+        assert_eq!(single.to_string(), "1.1.1.1/32");
+        assert!(single.contains(&IpAddr::from([1, 1, 1, 1])));
+        assert!(!single.contains(&IpAddr::from([1, 1, 1, 2])));
+
+        let all: IpRange = "::/0".parse().unwrap();
+        assert!(all.contains(&"2001:db8::1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpRange>().is_err());
+        assert!("10.0.0.0/x".parse::<IpRange>().is_err());
+        assert!("10.0.0/8".parse::<IpRange>().is_err());
+    }
+
     #[test]
     fn cannot_ban_non_global() {
         let mut ip = IpAddr::from([0, 0, 0, 0]);
@@ -119,10 +119,91 @@ impl Rate {
     }
 }
 
+/// A token bucket that refills continuously at a fixed rate, up to its capacity.
+///
+/// Unlike [RateLimit], which limits the number of calls per period, this limits a quantity, e.g.
+/// bytes, where a single call can consume an arbitrary amount. The bucket starts full, so up to
+/// `capacity` tokens can be consumed in a burst.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    /// The maximum number of tokens the bucket holds.
+    capacity: u64,
+    /// The number of tokens added per second.
+    refill_per_sec: u64,
+    /// The currently available tokens.
+    tokens: f64,
+    /// The last time the bucket was refilled.
+    last_refill: std::time::Instant,
+}
+
+// === impl TokenBucket ===
+
+impl TokenBucket {
+    /// Creates a new, full bucket that refills at `refill_per_sec` tokens per second and holds at
+    /// most `capacity` tokens.
+    pub fn new(refill_per_sec: u64, capacity: u64) -> Self {
+        Self {
+            capacity,
+            refill_per_sec,
+            tokens: capacity as f64,
+            last_refill: std::time::Instant::now(),
+        }
+    }
+
+    /// Returns the maximum number of tokens the bucket holds.
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Returns the number of tokens that are currently available.
+    pub fn available(&mut self) -> u64 {
+        self.refill(std::time::Instant::now());
+        self.tokens as u64
+    }
+
+    /// Returns true if the bucket is refilled to its capacity.
+    pub fn is_full(&mut self) -> bool {
+        self.available() >= self.capacity
+    }
+
+    /// Removes the given number of tokens from the bucket.
+    ///
+    /// This saturates at zero, so consuming more than is available drains the bucket.
+    pub fn consume(&mut self, tokens: u64) {
+        self.refill(std::time::Instant::now());
+        self.tokens = (self.tokens - tokens as f64).max(0.);
+    }
+
+    fn refill(&mut self, now: std::time::Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.refill_per_sec as f64)
+            .min(self.capacity as f64);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_token_bucket() {
+        let mut bucket = TokenBucket::new(1_000, 2_000);
+        assert_eq!(bucket.available(), 2_000);
+
+        bucket.consume(1_500);
+        assert!(bucket.available() < 600);
+        bucket.consume(1_000);
+        assert_eq!(bucket.tokens, 0.);
+
+        let start = bucket.last_refill;
+        bucket.refill(start + Duration::from_millis(500));
+        assert_eq!(bucket.tokens as u64, 500);
+
+        bucket.refill(start + Duration::from_secs(10));
+        assert!(bucket.is_full());
+    }
+
     #[tokio::test]
     async fn test_rate_limit() {
         let mut limit = RateLimit::new(Rate::new(2, Duration::from_millis(500)));
@@ -147,6 +147,14 @@ impl Discv5 {
         self.discv5.ban_ip(ip, None);
     }
 
+    /// Sets the socket advertised for discovery (UDP) or `RLPx` (TCP) in the local
+    /// [`Enr`](discv5::Enr), e.g. the external address of a port mapping.
+    ///
+    /// Returns `false` if the ENR could not be updated.
+    pub fn set_external_socket(&self, socket: SocketAddr, is_tcp: bool) -> bool {
+        self.discv5.update_local_enr_socket(socket, is_tcp)
+    }
+
     /// Returns the [`NodeRecord`] of the local node.
     ///
     /// This includes the currently tracked external IP address of the node.
@@ -0,0 +1,293 @@
+//! Publishing of [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) node lists.
+//!
+//! A [DnsTree] is built from a set of node records and links to other trees. The records are
+//! arranged in a merkle tree of branch entries, each child being addressed by the base32 encoded
+//! hash of its text, and the resulting root entry is signed with the tree's secret key.
+//!
+//! A single record or link is not wrapped in a branch, the root references it directly.
+
+use crate::{
+    resolver::MapResolver,
+    tree::{BranchEntry, LinkEntry, NodeEntry, TreeRootEntry},
+};
+use data_encoding::BASE32_NOPAD;
+use enr::{Enr, EnrKey};
+use reth_primitives::keccak256;
+use secp256k1::{PublicKey, SecretKey};
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashSet},
+    fmt::Write,
+};
+
+/// Size of an encoded child hash, including the separating comma.
+const HASH_ABBREV_SIZE: usize = 1 + 16 * 13 / 8;
+
+/// Maximum number of children of a branch entry, chosen so that the entry fits into a single
+/// TXT record.
+const MAX_CHILDREN: usize = 370 / HASH_ABBREV_SIZE;
+
+/// TTL of the root record in the zone file: 30min.
+///
+/// The root changes with every update of the tree and must be picked up by resolvers quickly.
+pub const ROOT_TTL: u32 = 30 * 60;
+
+/// TTL of all other records in the zone file: 4 weeks.
+///
+/// Entries are content addressed and never change.
+pub const ENTRY_TTL: u32 = 4 * 7 * 24 * 60 * 60;
+
+/// Maximum length of a single character-string of a TXT record.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+/// A signed EIP-1459 tree, ready to be published.
+#[derive(Debug, Clone)]
+pub struct DnsTree {
+    /// The signed root entry.
+    root: TreeRootEntry,
+    /// The public key of the key that signed the root.
+    pubkey: PublicKey,
+    /// All entries of the tree by their subdomain.
+    entries: BTreeMap<String, String>,
+}
+
+// === impl DnsTree ===
+
+impl DnsTree {
+    /// Builds a new tree from the given records and links and signs it with the given key.
+    ///
+    /// Records are deduplicated by node id, keeping the record with the highest sequence number.
+    pub fn new(
+        sequence_number: u64,
+        records: impl IntoIterator<Item = Enr<SecretKey>>,
+        links: impl IntoIterator<Item = LinkEntry>,
+        secret_key: &SecretKey,
+    ) -> Self {
+        let mut nodes = BTreeMap::new();
+        for enr in records {
+            match nodes.entry(enr.node_id().raw()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(enr);
+                }
+                Entry::Occupied(mut entry) => {
+                    if enr.seq() > entry.get().seq() {
+                        entry.insert(enr);
+                    }
+                }
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut links = links
+            .into_iter()
+            .map(|link| link.to_string())
+            .filter(|link| seen.insert(link.clone()))
+            .collect::<Vec<_>>();
+        links.sort_unstable();
+
+        let mut entries = BTreeMap::new();
+        let enr_root = build(
+            &mut entries,
+            nodes.into_values().map(|enr| NodeEntry { enr }.to_string()).collect(),
+        );
+        let enr_root = insert_entry(&mut entries, enr_root);
+        let link_root = build(&mut entries, links);
+        let link_root = insert_entry(&mut entries, link_root);
+
+        let mut root =
+            TreeRootEntry { enr_root, link_root, sequence_number, signature: Default::default() };
+        root.sign_recoverable(secret_key);
+
+        Self { root, pubkey: secret_key.public(), entries }
+    }
+
+    /// Returns the signed root entry of the tree.
+    pub fn root(&self) -> &TreeRootEntry {
+        &self.root
+    }
+
+    /// Returns the link to this tree when published under the given domain.
+    pub fn link(&self, domain: impl Into<String>) -> LinkEntry {
+        LinkEntry { domain: domain.into(), pubkey: self.pubkey }
+    }
+
+    /// Returns all entries of the tree, excluding the root, as `(subdomain, text)` pairs.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.entries.iter().map(|(hash, entry)| (hash.as_str(), entry.as_str()))
+    }
+
+    /// Returns all TXT records of the tree when published under the given domain, keyed by their
+    /// fully qualified name without the trailing dot.
+    ///
+    /// The root record is stored at the domain itself.
+    pub fn records(&self, domain: &str) -> BTreeMap<String, String> {
+        let mut records = BTreeMap::new();
+        records.insert(domain.to_string(), self.root.to_string());
+        records.extend(
+            self.entries().map(|(hash, entry)| (format!("{hash}.{domain}"), entry.to_string())),
+        );
+        records
+    }
+
+    /// Inserts all records of the tree published under the given domain into the resolver.
+    pub fn insert_into(&self, domain: &str, resolver: &MapResolver) {
+        for (name, txt) in self.records(domain) {
+            resolver.insert(name, txt);
+        }
+    }
+
+    /// Renders the tree as a zone file for the given domain.
+    ///
+    /// Records that exceed the maximum length of a TXT character-string are split into multiple
+    /// strings, which resolvers concatenate.
+    pub fn to_zone_file(&self, domain: &str) -> String {
+        let domain = domain.trim_end_matches('.');
+        let mut zone = String::new();
+        let _ = writeln!(zone, "$ORIGIN {domain}.");
+        let _ = writeln!(zone, "@ {ROOT_TTL} IN TXT {}", txt_strings(&self.root.to_string()));
+        for (hash, entry) in self.entries() {
+            let _ = writeln!(zone, "{hash} {ENTRY_TTL} IN TXT {}", txt_strings(entry));
+        }
+        zone
+    }
+}
+
+/// Arranges the given entries in a tree of branches, inserting all children into `entries`, and
+/// returns the text of the subtree's root.
+fn build(entries: &mut BTreeMap<String, String>, mut children: Vec<String>) -> String {
+    if children.len() == 1 {
+        return children.pop().expect("not empty")
+    }
+    if children.len() <= MAX_CHILDREN {
+        let children = children.into_iter().map(|child| insert_entry(entries, child)).collect();
+        return BranchEntry { children }.to_string()
+    }
+
+    let subtrees = children
+        .chunks(MAX_CHILDREN)
+        .map(|chunk| {
+            let subtree = build(entries, chunk.to_vec());
+            let _ = insert_entry(entries, subtree.clone());
+            subtree
+        })
+        .collect();
+    build(entries, subtrees)
+}
+
+/// Inserts the entry into `entries` and returns its subdomain.
+fn insert_entry(entries: &mut BTreeMap<String, String>, entry: String) -> String {
+    let hash = subdomain(&entry);
+    entries.insert(hash.clone(), entry);
+    hash
+}
+
+/// Returns the subdomain of an entry: the base32 encoded first 16 bytes of the keccak256 hash of
+/// its text.
+fn subdomain(entry: &str) -> String {
+    BASE32_NOPAD.encode(&keccak256(entry)[..16])
+}
+
+/// Formats the text as quoted TXT character-strings.
+fn txt_strings(text: &str) -> String {
+    text.as_bytes()
+        .chunks(MAX_TXT_STRING_LEN)
+        .map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tree::DnsEntry, DnsDiscoveryConfig, DnsDiscoveryEvent, DnsDiscoveryService};
+    use secp256k1::rand::thread_rng;
+    use std::{future::poll_fn, net::Ipv4Addr, num::NonZeroUsize, sync::Arc, time::Duration};
+
+    fn rng_enr(port: u16) -> Enr<SecretKey> {
+        let secret_key = SecretKey::new(&mut thread_rng());
+        Enr::builder().ip4(Ipv4Addr::LOCALHOST).udp4(port).tcp4(port).build(&secret_key).unwrap()
+    }
+
+    #[test]
+    fn build_tree_layout() {
+        let secret_key = SecretKey::new(&mut thread_rng());
+        let enrs = (0..30).map(|i| rng_enr(30303 + i)).collect::<Vec<_>>();
+        let tree = DnsTree::new(1, enrs.clone(), Vec::new(), &secret_key);
+
+        assert_eq!(tree.root().signature.len(), 65);
+        assert!(tree.root().verify::<SecretKey>(&secret_key.public()));
+
+        let mut leaves = 0;
+        let mut branches = 0;
+        for (hash, entry) in tree.entries() {
+            assert_eq!(subdomain(entry), hash);
+            match entry.parse::<DnsEntry<SecretKey>>().unwrap() {
+                DnsEntry::Node(node) => {
+                    assert!(enrs.contains(&node.enr));
+                    leaves += 1;
+                }
+                DnsEntry::Branch(branch) => {
+                    assert!(branch.children.len() <= MAX_CHILDREN);
+                    branches += 1;
+                }
+                entry => unreachable!("unexpected entry {entry}"),
+            }
+        }
+        assert_eq!(leaves, 30);
+        // enr root, 3 subtrees and the empty link root
+        assert_eq!(branches, 5);
+    }
+
+    #[test]
+    fn zone_file() {
+        let secret_key = SecretKey::new(&mut thread_rng());
+        let tree = DnsTree::new(7, vec![rng_enr(30303)], Vec::new(), &secret_key);
+        let zone = tree.to_zone_file("nodes.example.org.");
+
+        let mut lines = zone.lines();
+        assert_eq!(lines.next(), Some("$ORIGIN nodes.example.org."));
+        assert_eq!(lines.next(), Some(format!("@ 1800 IN TXT \"{}\"", tree.root()).as_str()));
+        assert_eq!(lines.count(), 2);
+
+        assert_eq!(txt_strings("abc"), "\"abc\"");
+        let long = "a".repeat(300);
+        assert_eq!(txt_strings(&long), format!("\"{}\" \"{}\"", "a".repeat(255), "a".repeat(45)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sync_published_tree() {
+        reth_tracing::init_test_tracing();
+
+        let secret_key = SecretKey::new(&mut thread_rng());
+        let other = SecretKey::new(&mut thread_rng());
+        let enrs = (0..20).map(|i| rng_enr(30303 + i)).collect::<Vec<_>>();
+        let links =
+            vec![LinkEntry { domain: "other.example.org".to_string(), pubkey: other.public() }];
+        let tree = DnsTree::new(1, enrs.clone(), links, &secret_key);
+
+        let resolver = MapResolver::default();
+        let domain = "nodes.example.org";
+        tree.insert_into(domain, &resolver);
+
+        let config = DnsDiscoveryConfig {
+            max_requests_per_sec: NonZeroUsize::new(1_000).unwrap(),
+            ..Default::default()
+        };
+        let mut service = DnsDiscoveryService::new(Arc::new(resolver), config);
+        service.sync_tree_with_link(tree.link(domain));
+
+        let mut discovered = Vec::new();
+        while discovered.len() < enrs.len() {
+            let event =
+                tokio::time::timeout(Duration::from_secs(10), poll_fn(|cx| service.poll(cx)))
+                    .await
+                    .unwrap();
+            match event {
+                DnsDiscoveryEvent::Enr(enr) => discovered.push(enr),
+            }
+        }
+
+        for enr in enrs {
+            assert!(discovered.contains(&enr));
+        }
+    }
+}
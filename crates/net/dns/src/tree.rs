@@ -23,8 +23,8 @@ use crate::error::{
 };
 use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD};
 use enr::{Enr, EnrError, EnrKey, EnrKeyUnambiguous, EnrPublicKey};
-use reth_primitives::{hex, Bytes};
-use secp256k1::SecretKey;
+use reth_primitives::{hex, keccak256, Bytes};
+use secp256k1::{SecretKey, SECP256K1};
 #[cfg(feature = "serde")]
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
@@ -141,6 +141,20 @@ impl TreeRootEntry {
         Ok(())
     }
 
+    /// Signs the content with the given secret key.
+    ///
+    /// Unlike [TreeRootEntry::sign], this produces the 65-byte recoverable signature (`[R || S ||
+    /// V]`) mandated by EIP-1459, which is what other clients expect from published trees.
+    pub fn sign_recoverable(&mut self, key: &SecretKey) {
+        let msg = secp256k1::Message::from_slice(keccak256(self.content()).as_ref())
+            .expect("B256.len() == MESSAGE_SIZE");
+        let (rec, sig) = SECP256K1.sign_ecdsa_recoverable(&msg, key).serialize_compact();
+        let mut signature = Vec::with_capacity(65);
+        signature.extend_from_slice(&sig);
+        signature.push(rec.to_i32() as u8);
+        self.signature = signature.into();
+    }
+
     /// Verify the signature of the record.
     #[must_use]
     pub fn verify<K: EnrKey>(&self, pubkey: &K::PublicKey) -> bool {
@@ -191,6 +205,9 @@ pub struct BranchEntry {
 impl BranchEntry {
     /// Parses the entry from text.
     ///
+    /// An empty value is a valid branch without children, this is what publishers emit for an
+    /// empty link subtree.
+    ///
     /// Caution: This assumes the prefix is already removed.
     fn parse_value(input: &str) -> ParseEntryResult<Self> {
         #[inline]
@@ -210,8 +227,13 @@ impl BranchEntry {
             Ok(hash.to_string())
         }
 
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(Self { children: Vec::new() })
+        }
+
         let children =
-            input.trim().split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
+            input.split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
         Ok(Self { children })
     }
 }
@@ -406,6 +428,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_empty_branch_entry() {
+        let s = "enrtree-branch:";
+        let entry: BranchEntry = s.parse().unwrap();
+        assert!(entry.children.is_empty());
+        assert_eq!(entry.to_string(), s);
+    }
+
+    #[test]
+    fn sign_recoverable_root() {
+        let secret_key = SecretKey::new(&mut secp256k1::rand::thread_rng());
+        let s = "enrtree-root:v1 e=QFT4PBCRX4XQCV3VUYJ6BTCEPU l=JGUFMSAGI7KZYB3P7IZW4S5Y3A seq=3 sig=3FmXuVwpa8Y7OstZTx9PIb1mt8FrW7VpDOFv4AaGCsZ2EIHmhraWhe4NxYhQDlw5MjeFXYMbJjsPeKlHzmJREQE";
+        let mut root: TreeRootEntry = s.parse().unwrap();
+        root.sign_recoverable(&secret_key);
+
+        assert_eq!(root.signature.len(), 65);
+        assert!(root.verify::<SecretKey>(&secret_key.public()));
+        assert_eq!(root.to_string().parse::<TreeRootEntry>().unwrap(), root);
+    }
+
     #[test]
     fn parse_invalid_branch_entry() {
         let s = "enrtree-branch:1,2";
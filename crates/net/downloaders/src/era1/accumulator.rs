@@ -0,0 +1,106 @@
+//! Epoch accumulator of pre-merge block headers.
+//!
+//! The accumulator root of an epoch is the SSZ `hash_tree_root` of
+//! `List[HeaderRecord, MAX_EPOCH_SIZE]`, where `HeaderRecord` is the container
+//! `(block_hash: Bytes32, total_difficulty: uint256)`.
+
+use reth_primitives::{B256, U256};
+use sha2::{Digest, Sha256};
+
+/// Maximum number of blocks in a single epoch.
+pub const MAX_EPOCH_SIZE: usize = 8192;
+
+/// Depth of the merkle tree of a full epoch, `log2(MAX_EPOCH_SIZE)`.
+const EPOCH_TREE_DEPTH: usize = 13;
+
+/// A single entry of the epoch accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderRecord {
+    /// Hash of the block.
+    pub block_hash: B256,
+    /// Total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+impl HeaderRecord {
+    /// Returns the SSZ `hash_tree_root` of the record.
+    pub fn tree_hash_root(&self) -> B256 {
+        hash_pair(self.block_hash, B256::from(self.total_difficulty.to_le_bytes::<32>()))
+    }
+}
+
+/// Computes the accumulator root of the given epoch.
+///
+/// # Panics
+///
+/// If more than [`MAX_EPOCH_SIZE`] records are passed.
+pub fn epoch_accumulator_root(records: &[HeaderRecord]) -> B256 {
+    assert!(records.len() <= MAX_EPOCH_SIZE, "epoch exceeds {MAX_EPOCH_SIZE} records");
+
+    let zero_hashes = zero_hashes();
+    let mut layer = records.iter().map(HeaderRecord::tree_hash_root).collect::<Vec<_>>();
+
+    let root = if layer.is_empty() {
+        zero_hashes[EPOCH_TREE_DEPTH]
+    } else {
+        for zero_hash in zero_hashes.iter().take(EPOCH_TREE_DEPTH) {
+            if layer.len() % 2 == 1 {
+                layer.push(*zero_hash);
+            }
+            layer = layer.chunks_exact(2).map(|pair| hash_pair(pair[0], pair[1])).collect();
+        }
+        layer[0]
+    };
+
+    // mix in the length of the list
+    hash_pair(root, B256::from(U256::from(records.len()).to_le_bytes::<32>()))
+}
+
+/// Returns the roots of empty subtrees for every depth of the epoch tree.
+fn zero_hashes() -> [B256; EPOCH_TREE_DEPTH + 1] {
+    let mut hashes = [B256::ZERO; EPOCH_TREE_DEPTH + 1];
+    for depth in 0..EPOCH_TREE_DEPTH {
+        hashes[depth + 1] = hash_pair(hashes[depth], hashes[depth]);
+    }
+    hashes
+}
+
+fn hash_pair(left: B256, right: B256) -> B256 {
+    B256::from_slice(&Sha256::new().chain_update(left).chain_update(right).finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_accumulator_root() {
+        // the root of an empty list is the mixed in zero length with the root of an empty tree
+        let root = epoch_accumulator_root(&[]);
+        assert_eq!(root, hash_pair(zero_hashes()[EPOCH_TREE_DEPTH], B256::ZERO));
+    }
+
+    #[test]
+    fn accumulator_root_depends_on_records() {
+        let record = |n: u8| HeaderRecord {
+            block_hash: B256::repeat_byte(n),
+            total_difficulty: U256::from(n),
+        };
+        let records = (1..=3).map(record).collect::<Vec<_>>();
+
+        let root = epoch_accumulator_root(&records);
+        assert_ne!(root, epoch_accumulator_root(&records[..2]));
+
+        let mut tampered = records.clone();
+        tampered[2].total_difficulty += U256::from(1);
+        assert_ne!(root, epoch_accumulator_root(&tampered));
+
+        // a single record is hashed with zero siblings up to the root
+        let mut expected = record(1).tree_hash_root();
+        for zero_hash in zero_hashes().iter().take(EPOCH_TREE_DEPTH) {
+            expected = hash_pair(expected, *zero_hash);
+        }
+        let expected = hash_pair(expected, B256::from(U256::from(1).to_le_bytes::<32>()));
+        assert_eq!(epoch_accumulator_root(&records[..1]), expected);
+    }
+}
@@ -0,0 +1,150 @@
+//! Low level `e2store` entry encoding used by Era1 archives.
+//!
+//! Every entry is a little endian `type (2 bytes) || length (4 bytes) || reserved (2 bytes)` header
+//! followed by `length` bytes of data. See also <https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md>.
+
+use super::Era1Error;
+use std::io::{self, Read, Write};
+
+/// Size of an `e2store` entry header in bytes.
+pub const HEADER_SIZE: usize = 8;
+
+/// Entry type of the version marker that starts every Era1 file.
+pub const VERSION: u16 = 0x3265;
+
+/// Entry type of a snappy compressed, RLP encoded header.
+pub const COMPRESSED_HEADER: u16 = 0x03;
+
+/// Entry type of a snappy compressed, RLP encoded block body.
+pub const COMPRESSED_BODY: u16 = 0x04;
+
+/// Entry type of snappy compressed, RLP encoded block receipts.
+pub const COMPRESSED_RECEIPTS: u16 = 0x05;
+
+/// Entry type of a little endian encoded total difficulty.
+pub const TOTAL_DIFFICULTY: u16 = 0x06;
+
+/// Entry type of the epoch accumulator root.
+pub const ACCUMULATOR: u16 = 0x07;
+
+/// Entry type of the block index that ends every Era1 file.
+pub const BLOCK_INDEX: u16 = 0x3266;
+
+/// A single `e2store` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The type of the entry.
+    pub entry_type: u16,
+    /// The raw data of the entry.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Creates a new entry.
+    pub fn new(entry_type: u16, data: Vec<u8>) -> Self {
+        Self { entry_type, data }
+    }
+
+    /// Creates a new entry with snappy framed compressed data.
+    pub fn compressed(entry_type: u16, data: &[u8]) -> io::Result<Self> {
+        let mut encoder = snap::write::FrameEncoder::new(Vec::with_capacity(data.len()));
+        encoder.write_all(data)?;
+        let data = encoder.into_inner().map_err(|err| err.into_error())?;
+        Ok(Self::new(entry_type, data))
+    }
+
+    /// Returns the decompressed data of an entry that was written with snappy framing.
+    pub fn decompress(&self) -> io::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(self.data.len() * 2);
+        snap::read::FrameDecoder::new(&self.data[..]).read_to_end(&mut out)?;
+        Ok(out)
+    }
+
+    /// Returns the number of bytes this entry occupies on disk, including the header.
+    pub fn encoded_len(&self) -> usize {
+        HEADER_SIZE + self.data.len()
+    }
+
+    /// Reads the next entry from the reader.
+    ///
+    /// Returns `None` if the reader is exhausted before the first byte of the entry header.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Option<Self>, Era1Error> {
+        let mut header = [0u8; HEADER_SIZE];
+        let mut filled = 0;
+        while filled < HEADER_SIZE {
+            match reader.read(&mut header[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(Era1Error::InvalidEntry("truncated entry header")),
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        if header[6] != 0 || header[7] != 0 {
+            return Err(Era1Error::InvalidEntry("reserved header bytes must be zero"))
+        }
+
+        let mut data = vec![0u8; len];
+        reader.read_exact(&mut data).map_err(|err| match err.kind() {
+            io::ErrorKind::UnexpectedEof => Era1Error::InvalidEntry("truncated entry data"),
+            _ => err.into(),
+        })?;
+
+        Ok(Some(Self { entry_type, data }))
+    }
+
+    /// Writes the entry, including its header, to the writer.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let len = u32::try_from(self.data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "e2store entry too large"))?;
+
+        let mut header = [0u8; HEADER_SIZE];
+        header[..2].copy_from_slice(&self.entry_type.to_le_bytes());
+        header[2..6].copy_from_slice(&len.to_le_bytes());
+
+        writer.write_all(&header)?;
+        writer.write_all(&self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn entry_roundtrip() {
+        let entries = vec![
+            Entry::new(VERSION, vec![]),
+            Entry::compressed(COMPRESSED_HEADER, b"some header bytes").unwrap(),
+            Entry::new(TOTAL_DIFFICULTY, vec![1; 32]),
+        ];
+
+        let mut buf = Vec::new();
+        for entry in &entries {
+            entry.write(&mut buf).unwrap();
+        }
+        assert_eq!(buf.len(), entries.iter().map(Entry::encoded_len).sum::<usize>());
+
+        let mut reader = &buf[..];
+        for entry in &entries {
+            assert_eq!(Entry::read(&mut reader).unwrap().as_ref(), Some(entry));
+        }
+        assert_eq!(Entry::read(&mut reader).unwrap(), None);
+
+        assert_eq!(entries[1].decompress().unwrap(), b"some header bytes");
+    }
+
+    #[test]
+    fn reject_truncated_entry() {
+        let mut buf = Vec::new();
+        Entry::new(ACCUMULATOR, vec![0; 32]).write(&mut buf).unwrap();
+        buf.truncate(buf.len() - 1);
+
+        assert_matches!(Entry::read(&mut &buf[..]), Err(Era1Error::InvalidEntry(_)));
+        assert_matches!(Entry::read(&mut &buf[..3]), Err(Era1Error::InvalidEntry(_)));
+    }
+}
@@ -0,0 +1,103 @@
+//! Reading and writing of Era1 history archives.
+//!
+//! An Era1 file stores up to [`MAX_EPOCH_SIZE`] consecutive pre-merge blocks together with their
+//! receipts, total difficulties and the epoch accumulator root, using the `e2store` format:
+//!
+//! ```text
+//! era1 := Version | block-tuple* | other-entries* | Accumulator | BlockIndex
+//! block-tuple := CompressedHeader | CompressedBody | CompressedReceipts | TotalDifficulty
+//! ```
+//!
+//! See also <https://github.com/ethereum/go-ethereum/blob/master/internal/era/era.go>.
+
+mod accumulator;
+pub mod e2s;
+mod reader;
+mod writer;
+
+pub use accumulator::{epoch_accumulator_root, HeaderRecord, MAX_EPOCH_SIZE};
+pub use reader::{era1_files, Era1Block, Era1Epoch, Era1Reader, Era1Receipts};
+pub use writer::{era1_file_name, Era1Writer};
+
+use reth_primitives::{BlockNumber, GotExpected, B256, U256};
+use thiserror::Error;
+
+/// File extension of Era1 archives.
+pub const ERA1_EXTENSION: &str = "era1";
+
+/// An error that can occur when reading or verifying Era1 archives.
+#[derive(Debug, Error)]
+pub enum Era1Error {
+    /// An error occurred when reading the archive.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// An error occurred when decoding RLP data of an entry.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+
+    /// An entry of the archive is malformed.
+    #[error("invalid e2store entry: {0}")]
+    InvalidEntry(&'static str),
+
+    /// An entry appeared where another entry type was expected.
+    #[error("unexpected e2store entry type {got:#06x}, expected {expected:#06x}")]
+    UnexpectedEntry {
+        /// The expected entry type.
+        expected: u16,
+        /// The entry type that was read.
+        got: u16,
+    },
+
+    /// The archive contains more blocks than an epoch can hold.
+    #[error("epoch contains more than {MAX_EPOCH_SIZE} blocks")]
+    EpochTooLarge,
+
+    /// The block index does not match the blocks of the archive.
+    #[error("block index mismatch: {0}")]
+    BlockIndexMismatch(GotExpected<u64>),
+
+    /// A block is not the child of the block before it.
+    #[error("block {0} does not extend the previous block of the epoch")]
+    NonContiguousBlock(BlockNumber),
+
+    /// The transactions root of a block does not match its body.
+    #[error("block {number} transactions root mismatch: {root}")]
+    TransactionsRootMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The mismatching roots.
+        root: GotExpected<B256>,
+    },
+
+    /// The ommers hash of a block does not match its body.
+    #[error("block {number} ommers hash mismatch: {root}")]
+    OmmersHashMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The mismatching roots.
+        root: GotExpected<B256>,
+    },
+
+    /// The receipts root of a block does not match its receipts.
+    #[error("block {number} receipts root mismatch: {root}")]
+    ReceiptsRootMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The mismatching roots.
+        root: GotExpected<B256>,
+    },
+
+    /// The total difficulty of a block is inconsistent with its difficulty.
+    #[error("block {number} total difficulty mismatch: {total_difficulty}")]
+    TotalDifficultyMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The mismatching total difficulties.
+        total_difficulty: GotExpected<U256>,
+    },
+
+    /// The accumulator root of the epoch does not match.
+    #[error("epoch accumulator root mismatch: {0}")]
+    AccumulatorRootMismatch(GotExpected<B256>),
+}
@@ -0,0 +1,618 @@
+use super::{
+    e2s::{
+        Entry, ACCUMULATOR, BLOCK_INDEX, COMPRESSED_BODY, COMPRESSED_HEADER, COMPRESSED_RECEIPTS,
+        HEADER_SIZE, TOTAL_DIFFICULTY, VERSION,
+    },
+    epoch_accumulator_root, Era1Error, HeaderRecord, ERA1_EXTENSION, MAX_EPOCH_SIZE,
+};
+use crate::file_client::FileClient;
+use alloy_rlp::Decodable;
+use reth_primitives::{
+    bytes::BufMut, proofs::ordered_trie_root_with_encoder, BlockBody, BlockNumber, GotExpected,
+    Header, Receipt, ReceiptWithBloom, SealedHeader, B256, U256,
+};
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use tracing::trace;
+
+/// A block read from an Era1 archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The sealed header of the block.
+    pub header: SealedHeader,
+    /// The body of the block.
+    pub body: BlockBody,
+    /// The receipts of the block.
+    ///
+    /// This is `None` if the receipts use the pre-Byzantium encoding with intermediate state
+    /// roots instead of a status code, which cannot be represented by [`Receipt`].
+    pub receipts: Option<Vec<Receipt>>,
+    /// The receipts root computed from the encoded receipts of the archive.
+    pub receipts_root: B256,
+    /// The total difficulty of the chain up to and including this block.
+    pub total_difficulty: U256,
+}
+
+/// The encoded receipts of a block read from an Era1 archive.
+///
+/// Unlike [`Era1Block::receipts`] this also holds pre-Byzantium receipts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Receipts {
+    /// The number of the block.
+    pub number: BlockNumber,
+    /// The receipts root computed from the encoded receipts.
+    pub receipts_root: B256,
+    /// The RLP list of the receipts of the block, as stored in the archive.
+    pub encoded: Vec<u8>,
+}
+
+/// All blocks of a single Era1 archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Epoch {
+    /// The blocks of the epoch, in ascending order.
+    pub blocks: Vec<Era1Block>,
+    /// The accumulator root stored in the archive.
+    pub accumulator_root: B256,
+}
+
+impl Era1Epoch {
+    /// Returns the number of the first block in the epoch, or `None` if the epoch is empty.
+    pub fn start_block(&self) -> Option<BlockNumber> {
+        self.blocks.first().map(|block| block.header.number)
+    }
+
+    /// Returns the number of the last block in the epoch, or `None` if the epoch is empty.
+    pub fn end_block(&self) -> Option<BlockNumber> {
+        self.blocks.last().map(|block| block.header.number)
+    }
+
+    /// Returns the index of the epoch, derived from its first block.
+    pub fn epoch(&self) -> Option<u64> {
+        self.start_block().map(|number| number / MAX_EPOCH_SIZE as u64)
+    }
+
+    /// Returns the accumulator records of the epoch.
+    pub fn header_records(&self) -> Vec<HeaderRecord> {
+        self.blocks
+            .iter()
+            .map(|block| HeaderRecord {
+                block_hash: block.header.hash(),
+                total_difficulty: block.total_difficulty,
+            })
+            .collect()
+    }
+
+    /// Verifies the integrity of the epoch.
+    ///
+    /// This checks that:
+    ///  - blocks form a chain
+    ///  - transactions root, ommers hash and receipts root of every header match the archive
+    ///  - total difficulties are consistent with the block difficulties, starting from
+    ///    `parent_total_difficulty` if known
+    ///  - the accumulator root computed from headers and total difficulties matches the one stored
+    ///    in the archive
+    pub fn verify(&self, parent_total_difficulty: Option<U256>) -> Result<(), Era1Error> {
+        let mut parent: Option<&Era1Block> = None;
+        for block in &self.blocks {
+            let number = block.header.number;
+            let header = &block.header;
+
+            let parent_td = match parent {
+                Some(parent) => {
+                    let is_child = parent.header.number + 1 == number &&
+                        parent.header.hash() == header.parent_hash;
+                    if !is_child {
+                        return Err(Era1Error::NonContiguousBlock(number))
+                    }
+                    Some(parent.total_difficulty)
+                }
+                None if number == 0 => Some(U256::ZERO),
+                None => parent_total_difficulty,
+            };
+
+            if let Some(parent_td) = parent_td {
+                let expected = parent_td + header.difficulty;
+                if block.total_difficulty != expected {
+                    return Err(Era1Error::TotalDifficultyMismatch {
+                        number,
+                        total_difficulty: GotExpected::new(block.total_difficulty, expected),
+                    })
+                }
+            }
+
+            let transactions_root = block.body.calculate_tx_root();
+            if transactions_root != header.transactions_root {
+                return Err(Era1Error::TransactionsRootMismatch {
+                    number,
+                    root: GotExpected::new(transactions_root, header.transactions_root),
+                })
+            }
+
+            let ommers_hash = block.body.calculate_ommers_root();
+            if ommers_hash != header.ommers_hash {
+                return Err(Era1Error::OmmersHashMismatch {
+                    number,
+                    root: GotExpected::new(ommers_hash, header.ommers_hash),
+                })
+            }
+
+            if block.receipts_root != header.receipts_root {
+                return Err(Era1Error::ReceiptsRootMismatch {
+                    number,
+                    root: GotExpected::new(block.receipts_root, header.receipts_root),
+                })
+            }
+
+            parent = Some(block);
+        }
+
+        let accumulator_root = epoch_accumulator_root(&self.header_records());
+        if accumulator_root != self.accumulator_root {
+            return Err(Era1Error::AccumulatorRootMismatch(GotExpected::new(
+                accumulator_root,
+                self.accumulator_root,
+            )))
+        }
+
+        Ok(())
+    }
+
+    /// Splits the epoch into a [`FileClient`] serving its headers and bodies, and the receipts of
+    /// every block in ascending order.
+    pub fn into_file_client(self) -> (FileClient, Vec<(BlockNumber, Option<Vec<Receipt>>)>) {
+        let mut receipts = Vec::with_capacity(self.blocks.len());
+        let blocks = self
+            .blocks
+            .into_iter()
+            .map(|block| {
+                receipts.push((block.header.number, block.receipts));
+                (block.header, block.body)
+            })
+            .collect::<Vec<_>>();
+
+        (FileClient::from_blocks(blocks), receipts)
+    }
+}
+
+/// Reader for a single Era1 archive.
+#[derive(Debug)]
+pub struct Era1Reader<R> {
+    reader: R,
+}
+
+impl Era1Reader<BufReader<File>> {
+    /// Opens the Era1 archive at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Era1Error> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> Era1Reader<R> {
+    /// Creates a new reader, consuming the version entry at the start of the archive.
+    pub fn new(mut reader: R) -> Result<Self, Era1Error> {
+        let version =
+            Entry::read(&mut reader)?.ok_or(Era1Error::InvalidEntry("missing version entry"))?;
+        if version.entry_type != VERSION {
+            return Err(Era1Error::UnexpectedEntry { expected: VERSION, got: version.entry_type })
+        }
+        Ok(Self { reader })
+    }
+
+    /// Reads all blocks of the archive, up to and including the block index.
+    pub fn read_epoch(mut self) -> Result<Era1Epoch, Era1Error> {
+        let mut blocks = Vec::new();
+        let mut accumulator_root = None;
+
+        loop {
+            let entry = Entry::read(&mut self.reader)?
+                .ok_or(Era1Error::InvalidEntry("missing block index"))?;
+
+            match entry.entry_type {
+                COMPRESSED_HEADER => {
+                    if accumulator_root.is_some() {
+                        return Err(Era1Error::InvalidEntry("block tuple after accumulator"))
+                    }
+                    if blocks.len() == MAX_EPOCH_SIZE {
+                        return Err(Era1Error::EpochTooLarge)
+                    }
+                    blocks.push(self.read_block(entry)?);
+                }
+                ACCUMULATOR => {
+                    if entry.data.len() != 32 {
+                        return Err(Era1Error::InvalidEntry("accumulator root must be 32 bytes"))
+                    }
+                    accumulator_root = Some(B256::from_slice(&entry.data));
+                }
+                BLOCK_INDEX => {
+                    let accumulator_root = accumulator_root
+                        .ok_or(Era1Error::InvalidEntry("missing accumulator entry"))?;
+                    let start_block = blocks.first().map(|block| block.header.number).unwrap_or(0);
+                    verify_block_index(&entry.data, start_block, blocks.len())?;
+
+                    trace!(target: "downloaders::era1",
+                        start_block,
+                        blocks = blocks.len(),
+                        "Read era1 epoch"
+                    );
+
+                    return Ok(Era1Epoch { blocks, accumulator_root })
+                }
+                // other entries are allowed but not used
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the encoded receipts of all blocks of the archive, up to and including the block
+    /// index.
+    ///
+    /// Bodies are skipped, and receipts are returned even if they use the pre-Byzantium encoding.
+    pub fn read_receipts(mut self) -> Result<Vec<Era1Receipts>, Era1Error> {
+        let mut receipts = Vec::new();
+
+        loop {
+            let entry = Entry::read(&mut self.reader)?
+                .ok_or(Era1Error::InvalidEntry("missing block index"))?;
+
+            match entry.entry_type {
+                COMPRESSED_HEADER => {
+                    if receipts.len() == MAX_EPOCH_SIZE {
+                        return Err(Era1Error::EpochTooLarge)
+                    }
+                    let number = Header::decode(&mut &entry.decompress()?[..])?.number;
+                    self.expect_entry(COMPRESSED_BODY)?;
+                    let encoded = self.expect_entry(COMPRESSED_RECEIPTS)?.decompress()?;
+                    let (receipts_root, _) = decode_receipts(&encoded)?;
+                    self.expect_entry(TOTAL_DIFFICULTY)?;
+
+                    receipts.push(Era1Receipts { number, receipts_root, encoded });
+                }
+                BLOCK_INDEX => {
+                    let start_block = receipts.first().map(|block| block.number).unwrap_or(0);
+                    verify_block_index(&entry.data, start_block, receipts.len())?;
+                    return Ok(receipts)
+                }
+                // other entries are allowed but not used
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the remaining entries of a block tuple that starts with the given header entry.
+    fn read_block(&mut self, header: Entry) -> Result<Era1Block, Era1Error> {
+        let header = Header::decode(&mut &header.decompress()?[..])?.seal_slow();
+
+        let body = self.expect_entry(COMPRESSED_BODY)?;
+        let body = BlockBody::decode(&mut &body.decompress()?[..])?;
+
+        let receipts = self.expect_entry(COMPRESSED_RECEIPTS)?;
+        let (receipts_root, receipts) = decode_receipts(&receipts.decompress()?)?;
+
+        let total_difficulty = self.expect_entry(TOTAL_DIFFICULTY)?;
+        if total_difficulty.data.len() != 32 {
+            return Err(Era1Error::InvalidEntry("total difficulty must be 32 bytes"))
+        }
+        let total_difficulty = U256::from_le_slice(&total_difficulty.data);
+
+        Ok(Era1Block { header, body, receipts, receipts_root, total_difficulty })
+    }
+
+    fn expect_entry(&mut self, expected: u16) -> Result<Entry, Era1Error> {
+        let entry = Entry::read(&mut self.reader)?
+            .ok_or(Era1Error::InvalidEntry("incomplete block tuple"))?;
+        if entry.entry_type != expected {
+            return Err(Era1Error::UnexpectedEntry { expected, got: entry.entry_type })
+        }
+        Ok(entry)
+    }
+}
+
+impl<R: Read + Seek> Era1Reader<R> {
+    /// Reads the range of blocks in the archive from the block index at the end of the archive,
+    /// without reading the blocks themselves.
+    ///
+    /// Returns `None` if the archive contains no blocks.
+    pub fn read_block_range(mut self) -> Result<Option<RangeInclusive<BlockNumber>>, Era1Error> {
+        // the block index ends with the number of blocks in the archive
+        let mut count = [0u8; 8];
+        self.reader.seek(SeekFrom::End(-8))?;
+        self.reader.read_exact(&mut count)?;
+        let count = u64::from_le_bytes(count);
+        if count > MAX_EPOCH_SIZE as u64 {
+            return Err(Era1Error::EpochTooLarge)
+        }
+
+        let index_len = HEADER_SIZE as u64 + 16 + 8 * count;
+        self.reader.seek(SeekFrom::End(-(index_len as i64)))?;
+        let entry =
+            Entry::read(&mut self.reader)?.ok_or(Era1Error::InvalidEntry("missing block index"))?;
+        if entry.entry_type != BLOCK_INDEX {
+            return Err(Era1Error::UnexpectedEntry { expected: BLOCK_INDEX, got: entry.entry_type })
+        }
+        if entry.data.len() as u64 != index_len - HEADER_SIZE as u64 {
+            return Err(Era1Error::InvalidEntry("malformed block index"))
+        }
+
+        let start_block = u64::from_le_bytes(entry.data[..8].try_into().expect("8 bytes"));
+        Ok(count.checked_sub(1).map(|last| start_block..=start_block + last))
+    }
+}
+
+/// Returns all Era1 archives in the given directory, ordered by file name.
+///
+/// Era1 file names contain the zero padded epoch index, so this is the order in which the archives
+/// must be imported.
+pub fn era1_files<P: AsRef<Path>>(dir: P) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == ERA1_EXTENSION) {
+            files.push(path);
+        }
+    }
+    files.sort_unstable();
+    Ok(files)
+}
+
+/// Checks the block index against the blocks that have been read.
+///
+/// The block index is encoded as `starting-number | index | index | ... | count`, all as little
+/// endian 64 bit integers.
+fn verify_block_index(data: &[u8], start_block: u64, count: usize) -> Result<(), Era1Error> {
+    if data.len() < 16 || data.len() % 8 != 0 {
+        return Err(Era1Error::InvalidEntry("malformed block index"))
+    }
+
+    let read_u64 = |bytes: &[u8]| u64::from_le_bytes(bytes.try_into().expect("8 bytes"));
+    let index_start = read_u64(&data[..8]);
+    let index_count = read_u64(&data[data.len() - 8..]);
+
+    if index_count != count as u64 || (data.len() - 16) / 8 != count {
+        return Err(Era1Error::BlockIndexMismatch(GotExpected::new(index_count, count as u64)))
+    }
+    if index_start != start_block {
+        return Err(Era1Error::BlockIndexMismatch(GotExpected::new(index_start, start_block)))
+    }
+
+    Ok(())
+}
+
+/// Decodes the RLP list of receipts of a block.
+///
+/// Returns the receipts root computed from the encoded receipts, and the decoded receipts unless
+/// they use the pre-Byzantium encoding.
+fn decode_receipts(data: &[u8]) -> Result<(B256, Option<Vec<Receipt>>), Era1Error> {
+    let mut buf = data;
+    let header = alloy_rlp::Header::decode(&mut buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString.into())
+    }
+    if buf.len() < header.payload_length {
+        return Err(alloy_rlp::Error::InputTooShort.into())
+    }
+    let mut payload = &buf[..header.payload_length];
+
+    // the consensus encoding of every receipt, as used in the receipts trie
+    let mut encoded = Vec::new();
+    let mut pre_byzantium = false;
+    while !payload.is_empty() {
+        let item = payload;
+        let mut rest = payload;
+        let item_header = alloy_rlp::Header::decode(&mut rest)?;
+        if rest.len() < item_header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort.into())
+        }
+        let item_len = item.len() - rest.len() + item_header.payload_length;
+
+        if item_header.list {
+            // legacy receipt, the first field is either the status or the post state root
+            let mut fields = rest;
+            let first = alloy_rlp::Header::decode(&mut fields)?;
+            pre_byzantium |= !first.list && first.payload_length == 32;
+            encoded.push(&item[..item_len]);
+        } else {
+            // typed receipts are encoded as a string of `type || rlp(receipt)`
+            encoded.push(&rest[..item_header.payload_length]);
+        }
+
+        payload = &item[item_len..];
+    }
+
+    let receipts_root =
+        ordered_trie_root_with_encoder(&encoded, |receipt, buf| buf.put_slice(receipt));
+    if pre_byzantium {
+        return Ok((receipts_root, None))
+    }
+
+    let receipts = Vec::<ReceiptWithBloom>::decode(&mut &data[..])?
+        .into_iter()
+        .map(ReceiptWithBloom::into_receipt)
+        .collect();
+
+    Ok((receipts_root, Some(receipts)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::era1::Era1Writer;
+    use alloy_rlp::Encodable;
+    use assert_matches::assert_matches;
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_block, random_receipt},
+    };
+    use reth_primitives::{proofs::calculate_receipt_root, Bloom};
+
+    /// Generates a chain of pre-merge blocks with receipts and consistent total difficulties.
+    fn generate_epoch(len: u64) -> Era1Epoch {
+        let mut rng = generators::rng();
+        let mut blocks: Vec<Era1Block> = Vec::new();
+
+        for number in 0..len {
+            let parent = blocks.last().map(|block| block.header.hash());
+            let block = random_block(&mut rng, number, parent, Some(2), Some(0));
+            let receipts = block
+                .body
+                .iter()
+                .map(|tx| random_receipt(&mut rng, tx, Some(1)))
+                .collect::<Vec<_>>();
+            let receipts_root = calculate_receipt_root(
+                &receipts.iter().cloned().map(Receipt::with_bloom).collect::<Vec<_>>(),
+            );
+
+            let (header, body) = block.split_header_body();
+            let mut header = header.unseal();
+            header.receipts_root = receipts_root;
+            header.difficulty = U256::from(number + 1);
+            let total_difficulty =
+                blocks.last().map(|block| block.total_difficulty).unwrap_or_default() +
+                    header.difficulty;
+
+            blocks.push(Era1Block {
+                header: header.seal_slow(),
+                body,
+                receipts: Some(receipts),
+                receipts_root,
+                total_difficulty,
+            });
+        }
+
+        let accumulator_root = epoch_accumulator_root(
+            &blocks
+                .iter()
+                .map(|block| HeaderRecord {
+                    block_hash: block.header.hash(),
+                    total_difficulty: block.total_difficulty,
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        Era1Epoch { blocks, accumulator_root }
+    }
+
+    /// Encodes the epoch as an Era1 archive.
+    fn encode_epoch(epoch: &Era1Epoch) -> Vec<u8> {
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        for block in &epoch.blocks {
+            writer.append(block).unwrap();
+        }
+        let (encoded, accumulator_root) = writer.finish().unwrap();
+        assert_eq!(accumulator_root, epoch.accumulator_root);
+        encoded
+    }
+
+    #[test]
+    fn read_and_verify_epoch() {
+        let epoch = generate_epoch(20);
+        let encoded = encode_epoch(&epoch);
+
+        let read = Era1Reader::new(&encoded[..]).unwrap().read_epoch().unwrap();
+        assert_eq!(read, epoch);
+        assert_eq!(read.start_block(), Some(0));
+        assert_eq!(read.end_block(), Some(19));
+        read.verify(None).unwrap();
+
+        let (client, receipts) = read.into_file_client();
+        assert_eq!(client.headers_len(), 20);
+        assert_eq!(client.tip(), Some(epoch.blocks[19].header.hash()));
+        assert_eq!(receipts.len(), 20);
+        assert!(receipts.iter().all(|(_, receipts)| receipts.as_ref().unwrap().len() == 2));
+    }
+
+    #[test]
+    fn reject_invalid_epoch() {
+        let epoch = generate_epoch(5);
+
+        let mut tampered = epoch.clone();
+        tampered.blocks[3].total_difficulty += U256::from(1);
+        assert_matches!(
+            tampered.verify(None),
+            Err(Era1Error::TotalDifficultyMismatch { number: 3, .. })
+        );
+
+        let mut tampered = epoch.clone();
+        tampered.accumulator_root = B256::ZERO;
+        assert_matches!(tampered.verify(None), Err(Era1Error::AccumulatorRootMismatch(_)));
+
+        let mut tampered = epoch.clone();
+        tampered.blocks[2].body.transactions.pop();
+        assert_matches!(
+            tampered.verify(None),
+            Err(Era1Error::TransactionsRootMismatch { number: 2, .. })
+        );
+
+        let mut tampered = epoch;
+        tampered.blocks.remove(1);
+        assert_matches!(tampered.verify(None), Err(Era1Error::NonContiguousBlock(2)));
+    }
+
+    #[test]
+    fn read_block_range() {
+        let mut epoch = generate_epoch(3);
+        let encoded = encode_epoch(&epoch);
+        assert_eq!(
+            Era1Reader::new(io::Cursor::new(&encoded)).unwrap().read_block_range().unwrap(),
+            Some(0..=2)
+        );
+
+        epoch.blocks.clear();
+        epoch.accumulator_root = epoch_accumulator_root(&[]);
+        let encoded = encode_epoch(&epoch);
+        assert_eq!(
+            Era1Reader::new(io::Cursor::new(&encoded)).unwrap().read_block_range().unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn read_pre_byzantium_receipts() {
+        // a legacy receipt with the intermediate state root instead of a status code
+        let mut fields = Vec::new();
+        B256::repeat_byte(1).encode(&mut fields);
+        21_000u64.encode(&mut fields);
+        Bloom::ZERO.encode(&mut fields);
+        fields.push(alloy_rlp::EMPTY_LIST_CODE);
+        let mut receipt = Vec::new();
+        alloy_rlp::Header { list: true, payload_length: fields.len() }.encode(&mut receipt);
+        receipt.extend_from_slice(&fields);
+        let mut encoded_receipts = Vec::new();
+        alloy_rlp::Header { list: true, payload_length: receipt.len() }
+            .encode(&mut encoded_receipts);
+        encoded_receipts.extend_from_slice(&receipt);
+
+        let epoch = generate_epoch(1);
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        writer.append_with_encoded_receipts(&epoch.blocks[0], &encoded_receipts).unwrap();
+        let (encoded, _) = writer.finish().unwrap();
+
+        let read = Era1Reader::new(&encoded[..]).unwrap().read_epoch().unwrap();
+        assert_eq!(read.blocks[0].receipts, None);
+
+        let receipts = Era1Reader::new(&encoded[..]).unwrap().read_receipts().unwrap();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].number, 0);
+        assert_eq!(receipts[0].encoded, encoded_receipts);
+        assert_eq!(
+            receipts[0].receipts_root,
+            ordered_trie_root_with_encoder(&[&receipt[..]], |receipt, buf| buf.put_slice(receipt))
+        );
+    }
+
+    #[test]
+    fn reject_missing_block_index() {
+        let epoch = generate_epoch(2);
+        let encoded = encode_epoch(&epoch);
+
+        // strip the block index entry: start number, two offsets and count
+        let truncated = &encoded[..encoded.len() - 8 - 4 * 8];
+        assert_matches!(
+            Era1Reader::new(truncated).unwrap().read_epoch(),
+            Err(Era1Error::InvalidEntry(_))
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use super::{
+    e2s::{
+        Entry, ACCUMULATOR, BLOCK_INDEX, COMPRESSED_BODY, COMPRESSED_HEADER, COMPRESSED_RECEIPTS,
+        TOTAL_DIFFICULTY, VERSION,
+    },
+    epoch_accumulator_root, Era1Block, Era1Error, HeaderRecord, ERA1_EXTENSION, MAX_EPOCH_SIZE,
+};
+use alloy_rlp::Encodable;
+use reth_primitives::{BlockNumber, Receipt, B256};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Writer for a single Era1 archive.
+///
+/// Blocks must be appended in ascending order. The archive is only complete once
+/// [`Era1Writer::finish`] wrote the accumulator and the block index.
+#[derive(Debug)]
+pub struct Era1Writer<W> {
+    writer: W,
+    /// Number of bytes written so far.
+    position: u64,
+    /// Number of the first appended block.
+    start_block: Option<BlockNumber>,
+    /// Offsets of the appended blocks, relative to the start of the archive.
+    offsets: Vec<u64>,
+    /// Accumulator records of the appended blocks.
+    records: Vec<HeaderRecord>,
+}
+
+impl<W: Write> Era1Writer<W> {
+    /// Creates a new writer, writing the version entry that starts the archive.
+    pub fn new(mut writer: W) -> Result<Self, Era1Error> {
+        let version = Entry::new(VERSION, Vec::new());
+        version.write(&mut writer)?;
+
+        Ok(Self {
+            writer,
+            position: version.encoded_len() as u64,
+            start_block: None,
+            offsets: Vec::new(),
+            records: Vec::new(),
+        })
+    }
+
+    /// Returns the number of blocks that have been appended.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if no block has been appended.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Appends a block to the archive.
+    ///
+    /// Blocks without receipts can't be written, since the archive must contain the receipts of
+    /// every block.
+    pub fn append(&mut self, block: &Era1Block) -> Result<(), Era1Error> {
+        let receipts = block
+            .receipts
+            .as_ref()
+            .ok_or(Era1Error::InvalidEntry("block tuple requires receipts"))?;
+
+        let mut encoded_receipts = Vec::new();
+        receipts
+            .iter()
+            .cloned()
+            .map(Receipt::with_bloom)
+            .collect::<Vec<_>>()
+            .encode(&mut encoded_receipts);
+
+        self.append_with_encoded_receipts(block, &encoded_receipts)
+    }
+
+    /// Appends a block to the archive with the given RLP list of its receipts, ignoring
+    /// [`Era1Block::receipts`].
+    ///
+    /// This allows writing pre-Byzantium receipts, e.g. taken from another archive with
+    /// [`Era1Reader::read_receipts`](super::Era1Reader::read_receipts).
+    pub fn append_with_encoded_receipts(
+        &mut self,
+        block: &Era1Block,
+        encoded_receipts: &[u8],
+    ) -> Result<(), Era1Error> {
+        let number = block.header.number;
+        match self.start_block {
+            Some(start) if start + self.records.len() as u64 != number => {
+                return Err(Era1Error::NonContiguousBlock(number))
+            }
+            None => self.start_block = Some(number),
+            _ => {}
+        }
+        if self.records.len() == MAX_EPOCH_SIZE {
+            return Err(Era1Error::EpochTooLarge)
+        }
+
+        self.offsets.push(self.position);
+
+        let mut buf = Vec::new();
+        block.header.header().encode(&mut buf);
+        self.write_entry(Entry::compressed(COMPRESSED_HEADER, &buf)?)?;
+
+        buf.clear();
+        block.body.encode(&mut buf);
+        self.write_entry(Entry::compressed(COMPRESSED_BODY, &buf)?)?;
+
+        self.write_entry(Entry::compressed(COMPRESSED_RECEIPTS, encoded_receipts)?)?;
+
+        self.write_entry(Entry::new(
+            TOTAL_DIFFICULTY,
+            block.total_difficulty.to_le_bytes::<32>().to_vec(),
+        ))?;
+
+        self.records.push(HeaderRecord {
+            block_hash: block.header.hash(),
+            total_difficulty: block.total_difficulty,
+        });
+
+        Ok(())
+    }
+
+    /// Writes the accumulator and the block index, completing the archive.
+    ///
+    /// Returns the inner writer and the accumulator root of the archive.
+    pub fn finish(mut self) -> Result<(W, B256), Era1Error> {
+        let accumulator_root = epoch_accumulator_root(&self.records);
+        self.write_entry(Entry::new(ACCUMULATOR, accumulator_root.to_vec()))?;
+
+        // offsets of the block index are relative to the start of the block index entry
+        let index_position = self.position as i64;
+        let mut index = Vec::with_capacity(16 + self.offsets.len() * 8);
+        index.extend_from_slice(&self.start_block.unwrap_or_default().to_le_bytes());
+        for offset in &self.offsets {
+            index.extend_from_slice(&(*offset as i64 - index_position).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        self.write_entry(Entry::new(BLOCK_INDEX, index))?;
+
+        self.writer.flush()?;
+        Ok((self.writer, accumulator_root))
+    }
+
+    fn write_entry(&mut self, entry: Entry) -> Result<(), Era1Error> {
+        entry.write(&mut self.writer)?;
+        self.position += entry.encoded_len() as u64;
+        Ok(())
+    }
+}
+
+impl Era1Writer<BufWriter<File>> {
+    /// Creates a new archive at the given path.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, Era1Error> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Returns the canonical file name of an Era1 archive:
+/// `<network>-<epoch>-<short accumulator root>.era1`.
+pub fn era1_file_name(network: &str, epoch: u64, accumulator_root: B256) -> PathBuf {
+    let short_root = &accumulator_root.to_string()[2..10];
+    PathBuf::from(format!("{network}-{epoch:05}-{short_root}.{ERA1_EXTENSION}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn era1_file_names() {
+        let root = B256::repeat_byte(0xab);
+        assert_eq!(
+            era1_file_name("mainnet", 12, root),
+            PathBuf::from("mainnet-00012-abababab.era1")
+        );
+    }
+}
@@ -11,7 +11,10 @@ use reth_primitives::{
     BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, BytesMut, Header, HeadersDirection,
     PeerId, SealedHeader, B256,
 };
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 use tokio::{fs::File, io::AsyncReadExt};
 use tokio_stream::StreamExt;
@@ -33,7 +36,8 @@ pub const DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE: u64 = 1_000_000_000;
 /// Blocks are assumed to have populated transactions, so reading headers will also buffer
 /// transactions in memory for use in the bodies stage.
 ///
-/// This reads the entire file into memory, so it is not suitable for large files.
+/// This reads the entire file into memory, so it is not suitable for large files. Use
+/// [`ChunkedFileReader`] to read large files in bounded chunks.
 #[derive(Debug)]
 pub struct FileClient {
     /// The buffered headers retrieved when fetching new bodies.
@@ -56,6 +60,14 @@ pub enum FileClientError {
     /// An error occurred when decoding blocks, headers, or rlp headers from the file.
     #[error("{0}")]
     Rlp(alloy_rlp::Error, Vec<u8>),
+
+    /// A single block does not fit into a chunk.
+    #[error("chunk byte length {0} is too small to hold a single block")]
+    ChunkTooSmall(u64),
+
+    /// The file ends with bytes that don't form a complete block.
+    #[error("file ends with {0} bytes that are not a complete block")]
+    TrailingBytes(usize),
 }
 
 impl FileClient {
@@ -74,13 +86,16 @@ impl FileClient {
         let mut reader = vec![];
         file.read_to_end(&mut reader).await?;
 
-        Ok(Self::from_reader(&reader[..], file_len).await?.0)
+        Ok(Self::from_reader(&reader[..], file_len, None).await?.0)
     }
 
     /// Initialize the [`FileClient`] from bytes that have been read from file.
+    ///
+    /// Blocks up to and including `skip_until` are decoded, but not buffered.
     pub(crate) async fn from_reader<B>(
         reader: B,
         num_bytes: u64,
+        skip_until: Option<BlockNumber>,
     ) -> Result<(Self, Vec<u8>), FileClientError>
     where
         B: AsyncReadExt + Unpin,
@@ -118,6 +133,9 @@ impl FileClient {
                 Err(err) => return Err(err),
             };
             let block_number = block.header.number;
+            if skip_until.is_some_and(|skip_until| block_number <= skip_until) {
+                continue
+            }
             let block_hash = block.header.hash_slow();
 
             // add to the internal maps
@@ -153,6 +171,25 @@ impl FileClient {
         Ok((Self { headers, hash_to_number, bodies }, remaining_bytes))
     }
 
+    /// Creates a new [`FileClient`] from blocks that have already been decoded, for example from
+    /// an Era1 archive.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = (SealedHeader, BlockBody)>) -> Self {
+        let mut headers = HashMap::new();
+        let mut hash_to_number = HashMap::new();
+        let mut bodies = HashMap::new();
+
+        for (header, body) in blocks {
+            let (header, hash) = header.split();
+            hash_to_number.insert(hash, header.number);
+            headers.insert(header.number, header);
+            bodies.insert(hash, body);
+        }
+
+        trace!(target: "downloaders::file", blocks = headers.len(), "Initialized file client");
+
+        Self { headers, hash_to_number, bodies }
+    }
+
     /// Get the tip hash of the chain.
     pub fn tip(&self) -> Option<B256> {
         self.headers.get(&self.max_block()?).map(|h| h.hash_slow())
@@ -315,7 +352,11 @@ impl DownloadClient for FileClient {
     }
 }
 
-/// Chunks file into several [`FileClient`]s.
+/// Chunks one or more files into several [`FileClient`]s.
+///
+/// Files are read one after another, each in chunks of at most the configured chunk byte length,
+/// so that memory usage is bounded regardless of the size of the files. Blocks must not span
+/// multiple files.
 #[derive(Debug)]
 pub struct ChunkedFileReader {
     /// File to read from.
@@ -326,6 +367,10 @@ pub struct ChunkedFileReader {
     chunk: Vec<u8>,
     /// Max bytes per chunk.
     chunk_byte_len: u64,
+    /// Files to read once the current file is exhausted.
+    next_files: VecDeque<PathBuf>,
+    /// Blocks up to and including this number are skipped.
+    skip_until: Option<BlockNumber>,
 }
 
 impl ChunkedFileReader {
@@ -335,10 +380,46 @@ impl ChunkedFileReader {
         path: P,
         chunk_byte_len: Option<u64>,
     ) -> Result<Self, FileClientError> {
-        let file = File::open(path).await?;
+        Self::from_paths([path.as_ref().to_path_buf()], chunk_byte_len).await
+    }
+
+    /// Opens the files to import from given paths, which are read in the given order. Paths that
+    /// are directories are expanded to the files they contain, ordered by file name.
+    ///
+    /// If no chunk byte length is passed, chunks have [`DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE`] (one
+    /// static file).
+    pub async fn from_paths(
+        paths: impl IntoIterator<Item = PathBuf>,
+        chunk_byte_len: Option<u64>,
+    ) -> Result<Self, FileClientError> {
+        let mut files = VecDeque::new();
+        for path in paths {
+            if tokio::fs::metadata(&path).await?.is_dir() {
+                let mut dir_files = Vec::new();
+                let mut entries = tokio::fs::read_dir(&path).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    if entry.file_type().await?.is_file() {
+                        dir_files.push(entry.path());
+                    }
+                }
+                dir_files.sort_unstable();
+                files.extend(dir_files);
+            } else {
+                files.push_back(path);
+            }
+        }
+
+        let first = files.pop_front().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no files to import")
+        })?;
+        debug!(target: "downloaders::file", files = files.len() + 1, "Opening files for import");
+
+        let file = File::open(first).await?;
         let chunk_byte_len = chunk_byte_len.unwrap_or(DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE);
 
-        Self::from_file(file, chunk_byte_len).await
+        let mut reader = Self::from_file(file, chunk_byte_len).await?;
+        reader.next_files = files;
+        Ok(reader)
     }
 
     /// Opens the file to import from given path. Returns a new instance.
@@ -347,7 +428,21 @@ impl ChunkedFileReader {
         let metadata = file.metadata().await?;
         let file_byte_len = metadata.len();
 
-        Ok(Self { file, file_byte_len, chunk: vec![], chunk_byte_len })
+        Ok(Self {
+            file,
+            file_byte_len,
+            chunk: vec![],
+            chunk_byte_len,
+            next_files: VecDeque::new(),
+            skip_until: None,
+        })
+    }
+
+    /// Skips all blocks up to and including the given block, e.g. to resume an import from the
+    /// last imported block.
+    pub fn skip_until(mut self, block: BlockNumber) -> Self {
+        self.skip_until = Some(block);
+        self
     }
 
     /// Calculates the number of bytes to read from the chain file. Returns a tuple of the chunk
@@ -364,11 +459,33 @@ impl ChunkedFileReader {
         }
     }
 
-    /// Read next chunk from file. Returns [`FileClient`] containing decoded chunk.
-    pub async fn next_chunk(&mut self) -> Result<Option<FileClient>, FileClientError> {
-        if self.file_byte_len == 0 && self.chunk.is_empty() {
-            // eof
-            return Ok(None)
+    /// Opens the next file, if the current one has been read entirely.
+    ///
+    /// Returns `false` if there are no more files.
+    async fn open_next_file(&mut self) -> Result<bool, FileClientError> {
+        let Some(path) = self.next_files.pop_front() else { return Ok(false) };
+
+        debug!(target: "downloaders::file", ?path, "Opening next file");
+        self.file = File::open(path).await?;
+        self.file_byte_len = self.file.metadata().await?.len();
+
+        Ok(true)
+    }
+
+    /// Reads the next chunk of bytes from the files into the chunk buffer, after any bytes that
+    /// were left over from decoding the previous chunk.
+    ///
+    /// Returns `false` if all files have been read entirely.
+    pub(crate) async fn read_next_chunk(&mut self) -> Result<bool, FileClientError> {
+        while self.file_byte_len == 0 {
+            if !self.chunk.is_empty() {
+                // items must not span multiple files
+                return Err(FileClientError::TrailingBytes(self.chunk.len()))
+            }
+            if !self.open_next_file().await? {
+                // eof
+                return Ok(false)
+            }
         }
 
         let chunk_target_len = self.chunk_len();
@@ -402,21 +519,64 @@ impl ChunkedFileReader {
             "new bytes were read from file"
         );
 
-        // make new file client from chunk
-        let (file_client, bytes) =
-            FileClient::from_reader(&self.chunk[..], next_chunk_byte_len as u64).await?;
+        Ok(true)
+    }
 
-        debug!(target: "downloaders::file",
-            headers_len=file_client.headers.len(),
-            bodies_len=file_client.bodies.len(),
-            remaining_bytes_len=bytes.len(),
-            "parsed blocks that were read from file"
-        );
+    /// Replaces the chunk buffer with the bytes that were left over after decoding it.
+    ///
+    /// Returns an error if no item at all could be decoded from the chunk.
+    pub(crate) fn set_remaining_bytes(&mut self, bytes: Vec<u8>) -> Result<(), FileClientError> {
+        if bytes.len() == self.chunk.len() {
+            // not a single item could be decoded from the chunk
+            if self.file_byte_len == 0 {
+                return Err(FileClientError::TrailingBytes(bytes.len()))
+            }
+            return Err(FileClientError::ChunkTooSmall(self.chunk_byte_len))
+        }
 
         // save left over bytes
         self.chunk = bytes;
+        Ok(())
+    }
+
+    /// Returns the bytes of the current chunk.
+    pub(crate) fn chunk(&self) -> &[u8] {
+        &self.chunk
+    }
 
-        Ok(Some(file_client))
+    /// Returns the block up to which items are skipped, if any.
+    pub(crate) const fn skipped_until(&self) -> Option<BlockNumber> {
+        self.skip_until
+    }
+
+    /// Read next chunk from file. Returns [`FileClient`] containing decoded chunk.
+    ///
+    /// Chunks that only contain skipped blocks are not returned.
+    pub async fn next_chunk(&mut self) -> Result<Option<FileClient>, FileClientError> {
+        while self.read_next_chunk().await? {
+            // make new file client from chunk
+            let (file_client, bytes) =
+                FileClient::from_reader(&self.chunk[..], self.chunk.len() as u64, self.skip_until)
+                    .await?;
+
+            debug!(target: "downloaders::file",
+                headers_len=file_client.headers.len(),
+                bodies_len=file_client.bodies.len(),
+                remaining_bytes_len=bytes.len(),
+                "parsed blocks that were read from file"
+            );
+
+            self.set_remaining_bytes(bytes)?;
+
+            if file_client.headers.is_empty() {
+                trace!(target: "downloaders::file", skip_until=?self.skip_until, "Skipped chunk");
+                continue
+            }
+
+            return Ok(Some(file_client))
+        }
+
+        Ok(None)
     }
 }
 
@@ -426,7 +586,7 @@ mod tests {
     use crate::{
         bodies::{
             bodies::BodiesDownloaderBuilder,
-            test_utils::{insert_headers, zip_blocks},
+            test_utils::{create_raw_bodies, insert_headers, zip_blocks},
         },
         headers::{reverse_headers::ReverseHeadersDownloaderBuilder, test_utils::child_header},
         test_utils::{generate_bodies, generate_bodies_file},
@@ -608,4 +768,57 @@ mod tests {
         // the first header is not included in the response
         assert_eq!(headers[1..], downloaded_headers);
     }
+
+    #[tokio::test]
+    async fn test_chunked_read_from_directory_with_skip() {
+        reth_tracing::init_test_tracing();
+
+        let (headers, mut bodies) = generate_bodies(0..=19);
+        let blocks = create_raw_bodies(headers.iter(), &mut bodies);
+
+        // write the blocks to two files in a directory
+        let dir = tempfile::tempdir().unwrap();
+        for (idx, blocks) in blocks.chunks(10).enumerate() {
+            let mut buf = vec![];
+            for block in blocks {
+                alloy_rlp::Encodable::encode(block, &mut buf);
+            }
+            std::fs::write(dir.path().join(format!("blocks-{idx:02}.rlp")), buf).unwrap();
+        }
+
+        let mut reader = ChunkedFileReader::from_paths([dir.path().to_path_buf()], None)
+            .await
+            .unwrap()
+            .skip_until(4);
+
+        let mut read_blocks = vec![];
+        while let Some(client) = reader.next_chunk().await.unwrap() {
+            read_blocks.extend(client.min_block().unwrap()..=client.max_block().unwrap());
+        }
+
+        // one chunk per file, without the skipped blocks
+        assert_eq!(read_blocks, (5..=19).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_chunked_read_rejects_truncated_file() {
+        let (headers, mut bodies) = generate_bodies(0..=1);
+        let blocks = create_raw_bodies(headers.iter(), &mut bodies);
+
+        let mut buf = vec![];
+        for block in &blocks {
+            alloy_rlp::Encodable::encode(block, &mut buf);
+        }
+        buf.truncate(buf.len() - 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocks.rlp");
+        std::fs::write(&path, buf).unwrap();
+
+        let mut reader = ChunkedFileReader::new(path, None).await.unwrap();
+        assert_matches!(reader.next_chunk().await, Ok(Some(client)) => {
+            assert_eq!(client.headers_len(), 1);
+        });
+        assert_matches!(reader.next_chunk().await, Err(FileClientError::TrailingBytes(_)));
+    }
 }
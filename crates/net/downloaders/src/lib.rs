@@ -32,5 +32,20 @@ pub mod file_client;
 /// Enables decoding and encoding `Block` types within file contexts.
 pub mod file_codec;
 
+/// Module with codecs for reading and encoding block receipts in files.
+///
+/// Contains the default [ReceiptFileCodec](receipt_file_codec::ReceiptFileCodec) and decoders
+/// for chain specific formats.
+pub mod receipt_file_codec;
+
+/// Module for reading receipts files in chunks.
+mod receipt_file_client;
+
+/// Module for reading and writing Era1 history archives.
+///
+/// Contains [Era1Reader](era1::Era1Reader) to read and verify epochs of pre-merge blocks and
+/// receipts, and [Era1Writer](era1::Era1Writer) to produce them.
+pub mod era1;
+
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
@@ -0,0 +1,152 @@
+use crate::{
+    file_client::{ChunkedFileReader, FileClientError},
+    receipt_file_codec::BlockReceipts,
+};
+use reth_primitives::BlockNumber;
+use tokio_stream::StreamExt;
+use tokio_util::codec::{Decoder, FramedRead};
+use tracing::{debug, trace};
+
+impl ChunkedFileReader {
+    /// Read next chunk from file and decode the receipts it contains with the decoder `D`.
+    ///
+    /// The decoder determines the file format, so chain specific formats can be read by plugging
+    /// in their own decoder, see [`ReceiptFileCodec`](crate::receipt_file_codec::ReceiptFileCodec)
+    /// for the default format.
+    ///
+    /// Consecutive items of the same block are merged into a single [`BlockReceipts`], in
+    /// ascending order. Note that the receipts of the last block of a chunk may continue in the
+    /// next chunk if the format stores receipts per transaction.
+    pub async fn next_receipts_chunk<D>(
+        &mut self,
+    ) -> Result<Option<Vec<BlockReceipts>>, FileClientError>
+    where
+        D: Decoder<Item = BlockReceipts, Error = FileClientError> + Default,
+    {
+        while self.read_next_chunk().await? {
+            let (receipts, bytes) =
+                decode_receipts::<D>(self.chunk(), self.skipped_until()).await?;
+
+            debug!(target: "downloaders::file",
+                blocks=receipts.len(),
+                remaining_bytes_len=bytes.len(),
+                "parsed receipts that were read from file"
+            );
+
+            self.set_remaining_bytes(bytes)?;
+
+            if receipts.is_empty() {
+                trace!(target: "downloaders::file", skip_until=?self.skipped_until(), "Skipped chunk");
+                continue
+            }
+
+            return Ok(Some(receipts))
+        }
+
+        Ok(None)
+    }
+}
+
+/// Decodes all complete items of the chunk, returning the merged receipts and the bytes left over.
+async fn decode_receipts<D>(
+    chunk: &[u8],
+    skip_until: Option<BlockNumber>,
+) -> Result<(Vec<BlockReceipts>, Vec<u8>), FileClientError>
+where
+    D: Decoder<Item = BlockReceipts, Error = FileClientError> + Default,
+{
+    // use with_capacity to make sure the internal buffer contains the entire chunk
+    let mut stream = FramedRead::with_capacity(chunk, D::default(), chunk.len());
+
+    let mut receipts: Vec<BlockReceipts> = Vec::new();
+    let mut remaining_bytes = vec![];
+
+    while let Some(res) = stream.next().await {
+        let block_receipts = match res {
+            Ok(block_receipts) => block_receipts,
+            Err(FileClientError::Rlp(err, bytes)) => {
+                trace!(target: "downloaders::file",
+                    %err,
+                    bytes_len=bytes.len(),
+                    "partial receipts returned from decoding chunk"
+                );
+                remaining_bytes = bytes;
+                break
+            }
+            Err(err) => return Err(err),
+        };
+
+        if skip_until.is_some_and(|skip_until| block_receipts.number <= skip_until) {
+            continue
+        }
+
+        match receipts.last_mut() {
+            Some(last) if last.number == block_receipts.number => {
+                last.receipts.extend(block_receipts.receipts)
+            }
+            _ => receipts.push(block_receipts),
+        }
+    }
+
+    Ok((receipts, remaining_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt_file_codec::ReceiptFileCodec;
+    use alloy_rlp::Encodable;
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_block, random_receipt},
+    };
+
+    #[tokio::test]
+    async fn read_receipts_in_chunks() {
+        let mut rng = generators::rng();
+
+        // two items for the same block are merged
+        let mut items = vec![];
+        for number in [1, 2, 2, 3, 4] {
+            let block = random_block(&mut rng, number, None, Some(1), Some(0));
+            items.push(BlockReceipts {
+                number,
+                receipts: vec![random_receipt(&mut rng, &block.body[0], Some(1)).with_bloom()],
+            });
+        }
+
+        let mut buf = vec![];
+        for item in &items {
+            item.encode(&mut buf);
+        }
+        let max_item_len = items.iter().map(|item| item.length()).max().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("receipts.rlp");
+        std::fs::write(&path, buf).unwrap();
+
+        let mut reader = ChunkedFileReader::new(&path, Some(2 * max_item_len as u64 + 1))
+            .await
+            .unwrap()
+            .skip_until(1);
+
+        let mut read = Vec::<BlockReceipts>::new();
+        while let Some(chunk) = reader.next_receipts_chunk::<ReceiptFileCodec>().await.unwrap() {
+            for block_receipts in chunk {
+                match read.last_mut() {
+                    Some(last) if last.number == block_receipts.number => {
+                        last.receipts.extend(block_receipts.receipts)
+                    }
+                    _ => read.push(block_receipts),
+                }
+            }
+        }
+
+        assert_eq!(read.iter().map(|item| item.number).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(read[0].receipts.len(), 2);
+        assert_eq!(
+            read[0].receipts,
+            [items[1].receipts.clone(), items[2].receipts.clone()].concat()
+        );
+    }
+}
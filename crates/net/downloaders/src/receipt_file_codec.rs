@@ -0,0 +1,212 @@
+//! Codec for reading and writing block receipts in a file.
+
+use crate::file_client::FileClientError;
+use alloy_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use reth_primitives::{
+    bytes::{Buf, BytesMut},
+    Address, BlockNumber, Bloom, Bytes, Log, Receipt, ReceiptWithBloom, TxType, B256,
+};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The receipts of a single block, as stored in a receipts file.
+///
+/// Receipts files are written one block after another, as rlp bytes:
+/// `rlp([number, [receipt, ...]]) || rlp([number, [receipt, ...]]) || ...`
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct BlockReceipts {
+    /// The number of the block.
+    pub number: BlockNumber,
+    /// The receipts of the block, in transaction order.
+    pub receipts: Vec<ReceiptWithBloom>,
+}
+
+/// Codec for reading and writing [`BlockReceipts`] in a file.
+///
+/// The same buffering considerations as for the block file codec apply: the framed reader must
+/// have capacity for an entire [`BlockReceipts`] item.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReceiptFileCodec;
+
+impl Decoder for ReceiptFileCodec {
+    type Item = BlockReceipts;
+    type Error = FileClientError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.is_empty() {
+            return Ok(None)
+        }
+
+        let buf_slice = &mut src.as_ref();
+        let receipts = BlockReceipts::decode(buf_slice)
+            .map_err(|err| FileClientError::Rlp(err, src.to_vec()))?;
+        src.advance(src.len() - buf_slice.len());
+
+        Ok(Some(receipts))
+    }
+}
+
+impl Encoder<BlockReceipts> for ReceiptFileCodec {
+    type Error = FileClientError;
+
+    fn encode(&mut self, item: BlockReceipts, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        item.encode(dst);
+        Ok(())
+    }
+}
+
+/// A receipt as exported by op-geth for OP Mainnet blocks below Bedrock.
+///
+/// Besides the consensus fields, it contains the transaction and block metadata of the legacy
+/// l2geth receipt.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct OpGethReceipt {
+    /// The transaction type.
+    pub tx_type: u8,
+    /// The post state root of pre-Byzantium receipts, empty otherwise.
+    pub post_state: Bytes,
+    /// The status of the transaction, `1` if it succeeded.
+    pub status: u64,
+    /// The cumulative gas used in the block after this transaction.
+    pub cumulative_gas_used: u64,
+    /// The bloom filter of the logs.
+    pub bloom: Bloom,
+    /// The logs emitted by the transaction.
+    pub logs: Vec<Log>,
+    /// The hash of the transaction.
+    pub tx_hash: B256,
+    /// The address of the contract created by the transaction, if any.
+    pub contract_address: Address,
+    /// The gas used by the transaction.
+    pub gas_used: u64,
+    /// The hash of the block.
+    pub block_hash: B256,
+    /// The number of the block.
+    pub block_number: u64,
+    /// The index of the transaction in the block.
+    pub transaction_index: u32,
+    /// The L1 gas price.
+    pub l1_gas_price: u64,
+    /// The L1 gas used.
+    pub l1_gas_used: u64,
+    /// The L1 fee.
+    pub l1_fee: u64,
+    /// The fee scalar, as a decimal string.
+    pub fee_scalar: String,
+}
+
+impl From<OpGethReceipt> for ReceiptWithBloom {
+    fn from(receipt: OpGethReceipt) -> Self {
+        ReceiptWithBloom {
+            receipt: Receipt {
+                // only legacy transactions exist below Bedrock
+                tx_type: TxType::Legacy,
+                success: receipt.status == 1,
+                cumulative_gas_used: receipt.cumulative_gas_used,
+                logs: receipt.logs,
+                #[cfg(feature = "optimism")]
+                deposit_nonce: None,
+                #[cfg(feature = "optimism")]
+                deposit_receipt_version: None,
+            },
+            bloom: receipt.bloom,
+        }
+    }
+}
+
+/// Codec for reading receipts exported by op-geth for OP Mainnet blocks below Bedrock.
+///
+/// The file contains one RLP encoded [`OpGethReceipt`] per transaction, in block order. Every
+/// receipt is decoded into a [`BlockReceipts`] with a single receipt, consecutive items of the
+/// same block are merged by the reader.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OpGethReceiptFileCodec;
+
+impl Decoder for OpGethReceiptFileCodec {
+    type Item = BlockReceipts;
+    type Error = FileClientError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.is_empty() {
+            return Ok(None)
+        }
+
+        let buf_slice = &mut src.as_ref();
+        let receipt = OpGethReceipt::decode(buf_slice)
+            .map_err(|err| FileClientError::Rlp(err, src.to_vec()))?;
+        src.advance(src.len() - buf_slice.len());
+
+        Ok(Some(BlockReceipts { number: receipt.block_number, receipts: vec![receipt.into()] }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_block, random_receipt},
+    };
+
+    #[test]
+    fn receipts_file_roundtrip() {
+        let mut rng = generators::rng();
+        let receipts = (1..=3)
+            .map(|number| {
+                let block = random_block(&mut rng, number, None, Some(2), Some(0));
+                BlockReceipts {
+                    number,
+                    receipts: block
+                        .body
+                        .iter()
+                        .map(|tx| random_receipt(&mut rng, tx, Some(1)).with_bloom())
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut buf = BytesMut::new();
+        for block_receipts in receipts.clone() {
+            ReceiptFileCodec.encode(block_receipts, &mut buf).unwrap();
+        }
+
+        let mut decoded = Vec::new();
+        while let Some(block_receipts) = ReceiptFileCodec.decode(&mut buf).unwrap() {
+            decoded.push(block_receipts);
+        }
+        assert_eq!(decoded, receipts);
+    }
+
+    #[test]
+    fn decode_op_geth_receipts() {
+        let receipt = OpGethReceipt {
+            tx_type: 0,
+            post_state: Bytes::default(),
+            status: 1,
+            cumulative_gas_used: 21_000,
+            bloom: Bloom::default(),
+            logs: vec![],
+            tx_hash: B256::repeat_byte(1),
+            contract_address: Address::ZERO,
+            gas_used: 21_000,
+            block_hash: B256::repeat_byte(2),
+            block_number: 7,
+            transaction_index: 0,
+            l1_gas_price: 1,
+            l1_gas_used: 2,
+            l1_fee: 3,
+            fee_scalar: "1.5".to_string(),
+        };
+
+        let mut buf = BytesMut::new();
+        receipt.encode(&mut buf);
+        receipt.encode(&mut buf);
+
+        for _ in 0..2 {
+            let decoded = OpGethReceiptFileCodec.decode(&mut buf).unwrap().unwrap();
+            assert_eq!(decoded.number, 7);
+            assert_eq!(decoded.receipts, vec![ReceiptWithBloom::from(receipt.clone())]);
+            assert!(decoded.receipts[0].receipt.success);
+        }
+        assert_eq!(OpGethReceiptFileCodec.decode(&mut buf).unwrap(), None);
+    }
+}
@@ -33,3 +33,6 @@ pub use state::*;
 
 pub mod receipts;
 pub use receipts::*;
+
+pub mod snap;
+pub use snap::{SnapMessageId, SnapProtocolMessage};
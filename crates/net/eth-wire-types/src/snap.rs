@@ -0,0 +1,581 @@
+//! Types for the `snap/1` protocol: <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+//!
+//! Unlike the `eth` protocol, the request id of a `snap` message is not wrapped in a separate
+//! request pair but is the first field of every message.
+
+use alloy_rlp::{
+    Decodable, Encodable, Header, RlpDecodable, RlpDecodableWrapper, RlpEncodable,
+    RlpEncodableWrapper,
+};
+use reth_codecs::derive_arbitrary;
+use reth_primitives::{
+    bytes::{Buf, BufMut},
+    constants::EMPTY_ROOT_HASH,
+    Bytes, B256, KECCAK_EMPTY, U256,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The number of message ids used by the `snap/1` protocol.
+pub const SNAP_PROTOCOL_MESSAGE_COUNT: u8 = 8;
+
+/// Represents message IDs for `snap/1` protocol messages.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SnapMessageId {
+    /// Requests a range of accounts of the state trie.
+    GetAccountRange = 0x00,
+    /// Response to [`SnapMessageId::GetAccountRange`].
+    AccountRange = 0x01,
+    /// Requests ranges of storage slots of storage tries.
+    GetStorageRanges = 0x02,
+    /// Response to [`SnapMessageId::GetStorageRanges`].
+    StorageRanges = 0x03,
+    /// Requests contract bytecodes by hash.
+    GetByteCodes = 0x04,
+    /// Response to [`SnapMessageId::GetByteCodes`].
+    ByteCodes = 0x05,
+    /// Requests trie nodes by path.
+    GetTrieNodes = 0x06,
+    /// Response to [`SnapMessageId::GetTrieNodes`].
+    TrieNodes = 0x07,
+}
+
+impl Encodable for SnapMessageId {
+    fn encode(&self, out: &mut dyn BufMut) {
+        out.put_u8(*self as u8);
+    }
+    fn length(&self) -> usize {
+        1
+    }
+}
+
+impl Decodable for SnapMessageId {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let id = match buf.first().ok_or(alloy_rlp::Error::InputTooShort)? {
+            0x00 => SnapMessageId::GetAccountRange,
+            0x01 => SnapMessageId::AccountRange,
+            0x02 => SnapMessageId::GetStorageRanges,
+            0x03 => SnapMessageId::StorageRanges,
+            0x04 => SnapMessageId::GetByteCodes,
+            0x05 => SnapMessageId::ByteCodes,
+            0x06 => SnapMessageId::GetTrieNodes,
+            0x07 => SnapMessageId::TrieNodes,
+            _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
+        };
+        buf.advance(1);
+        Ok(id)
+    }
+}
+
+/// Requests the accounts of the state trie with the given root, starting at `starting_hash`.
+///
+/// Accounts up to `limit_hash` are requested, the response is soft-limited to `response_bytes`.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetAccountRange {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve the accounts of.
+    pub root_hash: B256,
+    /// The hash of the first account to retrieve.
+    pub starting_hash: B256,
+    /// The hash after which to stop serving accounts.
+    pub limit_hash: B256,
+    /// The soft limit for the size of the response.
+    pub response_bytes: u64,
+}
+
+/// An account in the slim format used by the `snap` protocol.
+///
+/// Unlike the trie encoding of an account, the empty storage root and the empty code hash are
+/// encoded as empty strings.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SlimAccount {
+    /// Account nonce.
+    pub nonce: u64,
+    /// Account balance.
+    pub balance: U256,
+    /// Root of the account's storage trie.
+    pub storage_root: B256,
+    /// Hash of the account's bytecode.
+    pub code_hash: B256,
+}
+
+impl SlimAccount {
+    fn storage_root_slim(&self) -> &[u8] {
+        if self.storage_root == EMPTY_ROOT_HASH {
+            &[]
+        } else {
+            self.storage_root.as_slice()
+        }
+    }
+
+    fn code_hash_slim(&self) -> &[u8] {
+        if self.code_hash == KECCAK_EMPTY {
+            &[]
+        } else {
+            self.code_hash.as_slice()
+        }
+    }
+
+    fn payload_length(&self) -> usize {
+        self.nonce.length() +
+            self.balance.length() +
+            self.storage_root_slim().length() +
+            self.code_hash_slim().length()
+    }
+}
+
+impl Encodable for SlimAccount {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.nonce.encode(out);
+        self.balance.encode(out);
+        self.storage_root_slim().encode(out);
+        self.code_hash_slim().encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for SlimAccount {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let nonce = u64::decode(buf)?;
+        let balance = U256::decode(buf)?;
+        let storage_root = decode_slim_hash(buf, EMPTY_ROOT_HASH)?;
+        let code_hash = decode_slim_hash(buf, KECCAK_EMPTY)?;
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(Self { nonce, balance, storage_root, code_hash })
+    }
+}
+
+/// Decodes a hash that is encoded as an empty string if it equals `empty`.
+fn decode_slim_hash(buf: &mut &[u8], empty: B256) -> alloy_rlp::Result<B256> {
+    let bytes = Header::decode_bytes(buf, false)?;
+    match bytes.len() {
+        0 => Ok(empty),
+        32 => Ok(B256::from_slice(bytes)),
+        _ => Err(alloy_rlp::Error::UnexpectedLength),
+    }
+}
+
+/// An account of an [`AccountRange`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountData {
+    /// The hash of the account address.
+    pub hash: B256,
+    /// The account.
+    pub body: SlimAccount,
+}
+
+/// The response to [`GetAccountRange`], containing consecutive accounts and the merkle proofs of
+/// the boundaries of the range.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountRange {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The accounts, ordered by hash.
+    pub accounts: Vec<AccountData>,
+    /// The trie nodes proving the starting hash and the last returned account.
+    pub proof: Vec<Bytes>,
+}
+
+/// Requests the storage slots of the given accounts of the state trie with the given root.
+///
+/// The `starting_hash` applies to the first account only, the `limit_hash` to the last account
+/// only. Both are encoded as empty strings if they are not set.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetStorageRanges {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie the accounts belong to.
+    pub root_hash: B256,
+    /// The hashes of the accounts to retrieve the storage slots of.
+    pub account_hashes: Vec<B256>,
+    /// The hash of the first storage slot to retrieve.
+    pub starting_hash: Bytes,
+    /// The hash after which to stop serving storage slots.
+    pub limit_hash: Bytes,
+    /// The soft limit for the size of the response.
+    pub response_bytes: u64,
+}
+
+impl GetStorageRanges {
+    /// Returns the hash of the first storage slot to retrieve, or zero if it is not set.
+    pub fn starting_hash(&self) -> B256 {
+        left_pad_hash(&self.starting_hash, 0x00)
+    }
+
+    /// Returns the hash after which to stop serving storage slots, or the maximum hash if it is
+    /// not set.
+    pub fn limit_hash(&self) -> B256 {
+        left_pad_hash(&self.limit_hash, 0xff)
+    }
+}
+
+/// Converts a possibly empty or short hash to a [`B256`], filling the missing bytes with `fill`.
+fn left_pad_hash(hash: &[u8], fill: u8) -> B256 {
+    let mut out = B256::repeat_byte(fill);
+    let len = hash.len().min(32);
+    out[..len].copy_from_slice(&hash[..len]);
+    out
+}
+
+/// A storage slot of a [`StorageRanges`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageData {
+    /// The hash of the storage slot key.
+    pub hash: B256,
+    /// The RLP encoded storage value.
+    pub data: Bytes,
+}
+
+/// The response to [`GetStorageRanges`], containing the storage slots of the requested accounts.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageRanges {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The storage slots of each served account, ordered by hash.
+    pub slots: Vec<Vec<StorageData>>,
+    /// The trie nodes proving the boundaries of the storage range of the last served account, if
+    /// that range is incomplete.
+    pub proof: Vec<Bytes>,
+}
+
+/// Requests contract bytecodes by their hashes.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetByteCodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The code hashes to retrieve the bytecodes of.
+    pub hashes: Vec<B256>,
+    /// The soft limit for the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetByteCodes`], containing the requested bytecodes in request order.
+///
+/// Unavailable bytecodes are skipped.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ByteCodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The bytecodes.
+    pub codes: Vec<Bytes>,
+}
+
+/// A path set of a [`GetTrieNodes`] request.
+///
+/// The first element is the path of a node in the account trie. If more elements follow, the
+/// first element is the hash of an account instead, and the remaining elements are paths of
+/// nodes in the storage trie of that account. Paths are compact (hex-prefix) encoded.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrieNodePathSet(pub Vec<Bytes>);
+
+/// Requests trie nodes of the state trie with the given root by their paths.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetTrieNodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to retrieve the nodes from.
+    pub root_hash: B256,
+    /// The paths of the requested nodes.
+    pub paths: Vec<TrieNodePathSet>,
+    /// The soft limit for the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetTrieNodes`], containing the RLP encoded trie nodes in request order.
+///
+/// The response may be truncated, but nodes must not be skipped.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrieNodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The trie nodes.
+    pub nodes: Vec<Bytes>,
+}
+
+/// Represents all `snap/1` protocol messages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SnapProtocolMessage {
+    /// Represents a [`GetAccountRange`] request.
+    GetAccountRange(GetAccountRange),
+    /// Represents an [`AccountRange`] response.
+    AccountRange(AccountRange),
+    /// Represents a [`GetStorageRanges`] request.
+    GetStorageRanges(GetStorageRanges),
+    /// Represents a [`StorageRanges`] response.
+    StorageRanges(StorageRanges),
+    /// Represents a [`GetByteCodes`] request.
+    GetByteCodes(GetByteCodes),
+    /// Represents a [`ByteCodes`] response.
+    ByteCodes(ByteCodes),
+    /// Represents a [`GetTrieNodes`] request.
+    GetTrieNodes(GetTrieNodes),
+    /// Represents a [`TrieNodes`] response.
+    TrieNodes(TrieNodes),
+}
+
+impl SnapProtocolMessage {
+    /// Returns the message's ID.
+    pub fn message_id(&self) -> SnapMessageId {
+        match self {
+            SnapProtocolMessage::GetAccountRange(_) => SnapMessageId::GetAccountRange,
+            SnapProtocolMessage::AccountRange(_) => SnapMessageId::AccountRange,
+            SnapProtocolMessage::GetStorageRanges(_) => SnapMessageId::GetStorageRanges,
+            SnapProtocolMessage::StorageRanges(_) => SnapMessageId::StorageRanges,
+            SnapProtocolMessage::GetByteCodes(_) => SnapMessageId::GetByteCodes,
+            SnapProtocolMessage::ByteCodes(_) => SnapMessageId::ByteCodes,
+            SnapProtocolMessage::GetTrieNodes(_) => SnapMessageId::GetTrieNodes,
+            SnapProtocolMessage::TrieNodes(_) => SnapMessageId::TrieNodes,
+        }
+    }
+
+    /// Returns the id of the request this message is or responds to.
+    pub fn request_id(&self) -> u64 {
+        match self {
+            SnapProtocolMessage::GetAccountRange(msg) => msg.request_id,
+            SnapProtocolMessage::AccountRange(msg) => msg.request_id,
+            SnapProtocolMessage::GetStorageRanges(msg) => msg.request_id,
+            SnapProtocolMessage::StorageRanges(msg) => msg.request_id,
+            SnapProtocolMessage::GetByteCodes(msg) => msg.request_id,
+            SnapProtocolMessage::ByteCodes(msg) => msg.request_id,
+            SnapProtocolMessage::GetTrieNodes(msg) => msg.request_id,
+            SnapProtocolMessage::TrieNodes(msg) => msg.request_id,
+        }
+    }
+
+    /// Returns true if the message is a request.
+    pub fn is_request(&self) -> bool {
+        matches!(
+            self,
+            SnapProtocolMessage::GetAccountRange(_) |
+                SnapProtocolMessage::GetStorageRanges(_) |
+                SnapProtocolMessage::GetByteCodes(_) |
+                SnapProtocolMessage::GetTrieNodes(_)
+        )
+    }
+
+    /// Decodes a message, prefixed by its message id.
+    pub fn decode_message(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let message = match SnapMessageId::decode(buf)? {
+            SnapMessageId::GetAccountRange => {
+                SnapProtocolMessage::GetAccountRange(GetAccountRange::decode(buf)?)
+            }
+            SnapMessageId::AccountRange => {
+                SnapProtocolMessage::AccountRange(AccountRange::decode(buf)?)
+            }
+            SnapMessageId::GetStorageRanges => {
+                SnapProtocolMessage::GetStorageRanges(GetStorageRanges::decode(buf)?)
+            }
+            SnapMessageId::StorageRanges => {
+                SnapProtocolMessage::StorageRanges(StorageRanges::decode(buf)?)
+            }
+            SnapMessageId::GetByteCodes => {
+                SnapProtocolMessage::GetByteCodes(GetByteCodes::decode(buf)?)
+            }
+            SnapMessageId::ByteCodes => SnapProtocolMessage::ByteCodes(ByteCodes::decode(buf)?),
+            SnapMessageId::GetTrieNodes => {
+                SnapProtocolMessage::GetTrieNodes(GetTrieNodes::decode(buf)?)
+            }
+            SnapMessageId::TrieNodes => SnapProtocolMessage::TrieNodes(TrieNodes::decode(buf)?),
+        };
+        Ok(message)
+    }
+}
+
+impl Encodable for SnapProtocolMessage {
+    /// Encodes the message id as a single byte, followed by the RLP encoded message.
+    fn encode(&self, out: &mut dyn BufMut) {
+        self.message_id().encode(out);
+        match self {
+            SnapProtocolMessage::GetAccountRange(msg) => msg.encode(out),
+            SnapProtocolMessage::AccountRange(msg) => msg.encode(out),
+            SnapProtocolMessage::GetStorageRanges(msg) => msg.encode(out),
+            SnapProtocolMessage::StorageRanges(msg) => msg.encode(out),
+            SnapProtocolMessage::GetByteCodes(msg) => msg.encode(out),
+            SnapProtocolMessage::ByteCodes(msg) => msg.encode(out),
+            SnapProtocolMessage::GetTrieNodes(msg) => msg.encode(out),
+            SnapProtocolMessage::TrieNodes(msg) => msg.encode(out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = match self {
+            SnapProtocolMessage::GetAccountRange(msg) => msg.length(),
+            SnapProtocolMessage::AccountRange(msg) => msg.length(),
+            SnapProtocolMessage::GetStorageRanges(msg) => msg.length(),
+            SnapProtocolMessage::StorageRanges(msg) => msg.length(),
+            SnapProtocolMessage::GetByteCodes(msg) => msg.length(),
+            SnapProtocolMessage::ByteCodes(msg) => msg.length(),
+            SnapProtocolMessage::GetTrieNodes(msg) => msg.length(),
+            SnapProtocolMessage::TrieNodes(msg) => msg.length(),
+        };
+        self.message_id().length() + payload_length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::hex;
+
+    fn roundtrip(message: SnapProtocolMessage) {
+        let mut buf = Vec::new();
+        message.encode(&mut buf);
+        assert_eq!(buf.len(), message.length());
+        assert_eq!(buf[0], message.message_id() as u8);
+
+        let decoded = SnapProtocolMessage::decode_message(&mut &buf[..]).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn slim_account_encoding() {
+        let empty = SlimAccount {
+            nonce: 1,
+            balance: U256::from(2),
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        };
+        let encoded = alloy_rlp::encode(empty);
+        assert_eq!(encoded, hex!("c401028080"));
+        assert_eq!(SlimAccount::decode(&mut &encoded[..]).unwrap(), empty);
+
+        let contract = SlimAccount {
+            storage_root: B256::repeat_byte(0x11),
+            code_hash: B256::repeat_byte(0x22),
+            ..empty
+        };
+        let encoded = alloy_rlp::encode(contract);
+        assert_eq!(encoded.len(), contract.length());
+        assert_eq!(SlimAccount::decode(&mut &encoded[..]).unwrap(), contract);
+    }
+
+    #[test]
+    fn storage_range_bounds() {
+        let request = GetStorageRanges::default();
+        assert_eq!(request.starting_hash(), B256::ZERO);
+        assert_eq!(request.limit_hash(), B256::repeat_byte(0xff));
+
+        let request = GetStorageRanges {
+            starting_hash: B256::repeat_byte(0x01).into(),
+            limit_hash: B256::repeat_byte(0x02).into(),
+            ..Default::default()
+        };
+        assert_eq!(request.starting_hash(), B256::repeat_byte(0x01));
+        assert_eq!(request.limit_hash(), B256::repeat_byte(0x02));
+    }
+
+    #[test]
+    fn snap_messages_roundtrip() {
+        roundtrip(SnapProtocolMessage::GetAccountRange(GetAccountRange {
+            request_id: 1,
+            root_hash: B256::repeat_byte(0x01),
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: 512 * 1024,
+        }));
+        roundtrip(SnapProtocolMessage::AccountRange(AccountRange {
+            request_id: 1,
+            accounts: vec![AccountData {
+                hash: B256::repeat_byte(0x02),
+                body: SlimAccount {
+                    nonce: 3,
+                    balance: U256::from(4),
+                    storage_root: EMPTY_ROOT_HASH,
+                    code_hash: B256::repeat_byte(0x05),
+                },
+            }],
+            proof: vec![Bytes::from_static(&[0xc0])],
+        }));
+        roundtrip(SnapProtocolMessage::GetStorageRanges(GetStorageRanges {
+            request_id: 2,
+            root_hash: B256::repeat_byte(0x01),
+            account_hashes: vec![B256::repeat_byte(0x02), B256::repeat_byte(0x03)],
+            starting_hash: Bytes::new(),
+            limit_hash: B256::repeat_byte(0xff).into(),
+            response_bytes: 1024,
+        }));
+        roundtrip(SnapProtocolMessage::StorageRanges(StorageRanges {
+            request_id: 2,
+            slots: vec![
+                vec![StorageData { hash: B256::repeat_byte(0x04), data: Bytes::from_static(&[1]) }],
+                vec![],
+            ],
+            proof: vec![],
+        }));
+        roundtrip(SnapProtocolMessage::GetByteCodes(GetByteCodes {
+            request_id: 3,
+            hashes: vec![B256::repeat_byte(0x06)],
+            response_bytes: 1024,
+        }));
+        roundtrip(SnapProtocolMessage::ByteCodes(ByteCodes {
+            request_id: 3,
+            codes: vec![Bytes::from_static(&[0x60, 0x00])],
+        }));
+        roundtrip(SnapProtocolMessage::GetTrieNodes(GetTrieNodes {
+            request_id: 4,
+            root_hash: B256::repeat_byte(0x01),
+            paths: vec![
+                TrieNodePathSet(vec![Bytes::from_static(&[0x00])]),
+                TrieNodePathSet(vec![B256::repeat_byte(0x07).into(), Bytes::from_static(&[0x11])]),
+            ],
+            response_bytes: 1024,
+        }));
+        roundtrip(SnapProtocolMessage::TrieNodes(TrieNodes {
+            request_id: 4,
+            nodes: vec![Bytes::from_static(&[0xc0])],
+        }));
+    }
+
+    #[test]
+    fn decode_invalid_message_id() {
+        assert!(SnapProtocolMessage::decode_message(&mut &[0x08, 0xc0][..]).is_err());
+    }
+}
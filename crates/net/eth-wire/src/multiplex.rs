@@ -13,6 +13,10 @@ use std::{
     future::Future,
     io,
     pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::{ready, Context, Poll},
 };
 
@@ -260,7 +264,10 @@ impl<St> MultiplexInner<St> {
         let shared_cap =
             self.conn.shared_capabilities().ensure_matching_capability(cap).cloned()?;
         let (to_satellite, rx) = mpsc::unbounded_channel();
-        let proto_conn = ProtocolConnection { from_wire: UnboundedReceiverStream::new(rx) };
+        let proto_conn = ProtocolConnection {
+            from_wire: UnboundedReceiverStream::new(rx),
+            received_bytes: None,
+        };
         let st = f(proto_conn);
         let st = ProtocolStream { shared_cap, to_satellite, satellite_st: Box::pin(st) };
         self.protocols.push(st);
@@ -379,13 +386,27 @@ impl CanDisconnect<Bytes> for ProtocolProxy {
 #[derive(Debug)]
 pub struct ProtocolConnection {
     from_wire: UnboundedReceiverStream<BytesMut>,
+    /// Counts the bytes of all received messages, if set.
+    received_bytes: Option<Arc<AtomicU64>>,
+}
+
+impl ProtocolConnection {
+    /// Adds the size of every message received on this connection to the given counter.
+    pub fn with_received_bytes_counter(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.received_bytes = Some(counter);
+        self
+    }
 }
 
 impl Stream for ProtocolConnection {
     type Item = BytesMut;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.from_wire.poll_next_unpin(cx)
+        let msg = ready!(self.from_wire.poll_next_unpin(cx));
+        if let (Some(msg), Some(counter)) = (&msg, &self.received_bytes) {
+            counter.fetch_add(msg.len() as u64, Ordering::Relaxed);
+        }
+        Poll::Ready(msg)
     }
 }
 
@@ -1,4 +1,4 @@
-//! Helpers for resolving the external IP.
+//! Helpers for resolving the external IP and mapping ports on the local gateway.
 //!
 //! ## Feature Flags
 //!
@@ -12,6 +12,14 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod mapping;
+pub mod natpmp;
+
+pub use mapping::{
+    MappingProtocol, PortMapping, PortMappingConfig, PortMappingError, PortMappingEvent,
+    PortMappingHandle, PortMappingMethod, PortMappingRequest, PortMappingService,
+};
+
 use igd_next::aio::tokio::search_gateway;
 use pin_project_lite::pin_project;
 use std::{
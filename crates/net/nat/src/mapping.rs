@@ -0,0 +1,631 @@
+//! Port mapping on the local gateway via UPnP IGD or NAT-PMP/PCP.
+//!
+//! The [`PortMappingService`] keeps the configured mappings alive by renewing their leases before
+//! they expire, tracks the gateway's external address and removes all mappings on shutdown.
+
+use crate::natpmp::NatPmpClient;
+use igd_next::{
+    aio::{
+        tokio::{search_gateway, Tokio},
+        Gateway,
+    },
+    AddPortError, SearchOptions,
+};
+use std::{
+    fmt, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket},
+    str::FromStr,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::Instant,
+};
+use tracing::{debug, trace};
+
+#[cfg(feature = "serde")]
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+/// Default lease requested for mappings, as recommended by the NAT-PMP RFC.
+pub const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Default interval at which the gateway's external address is checked between renewals.
+pub const DEFAULT_EXTERNAL_IP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Default delay before retrying after a failed gateway search or mapping.
+pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Timeout for the UPnP gateway search.
+const UPNP_SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors that can occur when mapping ports on the gateway.
+#[derive(Debug, thiserror::Error)]
+pub enum PortMappingError {
+    /// I/O error while talking to the gateway.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// No gateway supporting the configured method was found.
+    #[error("no gateway found")]
+    NoGateway,
+    /// The gateway did not respond in time.
+    #[error("gateway did not respond")]
+    Timeout,
+    /// The gateway does not support the protocol version.
+    #[error("unsupported protocol version")]
+    UnsupportedVersion,
+    /// The gateway sent a response that could not be parsed.
+    #[error("malformed response")]
+    MalformedResponse,
+    /// The gateway rejected the request with the given result code.
+    #[error("request rejected with result code {0}")]
+    Rejected(u16),
+    /// The gateway does not know its external address.
+    #[error("gateway has no external address")]
+    NoExternalAddress,
+    /// A UPnP request failed.
+    #[error("upnp: {0}")]
+    Upnp(String),
+}
+
+/// Transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MappingProtocol {
+    /// TCP, used for `RLPx`.
+    Tcp,
+    /// UDP, used for discovery.
+    Udp,
+}
+
+impl MappingProtocol {
+    /// Returns the IANA protocol number.
+    pub const fn iana_number(&self) -> u8 {
+        match self {
+            MappingProtocol::Tcp => 6,
+            MappingProtocol::Udp => 17,
+        }
+    }
+}
+
+impl fmt::Display for MappingProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingProtocol::Tcp => f.write_str("tcp"),
+            MappingProtocol::Udp => f.write_str("udp"),
+        }
+    }
+}
+
+impl From<MappingProtocol> for igd_next::PortMappingProtocol {
+    fn from(protocol: MappingProtocol) -> Self {
+        match protocol {
+            MappingProtocol::Tcp => igd_next::PortMappingProtocol::TCP,
+            MappingProtocol::Udp => igd_next::PortMappingProtocol::UDP,
+        }
+    }
+}
+
+/// Which protocol to use for mapping ports on the gateway.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(SerializeDisplay, DeserializeFromStr))]
+pub enum PortMappingMethod {
+    /// Use UPnP if a gateway is found, NAT-PMP/PCP otherwise.
+    #[default]
+    Any,
+    /// Map via UPnP IGD.
+    Upnp,
+    /// Map via PCP, falling back to NAT-PMP.
+    NatPmp,
+}
+
+impl fmt::Display for PortMappingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortMappingMethod::Any => f.write_str("any"),
+            PortMappingMethod::Upnp => f.write_str("upnp"),
+            PortMappingMethod::NatPmp => f.write_str("natpmp"),
+        }
+    }
+}
+
+/// Error when parsing a [PortMappingMethod]
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown port mapping method: {0}")]
+pub struct ParsePortMappingMethodError(String);
+
+impl FromStr for PortMappingMethod {
+    type Err = ParsePortMappingMethodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(PortMappingMethod::Any),
+            "upnp" => Ok(PortMappingMethod::Upnp),
+            "natpmp" | "nat-pmp" | "pcp" => Ok(PortMappingMethod::NatPmp),
+            s => Err(ParsePortMappingMethodError(s.to_string())),
+        }
+    }
+}
+
+/// Configures the [`PortMappingService`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortMappingConfig {
+    /// Which protocol to map ports with.
+    pub method: PortMappingMethod,
+    /// The lease requested for mappings, renewed when half of it elapsed.
+    pub lease_duration: Duration,
+    /// How often the gateway's external address is checked between renewals.
+    pub external_ip_interval: Duration,
+    /// How long to wait before retrying after a failure.
+    pub retry_interval: Duration,
+    /// The NAT-PMP/PCP server to use, defaults to the gateway of the default route.
+    pub natpmp_gateway: Option<SocketAddr>,
+    /// Description attached to UPnP mappings.
+    pub description: String,
+}
+
+impl PortMappingConfig {
+    /// Creates a new config for the given method with default timings.
+    pub fn new(method: PortMappingMethod) -> Self {
+        Self { method, ..Default::default() }
+    }
+
+    /// Sets the lease requested for mappings.
+    pub fn with_lease_duration(mut self, lease_duration: Duration) -> Self {
+        self.lease_duration = lease_duration;
+        self
+    }
+
+    /// Sets the NAT-PMP/PCP server to use instead of the default route gateway.
+    pub fn with_natpmp_gateway(mut self, gateway: SocketAddr) -> Self {
+        self.natpmp_gateway = Some(gateway);
+        self
+    }
+}
+
+impl Default for PortMappingConfig {
+    fn default() -> Self {
+        Self {
+            method: PortMappingMethod::Any,
+            lease_duration: DEFAULT_LEASE_DURATION,
+            external_ip_interval: DEFAULT_EXTERNAL_IP_INTERVAL,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            natpmp_gateway: None,
+            description: "reth".to_string(),
+        }
+    }
+}
+
+/// A local port that should be reachable from outside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortMappingRequest {
+    /// The transport protocol.
+    pub protocol: MappingProtocol,
+    /// The local port.
+    pub internal_port: u16,
+    /// The preferred external port, the gateway may assign a different one.
+    pub external_port: u16,
+}
+
+impl PortMappingRequest {
+    /// Requests the local port to be mapped to the same external port.
+    pub const fn new(protocol: MappingProtocol, port: u16) -> Self {
+        Self { protocol, internal_port: port, external_port: port }
+    }
+}
+
+/// A mapping that is active on the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortMapping {
+    /// The transport protocol.
+    pub protocol: MappingProtocol,
+    /// The local port.
+    pub internal_port: u16,
+    /// The external address the local port is reachable at.
+    pub external: SocketAddr,
+    /// The lease granted by the gateway.
+    pub lifetime: Duration,
+}
+
+/// A gateway that supports port mapping.
+#[derive(Debug)]
+pub enum PortMapper {
+    /// A UPnP Internet Gateway Device.
+    Upnp(Gateway<Tokio>),
+    /// A NAT-PMP or PCP server.
+    NatPmp(NatPmpClient),
+}
+
+// === impl PortMapper ===
+
+impl PortMapper {
+    /// Searches for a gateway supporting the configured method.
+    pub async fn discover(config: &PortMappingConfig) -> Result<Self, PortMappingError> {
+        match config.method {
+            PortMappingMethod::Upnp => Self::discover_upnp().await,
+            PortMappingMethod::NatPmp => Self::discover_natpmp(config).await,
+            PortMappingMethod::Any => match Self::discover_upnp().await {
+                Ok(mapper) => Ok(mapper),
+                Err(err) => {
+                    trace!(target: "net::nat", %err, "No UPnP gateway found, trying NAT-PMP");
+                    Self::discover_natpmp(config).await
+                }
+            },
+        }
+    }
+
+    async fn discover_upnp() -> Result<Self, PortMappingError> {
+        let options = SearchOptions { timeout: Some(UPNP_SEARCH_TIMEOUT), ..Default::default() };
+        let gateway = search_gateway(options).await.map_err(|err| {
+            trace!(target: "net::nat", %err, "UPnP gateway search failed");
+            PortMappingError::NoGateway
+        })?;
+        Ok(Self::Upnp(gateway))
+    }
+
+    async fn discover_natpmp(config: &PortMappingConfig) -> Result<Self, PortMappingError> {
+        let client = match config.natpmp_gateway {
+            Some(gateway) => NatPmpClient::new(gateway),
+            None => NatPmpClient::from_default_gateway().ok_or(PortMappingError::NoGateway)?,
+        };
+        // make sure there's a server listening
+        match client.external_ip().await {
+            Ok(_) | Err(PortMappingError::NoExternalAddress) => Ok(Self::NatPmp(client)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the external address of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        match self {
+            Self::Upnp(gateway) => gateway
+                .get_external_ip()
+                .await
+                .map_err(|err| PortMappingError::Upnp(err.to_string())),
+            Self::NatPmp(client) => client.external_ip().await,
+        }
+    }
+
+    /// Maps the requested port on the gateway.
+    pub async fn map(
+        &self,
+        request: PortMappingRequest,
+        lease: Duration,
+        description: &str,
+    ) -> Result<PortMapping, PortMappingError> {
+        let PortMappingRequest { protocol, internal_port, external_port } = request;
+        match self {
+            Self::Upnp(gateway) => {
+                let local_addr = SocketAddr::new(local_ip_towards(gateway.addr)?, internal_port);
+                let lease_secs = u32::try_from(lease.as_secs()).unwrap_or(u32::MAX);
+                let mut lifetime = lease;
+                let mut res = gateway
+                    .add_port(protocol.into(), external_port, local_addr, lease_secs, description)
+                    .await;
+                if let Err(AddPortError::OnlyPermanentLeasesSupported) = res {
+                    // still renewed periodically to recover from gateway restarts
+                    lifetime = Duration::ZERO;
+                    res = gateway
+                        .add_port(protocol.into(), external_port, local_addr, 0, description)
+                        .await;
+                }
+                res.map_err(|err| PortMappingError::Upnp(err.to_string()))?;
+                let ip = self.external_ip().await?;
+                Ok(PortMapping {
+                    protocol,
+                    internal_port,
+                    external: SocketAddr::new(ip, external_port),
+                    lifetime,
+                })
+            }
+            Self::NatPmp(client) => {
+                let mapped = client.map(protocol, internal_port, external_port, lease).await?;
+                let ip = match mapped.external_ip {
+                    Some(ip) => ip,
+                    None => client.external_ip().await?,
+                };
+                Ok(PortMapping {
+                    protocol,
+                    internal_port,
+                    external: SocketAddr::new(ip, mapped.external_port),
+                    lifetime: mapped.lifetime,
+                })
+            }
+        }
+    }
+
+    /// Removes the mapping from the gateway.
+    pub async fn unmap(&self, mapping: &PortMapping) -> Result<(), PortMappingError> {
+        match self {
+            Self::Upnp(gateway) => gateway
+                .remove_port(mapping.protocol.into(), mapping.external.port())
+                .await
+                .map_err(|err| PortMappingError::Upnp(err.to_string())),
+            Self::NatPmp(client) => client.unmap(mapping.protocol, mapping.internal_port).await,
+        }
+    }
+}
+
+/// Returns the local address used to reach the given gateway.
+fn local_ip_towards(gateway: SocketAddr) -> io::Result<IpAddr> {
+    // connecting a UDP socket only selects the route, nothing is sent
+    let bind_addr: SocketAddr = match gateway {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(bind_addr)?;
+    socket.connect(gateway)?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// Updates emitted by the [`PortMappingService`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortMappingEvent {
+    /// A mapping was created, or its external address changed.
+    Mapped(PortMapping),
+    /// A mapping could not be renewed and is no longer active.
+    Removed(PortMapping),
+    /// The external address of the gateway changed.
+    ExternalIpChanged(IpAddr),
+}
+
+/// Handle to a spawned [`PortMappingService`].
+///
+/// Dropping the handle shuts the service down, which removes all mappings from the gateway.
+#[derive(Debug)]
+pub struct PortMappingHandle {
+    /// Updates from the service.
+    events: mpsc::UnboundedReceiver<PortMappingEvent>,
+    /// Tells the service to shut down, acknowledged once all mappings are removed.
+    shutdown: oneshot::Sender<oneshot::Sender<()>>,
+}
+
+// === impl PortMappingHandle ===
+
+impl PortMappingHandle {
+    /// Returns the next update of the service.
+    pub async fn next_event(&mut self) -> Option<PortMappingEvent> {
+        self.events.recv().await
+    }
+
+    /// Polls the next update of the service.
+    pub fn poll_next_event(&mut self, cx: &mut Context<'_>) -> Poll<Option<PortMappingEvent>> {
+        self.events.poll_recv(cx)
+    }
+
+    /// Shuts the service down and waits until all mappings are removed from the gateway.
+    pub async fn shutdown(self) {
+        let (tx, rx) = oneshot::channel();
+        if self.shutdown.send(tx).is_ok() {
+            let _ = rx.await;
+        }
+    }
+}
+
+/// Maps a set of ports on the gateway and keeps them alive.
+#[derive(Debug)]
+pub struct PortMappingService {
+    config: PortMappingConfig,
+    /// The gateway, once found.
+    mapper: Option<PortMapper>,
+    /// All requested mappings, with the active mapping and its renewal deadline.
+    mappings: Vec<(PortMappingRequest, Option<(PortMapping, Instant)>)>,
+    /// The last known external address of the gateway.
+    external_ip: Option<IpAddr>,
+    /// Sender half for updates.
+    events: mpsc::UnboundedSender<PortMappingEvent>,
+}
+
+// === impl PortMappingService ===
+
+impl PortMappingService {
+    /// Spawns the service onto a new task, mapping the given requests on the first gateway
+    /// supporting the configured method.
+    pub fn spawn(
+        config: PortMappingConfig,
+        requests: impl IntoIterator<Item = PortMappingRequest>,
+    ) -> PortMappingHandle {
+        Self::spawn_with_mapper(config, None, requests)
+    }
+
+    /// Same as [`PortMappingService::spawn`] but with an already discovered gateway.
+    pub fn spawn_with_mapper(
+        config: PortMappingConfig,
+        mapper: Option<PortMapper>,
+        requests: impl IntoIterator<Item = PortMappingRequest>,
+    ) -> PortMappingHandle {
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let service = Self {
+            config,
+            mapper,
+            mappings: requests.into_iter().map(|request| (request, None)).collect(),
+            external_ip: None,
+            events: events_tx,
+        };
+        tokio::spawn(service.run(shutdown_rx));
+        PortMappingHandle { events, shutdown }
+    }
+
+    async fn run(mut self, mut shutdown: oneshot::Receiver<oneshot::Sender<()>>) {
+        loop {
+            let delay = self.update().await;
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                ack = &mut shutdown => {
+                    self.unmap_all().await;
+                    if let Ok(ack) = ack {
+                        let _ = ack.send(());
+                    }
+                    return
+                }
+            }
+        }
+    }
+
+    /// Creates or renews all due mappings and checks the external address.
+    ///
+    /// Returns the delay until the next update.
+    async fn update(&mut self) -> Duration {
+        if self.mapper.is_none() {
+            match PortMapper::discover(&self.config).await {
+                Ok(mapper) => {
+                    debug!(target: "net::nat", ?mapper, "Found gateway for port mapping");
+                    self.mapper = Some(mapper);
+                }
+                Err(err) => {
+                    debug!(target: "net::nat", %err, method=%self.config.method, "Failed to find gateway for port mapping");
+                    return self.config.retry_interval
+                }
+            }
+        }
+        let Some(mapper) = &self.mapper else { return self.config.retry_interval };
+
+        let mut next_update = self.config.external_ip_interval;
+        let mut external_ip = None;
+        for (request, active) in &mut self.mappings {
+            let now = Instant::now();
+            if let Some((_, renew_at)) = active {
+                if *renew_at > now {
+                    next_update = next_update.min(*renew_at - now);
+                    continue
+                }
+            }
+
+            match mapper.map(*request, self.config.lease_duration, &self.config.description).await {
+                Ok(mapping) => {
+                    trace!(target: "net::nat", ?mapping, "Mapped port");
+                    if active.as_ref().map(|(prev, _)| prev.external) != Some(mapping.external) {
+                        debug!(target: "net::nat", protocol=%mapping.protocol, port=mapping.internal_port, external=%mapping.external, "Port mapped on gateway");
+                        let _ = self.events.send(PortMappingEvent::Mapped(mapping));
+                    }
+                    // renew after half of the lease elapsed
+                    let renew_in = if mapping.lifetime.is_zero() {
+                        self.config.lease_duration / 2
+                    } else {
+                        mapping.lifetime / 2
+                    };
+                    next_update = next_update.min(renew_in);
+                    external_ip = Some(mapping.external.ip());
+                    *active = Some((mapping, now + renew_in));
+                }
+                Err(err) => {
+                    debug!(target: "net::nat", %err, ?request, "Failed to map port");
+                    if let Some((mapping, _)) = active.take() {
+                        let _ = self.events.send(PortMappingEvent::Removed(mapping));
+                    }
+                    next_update = next_update.min(self.config.retry_interval);
+                }
+            }
+        }
+
+        let external_ip = match external_ip {
+            Some(ip) => Some(ip),
+            None => mapper
+                .external_ip()
+                .await
+                .map_err(|err| {
+                    trace!(target: "net::nat", %err, "Failed to get external address of gateway");
+                })
+                .ok(),
+        };
+        if let Some(ip) = external_ip {
+            self.on_external_ip(ip);
+        }
+
+        next_update
+    }
+
+    /// Updates the external address of all active mappings if it changed.
+    fn on_external_ip(&mut self, ip: IpAddr) {
+        if self.external_ip == Some(ip) {
+            return
+        }
+        debug!(target: "net::nat", ?ip, prev=?self.external_ip, "External address changed");
+        self.external_ip = Some(ip);
+        let _ = self.events.send(PortMappingEvent::ExternalIpChanged(ip));
+
+        for (mapping, _) in self.mappings.iter_mut().filter_map(|(_, active)| active.as_mut()) {
+            if mapping.external.ip() != ip {
+                mapping.external.set_ip(ip);
+                let _ = self.events.send(PortMappingEvent::Mapped(*mapping));
+            }
+        }
+    }
+
+    /// Removes all active mappings from the gateway.
+    async fn unmap_all(&mut self) {
+        let Some(mapper) = &self.mapper else { return };
+        for (mapping, _) in self.mappings.iter_mut().filter_map(|(_, active)| active.take()) {
+            match mapper.unmap(&mapping).await {
+                Ok(()) => {
+                    debug!(target: "net::nat", protocol=%mapping.protocol, port=mapping.internal_port, "Removed port mapping")
+                }
+                Err(err) => {
+                    debug!(target: "net::nat", %err, ?mapping, "Failed to remove port mapping")
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::natpmp::{tests::MockGateway, NatPmpVersion};
+
+    #[test]
+    fn parse_method() {
+        for method in [PortMappingMethod::Any, PortMappingMethod::Upnp, PortMappingMethod::NatPmp] {
+            assert_eq!(method.to_string().parse::<PortMappingMethod>().unwrap(), method);
+        }
+        assert_eq!("pcp".parse::<PortMappingMethod>().unwrap(), PortMappingMethod::NatPmp);
+        assert!("none".parse::<PortMappingMethod>().is_err());
+    }
+
+    #[tokio::test]
+    async fn renews_and_unmaps_on_mock_gateway() {
+        let first_ip = Ipv4Addr::new(1, 2, 3, 4);
+        let second_ip = Ipv4Addr::new(5, 6, 7, 8);
+        // the gateway grants only 2s leases, so the service renews every second
+        let (gateway_addr, gateway) = MockGateway::spawn(NatPmpVersion::NatPmp, first_ip, 2).await;
+
+        let config =
+            PortMappingConfig::new(PortMappingMethod::NatPmp).with_natpmp_gateway(gateway_addr);
+        let mut handle = PortMappingService::spawn(
+            config,
+            [
+                PortMappingRequest::new(MappingProtocol::Tcp, 30303),
+                PortMappingRequest::new(MappingProtocol::Udp, 30304),
+            ],
+        );
+
+        let mapped = |ip: Ipv4Addr, protocol, port| {
+            PortMappingEvent::Mapped(PortMapping {
+                protocol,
+                internal_port: port,
+                external: SocketAddr::new(ip.into(), port),
+                lifetime: Duration::from_secs(2),
+            })
+        };
+        assert_eq!(handle.next_event().await, Some(mapped(first_ip, MappingProtocol::Tcp, 30303)));
+        assert_eq!(handle.next_event().await, Some(mapped(first_ip, MappingProtocol::Udp, 30304)));
+        assert_eq!(
+            handle.next_event().await,
+            Some(PortMappingEvent::ExternalIpChanged(first_ip.into()))
+        );
+
+        // the next renewal picks up the new address
+        gateway.set_external_ip(second_ip);
+        assert_eq!(handle.next_event().await, Some(mapped(second_ip, MappingProtocol::Tcp, 30303)));
+        assert_eq!(handle.next_event().await, Some(mapped(second_ip, MappingProtocol::Udp, 30304)));
+        assert_eq!(
+            handle.next_event().await,
+            Some(PortMappingEvent::ExternalIpChanged(second_ip.into()))
+        );
+
+        handle.shutdown().await;
+        let requests = gateway.requests.lock().unwrap();
+        assert!(requests.len() >= 6);
+        assert_eq!(&requests[requests.len() - 2..], &[(2, 30303, 0), (1, 30304, 0)]);
+    }
+}
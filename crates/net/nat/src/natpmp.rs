@@ -0,0 +1,526 @@
+//! Minimal NAT-PMP ([RFC 6886](https://datatracker.ietf.org/doc/html/rfc6886)) and PCP
+//! ([RFC 6887](https://datatracker.ietf.org/doc/html/rfc6887)) client.
+//!
+//! The client speaks PCP first and falls back to NAT-PMP if the gateway only understands the older
+//! protocol. Both protocols share the same server port and are backwards compatible in how a server
+//! signals an unsupported version.
+
+use crate::mapping::{MappingProtocol, PortMappingError};
+use std::{
+    fs, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+use tracing::trace;
+
+/// The port NAT-PMP and PCP servers listen on.
+pub const NATPMP_PORT: u16 = 5351;
+
+/// Timeout for the first attempt of a request, doubled on every retry.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc6886#section-3.1>
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Number of times a request is sent before giving up.
+///
+/// The RFC allows up to 9 attempts (~64s), which is far too long to block on an absent gateway.
+const MAX_ATTEMPTS: usize = 4;
+
+const NATPMP_VERSION: u8 = 0;
+const PCP_VERSION: u8 = 2;
+
+const NATPMP_OP_EXTERNAL_ADDRESS: u8 = 0;
+const NATPMP_OP_MAP_UDP: u8 = 1;
+const NATPMP_OP_MAP_TCP: u8 = 2;
+const PCP_OP_MAP: u8 = 1;
+
+/// Set in the opcode of all responses.
+const RESPONSE_BIT: u8 = 0x80;
+
+/// Result code for an unsupported version, same value in both protocols.
+const RESULT_UNSUPPORTED_VERSION: u16 = 1;
+
+const NATPMP_EXTERNAL_ADDRESS_RESPONSE_LEN: usize = 12;
+const NATPMP_MAP_RESPONSE_LEN: usize = 16;
+const PCP_MAP_LEN: usize = 60;
+
+/// Largest datagram a PCP server is allowed to send.
+const MAX_RESPONSE_LEN: usize = 1100;
+
+/// The protocol version a gateway was found to speak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NatPmpVersion {
+    /// NAT-PMP, version 0.
+    NatPmp,
+    /// Port Control Protocol, version 2.
+    Pcp,
+}
+
+/// A port mapping granted by the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MappedPort {
+    /// The external port assigned by the gateway.
+    pub external_port: u16,
+    /// The external address, only reported by PCP gateways.
+    pub external_ip: Option<IpAddr>,
+    /// The lifetime granted by the gateway.
+    pub lifetime: Duration,
+}
+
+/// A NAT-PMP/PCP client for a single gateway.
+#[derive(Debug)]
+pub struct NatPmpClient {
+    /// Address of the gateway's NAT-PMP/PCP server.
+    gateway: SocketAddr,
+    /// The protocol version detected on first use.
+    version: OnceLock<NatPmpVersion>,
+    /// PCP mapping nonce, which must be identical for renewals and deletions of a mapping.
+    nonce: [u8; 12],
+    /// The external address reported in the last PCP mapping response.
+    last_external_ip: Mutex<Option<IpAddr>>,
+}
+
+// === impl NatPmpClient ===
+
+impl NatPmpClient {
+    /// Creates a new client for the NAT-PMP/PCP server at the given address.
+    pub fn new(gateway: SocketAddr) -> Self {
+        Self {
+            gateway,
+            version: OnceLock::new(),
+            nonce: rand::random(),
+            last_external_ip: Mutex::new(None),
+        }
+    }
+
+    /// Creates a new client for the gateway of the default route, if one can be determined.
+    pub fn from_default_gateway() -> Option<Self> {
+        default_gateway().map(|ip| Self::new(SocketAddr::new(ip, NATPMP_PORT)))
+    }
+
+    /// Returns the address of the gateway.
+    pub const fn gateway(&self) -> SocketAddr {
+        self.gateway
+    }
+
+    /// Returns the protocol version the gateway speaks, if already detected.
+    pub fn version(&self) -> Option<NatPmpVersion> {
+        self.version.get().copied()
+    }
+
+    /// Requests the external address of the gateway.
+    ///
+    /// PCP has no dedicated request for this, so for PCP-only gateways this returns the address
+    /// reported by the last mapping.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let socket = self.connect().await?;
+        let response = match request(&socket, &[NATPMP_VERSION, NATPMP_OP_EXTERNAL_ADDRESS]).await {
+            Ok(response) => response,
+            Err(PortMappingError::UnsupportedVersion) => {
+                let last = *self.last_external_ip.lock().unwrap_or_else(|err| err.into_inner());
+                return last.ok_or(PortMappingError::NoExternalAddress)
+            }
+            Err(err) => return Err(err),
+        };
+        check_natpmp_response(&response, NATPMP_OP_EXTERNAL_ADDRESS)?;
+        if response.len() < NATPMP_EXTERNAL_ADDRESS_RESPONSE_LEN {
+            return Err(PortMappingError::MalformedResponse)
+        }
+        let ip = Ipv4Addr::new(response[8], response[9], response[10], response[11]);
+        if ip.is_unspecified() {
+            return Err(PortMappingError::NoExternalAddress)
+        }
+        Ok(ip.into())
+    }
+
+    /// Requests a mapping of the local `internal_port` to `external_port` on the gateway.
+    ///
+    /// The gateway may assign a different external port and a shorter lifetime than requested.
+    pub async fn map(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+    ) -> Result<MappedPort, PortMappingError> {
+        let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+        match self.version() {
+            Some(NatPmpVersion::Pcp) => {
+                self.map_pcp(protocol, internal_port, external_port, lifetime).await
+            }
+            Some(NatPmpVersion::NatPmp) => {
+                self.map_natpmp(protocol, internal_port, external_port, lifetime).await
+            }
+            None => match self.map_pcp(protocol, internal_port, external_port, lifetime).await {
+                Ok(mapped) => {
+                    let _ = self.version.set(NatPmpVersion::Pcp);
+                    Ok(mapped)
+                }
+                Err(PortMappingError::UnsupportedVersion | PortMappingError::Timeout) => {
+                    trace!(target: "net::nat", gateway=%self.gateway, "PCP unsupported, falling back to NAT-PMP");
+                    let mapped =
+                        self.map_natpmp(protocol, internal_port, external_port, lifetime).await?;
+                    let _ = self.version.set(NatPmpVersion::NatPmp);
+                    Ok(mapped)
+                }
+                Err(err) => Err(err),
+            },
+        }
+    }
+
+    /// Deletes the mapping of the local `internal_port`.
+    pub async fn unmap(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+    ) -> Result<(), PortMappingError> {
+        // a deletion is a mapping request with a lifetime and suggested external port of zero
+        match self.version() {
+            Some(NatPmpVersion::NatPmp) => {
+                self.map_natpmp(protocol, internal_port, 0, 0).await?;
+            }
+            _ => {
+                self.map_pcp(protocol, internal_port, 0, 0).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn map_natpmp(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: u32,
+    ) -> Result<MappedPort, PortMappingError> {
+        let op = match protocol {
+            MappingProtocol::Udp => NATPMP_OP_MAP_UDP,
+            MappingProtocol::Tcp => NATPMP_OP_MAP_TCP,
+        };
+        let mut packet = [0u8; 12];
+        packet[0] = NATPMP_VERSION;
+        packet[1] = op;
+        packet[4..6].copy_from_slice(&internal_port.to_be_bytes());
+        packet[6..8].copy_from_slice(&external_port.to_be_bytes());
+        packet[8..12].copy_from_slice(&lifetime.to_be_bytes());
+
+        let socket = self.connect().await?;
+        let response = request(&socket, &packet).await?;
+        check_natpmp_response(&response, op)?;
+        if response.len() < NATPMP_MAP_RESPONSE_LEN ||
+            u16::from_be_bytes([response[8], response[9]]) != internal_port
+        {
+            return Err(PortMappingError::MalformedResponse)
+        }
+
+        Ok(MappedPort {
+            external_port: u16::from_be_bytes([response[10], response[11]]),
+            external_ip: None,
+            lifetime: Duration::from_secs(u32::from_be_bytes([
+                response[12],
+                response[13],
+                response[14],
+                response[15],
+            ]) as u64),
+        })
+    }
+
+    async fn map_pcp(
+        &self,
+        protocol: MappingProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: u32,
+    ) -> Result<MappedPort, PortMappingError> {
+        let socket = self.connect().await?;
+        let client_ip = socket.local_addr()?.ip();
+
+        let mut packet = [0u8; PCP_MAP_LEN];
+        // common request header
+        packet[0] = PCP_VERSION;
+        packet[1] = PCP_OP_MAP;
+        packet[4..8].copy_from_slice(&lifetime.to_be_bytes());
+        packet[8..24].copy_from_slice(&to_ipv6(client_ip).octets());
+        // MAP opcode payload
+        packet[24..36].copy_from_slice(&self.nonce);
+        packet[36] = protocol.iana_number();
+        packet[40..42].copy_from_slice(&internal_port.to_be_bytes());
+        packet[42..44].copy_from_slice(&external_port.to_be_bytes());
+        // no preference for the external address
+        let suggested_ip = match client_ip {
+            IpAddr::V4(_) => Ipv4Addr::UNSPECIFIED.to_ipv6_mapped(),
+            IpAddr::V6(_) => Ipv6Addr::UNSPECIFIED,
+        };
+        packet[44..60].copy_from_slice(&suggested_ip.octets());
+
+        let response = request(&socket, &packet).await?;
+        if response.len() < 4 {
+            return Err(PortMappingError::MalformedResponse)
+        }
+        if response[0] != PCP_VERSION {
+            // NAT-PMP servers reply with their own version
+            return Err(PortMappingError::UnsupportedVersion)
+        }
+        if response[1] != PCP_OP_MAP | RESPONSE_BIT {
+            return Err(PortMappingError::MalformedResponse)
+        }
+        check_result_code(response[3] as u16)?;
+        if response.len() < PCP_MAP_LEN ||
+            response[24..36] != self.nonce ||
+            u16::from_be_bytes([response[40], response[41]]) != internal_port
+        {
+            return Err(PortMappingError::MalformedResponse)
+        }
+
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(&response[44..60]);
+        let ip = Ipv6Addr::from(octets);
+        let external_ip = ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(ip));
+        let external_ip = (!external_ip.is_unspecified()).then_some(external_ip);
+        if external_ip.is_some() {
+            *self.last_external_ip.lock().unwrap_or_else(|err| err.into_inner()) = external_ip;
+        }
+
+        Ok(MappedPort {
+            external_port: u16::from_be_bytes([response[42], response[43]]),
+            external_ip,
+            lifetime: Duration::from_secs(u32::from_be_bytes([
+                response[4],
+                response[5],
+                response[6],
+                response[7],
+            ]) as u64),
+        })
+    }
+
+    /// Opens a socket connected to the gateway.
+    async fn connect(&self) -> io::Result<UdpSocket> {
+        let bind_addr: SocketAddr = match self.gateway {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(bind_addr).await?;
+        socket.connect(self.gateway).await?;
+        Ok(socket)
+    }
+}
+
+/// Sends the request and waits for the response, retrying with exponential backoff.
+async fn request(socket: &UdpSocket, packet: &[u8]) -> Result<Vec<u8>, PortMappingError> {
+    let mut buf = [0u8; MAX_RESPONSE_LEN];
+    let mut timeout = INITIAL_TIMEOUT;
+    for _ in 0..MAX_ATTEMPTS {
+        socket.send(packet).await?;
+        match tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+            Ok(res) => return Ok(buf[..res?].to_vec()),
+            Err(_) => timeout *= 2,
+        }
+    }
+    Err(PortMappingError::Timeout)
+}
+
+/// Validates the header of a NAT-PMP response to a request with the given opcode.
+fn check_natpmp_response(response: &[u8], op: u8) -> Result<(), PortMappingError> {
+    if response.len() < 4 {
+        return Err(PortMappingError::MalformedResponse)
+    }
+    if response[0] != NATPMP_VERSION {
+        // PCP servers reply with their own version
+        return Err(PortMappingError::UnsupportedVersion)
+    }
+    if response[1] != op | RESPONSE_BIT {
+        return Err(PortMappingError::MalformedResponse)
+    }
+    check_result_code(u16::from_be_bytes([response[2], response[3]]))
+}
+
+fn check_result_code(code: u16) -> Result<(), PortMappingError> {
+    match code {
+        0 => Ok(()),
+        RESULT_UNSUPPORTED_VERSION => Err(PortMappingError::UnsupportedVersion),
+        code => Err(PortMappingError::Rejected(code)),
+    }
+}
+
+fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+/// Returns the IPv4 gateway of the default route.
+///
+/// This is only supported on Linux, where it's read from `/proc/net/route`.
+pub fn default_gateway() -> Option<IpAddr> {
+    let routes = fs::read_to_string("/proc/net/route").ok()?;
+    parse_default_gateway(&routes)
+}
+
+fn parse_default_gateway(routes: &str) -> Option<IpAddr> {
+    // RTF_UP | RTF_GATEWAY
+    const FLAGS: u16 = 0x3;
+
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let destination = fields.next()?;
+        let gateway = u32::from_str_radix(fields.next()?, 16).ok()?;
+        let flags = u16::from_str_radix(fields.next()?, 16).ok()?;
+        (destination == "00000000" && flags & FLAGS == FLAGS)
+            // addresses are in host byte order
+            .then(|| IpAddr::V4(Ipv4Addr::from(gateway.to_le_bytes())))
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// A mock gateway speaking either NAT-PMP or PCP.
+    #[derive(Debug)]
+    pub(crate) struct MockGateway {
+        socket: UdpSocket,
+        version: NatPmpVersion,
+        external_ip: Mutex<Ipv4Addr>,
+        lifetime: u32,
+        /// All received mapping requests as `(protocol, internal port, lifetime)`.
+        pub(crate) requests: Mutex<Vec<(u8, u16, u32)>>,
+    }
+
+    impl MockGateway {
+        pub(crate) async fn spawn(
+            version: NatPmpVersion,
+            external_ip: Ipv4Addr,
+            lifetime: u32,
+        ) -> (SocketAddr, Arc<Self>) {
+            let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let addr = socket.local_addr().unwrap();
+            let gateway = Arc::new(Self {
+                socket,
+                version,
+                external_ip: Mutex::new(external_ip),
+                lifetime,
+                requests: Default::default(),
+            });
+            tokio::spawn(Arc::clone(&gateway).run());
+            (addr, gateway)
+        }
+
+        pub(crate) fn set_external_ip(&self, ip: Ipv4Addr) {
+            *self.external_ip.lock().unwrap() = ip;
+        }
+
+        async fn run(self: Arc<Self>) {
+            let mut buf = [0u8; MAX_RESPONSE_LEN];
+            loop {
+                let (n, from) = self.socket.recv_from(&mut buf).await.unwrap();
+                let response = self.on_request(&buf[..n]);
+                self.socket.send_to(&response, from).await.unwrap();
+            }
+        }
+
+        fn on_request(&self, req: &[u8]) -> Vec<u8> {
+            let external_ip = *self.external_ip.lock().unwrap();
+            match (self.version, req[0]) {
+                (NatPmpVersion::NatPmp, NATPMP_VERSION) if req[1] == NATPMP_OP_EXTERNAL_ADDRESS => {
+                    let mut resp = vec![0, RESPONSE_BIT, 0, 0, 0, 0, 0, 1];
+                    resp.extend_from_slice(&external_ip.octets());
+                    resp
+                }
+                (NatPmpVersion::NatPmp, NATPMP_VERSION) => {
+                    let internal_port = u16::from_be_bytes([req[4], req[5]]);
+                    let lifetime = u32::from_be_bytes([req[8], req[9], req[10], req[11]]);
+                    self.requests.lock().unwrap().push((req[1], internal_port, lifetime));
+                    let granted = lifetime.min(self.lifetime);
+                    let mut resp = vec![0, req[1] | RESPONSE_BIT, 0, 0, 0, 0, 0, 1];
+                    resp.extend_from_slice(&req[4..6]);
+                    resp.extend_from_slice(if lifetime == 0 { &[0, 0] } else { &req[4..6] });
+                    resp.extend_from_slice(&granted.to_be_bytes());
+                    resp
+                }
+                (NatPmpVersion::Pcp, PCP_VERSION) => {
+                    let internal_port = u16::from_be_bytes([req[40], req[41]]);
+                    let lifetime = u32::from_be_bytes([req[4], req[5], req[6], req[7]]);
+                    self.requests.lock().unwrap().push((req[36], internal_port, lifetime));
+                    let granted = lifetime.min(self.lifetime);
+                    let mut resp = vec![0u8; PCP_MAP_LEN];
+                    resp[0] = PCP_VERSION;
+                    resp[1] = PCP_OP_MAP | RESPONSE_BIT;
+                    resp[4..8].copy_from_slice(&granted.to_be_bytes());
+                    resp[24..44].copy_from_slice(&req[24..44]);
+                    resp[42..44].copy_from_slice(&req[40..42]);
+                    resp[44..60].copy_from_slice(&external_ip.to_ipv6_mapped().octets());
+                    resp
+                }
+                // unsupported version
+                (NatPmpVersion::NatPmp, _) => vec![NATPMP_VERSION, req[1] | RESPONSE_BIT, 0, 1],
+                (NatPmpVersion::Pcp, _) => {
+                    let mut resp = vec![0u8; 24];
+                    resp[0] = PCP_VERSION;
+                    resp[1] = req[1] | RESPONSE_BIT;
+                    resp[3] = RESULT_UNSUPPORTED_VERSION as u8;
+                    resp
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn natpmp_fallback() {
+        let external = Ipv4Addr::new(1, 2, 3, 4);
+        let (addr, gateway) = MockGateway::spawn(NatPmpVersion::NatPmp, external, 60).await;
+        let client = NatPmpClient::new(addr);
+
+        let mapped = client
+            .map(MappingProtocol::Tcp, 30303, 30303, Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert_eq!(client.version(), Some(NatPmpVersion::NatPmp));
+        assert_eq!(mapped.external_port, 30303);
+        assert_eq!(mapped.external_ip, None);
+        assert_eq!(mapped.lifetime, Duration::from_secs(60));
+        assert_eq!(client.external_ip().await.unwrap(), IpAddr::V4(external));
+
+        client.unmap(MappingProtocol::Tcp, 30303).await.unwrap();
+        assert_eq!(
+            *gateway.requests.lock().unwrap(),
+            vec![(NATPMP_OP_MAP_TCP, 30303, 3600), (NATPMP_OP_MAP_TCP, 30303, 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn pcp_map() {
+        let external = Ipv4Addr::new(1, 2, 3, 4);
+        let (addr, gateway) = MockGateway::spawn(NatPmpVersion::Pcp, external, 60).await;
+        let client = NatPmpClient::new(addr);
+
+        let mapped = client
+            .map(MappingProtocol::Udp, 30303, 30303, Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert_eq!(client.version(), Some(NatPmpVersion::Pcp));
+        assert_eq!(mapped.external_port, 30303);
+        assert_eq!(mapped.external_ip, Some(IpAddr::V4(external)));
+        assert_eq!(client.external_ip().await.unwrap(), IpAddr::V4(external));
+
+        client.unmap(MappingProtocol::Udp, 30303).await.unwrap();
+        assert_eq!(*gateway.requests.lock().unwrap(), vec![(17, 30303, 3600), (17, 30303, 0)]);
+    }
+
+    #[test]
+    fn parse_route_table() {
+        let routes = "\
+Iface	Destination	Gateway 	Flags	RefCnt	Use	Metric	Mask		MTU	Window	IRTT
+eth0	0000A8C0	00000000	0001	0	0	100	00FFFFFF	0	0	0
+eth0	00000000	0100A8C0	0003	0	0	100	00000000	0	0	0
+";
+        assert_eq!(parse_default_gateway(routes), Some("192.168.0.1".parse().unwrap()));
+        assert_eq!(
+            parse_default_gateway(&routes.lines().take(2).collect::<Vec<_>>().join("\n")),
+            None
+        );
+    }
+}
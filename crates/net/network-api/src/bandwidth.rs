@@ -0,0 +1,48 @@
+use reth_eth_wire::{capability::Capability, EthMessageID};
+
+/// Number of bytes exchanged with a peer in each direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ByteCount {
+    /// Bytes received from the peer.
+    pub inbound: u64,
+    /// Bytes sent to the peer.
+    pub outbound: u64,
+}
+
+impl ByteCount {
+    /// Returns true if no bytes were exchanged.
+    pub fn is_zero(&self) -> bool {
+        self.inbound == 0 && self.outbound == 0
+    }
+}
+
+impl std::ops::Add for ByteCount {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self { inbound: self.inbound + rhs.inbound, outbound: self.outbound + rhs.outbound }
+    }
+}
+
+/// The bytes exchanged with a peer over its session, by message type and protocol.
+///
+/// Sizes are the uncompressed sizes of the messages, including their message id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerBandwidth {
+    /// Bytes of `eth` messages by message type, only including message types that were exchanged.
+    pub eth: Vec<(EthMessageID, ByteCount)>,
+    /// Bytes of all additional RLPx subprotocols of the session.
+    pub protocols: Vec<(Capability, ByteCount)>,
+}
+
+impl PeerBandwidth {
+    /// Returns the bytes of all `eth` messages.
+    pub fn eth_total(&self) -> ByteCount {
+        self.eth.iter().fold(ByteCount::default(), |acc, (_, count)| acc + *count)
+    }
+
+    /// Returns the bytes of all messages of all protocols.
+    pub fn total(&self) -> ByteCount {
+        self.protocols.iter().fold(self.eth_total(), |acc, (_, count)| acc + *count)
+    }
+}
@@ -15,17 +15,29 @@
 
 use reth_eth_wire::{DisconnectReason, EthVersion, Status};
 use reth_primitives::{NodeRecord, PeerId};
-use std::{future::Future, net::SocketAddr, sync::Arc, time::Instant};
-
+use std::{
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+pub use bandwidth::{ByteCount, PeerBandwidth};
 pub use error::NetworkError;
 pub use reputation::{Reputation, ReputationChangeKind};
 use reth_eth_wire::capability::Capabilities;
+pub use reth_net_common::ban_list::{BanList, IpRange};
 use reth_rpc_types::NetworkStatus;
+pub use score::PeerScore;
 
+/// Bandwidth accounting
+pub mod bandwidth;
 /// Network Error
 pub mod error;
 /// Reputation score
 pub mod reputation;
+/// Response quality score
+pub mod score;
 
 /// Implementation of network traits for that does nothing.
 pub mod noop;
@@ -137,6 +149,39 @@ pub trait Peers: PeersInfo {
         &self,
         peer_id: PeerId,
     ) -> impl Future<Output = Result<Option<Reputation>, NetworkError>> + Send;
+
+    /// Resets the reputation of all known peers to the default value.
+    fn reset_reputations(&self);
+
+    /// Bans the peer for the given duration, or indefinitely if `None`.
+    ///
+    /// The peer is disconnected if it is currently connected.
+    fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>);
+
+    /// Lifts the ban of the peer.
+    fn unban_peer(&self, peer_id: PeerId);
+
+    /// Bans all addresses of the ip range for the given duration, or indefinitely if `None`.
+    ///
+    /// All connected peers with an address in the range are disconnected.
+    fn ban_ip_range(&self, range: IpRange, duration: Option<Duration>);
+
+    /// Lifts the ban of the ip range.
+    fn unban_ip_range(&self, range: IpRange);
+
+    /// Returns the currently banned peers and ip addresses.
+    fn ban_list(&self) -> impl Future<Output = Result<BanList, NetworkError>> + Send;
+
+    /// Changes the maximum number of inbound and outbound connections, limits that are `None` are
+    /// kept.
+    ///
+    /// Established sessions are kept when a limit is lowered.
+    fn set_peer_limits(&self, max_inbound: Option<usize>, max_outbound: Option<usize>);
+
+    /// Toggles whether only trusted peers are accepted and connected to.
+    ///
+    /// When enabled, all connected peers that are not trusted are disconnected.
+    fn set_trusted_nodes_only(&self, trusted_only: bool);
 }
 
 /// Represents the kind of peer
@@ -182,6 +227,10 @@ pub struct PeerInfo {
     pub status: Arc<Status>,
     /// The timestamp when the session to that peer has been established.
     pub session_established: Instant,
+    /// The score of the responses the peer served, if any request was sent to the peer.
+    pub score: Option<PeerScore>,
+    /// The bytes exchanged with the peer over the session.
+    pub bandwidth: PeerBandwidth,
 }
 
 /// The direction of the connection.
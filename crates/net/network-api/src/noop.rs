@@ -4,7 +4,7 @@
 //! generic over it.
 
 use crate::{
-    NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
+    BanList, IpRange, NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
     ReputationChangeKind,
 };
 use enr::{secp256k1::SecretKey, Enr};
@@ -12,7 +12,10 @@ use reth_discv4::DEFAULT_DISCOVERY_PORT;
 use reth_eth_wire::{DisconnectReason, ProtocolVersion};
 use reth_primitives::{Chain, NodeRecord, PeerId};
 use reth_rpc_types::{admin::EthProtocolInfo, NetworkStatus};
-use std::net::{IpAddr, SocketAddr};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
 /// A type that implements all network trait that does nothing.
 ///
@@ -100,4 +103,22 @@ impl Peers for NoopNetwork {
     async fn reputation_by_id(&self, _peer_id: PeerId) -> Result<Option<Reputation>, NetworkError> {
         Ok(None)
     }
+
+    fn reset_reputations(&self) {}
+
+    fn ban_peer(&self, _peer_id: PeerId, _duration: Option<Duration>) {}
+
+    fn unban_peer(&self, _peer_id: PeerId) {}
+
+    fn ban_ip_range(&self, _range: IpRange, _duration: Option<Duration>) {}
+
+    fn unban_ip_range(&self, _range: IpRange) {}
+
+    async fn ban_list(&self) -> Result<BanList, NetworkError> {
+        Ok(BanList::default())
+    }
+
+    fn set_peer_limits(&self, _max_inbound: Option<usize>, _max_outbound: Option<usize>) {}
+
+    fn set_trusted_nodes_only(&self, _trusted_only: bool) {}
 }
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+/// The highest possible score of a peer, which is also the score a new peer starts with.
+pub const MAX_PEER_SCORE: f64 = 100.0;
+
+/// How much the latest response contributes to the moving averages of a [`PeerScore`].
+const SAMPLE_WEIGHT: f64 = 0.1;
+
+/// The response latency at which a response only counts half as much as an instant response.
+const REFERENCE_LATENCY: Duration = Duration::from_millis(500);
+
+/// How much a valid response that contains none of the requested items counts, relative to a
+/// complete response.
+///
+/// Honest peers may not have all requested items, e.g. if they only keep partial history, so empty
+/// or short responses are only a small penalty.
+const EMPTY_RESPONSE_WEIGHT: f64 = 0.8;
+
+/// Tracks how useful a peer is when serving requests.
+///
+/// Unlike the [`Reputation`](crate::Reputation), which only changes on discrete events, the score
+/// is an exponential moving average over the quality of all responses of the peer: fast and
+/// complete responses keep the score close to [`MAX_PEER_SCORE`], slow responses lower it,
+/// incomplete responses lower it slightly and timeouts or bad responses count as zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeerScore {
+    /// The moving average of the response quality, in `0..=MAX_PEER_SCORE`.
+    score: f64,
+    /// The moving average of the latency of answered requests.
+    latency: Option<Duration>,
+    /// Total number of bytes the peer served.
+    bytes_served: u64,
+    /// Total number of requests that were answered or failed because of the peer.
+    requests: u64,
+    /// Number of requests that timed out.
+    timeouts: u64,
+    /// Number of requests that were answered with a bad response.
+    bad_responses: u64,
+}
+
+// === impl PeerScore ===
+
+impl PeerScore {
+    /// Returns the current score of the peer, in `0..=MAX_PEER_SCORE`.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Returns the average latency of the answered requests, if any.
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// Returns the total number of bytes the peer served.
+    pub fn bytes_served(&self) -> u64 {
+        self.bytes_served
+    }
+
+    /// Returns the number of requests that were scored.
+    pub fn requests(&self) -> u64 {
+        self.requests
+    }
+
+    /// Returns the number of requests that timed out.
+    pub fn timeouts(&self) -> u64 {
+        self.timeouts
+    }
+
+    /// Returns the number of requests that were answered with a bad response.
+    pub fn bad_responses(&self) -> u64 {
+        self.bad_responses
+    }
+
+    /// Returns the share of requests that timed out or were answered with a bad response.
+    pub fn failure_ratio(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0
+        }
+        (self.timeouts + self.bad_responses) as f64 / self.requests as f64
+    }
+
+    /// Records a valid response that arrived after `latency`, carrying `bytes` bytes.
+    ///
+    /// The `usefulness` is the share of the requested items the response contains, in `0..=1`. An
+    /// empty response still counts [`EMPTY_RESPONSE_WEIGHT`] as much as a complete one.
+    pub fn on_response(&mut self, latency: Duration, usefulness: f64, bytes: usize) {
+        self.requests += 1;
+        self.bytes_served = self.bytes_served.saturating_add(bytes as u64);
+        self.latency = Some(match self.latency {
+            Some(avg) => avg.mul_f64(1.0 - SAMPLE_WEIGHT) + latency.mul_f64(SAMPLE_WEIGHT),
+            None => latency,
+        });
+
+        let reference = REFERENCE_LATENCY.as_secs_f64();
+        let speed = reference / (reference + latency.as_secs_f64());
+        let completeness =
+            EMPTY_RESPONSE_WEIGHT + (1.0 - EMPTY_RESPONSE_WEIGHT) * usefulness.clamp(0.0, 1.0);
+        self.record(MAX_PEER_SCORE * speed * completeness);
+    }
+
+    /// Records a request that timed out.
+    pub fn on_timeout(&mut self) {
+        self.requests += 1;
+        self.timeouts += 1;
+        self.record(0.0);
+    }
+
+    /// Records a request that was answered with a bad response.
+    pub fn on_bad_response(&mut self) {
+        self.requests += 1;
+        self.bad_responses += 1;
+        self.record(0.0);
+    }
+
+    /// Adds the quality of a response to the moving average.
+    fn record(&mut self, sample: f64) {
+        self.score = self.score * (1.0 - SAMPLE_WEIGHT) + sample * SAMPLE_WEIGHT;
+    }
+}
+
+impl Default for PeerScore {
+    fn default() -> Self {
+        Self {
+            score: MAX_PEER_SCORE,
+            latency: None,
+            bytes_served: 0,
+            requests: 0,
+            timeouts: 0,
+            bad_responses: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_decays_on_failures() {
+        let mut score = PeerScore::default();
+        assert_eq!(score.score(), MAX_PEER_SCORE);
+
+        score.on_timeout();
+        score.on_bad_response();
+        assert_eq!(score.requests(), 2);
+        assert_eq!(score.failure_ratio(), 1.0);
+        assert!((score.score() - 81.0).abs() < 1e-9);
+
+        for _ in 0..100 {
+            score.on_timeout();
+        }
+        assert!(score.score() < 1.0);
+    }
+
+    #[test]
+    fn fast_peers_score_higher() {
+        let mut fast = PeerScore::default();
+        let mut slow = PeerScore::default();
+        let mut empty = PeerScore::default();
+        for _ in 0..50 {
+            fast.on_response(Duration::from_millis(50), 1.0, 1000);
+            slow.on_response(Duration::from_secs(5), 1.0, 1000);
+            empty.on_response(Duration::from_millis(50), 0.0, 0);
+        }
+
+        assert!(fast.score() > 85.0);
+        assert!(slow.score() < 15.0);
+        assert_eq!(fast.bytes_served(), 50_000);
+        assert_eq!(fast.failure_ratio(), 0.0);
+
+        // honest peers that don't have the requested items are only penalized slightly
+        assert!(empty.score() > 65.0 && empty.score() < fast.score());
+
+        let latency = slow.latency().unwrap();
+        assert!(latency > Duration::from_millis(4_999) && latency < Duration::from_millis(5_001));
+    }
+}
@@ -0,0 +1,358 @@
+//! Bandwidth accounting of sessions and limits for serving requests.
+
+use crate::metrics::BandwidthMetrics;
+use alloy_rlp::Encodable;
+use futures::Stream;
+use reth_eth_wire::{capability::Capability, EthBroadcastMessage, EthMessage, EthMessageID};
+use reth_net_common::ratelimit::TokenBucket;
+use reth_network_api::{ByteCount, PeerBandwidth};
+use reth_primitives::{BytesMut, PeerId};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+};
+
+/// Number of tracked per peer buckets at which buckets that are full again are dropped.
+const PRUNE_PEER_BUCKETS_THRESHOLD: usize = 256;
+
+/// Byte counters of a single session, shared between the
+/// [`ActiveSession`](crate::session::ActiveSession) and its handle.
+///
+/// Counts the uncompressed size of all messages, including their message id, so that the numbers
+/// are comparable across protocols.
+#[derive(Debug)]
+pub struct SessionBandwidth {
+    /// Counters of `eth` messages, indexed by [`EthMessageID`].
+    eth: Vec<ByteCounter>,
+    /// Metrics of `eth` messages, indexed by [`EthMessageID`].
+    eth_metrics: Vec<BandwidthMetrics>,
+    /// Counters of the additional RLPx subprotocols of the session.
+    protocols: Vec<(Capability, ByteCounter)>,
+}
+
+// === impl SessionBandwidth ===
+
+impl SessionBandwidth {
+    /// Creates new counters for a session.
+    pub(crate) fn new() -> Self {
+        let len = EthMessageID::max() as usize + 1;
+        let eth_metrics = (0..len)
+            .map(|id| {
+                let message = EthMessageID::try_from(id)
+                    .map(|id| format!("{id:?}"))
+                    .unwrap_or_else(|_| format!("{id:#04x}"));
+                BandwidthMetrics::new_with_labels(&[("message", message)])
+            })
+            .collect();
+        Self {
+            eth: (0..len).map(|_| ByteCounter::default()).collect(),
+            eth_metrics,
+            protocols: Vec::new(),
+        }
+    }
+
+    /// Registers an additional subprotocol and returns its counter.
+    pub(crate) fn add_protocol(&mut self, cap: Capability) -> ByteCounter {
+        let counter = ByteCounter::default();
+        self.protocols.push((cap, counter.clone()));
+        counter
+    }
+
+    /// Removes a subprotocol that couldn't be installed.
+    pub(crate) fn remove_protocol(&mut self, cap: &Capability) {
+        self.protocols.retain(|(c, _)| c != cap);
+    }
+
+    /// Records a message received from the peer.
+    pub(crate) fn on_inbound(&self, msg: &EthMessage) {
+        let id = msg.message_id();
+        let size = (id.length() + msg.length()) as u64;
+        self.eth[id as usize].add_inbound(size);
+        self.eth_metrics[id as usize].inbound_bytes.increment(size);
+    }
+
+    /// Records a message sent to the peer.
+    pub(crate) fn on_outbound(&self, msg: &EthMessage) {
+        self.record_outbound(msg.message_id(), msg.length());
+    }
+
+    /// Records a broadcast message sent to the peer.
+    pub(crate) fn on_outbound_broadcast(&self, msg: &EthBroadcastMessage) {
+        self.record_outbound(msg.message_id(), msg.length());
+    }
+
+    fn record_outbound(&self, id: EthMessageID, payload_length: usize) {
+        let size = (id.length() + payload_length) as u64;
+        self.eth[id as usize].add_outbound(size);
+        self.eth_metrics[id as usize].outbound_bytes.increment(size);
+    }
+
+    /// Returns the current value of all counters.
+    pub(crate) fn snapshot(&self) -> PeerBandwidth {
+        let eth = self
+            .eth
+            .iter()
+            .enumerate()
+            .filter_map(|(id, counter)| {
+                let count = counter.get();
+                if count.is_zero() {
+                    return None
+                }
+                Some((EthMessageID::try_from(id).ok()?, count))
+            })
+            .collect();
+        let protocols =
+            self.protocols.iter().map(|(cap, counter)| (cap.clone(), counter.get())).collect();
+        PeerBandwidth { eth, protocols }
+    }
+}
+
+/// Inbound and outbound byte counters that can be shared between tasks.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ByteCounter {
+    inbound: Arc<AtomicU64>,
+    outbound: Arc<AtomicU64>,
+}
+
+// === impl ByteCounter ===
+
+impl ByteCounter {
+    /// Returns the inbound counter, which can be handed to the
+    /// [`ProtocolConnection`](reth_eth_wire::multiplex::ProtocolConnection) of a subprotocol.
+    pub(crate) fn inbound(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.inbound)
+    }
+
+    fn add_inbound(&self, bytes: u64) {
+        self.inbound.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn add_outbound(&self, bytes: u64) {
+        self.outbound.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> ByteCount {
+        ByteCount {
+            inbound: self.inbound.load(Ordering::Relaxed),
+            outbound: self.outbound.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Wraps the outgoing message stream of an RLPx subprotocol and counts the bytes of all messages
+/// it yields.
+///
+/// Inbound bytes are counted by the protocol's connection, they're reported to the metrics
+/// whenever this stream is polled.
+pub(crate) struct MeteredProtocolStream<S> {
+    inner: S,
+    counter: ByteCounter,
+    metrics: BandwidthMetrics,
+    /// Inbound bytes that were already reported to the metrics.
+    reported_inbound: u64,
+}
+
+impl<S> MeteredProtocolStream<S> {
+    /// Creates a new stream that records into the given counter.
+    pub(crate) fn new(inner: S, cap: &Capability, counter: ByteCounter) -> Self {
+        let metrics = BandwidthMetrics::new_with_labels(&[("protocol", cap.to_string())]);
+        Self { inner, counter, metrics, reported_inbound: 0 }
+    }
+}
+
+impl<S> std::fmt::Debug for MeteredProtocolStream<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MeteredProtocolStream").field("counter", &self.counter).finish()
+    }
+}
+
+impl<S> Stream for MeteredProtocolStream<S>
+where
+    S: Stream<Item = BytesMut> + Unpin,
+{
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        let inbound = this.counter.inbound.load(Ordering::Relaxed);
+        if inbound > this.reported_inbound {
+            this.metrics.inbound_bytes.increment(inbound - this.reported_inbound);
+            this.reported_inbound = inbound;
+        }
+
+        let msg = ready!(Pin::new(&mut this.inner).poll_next(cx));
+        if let Some(msg) = &msg {
+            this.counter.add_outbound(msg.len() as u64);
+            this.metrics.outbound_bytes.increment(msg.len() as u64);
+        }
+        Poll::Ready(msg)
+    }
+}
+
+/// A limit on the bandwidth used for serving requests, enforced with a token bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BandwidthLimit {
+    /// The sustained rate in bytes per second.
+    pub bytes_per_sec: u64,
+    /// The number of bytes that can be served in a burst, before the rate applies.
+    pub burst: u64,
+}
+
+impl BandwidthLimit {
+    /// Creates a new limit with the given rate, allowing a burst of one second worth of bytes.
+    pub fn new(bytes_per_sec: u64) -> Self {
+        Self { bytes_per_sec, burst: bytes_per_sec }
+    }
+
+    /// Sets the number of bytes that can be served in a burst.
+    pub fn with_burst(mut self, burst: u64) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    fn bucket(&self) -> TokenBucket {
+        TokenBucket::new(self.bytes_per_sec, self.burst)
+    }
+}
+
+/// Limits on the bandwidth used for serving requests of peers.
+///
+/// Responses are truncated to the remaining budget, peers that exhausted their budget receive
+/// empty responses until it's refilled. By default, serving is unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServeLimits {
+    /// The limit across all peers.
+    pub total: Option<BandwidthLimit>,
+    /// The limit for each individual peer.
+    pub per_peer: Option<BandwidthLimit>,
+}
+
+impl ServeLimits {
+    /// Sets the limit across all peers.
+    pub fn with_total(mut self, limit: BandwidthLimit) -> Self {
+        self.total = Some(limit);
+        self
+    }
+
+    /// Sets the limit for each individual peer.
+    pub fn with_per_peer(mut self, limit: BandwidthLimit) -> Self {
+        self.per_peer = Some(limit);
+        self
+    }
+
+    /// Returns true if no limit is configured.
+    pub fn is_unlimited(&self) -> bool {
+        self.total.is_none() && self.per_peer.is_none()
+    }
+}
+
+/// Enforces [`ServeLimits`] on the responses of a request handler.
+#[derive(Debug, Default)]
+pub(crate) struct ServeRateLimiter {
+    limits: ServeLimits,
+    total: Option<TokenBucket>,
+    peers: HashMap<PeerId, TokenBucket>,
+}
+
+// === impl ServeRateLimiter ===
+
+impl ServeRateLimiter {
+    /// Creates a new limiter that enforces the given limits.
+    pub(crate) fn new(limits: ServeLimits) -> Self {
+        Self { limits, total: limits.total.map(|limit| limit.bucket()), peers: HashMap::new() }
+    }
+
+    /// Returns true if serving is unlimited.
+    pub(crate) fn is_unlimited(&self) -> bool {
+        self.limits.is_unlimited()
+    }
+
+    /// Returns the number of bytes that can be served to the peer right now, `None` if serving is
+    /// unlimited.
+    pub(crate) fn available(&mut self, peer_id: &PeerId) -> Option<u64> {
+        let total = self.total.as_mut().map(|bucket| bucket.available());
+        let peer = self.limits.per_peer.map(|limit| match self.peers.get_mut(peer_id) {
+            Some(bucket) => bucket.available(),
+            None => limit.burst,
+        });
+        match (total, peer) {
+            (Some(total), Some(peer)) => Some(total.min(peer)),
+            (total, peer) => total.or(peer),
+        }
+    }
+
+    /// Records that the given number of bytes were served to the peer.
+    pub(crate) fn on_served(&mut self, peer_id: PeerId, bytes: u64) {
+        if let Some(bucket) = self.total.as_mut() {
+            bucket.consume(bytes);
+        }
+        let Some(limit) = self.limits.per_peer else { return };
+        if !self.peers.contains_key(&peer_id) && self.peers.len() >= PRUNE_PEER_BUCKETS_THRESHOLD {
+            // a full bucket is equivalent to an untracked peer
+            self.peers.retain(|_, bucket| !bucket.is_full());
+        }
+        self.peers.entry(peer_id).or_insert_with(|| limit.bucket()).consume(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_eth_wire::{GetBlockBodies, RequestPair};
+    use reth_primitives::B256;
+
+    #[test]
+    fn session_bandwidth_snapshot() {
+        let mut bandwidth = SessionBandwidth::new();
+        let snap = bandwidth.add_protocol(Capability::new_static("snap", 1));
+
+        let request = EthMessage::GetBlockBodies(RequestPair {
+            request_id: 1,
+            message: GetBlockBodies(vec![B256::ZERO; 2]),
+        });
+        bandwidth.on_inbound(&request);
+        bandwidth.on_outbound(&request);
+        bandwidth.on_outbound(&request);
+        snap.add_inbound(100);
+
+        let snapshot = bandwidth.snapshot();
+        let size = 1 + request.length() as u64;
+        assert_eq!(
+            snapshot.eth,
+            vec![(EthMessageID::GetBlockBodies, ByteCount { inbound: size, outbound: 2 * size })]
+        );
+        assert_eq!(
+            snapshot.protocols,
+            vec![(Capability::new_static("snap", 1), ByteCount { inbound: 100, outbound: 0 })]
+        );
+        assert_eq!(snapshot.total(), ByteCount { inbound: size + 100, outbound: 2 * size });
+    }
+
+    #[test]
+    fn serve_rate_limiter() {
+        let mut unlimited = ServeRateLimiter::default();
+        assert_eq!(unlimited.available(&PeerId::random()), None);
+
+        let limits = ServeLimits::default()
+            .with_total(BandwidthLimit::new(1).with_burst(1_000))
+            .with_per_peer(BandwidthLimit::new(1).with_burst(600));
+        let mut limiter = ServeRateLimiter::new(limits);
+        let (a, b) = (PeerId::random(), PeerId::random());
+
+        assert_eq!(limiter.available(&a), Some(600));
+        limiter.on_served(a, 500);
+        assert!(limiter.available(&a).unwrap() < 110);
+        // the total budget is shared
+        assert!(limiter.available(&b).unwrap() < 510);
+        limiter.on_served(b, 600);
+        assert_eq!(limiter.available(&a), Some(0));
+    }
+}
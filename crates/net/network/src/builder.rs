@@ -2,9 +2,12 @@
 
 use crate::{
     eth_requests::EthRequestHandler,
+    snap::{SnapProtocolHandler, SnapRequestHandler},
     transactions::{TransactionsManager, TransactionsManagerConfig},
     NetworkHandle, NetworkManager,
 };
+use reth_provider::BlockNumReader;
+use reth_tasks::pool::BlockingTaskPool;
 use reth_transaction_pool::TransactionPool;
 use tokio::sync::mpsc;
 
@@ -12,6 +15,9 @@ use tokio::sync::mpsc;
 /// 256 requests with malicious 10MB body requests is 2.6GB which can be absorbed by the node.
 pub(crate) const ETH_REQUEST_CHANNEL_CAPACITY: usize = 256;
 
+/// The max channel capacity of the [`SnapRequestHandler`], responses are soft-limited to 2MB.
+pub(crate) const SNAP_REQUEST_CHANNEL_CAPACITY: usize = 256;
+
 /// A builder that can configure all components of the network.
 #[allow(missing_debug_implementations)]
 pub struct NetworkBuilder<C, Tx, Eth> {
@@ -74,7 +80,30 @@ impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
         let (tx, rx) = mpsc::channel(ETH_REQUEST_CHANNEL_CAPACITY);
         network.set_eth_request_handler(tx);
         let peers = network.handle().peers_handle().clone();
-        let request_handler = EthRequestHandler::new(client, peers, rx);
+        let config = network.eth_requests_config().clone();
+        let request_handler = EthRequestHandler::new(client, peers, rx).with_config(config);
         NetworkBuilder { network, request_handler, transactions }
     }
 }
+
+impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth>
+where
+    C: BlockNumReader,
+{
+    /// Creates a new [`SnapRequestHandler`] and registers the `snap/1` sub-protocol with the
+    /// network.
+    ///
+    /// Once registered, `snap` requests of peers are delegated to the returned handler, which needs
+    /// to be spawned and serves them on the given [`BlockingTaskPool`], and the
+    /// [`FetchClient`](crate::FetchClient) can send `snap` requests.
+    pub fn snap_request_handler<DB, Client>(
+        &mut self,
+        client: Client,
+        blocking_task_pool: BlockingTaskPool,
+    ) -> SnapRequestHandler<DB, Client> {
+        let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+        let peers = self.network.fetch_client().snap_peers;
+        self.network.add_rlpx_sub_protocol(SnapProtocolHandler::new(peers, tx));
+        SnapRequestHandler::new(client, rx, blocking_task_pool)
+    }
+}
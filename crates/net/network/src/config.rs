@@ -2,6 +2,7 @@
 
 use crate::{
     error::NetworkError,
+    eth_requests::EthRequestsConfig,
     import::{BlockImport, ProofOfStakeBlockImport},
     peers::PeersConfig,
     session::SessionsConfig,
@@ -12,6 +13,7 @@ use reth_discv4::{Discv4Config, Discv4ConfigBuilder, DEFAULT_DISCOVERY_ADDRESS};
 use reth_discv5::config::OPSTACK;
 use reth_dns_discovery::DnsDiscoveryConfig;
 use reth_eth_wire::{HelloMessage, HelloMessageWithProtocols, Status};
+use reth_net_nat::PortMappingConfig;
 use reth_primitives::{
     mainnet_nodes, pk2id, sepolia_nodes, ChainSpec, ForkFilter, Head, NodeRecord, PeerId, MAINNET,
 };
@@ -79,6 +81,10 @@ pub struct NetworkConfig<C> {
     pub tx_gossip_disabled: bool,
     /// How to instantiate transactions manager.
     pub transactions_manager_config: TransactionsManagerConfig,
+    /// How to map the listener and discovery ports on the local gateway, disabled if `None`.
+    pub port_mapping: Option<PortMappingConfig>,
+    /// How to serve `eth` requests for headers, bodies and receipts.
+    pub eth_requests_config: EthRequestsConfig,
 }
 
 // === impl NetworkConfig ===
@@ -207,6 +213,11 @@ pub struct NetworkConfigBuilder {
     block_import: Option<Box<dyn BlockImport>>,
     /// How to instantiate transactions manager.
     transactions_manager_config: TransactionsManagerConfig,
+    /// How to map ports on the local gateway.
+    #[serde(skip)]
+    port_mapping: Option<PortMappingConfig>,
+    /// How to serve `eth` requests.
+    eth_requests_config: EthRequestsConfig,
 }
 
 // === impl NetworkConfigBuilder ===
@@ -233,6 +244,8 @@ impl NetworkConfigBuilder {
             tx_gossip_disabled: false,
             block_import: None,
             transactions_manager_config: Default::default(),
+            port_mapping: None,
+            eth_requests_config: Default::default(),
         }
     }
 
@@ -303,6 +316,12 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the policies for serving `eth` requests.
+    pub fn eth_requests_config(mut self, config: EthRequestsConfig) -> Self {
+        self.eth_requests_config = config;
+        self
+    }
+
     /// Sets the discovery and listener address
     ///
     /// This is a convenience function for both [NetworkConfigBuilder::listener_addr] and
@@ -447,6 +466,14 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Maps the listener and discovery ports on the local gateway via UPnP or NAT-PMP/PCP.
+    ///
+    /// The external address of the mappings is advertised in the discovery records.
+    pub fn port_mapping(mut self, config: PortMappingConfig) -> Self {
+        self.port_mapping = Some(config);
+        self
+    }
+
     /// Convenience function for creating a [NetworkConfig] with a noop provider that does nothing.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn build_with_noop_provider(
@@ -482,6 +509,8 @@ impl NetworkConfigBuilder {
             tx_gossip_disabled,
             block_import,
             transactions_manager_config,
+            port_mapping,
+            eth_requests_config,
         } = self;
 
         let listener_addr = listener_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS);
@@ -536,6 +565,8 @@ impl NetworkConfigBuilder {
             fork_filter,
             tx_gossip_disabled,
             transactions_manager_config,
+            port_mapping,
+            eth_requests_config,
         }
     }
 }
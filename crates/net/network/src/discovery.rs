@@ -12,6 +12,10 @@ use reth_discv5::{DiscoveredPeer, Discv5};
 use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
+use reth_net_nat::{
+    MappingProtocol, PortMapping, PortMappingConfig, PortMappingEvent, PortMappingHandle,
+    PortMappingRequest, PortMappingService,
+};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use secp256k1::SecretKey;
 use std::{
@@ -23,7 +27,7 @@ use std::{
 };
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
-use tracing::trace;
+use tracing::{debug, trace};
 
 /// Default max capacity for cache of discovered peers.
 ///
@@ -62,6 +66,8 @@ pub struct Discovery {
     queued_events: VecDeque<DiscoveryEvent>,
     /// List of listeners subscribed to discovery events.
     discovery_listeners: Vec<mpsc::UnboundedSender<DiscoveryEvent>>,
+    /// Updates from the port mapping service, if enabled.
+    port_mapping: Option<PortMappingUpdates>,
 }
 
 impl Discovery {
@@ -129,9 +135,38 @@ impl Discovery {
             _dns_disc_service,
             _dns_discovery,
             dns_discovery_updates,
+            port_mapping: None,
         })
     }
 
+    /// Spawns the port mapping service for the `RLPx` port and the discovery ports.
+    ///
+    /// The external addresses of the mappings are advertised in the local records of discv4 and
+    /// discv5.
+    pub(crate) fn spawn_port_mapping(
+        &mut self,
+        config: PortMappingConfig,
+        tcp_port: u16,
+        discv5_port: Option<u16>,
+    ) {
+        let discv4_port = self.discv4.as_ref().map(|discv4| discv4.local_addr().port());
+        let discv5_port = discv5_port.filter(|port| *port != 0 && Some(*port) != discv4_port);
+        let requests = std::iter::once(PortMappingRequest::new(MappingProtocol::Tcp, tcp_port))
+            .chain(
+                discv4_port
+                    .into_iter()
+                    .chain(discv5_port)
+                    .map(|port| PortMappingRequest::new(MappingProtocol::Udp, port)),
+            );
+        let handle = PortMappingService::spawn(config, requests);
+        self.port_mapping = Some(PortMappingUpdates { handle, tcp_port, discv4_port, discv5_port });
+    }
+
+    /// Takes the handle of the port mapping service, if enabled.
+    pub(crate) fn take_port_mapping(&mut self) -> Option<PortMappingHandle> {
+        self.port_mapping.take().map(|mapping| mapping.handle)
+    }
+
     /// Registers a listener for receiving [DiscoveryEvent] updates.
     pub(crate) fn add_listener(&mut self, tx: mpsc::UnboundedSender<DiscoveryEvent>) {
         self.discovery_listeners.push(tx);
@@ -234,6 +269,45 @@ impl Discovery {
         }
     }
 
+    /// Advertises the external addresses of port mappings in the local records.
+    fn on_port_mapping_event(&self, event: PortMappingEvent) {
+        let Some(ports) = &self.port_mapping else { return };
+        match event {
+            PortMappingEvent::ExternalIpChanged(ip) => {
+                if let Some(discv4) = &self.discv4 {
+                    discv4.set_external_ip(ip);
+                }
+            }
+            PortMappingEvent::Mapped(PortMapping { protocol, internal_port, external, .. }) => {
+                debug!(target: "net::discovery", %protocol, internal_port, %external, "Advertising mapped port");
+                match protocol {
+                    MappingProtocol::Tcp if internal_port == ports.tcp_port => {
+                        if let Some(discv4) = &self.discv4 {
+                            discv4.set_tcp_port(external.port());
+                        }
+                        if let Some(discv5) = &self.discv5 {
+                            discv5.set_external_socket(external, true);
+                        }
+                    }
+                    MappingProtocol::Udp if Some(internal_port) == ports.discv4_port => {
+                        if let Some(discv4) = &self.discv4 {
+                            discv4.set_udp_port(external.port());
+                        }
+                    }
+                    MappingProtocol::Udp if Some(internal_port) == ports.discv5_port => {
+                        if let Some(discv5) = &self.discv5 {
+                            discv5.set_external_socket(external, false);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            PortMappingEvent::Removed(mapping) => {
+                trace!(target: "net::discovery", ?mapping, "Port mapping lost");
+            }
+        }
+    }
+
     pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<DiscoveryEvent> {
         loop {
             // Drain all buffered events first
@@ -276,6 +350,13 @@ impl Discovery {
                 self.on_node_record_update(update.node_record, update.fork_id);
             }
 
+            // drain the port mapping updates
+            while let Some(Poll::Ready(Some(event))) =
+                self.port_mapping.as_mut().map(|mapping| mapping.handle.poll_next_event(cx))
+            {
+                self.on_port_mapping_event(event);
+            }
+
             if self.queued_events.is_empty() {
                 return Poll::Pending
             }
@@ -318,10 +399,25 @@ impl Discovery {
             dns_discovery_updates: None,
             _dns_disc_service: None,
             discovery_listeners: Default::default(),
+            port_mapping: None,
         }
     }
 }
 
+/// The port mapping service and the local ports it maps, to route its updates to the matching
+/// discovery service.
+#[derive(Debug)]
+struct PortMappingUpdates {
+    /// Handle to the port mapping service.
+    handle: PortMappingHandle,
+    /// Local `RLPx` port.
+    tcp_port: u16,
+    /// Local discv4 port.
+    discv4_port: Option<u16>,
+    /// Local discv5 port.
+    discv5_port: Option<u16>,
+}
+
 /// Events produced by the [`Discovery`] manager.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DiscoveryEvent {
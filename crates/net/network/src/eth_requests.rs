@@ -1,7 +1,10 @@
 //! Blocks/Headers management for the p2p network.
 
 use crate::{
-    budget::DEFAULT_BUDGET_TRY_DRAIN_STREAM, metrics::EthRequestHandlerMetrics, peers::PeersHandle,
+    bandwidth::{ServeLimits, ServeRateLimiter},
+    budget::DEFAULT_BUDGET_TRY_DRAIN_STREAM,
+    metrics::EthRequestHandlerMetrics,
+    peers::PeersHandle,
     poll_nested_stream_with_budget,
 };
 use alloy_rlp::Encodable;
@@ -11,14 +14,22 @@ use reth_eth_wire::{
     Receipts,
 };
 use reth_interfaces::p2p::error::RequestResult;
-use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, PeerId};
+use reth_primitives::{
+    BlockBody, BlockHashOrNumber, BlockNumber, Header, HeadersDirection, PeerId, PruneMode,
+    PruneModes, MINIMUM_PRUNING_DISTANCE,
+};
 use reth_provider::{BlockReader, HeaderProvider, ReceiptProvider};
 use std::{
+    collections::{HashMap, VecDeque},
     future::Future,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc::Receiver, oneshot},
+    time::{Interval, MissedTickBehavior},
 };
-use tokio::sync::{mpsc::Receiver, oneshot};
 use tokio_stream::wrappers::ReceiverStream;
 
 // Limits: <https://github.com/ethereum/go-ethereum/blob/b0d44338bbcefee044f1f635a84487cbbd8f0538/eth/protocols/eth/handler.go#L34-L56>
@@ -42,6 +53,126 @@ const MAX_BODIES_SERVE: usize = 1024;
 /// Maximum size of replies to data retrievals.
 const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
 
+/// Default maximum number of queued requests for older history.
+const DEFAULT_MAX_QUEUED_HISTORY_REQUESTS: usize = 256;
+
+/// Default maximum number of queued requests for older history of a single peer.
+const DEFAULT_MAX_QUEUED_HISTORY_REQUESTS_PER_PEER: usize = 32;
+
+/// Default maximum number of queued requests for older history that are served per
+/// [`HISTORY_REQUESTS_REFILL_INTERVAL`].
+const DEFAULT_HISTORY_REQUESTS_PER_INTERVAL: usize = 8;
+
+/// Interval at which the budget for serving queued requests for older history is refilled.
+pub const HISTORY_REQUESTS_REFILL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Policies for serving `eth` requests for headers, bodies and receipts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct EthRequestsConfig {
+    /// Maximum number of headers served per request.
+    pub max_headers: usize,
+    /// Maximum number of block bodies served per request.
+    pub max_bodies: usize,
+    /// Maximum number of block receipts served per request.
+    pub max_receipts: usize,
+    /// Soft limit for the byte size of a response.
+    pub soft_response_limit: usize,
+    /// Number of blocks below the tip that are considered recent.
+    ///
+    /// If set, requests for recent blocks are served right away while requests for older history
+    /// are queued and served at a limited rate, so they can't delay the former.
+    pub recent_blocks: Option<u64>,
+    /// Maximum number of queued requests for older history, further requests for older history
+    /// are answered with empty responses.
+    pub max_queued_history_requests: usize,
+    /// Maximum number of queued requests for older history of a single peer, so that a single
+    /// peer can't fill the queue.
+    pub max_queued_history_requests_per_peer: usize,
+    /// Maximum number of queued requests for older history that are served per
+    /// [`HISTORY_REQUESTS_REFILL_INTERVAL`].
+    pub history_requests_per_interval: usize,
+    /// Limits on the bandwidth used for serving responses.
+    pub serve_limits: ServeLimits,
+    /// Receipts of blocks that are pruned according to any of these modes are not served.
+    ///
+    /// Derived from the node's prune configuration, see [`Self::with_prune_modes`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub pruned_receipts: Vec<PruneMode>,
+}
+
+impl EthRequestsConfig {
+    /// Sets the maximum number of headers served per request.
+    pub fn with_max_headers(mut self, max_headers: usize) -> Self {
+        self.max_headers = max_headers;
+        self
+    }
+
+    /// Sets the maximum number of block bodies served per request.
+    pub fn with_max_bodies(mut self, max_bodies: usize) -> Self {
+        self.max_bodies = max_bodies;
+        self
+    }
+
+    /// Sets the maximum number of block receipts served per request.
+    pub fn with_max_receipts(mut self, max_receipts: usize) -> Self {
+        self.max_receipts = max_receipts;
+        self
+    }
+
+    /// Serves requests for the given number of most recent blocks before older history.
+    pub fn with_recent_blocks(mut self, recent_blocks: u64) -> Self {
+        self.recent_blocks = Some(recent_blocks);
+        self
+    }
+
+    /// Sets the maximum number of queued requests for older history that are served per
+    /// [`HISTORY_REQUESTS_REFILL_INTERVAL`].
+    pub fn with_history_requests_per_interval(mut self, requests: usize) -> Self {
+        self.history_requests_per_interval = requests;
+        self
+    }
+
+    /// Sets the limits on the bandwidth used for serving responses.
+    pub fn with_serve_limits(mut self, limits: ServeLimits) -> Self {
+        self.serve_limits = limits;
+        self
+    }
+
+    /// Refuses to serve receipts that are pruned according to the node's prune modes.
+    pub fn with_prune_modes(mut self, modes: &PruneModes) -> Self {
+        self.pruned_receipts = modes.receipts.into_iter().collect();
+        if modes.receipts.is_none() && !modes.receipts_log_filter.is_empty() {
+            // receipts that don't match the filter are pruned, so older blocks may be incomplete
+            self.pruned_receipts.push(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE));
+        }
+        self
+    }
+
+    /// Returns true if the receipts of the block are pruned, given the current tip.
+    fn is_receipts_pruned(&self, block: BlockNumber, tip: BlockNumber) -> bool {
+        self.pruned_receipts.iter().any(|mode| mode.should_prune(block, tip))
+    }
+}
+
+impl Default for EthRequestsConfig {
+    fn default() -> Self {
+        Self {
+            max_headers: MAX_HEADERS_SERVE,
+            max_bodies: MAX_BODIES_SERVE,
+            max_receipts: MAX_RECEIPTS_SERVE,
+            soft_response_limit: SOFT_RESPONSE_LIMIT,
+            recent_blocks: None,
+            max_queued_history_requests: DEFAULT_MAX_QUEUED_HISTORY_REQUESTS,
+            max_queued_history_requests_per_peer: DEFAULT_MAX_QUEUED_HISTORY_REQUESTS_PER_PEER,
+            history_requests_per_interval: DEFAULT_HISTORY_REQUESTS_PER_INTERVAL,
+            serve_limits: ServeLimits::default(),
+            pruned_receipts: Vec::new(),
+        }
+    }
+}
+
 /// Manages eth related requests on top of the p2p network.
 ///
 /// This can be spawned to another task and is supposed to be run as background service.
@@ -58,6 +189,24 @@ pub struct EthRequestHandler<C> {
     incoming_requests: ReceiverStream<IncomingEthRequest>,
     /// Metrics for the eth request handler.
     metrics: EthRequestHandlerMetrics,
+    /// Limits the bandwidth used for serving responses.
+    limiter: ServeRateLimiter,
+    /// Serving policies.
+    config: EthRequestsConfig,
+    /// Requests for older history by peer, served after requests for recent blocks.
+    history_requests: HashMap<PeerId, VecDeque<IncomingEthRequest>>,
+    /// Peers with queued requests for older history, in the order they are served.
+    ///
+    /// Peers are served round-robin, one request at a time, so a peer with many queued requests
+    /// can't delay the requests of other peers.
+    history_peers: VecDeque<PeerId>,
+    /// Number of queued requests for older history of all peers.
+    queued_history_requests: usize,
+    /// Number of queued requests for older history that can be served until the budget is
+    /// refilled.
+    history_budget: usize,
+    /// Refills the budget for serving queued requests for older history.
+    history_refill_interval: Interval,
 }
 
 // === impl EthRequestHandler ===
@@ -65,7 +214,89 @@ impl<C> EthRequestHandler<C> {
     /// Create a new instance
     pub fn new(client: C, peers: PeersHandle, incoming: Receiver<IncomingEthRequest>) -> Self {
         let metrics = Default::default();
-        Self { client, peers, incoming_requests: ReceiverStream::new(incoming), metrics }
+        let mut history_refill_interval = tokio::time::interval(HISTORY_REQUESTS_REFILL_INTERVAL);
+        // the interval is only polled while the budget is exhausted
+        history_refill_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        Self {
+            client,
+            peers,
+            incoming_requests: ReceiverStream::new(incoming),
+            metrics,
+            limiter: Default::default(),
+            config: Default::default(),
+            history_requests: Default::default(),
+            history_peers: Default::default(),
+            queued_history_requests: 0,
+            // filled by the first tick of the interval, which completes immediately
+            history_budget: 0,
+            history_refill_interval,
+        }
+    }
+
+    /// Sets the serving policies.
+    pub fn with_config(mut self, config: EthRequestsConfig) -> Self {
+        self.limiter = ServeRateLimiter::new(config.serve_limits);
+        self.config = config;
+        self
+    }
+
+    /// Sets the limits on the bandwidth used for serving requests.
+    ///
+    /// Responses are truncated to the remaining budget of the peer, once the budget is exhausted
+    /// empty responses are served until it's refilled.
+    pub fn with_serve_limits(mut self, limits: ServeLimits) -> Self {
+        self.limiter = ServeRateLimiter::new(limits);
+        self.config.serve_limits = limits;
+        self
+    }
+
+    /// Returns the soft limit for the byte size of the next response to the peer.
+    fn response_limit(&mut self, peer_id: &PeerId) -> usize {
+        let soft_limit = self.config.soft_response_limit;
+        self.limiter
+            .available(peer_id)
+            .map_or(soft_limit, |available| soft_limit.min(available as usize))
+    }
+
+    /// Records a response of the given byte size served to the peer.
+    fn on_response(&mut self, peer_id: PeerId, bytes: usize, limit: usize) {
+        if limit < self.config.soft_response_limit && (limit == 0 || bytes > limit) {
+            self.metrics.throttled_responses.increment(1);
+        }
+        self.limiter.on_served(peer_id, bytes as u64);
+    }
+
+    /// Queues a request for older history, or answers it with an empty response if too many
+    /// requests are queued, in total or of the peer.
+    fn queue_history_request(&mut self, request: IncomingEthRequest) {
+        let peer_id = request.peer_id();
+        let queued_by_peer = self.history_requests.get(&peer_id).map_or(0, VecDeque::len);
+        if self.queued_history_requests >= self.config.max_queued_history_requests ||
+            queued_by_peer >= self.config.max_queued_history_requests_per_peer
+        {
+            self.metrics.rejected_history_requests.increment(1);
+            return request.respond_empty()
+        }
+
+        if queued_by_peer == 0 {
+            self.history_peers.push_back(peer_id);
+        }
+        self.history_requests.entry(peer_id).or_default().push_back(request);
+        self.queued_history_requests += 1;
+    }
+
+    /// Returns the next queued request for older history, of the next peer in turn.
+    fn pop_history_request(&mut self) -> Option<IncomingEthRequest> {
+        let peer_id = self.history_peers.pop_front()?;
+        let requests = self.history_requests.get_mut(&peer_id)?;
+        let request = requests.pop_front();
+        if requests.is_empty() {
+            self.history_requests.remove(&peer_id);
+        } else {
+            self.history_peers.push_back(peer_id);
+        }
+        self.queued_history_requests -= 1;
+        request
     }
 }
 
@@ -73,24 +304,82 @@ impl<C> EthRequestHandler<C>
 where
     C: BlockReader + HeaderProvider + ReceiptProvider,
 {
-    /// Returns the list of requested headers
-    fn get_headers_response(&self, request: GetBlockHeaders) -> Vec<Header> {
+    /// Serves requests for recent blocks right away and queues requests for older history.
+    fn on_incoming_request(&mut self, request: IncomingEthRequest) {
+        if !self.is_history_request(&request) {
+            return self.on_request(request)
+        }
+
+        self.queue_history_request(request)
+    }
+
+    /// Returns true if the request starts at a block older than the configured recent blocks.
+    fn is_history_request(&self, request: &IncomingEthRequest) -> bool {
+        let Some(recent_blocks) = self.config.recent_blocks else { return false };
+        let block = match request {
+            IncomingEthRequest::GetBlockHeaders { request, .. } => match request.start_block {
+                BlockHashOrNumber::Number(number) => Some(number),
+                BlockHashOrNumber::Hash(hash) => self.client.block_number(hash).unwrap_or_default(),
+            },
+            IncomingEthRequest::GetBlockBodies { request, .. } => request
+                .0
+                .first()
+                .and_then(|hash| self.client.block_number(*hash).unwrap_or_default()),
+            IncomingEthRequest::GetReceipts { request, .. } => request
+                .0
+                .first()
+                .and_then(|hash| self.client.block_number(*hash).unwrap_or_default()),
+            IncomingEthRequest::GetNodeData { .. } => None,
+        };
+        // unknown blocks are cheap to serve
+        let Some(block) = block else { return false };
+        let tip = self.client.best_block_number().unwrap_or_default();
+        block.saturating_add(recent_blocks) < tip
+    }
+
+    fn on_request(&mut self, request: IncomingEthRequest) {
+        match request {
+            IncomingEthRequest::GetBlockHeaders { peer_id, request, response } => {
+                self.on_headers_request(peer_id, request, response)
+            }
+            IncomingEthRequest::GetBlockBodies { peer_id, request, response } => {
+                self.on_bodies_request(peer_id, request, response)
+            }
+            IncomingEthRequest::GetNodeData { .. } => {}
+            IncomingEthRequest::GetReceipts { peer_id, request, response } => {
+                self.on_receipts_request(peer_id, request, response)
+            }
+        }
+    }
+
+    /// Returns the list of requested headers and their total byte size.
+    ///
+    /// Stops once the total size exceeds `size_limit`.
+    fn get_headers_response(
+        &self,
+        request: GetBlockHeaders,
+        size_limit: usize,
+    ) -> (Vec<Header>, usize) {
         let GetBlockHeaders { start_block, limit, skip, direction } = request;
 
         let mut headers = Vec::new();
+        let mut total_bytes = 0;
+
+        if size_limit == 0 {
+            return (headers, total_bytes)
+        }
 
         let mut block: BlockHashOrNumber = match start_block {
             BlockHashOrNumber::Hash(start) => start.into(),
             BlockHashOrNumber::Number(num) => {
                 let Some(hash) = self.client.block_hash(num).unwrap_or_default() else {
-                    return headers
+                    return (headers, total_bytes)
                 };
                 hash.into()
             }
         };
 
         let skip = skip as u64;
-        let mut total_bytes = 0;
 
         for _ in 0..limit {
             if let Some(header) = self.client.header_by_hash_or_number(block).unwrap_or_default() {
@@ -122,11 +411,11 @@ where
                 total_bytes += header.length();
                 headers.push(header);
 
-                if headers.len() >= MAX_HEADERS_SERVE {
+                if headers.len() >= self.config.max_headers {
                     break
                 }
 
-                if total_bytes > SOFT_RESPONSE_LIMIT {
+                if total_bytes > size_limit {
                     break
                 }
             } else {
@@ -134,27 +423,36 @@ where
             }
         }
 
-        headers
+        (headers, total_bytes)
     }
 
     fn on_headers_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetBlockHeaders,
         response: oneshot::Sender<RequestResult<BlockHeaders>>,
     ) {
         self.metrics.received_headers_requests.increment(1);
-        let headers = self.get_headers_response(request);
+        let size_limit = self.response_limit(&peer_id);
+        let (headers, total_bytes) = self.get_headers_response(request, size_limit);
+        self.on_response(peer_id, total_bytes, size_limit);
         let _ = response.send(Ok(BlockHeaders(headers)));
     }
 
     fn on_bodies_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetBlockBodies,
         response: oneshot::Sender<RequestResult<BlockBodies>>,
     ) {
         self.metrics.received_bodies_requests.increment(1);
+        let size_limit = self.response_limit(&peer_id);
+        if size_limit == 0 {
+            self.on_response(peer_id, 0, size_limit);
+            let _ = response.send(Ok(BlockBodies::default()));
+            return
+        }
+
         let mut bodies = Vec::new();
 
         let mut total_bytes = 0;
@@ -170,11 +468,11 @@ where
                 total_bytes += body.length();
                 bodies.push(body);
 
-                if bodies.len() >= MAX_BODIES_SERVE {
+                if bodies.len() >= self.config.max_bodies {
                     break
                 }
 
-                if total_bytes > SOFT_RESPONSE_LIMIT {
+                if total_bytes > size_limit {
                     break
                 }
             } else {
@@ -182,22 +480,53 @@ where
             }
         }
 
+        self.on_response(peer_id, total_bytes, size_limit);
         let _ = response.send(Ok(BlockBodies(bodies)));
     }
 
     fn on_receipts_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetReceipts,
         response: oneshot::Sender<RequestResult<Receipts>>,
     ) {
+        self.metrics.received_receipts_requests.increment(1);
+        let size_limit = self.response_limit(&peer_id);
+        if size_limit == 0 {
+            self.on_response(peer_id, 0, size_limit);
+            let _ = response.send(Ok(Receipts::default()));
+            return
+        }
+
         let mut receipts = Vec::new();
 
         let mut total_bytes = 0;
 
+        // receipts of pruned blocks are missing or incomplete, so the response stops before the
+        // first pruned block
+        let tip = if self.config.pruned_receipts.is_empty() {
+            None
+        } else {
+            Some(self.client.best_block_number().unwrap_or_default())
+        };
+
         for hash in request.0 {
+            let block = match tip {
+                Some(tip) => {
+                    let Some(number) = self.client.block_number(hash).unwrap_or_default() else {
+                        break
+                    };
+                    if self.config.is_receipts_pruned(number, tip) {
+                        self.metrics.pruned_receipts_responses.increment(1);
+                        break
+                    }
+                    BlockHashOrNumber::Number(number)
+                }
+                None => BlockHashOrNumber::Hash(hash),
+            };
+
             if let Some(receipts_by_block) =
-                self.client.receipts_by_block(BlockHashOrNumber::Hash(hash)).unwrap_or_default()
+                self.client.receipts_by_block(block).unwrap_or_default()
             {
                 let receipt = receipts_by_block
                     .into_iter()
@@ -207,11 +536,11 @@ where
                 total_bytes += receipt.length();
                 receipts.push(receipt);
 
-                if receipts.len() >= MAX_RECEIPTS_SERVE {
+                if receipts.len() >= self.config.max_receipts {
                     break
                 }
 
-                if total_bytes > SOFT_RESPONSE_LIMIT {
+                if total_bytes > size_limit {
                     break
                 }
             } else {
@@ -219,6 +548,7 @@ where
             }
         }
 
+        self.on_response(peer_id, total_bytes, size_limit);
         let _ = response.send(Ok(Receipts(receipts)));
     }
 }
@@ -240,22 +570,24 @@ where
             "Incoming eth requests stream",
             DEFAULT_BUDGET_TRY_DRAIN_STREAM,
             this.incoming_requests.poll_next_unpin(cx),
-            |incoming| {
-                match incoming {
-                    IncomingEthRequest::GetBlockHeaders { peer_id, request, response } => {
-                        this.on_headers_request(peer_id, request, response)
-                    }
-                    IncomingEthRequest::GetBlockBodies { peer_id, request, response } => {
-                        this.on_bodies_request(peer_id, request, response)
-                    }
-                    IncomingEthRequest::GetNodeData { .. } => {}
-                    IncomingEthRequest::GetReceipts { peer_id, request, response } => {
-                        this.on_receipts_request(peer_id, request, response)
-                    }
-                }
-            },
+            |incoming| this.on_incoming_request(incoming),
         );
 
+        // serve the queued requests for older history at a limited rate, once the budget is
+        // exhausted the interval wakes us up when it's refilled
+        while this.queued_history_requests > 0 {
+            if this.history_budget == 0 {
+                if this.history_refill_interval.poll_tick(cx).is_pending() {
+                    break
+                }
+                this.history_budget = this.config.history_requests_per_interval;
+            }
+            if let Some(request) = this.pop_history_request() {
+                this.history_budget -= 1;
+                this.on_request(request);
+            }
+        }
+
         // stream is fully drained and import futures pending
         if maybe_more_incoming_requests {
             // make sure we're woken up again
@@ -315,3 +647,209 @@ pub enum IncomingEthRequest {
         response: oneshot::Sender<RequestResult<Receipts>>,
     },
 }
+
+impl IncomingEthRequest {
+    /// Returns the peer that sent the request.
+    fn peer_id(&self) -> PeerId {
+        match self {
+            IncomingEthRequest::GetBlockHeaders { peer_id, .. } |
+            IncomingEthRequest::GetBlockBodies { peer_id, .. } |
+            IncomingEthRequest::GetNodeData { peer_id, .. } |
+            IncomingEthRequest::GetReceipts { peer_id, .. } => *peer_id,
+        }
+    }
+
+    /// Answers the request with an empty response.
+    fn respond_empty(self) {
+        match self {
+            IncomingEthRequest::GetBlockHeaders { response, .. } => {
+                let _ = response.send(Ok(BlockHeaders::default()));
+            }
+            IncomingEthRequest::GetBlockBodies { response, .. } => {
+                let _ = response.send(Ok(BlockBodies::default()));
+            }
+            IncomingEthRequest::GetNodeData { response, .. } => {
+                let _ = response.send(Ok(NodeData::default()));
+            }
+            IncomingEthRequest::GetReceipts { response, .. } => {
+                let _ = response.send(Ok(Receipts::default()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers::PeersManager;
+    use futures::task::noop_waker;
+    use reth_primitives::{Block, B256};
+    use reth_provider::test_utils::MockEthProvider;
+    use std::future::poll_fn;
+    use tokio::sync::mpsc;
+
+    fn handler(config: EthRequestsConfig, blocks: u64) -> EthRequestHandler<MockEthProvider> {
+        let provider = MockEthProvider::default();
+        for number in 0..blocks {
+            let block =
+                Block { header: Header { number, ..Default::default() }, ..Default::default() };
+            provider.add_block(B256::with_last_byte(number as u8 + 1), block);
+        }
+        let (_tx, rx) = mpsc::channel(1);
+        let peers = PeersManager::default().handle();
+        EthRequestHandler::new(provider, peers, rx).with_config(config)
+    }
+
+    fn headers_request(
+        start: u64,
+    ) -> (IncomingEthRequest, oneshot::Receiver<RequestResult<BlockHeaders>>) {
+        peer_headers_request(PeerId::random(), start)
+    }
+
+    fn peer_headers_request(
+        peer_id: PeerId,
+        start: u64,
+    ) -> (IncomingEthRequest, oneshot::Receiver<RequestResult<BlockHeaders>>) {
+        let (tx, rx) = oneshot::channel();
+        let request = IncomingEthRequest::GetBlockHeaders {
+            peer_id,
+            request: GetBlockHeaders {
+                start_block: BlockHashOrNumber::Number(start),
+                limit: 10,
+                skip: 0,
+                direction: HeadersDirection::Rising,
+            },
+            response: tx,
+        };
+        (request, rx)
+    }
+
+    #[tokio::test]
+    async fn serves_configured_max_headers() {
+        let mut handler = handler(EthRequestsConfig::default().with_max_headers(4), 10);
+
+        let (request, mut rx) = headers_request(0);
+        handler.on_incoming_request(request);
+        assert_eq!(rx.try_recv().unwrap().unwrap().0.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn queues_history_requests() {
+        let config = EthRequestsConfig {
+            recent_blocks: Some(2),
+            max_queued_history_requests: 1,
+            ..Default::default()
+        };
+        let mut handler = handler(config, 10);
+
+        // recent blocks are served right away
+        let (request, mut recent) = headers_request(8);
+        handler.on_incoming_request(request);
+        assert_eq!(recent.try_recv().unwrap().unwrap().0.len(), 2);
+
+        let (request, mut queued) = headers_request(0);
+        handler.on_incoming_request(request);
+        assert!(queued.try_recv().is_err());
+
+        // queue is full
+        let (request, mut rejected) = headers_request(1);
+        handler.on_incoming_request(request);
+        assert!(rejected.try_recv().unwrap().unwrap().0.is_empty());
+
+        let request = handler.pop_history_request().unwrap();
+        handler.on_request(request);
+        assert_eq!(queued.try_recv().unwrap().unwrap().0.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn serves_history_requests_round_robin() {
+        let config = EthRequestsConfig {
+            recent_blocks: Some(2),
+            max_queued_history_requests_per_peer: 2,
+            ..Default::default()
+        };
+        let mut handler = handler(config, 10);
+        let (busy, other) = (PeerId::random(), PeerId::random());
+
+        let mut busy_responses = Vec::new();
+        for start in 0..3 {
+            let (request, rx) = peer_headers_request(busy, start);
+            handler.on_incoming_request(request);
+            busy_responses.push(rx);
+        }
+        let (request, _other_response) = peer_headers_request(other, 0);
+        handler.on_incoming_request(request);
+
+        // the queue of the busy peer is full, the other peer is not affected
+        assert!(busy_responses[2].try_recv().unwrap().unwrap().0.is_empty());
+        assert_eq!(handler.queued_history_requests, 3);
+
+        // the other peer is served before the second request of the busy peer
+        let order = std::iter::from_fn(|| handler.pop_history_request())
+            .map(|request| request.peer_id())
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec![busy, other, busy]);
+        assert_eq!(handler.queued_history_requests, 0);
+        assert!(handler.history_requests.is_empty());
+    }
+
+    /// Drops the receivers of answered requests and returns their number.
+    fn answered(responses: &mut Vec<oneshot::Receiver<RequestResult<BlockHeaders>>>) -> usize {
+        let pending = responses.len();
+        responses.retain_mut(|rx| rx.try_recv().is_err());
+        pending - responses.len()
+    }
+
+    #[tokio::test]
+    async fn throttles_history_requests() {
+        let per_interval = 4;
+        let config = EthRequestsConfig::default()
+            .with_recent_blocks(2)
+            .with_history_requests_per_interval(per_interval);
+        let mut handler = handler(config, 10);
+
+        let mut responses = Vec::new();
+        for _ in 0..2 * per_interval + 1 {
+            let (request, rx) = headers_request(0);
+            handler.on_incoming_request(request);
+            responses.push(rx);
+        }
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        // only the budget is served, polling again doesn't serve more requests
+        assert!(Pin::new(&mut handler).poll(&mut cx).is_pending());
+        assert_eq!(answered(&mut responses), per_interval);
+        assert!(Pin::new(&mut handler).poll(&mut cx).is_pending());
+        assert_eq!(answered(&mut responses), 0);
+
+        // the budget is refilled by the interval
+        tokio::time::sleep(HISTORY_REQUESTS_REFILL_INTERVAL).await;
+        assert!(Pin::new(&mut handler).poll(&mut cx).is_pending());
+        assert_eq!(answered(&mut responses), per_interval);
+
+        // the interval wakes up the handler to serve the remaining request
+        let remaining = poll_fn(|cx| {
+            let _ = Pin::new(&mut handler).poll(cx);
+            answered(&mut responses);
+            if responses.is_empty() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        });
+        tokio::time::timeout(4 * HISTORY_REQUESTS_REFILL_INTERVAL, remaining).await.unwrap();
+    }
+
+    #[test]
+    fn pruned_receipts_from_prune_modes() {
+        let config = EthRequestsConfig::default().with_prune_modes(&PruneModes::none());
+        assert!(config.pruned_receipts.is_empty());
+
+        let modes = PruneModes { receipts: Some(PruneMode::Before(5)), ..PruneModes::none() };
+        let config = EthRequestsConfig::default().with_prune_modes(&modes);
+        assert!(config.is_receipts_pruned(4, 10));
+        assert!(!config.is_receipts_pruned(5, 10));
+    }
+}
@@ -1,7 +1,19 @@
 //! A client implementation that can interact with the network and download data.
 
-use crate::{fetch::DownloadRequest, flattened_response::FlattenedResponse, peers::PeersHandle};
+use crate::{
+    fetch::DownloadRequest,
+    flattened_response::FlattenedResponse,
+    peers::PeersHandle,
+    snap::{SnapPeerRequest, SnapPeers},
+};
 use futures::{future, future::Either};
+use reth_eth_wire::{
+    snap::{
+        AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges, GetTrieNodes,
+        StorageRanges, TrieNodes,
+    },
+    SnapProtocolMessage,
+};
 
 use reth_interfaces::p2p::{
     bodies::client::{BodiesClient, BodiesFut},
@@ -9,15 +21,22 @@ use reth_interfaces::p2p::{
     error::{PeerRequestResult, RequestError},
     headers::client::{HeadersClient, HeadersRequest},
     priority::Priority,
+    snap::client::{SnapClient, SnapFut},
 };
 use reth_network_api::ReputationChangeKind;
-use reth_primitives::{Header, PeerId, B256};
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use reth_primitives::{Header, PeerId, WithPeerId, B256};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 use tokio::sync::{mpsc::UnboundedSender, oneshot};
 
+/// The time to wait for the response to a `snap` request.
+const SNAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// Front-end API for fetching data from the network.
 ///
@@ -33,6 +52,93 @@ pub struct FetchClient {
     pub(crate) peers_handle: PeersHandle,
     /// Number of active peer sessions the node's currently handling.
     pub(crate) num_active_peers: Arc<AtomicUsize>,
+    /// The connected peers that support the `snap` protocol.
+    pub(crate) snap_peers: SnapPeers,
+}
+
+impl FetchClient {
+    /// Returns the number of connected peers that support the `snap` protocol.
+    pub fn num_connected_snap_peers(&self) -> usize {
+        self.snap_peers.len()
+    }
+
+    /// Sends a `GetAccountRange` request to a `snap` peer.
+    ///
+    /// The request id is assigned when the request is sent.
+    pub async fn get_account_range(
+        &self,
+        request: GetAccountRange,
+    ) -> PeerRequestResult<AccountRange> {
+        let (peer_id, response) =
+            self.snap_request(SnapProtocolMessage::GetAccountRange(request)).await?.split();
+        match response {
+            SnapProtocolMessage::AccountRange(response) => Ok(WithPeerId::new(peer_id, response)),
+            _ => Err(RequestError::BadResponse),
+        }
+    }
+
+    /// Sends a `GetStorageRanges` request to a `snap` peer.
+    ///
+    /// The request id is assigned when the request is sent.
+    pub async fn get_storage_ranges(
+        &self,
+        request: GetStorageRanges,
+    ) -> PeerRequestResult<StorageRanges> {
+        let (peer_id, response) =
+            self.snap_request(SnapProtocolMessage::GetStorageRanges(request)).await?.split();
+        match response {
+            SnapProtocolMessage::StorageRanges(response) => Ok(WithPeerId::new(peer_id, response)),
+            _ => Err(RequestError::BadResponse),
+        }
+    }
+
+    /// Sends a `GetByteCodes` request to a `snap` peer.
+    ///
+    /// The request id is assigned when the request is sent.
+    pub async fn get_byte_codes(&self, request: GetByteCodes) -> PeerRequestResult<ByteCodes> {
+        let (peer_id, response) =
+            self.snap_request(SnapProtocolMessage::GetByteCodes(request)).await?.split();
+        match response {
+            SnapProtocolMessage::ByteCodes(response) => Ok(WithPeerId::new(peer_id, response)),
+            _ => Err(RequestError::BadResponse),
+        }
+    }
+
+    /// Sends a `GetTrieNodes` request to a `snap` peer.
+    ///
+    /// The request id is assigned when the request is sent.
+    pub async fn get_trie_nodes(&self, request: GetTrieNodes) -> PeerRequestResult<TrieNodes> {
+        let (peer_id, response) =
+            self.snap_request(SnapProtocolMessage::GetTrieNodes(request)).await?.split();
+        match response {
+            SnapProtocolMessage::TrieNodes(response) => Ok(WithPeerId::new(peer_id, response)),
+            _ => Err(RequestError::BadResponse),
+        }
+    }
+
+    /// Sends the request to the `snap` peer with the fewest inflight requests and waits for the
+    /// response.
+    ///
+    /// Returns [`RequestError::UnsupportedCapability`] if no `snap` peer is connected.
+    async fn snap_request(
+        &self,
+        request: SnapProtocolMessage,
+    ) -> PeerRequestResult<SnapProtocolMessage> {
+        let Some((peer_id, peer)) = self.snap_peers.idlest() else {
+            return Err(RequestError::UnsupportedCapability)
+        };
+
+        let (response, rx) = oneshot::channel();
+        peer.to_connection
+            .send(SnapPeerRequest { request, response })
+            .map_err(|_| RequestError::ConnectionDropped)?;
+
+        match tokio::time::timeout(SNAP_REQUEST_TIMEOUT, rx).await {
+            Ok(Ok(response)) => response.map(|response| WithPeerId::new(peer_id, response)),
+            Ok(Err(_)) => Err(RequestError::ConnectionDropped),
+            Err(_) => Err(RequestError::Timeout),
+        }
+    }
 }
 
 impl DownloadClient for FetchClient {
@@ -92,3 +198,29 @@ impl BodiesClient for FetchClient {
         }
     }
 }
+
+impl SnapClient for FetchClient {
+    /// Sends a `GetAccountRange` request to the most idle `snap` peer.
+    fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange> {
+        let client = self.clone();
+        Box::pin(async move { client.get_account_range(request).await })
+    }
+
+    /// Sends a `GetStorageRanges` request to the most idle `snap` peer.
+    fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges> {
+        let client = self.clone();
+        Box::pin(async move { client.get_storage_ranges(request).await })
+    }
+
+    /// Sends a `GetByteCodes` request to the most idle `snap` peer.
+    fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes> {
+        let client = self.clone();
+        Box::pin(async move { client.get_byte_codes(request).await })
+    }
+
+    /// Sends a `GetTrieNodes` request to the most idle `snap` peer.
+    fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes> {
+        let client = self.clone();
+        Box::pin(async move { client.get_trie_nodes(request).await })
+    }
+}
@@ -1,6 +1,7 @@
 //! Fetch data from the network.
 
-use crate::{message::BlockRequest, peers::PeersHandle};
+use crate::{message::BlockRequest, peers::PeersHandle, snap::SnapPeers};
+use alloy_rlp::Encodable;
 use futures::StreamExt;
 use reth_eth_wire::{GetBlockBodies, GetBlockHeaders};
 use reth_interfaces::p2p::{
@@ -8,7 +9,7 @@ use reth_interfaces::p2p::{
     headers::client::HeadersRequest,
     priority::Priority,
 };
-use reth_network_api::ReputationChangeKind;
+use reth_network_api::{PeerScore, ReputationChangeKind};
 use reth_primitives::{BlockBody, Header, PeerId, B256};
 use std::{
     collections::{HashMap, VecDeque},
@@ -17,6 +18,7 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
+    time::Instant,
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -50,6 +52,8 @@ pub struct StateFetcher {
     download_requests_rx: UnboundedReceiverStream<DownloadRequest>,
     /// Sender for download requests, used to detach a [`FetchClient`]
     download_requests_tx: UnboundedSender<DownloadRequest>,
+    /// The connected peers that support the `snap` protocol, shared with the [`FetchClient`]s.
+    snap_peers: SnapPeers,
 }
 
 // === impl StateSyncer ===
@@ -66,6 +70,7 @@ impl StateFetcher {
             queued_requests: Default::default(),
             download_requests_rx: UnboundedReceiverStream::new(download_requests_rx),
             download_requests_tx,
+            snap_peers: Default::default(),
         }
     }
 
@@ -77,8 +82,21 @@ impl StateFetcher {
         best_number: u64,
         timeout: Arc<AtomicU64>,
     ) {
-        self.peers
-            .insert(peer_id, Peer { state: PeerState::Idle, best_hash, best_number, timeout });
+        self.peers.insert(
+            peer_id,
+            Peer {
+                state: PeerState::Idle,
+                best_hash,
+                best_number,
+                timeout,
+                score: Default::default(),
+            },
+        );
+    }
+
+    /// Returns the [`PeerScore`] of the connected peer.
+    pub(crate) fn peer_score(&self, peer_id: &PeerId) -> Option<PeerScore> {
+        self.peers.get(peer_id).map(|peer| peer.score)
     }
 
     /// Removes the peer from the peer list, after which it is no longer available for future
@@ -119,13 +137,15 @@ impl StateFetcher {
     }
 
     /// Returns the _next_ idle peer that's ready to accept a request,
-    /// prioritizing those with the lowest timeout/latency.
+    /// prioritizing those with the highest [`PeerScore`] and then the lowest timeout/latency.
     /// Once a peer has been yielded, it will be moved to the end of the map
     fn next_peer(&mut self) -> Option<PeerId> {
         self.peers
             .iter()
             .filter(|(_, peer)| peer.state.is_idle())
-            .min_by_key(|(_, peer)| peer.timeout())
+            .min_by(|(_, a), (_, b)| {
+                b.score.score().total_cmp(&a.score.score()).then(a.timeout().cmp(&b.timeout()))
+            })
             .map(|(id, _)| *id)
     }
 
@@ -196,7 +216,8 @@ impl StateFetcher {
 
         match req {
             DownloadRequest::GetBlockHeaders { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, started: Instant::now() };
                 self.inflight_headers_requests.insert(peer_id, inflight);
                 let HeadersRequest { start, limit, direction } = request;
                 BlockRequest::GetBlockHeaders(GetBlockHeaders {
@@ -207,7 +228,8 @@ impl StateFetcher {
                 })
             }
             DownloadRequest::GetBlockBodies { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, started: Instant::now() };
                 self.inflight_bodies_requests.insert(peer_id, inflight);
                 BlockRequest::GetBlockBodies(GetBlockBodies(request))
             }
@@ -244,6 +266,11 @@ impl StateFetcher {
             .unwrap_or_default();
 
         if let Some(resp) = resp {
+            if let Some(peer) = self.peers.get_mut(&peer_id) {
+                let requested = resp.request.limit as usize;
+                peer.on_response(&res, requested, resp.started, is_likely_bad_response);
+            }
+
             // delegate the response
             let _ = resp.response.send(res.map(|h| (peer_id, h).into()));
         }
@@ -269,6 +296,9 @@ impl StateFetcher {
         res: RequestResult<Vec<BlockBody>>,
     ) -> Option<BlockResponseOutcome> {
         if let Some(resp) = self.inflight_bodies_requests.remove(&peer_id) {
+            if let Some(peer) = self.peers.get_mut(&peer_id) {
+                peer.on_response(&res, resp.request.len(), resp.started, false);
+            }
             let _ = resp.response.send(res.map(|b| (peer_id, b).into()));
         }
         if let Some(peer) = self.peers.get_mut(&peer_id) {
@@ -285,6 +315,7 @@ impl StateFetcher {
             request_tx: self.download_requests_tx.clone(),
             peers_handle: self.peers_handle.clone(),
             num_active_peers: Arc::clone(&self.num_active_peers),
+            snap_peers: self.snap_peers.clone(),
         }
     }
 }
@@ -307,12 +338,37 @@ struct Peer {
     best_number: u64,
     /// Tracks the current timeout value we use for the peer.
     timeout: Arc<AtomicU64>,
+    /// How well the peer served the requests of this session.
+    score: PeerScore,
 }
 
 impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Scores the response to a request for `requested` items that was sent at `started`.
+    fn on_response<T: Encodable>(
+        &mut self,
+        res: &RequestResult<Vec<T>>,
+        requested: usize,
+        started: Instant,
+        is_likely_bad_response: bool,
+    ) {
+        match res {
+            Err(RequestError::Timeout) => self.score.on_timeout(),
+            Err(RequestError::BadResponse) => self.score.on_bad_response(),
+            // the remaining errors are not caused by the quality of the peer's responses
+            Err(_) => {}
+            Ok(_) if is_likely_bad_response => self.score.on_bad_response(),
+            Ok(items) => {
+                let usefulness =
+                    if requested == 0 { 1.0 } else { items.len() as f64 / requested as f64 };
+                let bytes = items.iter().map(Encodable::length).sum();
+                self.score.on_response(started.elapsed(), usefulness, bytes);
+            }
+        }
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -356,9 +412,10 @@ impl PeerState {
 struct Request<Req, Resp> {
     /// The issued request object
     // TODO: this can be attached to the response in error case
-    #[allow(dead_code)]
     request: Req,
     response: oneshot::Sender<Resp>,
+    /// When the request was sent to the peer.
+    started: Instant,
 }
 
 /// Requests that can be sent to the Syncer from a [`FetchClient`]
@@ -500,6 +557,44 @@ mod tests {
         assert_eq!(fetcher.next_peer(), Some(peer2));
     }
 
+    #[tokio::test]
+    async fn test_peer_score_prioritization() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let peer1 = B512::random();
+        let peer2 = B512::random();
+
+        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(10)));
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(300)));
+
+        // equal scores, the peer with the lowest timeout is preferred
+        assert_eq!(fetcher.next_peer(), Some(peer1));
+
+        let (tx, _rx) = oneshot::channel();
+        fetcher.inflight_bodies_requests.insert(
+            peer1,
+            Request { request: vec![B256::random()], response: tx, started: Instant::now() },
+        );
+        assert_eq!(fetcher.on_block_bodies_response(peer1, Err(RequestError::Timeout)), None);
+
+        let score = fetcher.peer_score(&peer1).unwrap();
+        assert_eq!(score.timeouts(), 1);
+        assert!(score.score() < fetcher.peer_score(&peer2).unwrap().score());
+        assert_eq!(fetcher.next_peer(), Some(peer2));
+
+        let (tx, _rx) = oneshot::channel();
+        fetcher.inflight_bodies_requests.insert(
+            peer2,
+            Request { request: vec![B256::random()], response: tx, started: Instant::now() },
+        );
+        fetcher.on_block_bodies_response(peer2, Ok(vec![BlockBody::default()]));
+
+        let score = fetcher.peer_score(&peer2).unwrap();
+        assert_eq!(score.requests(), 1);
+        assert_eq!(score.bytes_served(), BlockBody::default().length() as u64);
+        assert_eq!(fetcher.next_peer(), Some(peer2));
+    }
+
     #[tokio::test]
     async fn test_on_block_headers_response() {
         let manager = PeersManager::new(PeersConfig::default());
@@ -545,6 +640,7 @@ mod tests {
                     direction: Default::default(),
                 },
                 response: tx,
+                started: Instant::now(),
             };
             let mut header = SealedHeader::default().unseal();
             header.number = 0u64;
@@ -114,6 +114,7 @@
 /// Common helpers for network testing.
 pub mod test_utils;
 
+pub mod bandwidth;
 mod budget;
 mod builder;
 mod cache;
@@ -132,6 +133,7 @@ mod network;
 pub mod peers;
 pub mod protocol;
 mod session;
+pub mod snap;
 mod state;
 mod swarm;
 pub mod transactions;
@@ -139,6 +141,7 @@ pub mod transactions;
 pub use builder::NetworkBuilder;
 pub use config::{NetworkConfig, NetworkConfigBuilder};
 pub use discovery::{Discovery, DiscoveryEvent};
+pub use eth_requests::EthRequestsConfig;
 pub use fetch::FetchClient;
 pub use manager::{NetworkEvent, NetworkManager};
 pub use message::PeerRequest;
@@ -20,13 +20,13 @@ use crate::{
     config::NetworkConfig,
     discovery::Discovery,
     error::{NetworkError, ServiceKind},
-    eth_requests::IncomingEthRequest,
+    eth_requests::{EthRequestsConfig, IncomingEthRequest},
     import::{BlockImport, BlockImportOutcome, BlockValidation},
     listener::ConnectionListener,
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerRequestSender},
     metrics::{DisconnectMetrics, NetworkMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE},
     network::{NetworkHandle, NetworkHandleMessage},
-    peers::{PeersHandle, PeersManager},
+    peers::{PeersHandle, PeersManager, PersistedPeers},
     poll_nested_stream_with_budget,
     protocol::IntoRlpxSubProtocol,
     session::SessionManager,
@@ -43,7 +43,7 @@ use reth_eth_wire::{
 };
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_net_common::bandwidth_meter::BandwidthMeter;
-use reth_network_api::ReputationChangeKind;
+use reth_network_api::{PeerInfo, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use reth_provider::{BlockNumReader, BlockReader};
 use reth_rpc_types::{admin::EthProtocolInfo, NetworkStatus};
@@ -111,6 +111,8 @@ pub struct NetworkManager<C> {
     metrics: NetworkMetrics,
     /// Disconnect metrics for the Network
     disconnect_metrics: DisconnectMetrics,
+    /// How the [`EthRequestHandler`](crate::eth_requests::EthRequestHandler) serves requests.
+    eth_requests_config: EthRequestsConfig,
 }
 
 // === impl NetworkManager ===
@@ -128,6 +130,11 @@ impl<C> NetworkManager<C> {
         self.to_eth_request_handler = Some(tx);
     }
 
+    /// Returns the configured policies for serving `eth` requests.
+    pub fn eth_requests_config(&self) -> &EthRequestsConfig {
+        &self.eth_requests_config
+    }
+
     /// Adds an additional protocol handler to the RLPx sub-protocol list.
     pub fn add_rlpx_sub_protocol(&mut self, protocol: impl IntoRlpxSubProtocol) {
         self.swarm.add_rlpx_sub_protocol(protocol)
@@ -195,6 +202,8 @@ where
             extra_protocols,
             tx_gossip_disabled,
             transactions_manager_config: _,
+            port_mapping,
+            eth_requests_config,
         } = config;
 
         let peers_manager = PeersManager::new(peers_config);
@@ -212,7 +221,9 @@ where
             disc_config
         });
 
-        let discovery = Discovery::new(
+        let discv5_port =
+            discovery_v5_config.as_ref().map(|config| config.discovery_socket().port());
+        let mut discovery = Discovery::new(
             discovery_v4_addr,
             secret_key,
             discovery_v4_config,
@@ -220,6 +231,13 @@ where
             dns_discovery_config,
         )
         .await?;
+        if let Some(port_mapping) = port_mapping {
+            discovery.spawn_port_mapping(
+                port_mapping,
+                incoming.local_address().port(),
+                discv5_port,
+            );
+        }
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
         let discv4 = discovery.discv4();
@@ -270,6 +288,7 @@ where
             num_active_peers,
             metrics: Default::default(),
             disconnect_metrics: Default::default(),
+            eth_requests_config,
         })
     }
 
@@ -333,6 +352,11 @@ where
         self.swarm.state().peers().iter_peers()
     }
 
+    /// Returns the state of the peer set that should be persisted across restarts.
+    pub fn persisted_peers(&self) -> PersistedPeers {
+        self.swarm.state().peers().persisted_peers()
+    }
+
     /// Returns a new [`PeersHandle`] that can be cloned and shared.
     ///
     /// The [`PeersHandle`] can be used to interact with the network's peer set.
@@ -366,6 +390,12 @@ where
         }
     }
 
+    /// Attaches the [`PeerScore`](reth_network_api::PeerScore) of the peer to its [`PeerInfo`].
+    fn with_peer_score(&self, mut info: PeerInfo) -> PeerInfo {
+        info.score = self.swarm.state().peer_score(&info.remote_id);
+        info
+    }
+
     /// Event hook for an unexpected message from the peer.
     fn on_invalid_message(
         &mut self,
@@ -604,17 +634,21 @@ where
                 }
             }
             NetworkHandleMessage::GetPeerInfos(tx) => {
-                let _ = tx.send(self.swarm.sessions_mut().get_peer_info());
+                let infos = self.swarm.sessions_mut().get_peer_info();
+                let _ = tx.send(infos.into_iter().map(|info| self.with_peer_score(info)).collect());
             }
             NetworkHandleMessage::GetPeerInfoById(peer_id, tx) => {
-                let _ = tx.send(self.swarm.sessions_mut().get_peer_info_by_id(peer_id));
+                let info = self.swarm.sessions_mut().get_peer_info_by_id(peer_id);
+                let _ = tx.send(info.map(|info| self.with_peer_score(info)));
             }
             NetworkHandleMessage::GetPeerInfosByIds(peer_ids, tx) => {
-                let _ = tx.send(self.swarm.sessions().get_peer_infos_by_ids(peer_ids));
+                let infos = self.swarm.sessions().get_peer_infos_by_ids(peer_ids);
+                let _ = tx.send(infos.into_iter().map(|info| self.with_peer_score(info)).collect());
             }
             NetworkHandleMessage::GetPeerInfosByPeerKind(kind, tx) => {
                 let peers = self.swarm.state().peers().peers_by_kind(kind);
-                let _ = tx.send(self.swarm.sessions().get_peer_infos_by_ids(peers));
+                let infos = self.swarm.sessions().get_peer_infos_by_ids(peers);
+                let _ = tx.send(infos.into_iter().map(|info| self.with_peer_score(info)).collect());
             }
             NetworkHandleMessage::AddRlpxSubProtocol(proto) => self.add_rlpx_sub_protocol(proto),
             NetworkHandleMessage::GetTransactionsHandle(tx) => {
@@ -687,6 +721,8 @@ where
                     self.swarm.state_mut().peers_mut().on_active_outgoing_established(peer_id);
                 }
 
+                self.swarm.state_mut().peers_mut().on_session_capabilities(&peer_id, &capabilities);
+
                 self.update_active_connection_metrics();
 
                 self.event_listeners.notify(NetworkEvent::SessionEstablished {
@@ -912,6 +948,11 @@ where
         }
 
         shutdown_hook(&mut network);
+
+        // remove the port mappings from the gateway before completing the shutdown
+        if let Some(port_mapping) = network.swarm.state_mut().discovery_mut().take_port_mapping() {
+            port_mapping.shutdown().await;
+        }
         drop(graceful_guard);
     }
 }
@@ -94,6 +94,9 @@ pub struct TransactionsManagerMetrics {
     pub(crate) propagated_transactions: Counter,
     /// Total number of reported bad transactions
     pub(crate) reported_bad_transactions: Counter,
+    /// Total number of `PooledTransactions` responses that were truncated because the serving
+    /// bandwidth limit was reached
+    pub(crate) throttled_pooled_transactions_responses: Counter,
 
     /* -- Freq txns already marked as seen by peer -- */
     /// Total number of messages from a peer, announcing transactions that have already been
@@ -315,6 +318,30 @@ pub struct EthRequestHandlerMetrics {
 
     /// Number of received bodies requests
     pub(crate) received_bodies_requests: Counter,
+
+    /// Number of received receipts requests
+    pub(crate) received_receipts_requests: Counter,
+
+    /// Number of responses that were truncated because the serving bandwidth limit was reached
+    pub(crate) throttled_responses: Counter,
+
+    /// Number of requests for older history that were answered with an empty response because
+    /// too many were queued
+    pub(crate) rejected_history_requests: Counter,
+
+    /// Number of receipts responses that were truncated because the receipts are pruned
+    pub(crate) pruned_receipts_responses: Counter,
+}
+
+/// Bytes exchanged with all peers, labeled by `eth` message type or RLPx subprotocol.
+#[derive(Metrics)]
+#[metrics(scope = "network.bandwidth")]
+pub struct BandwidthMetrics {
+    /// Total number of bytes received
+    pub(crate) inbound_bytes: Counter,
+
+    /// Total number of bytes sent
+    pub(crate) outbound_bytes: Counter,
 }
 
 /// Eth67 announcement metrics, track entries by TxType
@@ -10,7 +10,7 @@ use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, Shar
 use reth_interfaces::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
 use reth_net_common::bandwidth_meter::BandwidthMeter;
 use reth_network_api::{
-    NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
+    BanList, IpRange, NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
     ReputationChangeKind,
 };
 use reth_primitives::{Head, NodeRecord, PeerId, TransactionSigned, B256};
@@ -22,6 +22,7 @@ use std::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
+    time::Duration,
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -317,6 +318,38 @@ impl Peers for NetworkHandle {
         let _ = self.manager().send(NetworkHandleMessage::GetReputationById(peer_id, tx));
         Ok(rx.await?)
     }
+
+    fn reset_reputations(&self) {
+        self.inner.peers.reset_reputations()
+    }
+
+    fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+        self.inner.peers.ban_peer(peer_id, duration)
+    }
+
+    fn unban_peer(&self, peer_id: PeerId) {
+        self.inner.peers.unban_peer(peer_id)
+    }
+
+    fn ban_ip_range(&self, range: IpRange, duration: Option<Duration>) {
+        self.inner.peers.ban_ip_range(range, duration)
+    }
+
+    fn unban_ip_range(&self, range: IpRange) {
+        self.inner.peers.unban_ip_range(range)
+    }
+
+    async fn ban_list(&self) -> Result<BanList, NetworkError> {
+        Ok(self.inner.peers.ban_list().await)
+    }
+
+    fn set_peer_limits(&self, max_inbound: Option<usize>, max_outbound: Option<usize>) {
+        self.inner.peers.set_peer_limits(max_inbound, max_outbound)
+    }
+
+    fn set_trusted_nodes_only(&self, trusted_only: bool) {
+        self.inner.peers.set_trusted_nodes_only(trusted_only)
+    }
 }
 
 impl NetworkInfo for NetworkHandle {
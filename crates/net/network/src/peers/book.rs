@@ -0,0 +1,215 @@
+//! The persisted state of known peers.
+//!
+//! Timestamps are stored as seconds since the unix epoch, since [`Instant`]s are only meaningful
+//! within the running process.
+
+use super::reputation::DEFAULT_REPUTATION;
+use reth_eth_wire::Capability;
+use reth_net_common::ban_list::IpRange;
+use reth_network_api::Reputation;
+use reth_primitives::{ForkId, NodeRecord, PeerId};
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The state of the peer set that is kept across restarts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeers {
+    /// The known peers.
+    pub peers: Vec<PersistedPeer>,
+    /// Banned peers, including peers that are not part of the peer set.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub banned_peers: Vec<PersistedBan<PeerId>>,
+    /// Banned ip addresses.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub banned_ips: Vec<PersistedBan<IpAddr>>,
+    /// Banned ip ranges.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub banned_ip_ranges: Vec<PersistedBan<IpRange>>,
+}
+
+/// A ban that is kept across restarts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedBan<T> {
+    /// The banned peer or address.
+    pub banned: T,
+    /// Until when the ban lasts, in seconds since the unix epoch, or `None` if it is indefinite.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub until: Option<u64>,
+}
+
+/// The state of a known peer that is kept across restarts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeer {
+    /// Where to reach the peer.
+    pub record: NodeRecord,
+    /// Reputation of the peer.
+    pub reputation: Reputation,
+    /// When the peer was last connected, in seconds since the unix epoch.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub last_seen: Option<u64>,
+    /// Until when the peer is backed off, in seconds since the unix epoch.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backed_off_until: Option<u64>,
+    /// Counts number of times the peer was backed off due to a severe backoff.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub severe_backoff_counter: u8,
+    /// The [`ForkId`] that the peer announced via discovery.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fork_id: Option<ForkId>,
+    /// The capabilities the peer announced in its last session.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub capabilities: Vec<Capability>,
+}
+
+impl PersistedPeer {
+    /// Creates the entry of a peer without any persisted state.
+    pub fn new(record: NodeRecord) -> Self {
+        Self {
+            record,
+            reputation: DEFAULT_REPUTATION,
+            last_seen: None,
+            backed_off_until: None,
+            severe_backoff_counter: 0,
+            fork_id: None,
+            capabilities: Vec::new(),
+        }
+    }
+}
+
+/// The content of the peers file.
+///
+/// Older versions only persisted a list of the [`NodeRecord`]s of the peers.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+pub(crate) enum PeersFile {
+    /// The peer set with its persisted state.
+    Peers(PersistedPeers),
+    /// Peers without any persisted state.
+    Records(Vec<NodeRecord>),
+}
+
+#[cfg(feature = "serde")]
+impl From<PeersFile> for PersistedPeers {
+    fn from(file: PeersFile) -> Self {
+        match file {
+            PeersFile::Peers(peers) => peers,
+            PeersFile::Records(records) => Self {
+                peers: records.into_iter().map(PersistedPeer::new).collect(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Converts the time to seconds since the unix epoch.
+pub(crate) fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Converts the instant to seconds since the unix epoch, relative to the current time.
+pub(crate) fn instant_to_unix_secs(instant: Instant, now: Instant, now_unix: SystemTime) -> u64 {
+    unix_secs(now_unix + instant.saturating_duration_since(now))
+}
+
+/// Converts seconds since the unix epoch to an instant, relative to the current time.
+///
+/// Returns `None` if the time already passed.
+pub(crate) fn unix_secs_to_instant(
+    secs: u64,
+    now: Instant,
+    now_unix: SystemTime,
+) -> Option<Instant> {
+    let remaining = (UNIX_EPOCH + Duration::from_secs(secs)).duration_since(now_unix).ok()?;
+    (!remaining.is_zero()).then(|| now + remaining)
+}
+
+/// Converts the deadline of a ban, which is `None` if the ban is indefinite, to a [`PersistedBan`].
+pub(crate) fn persist_ban<T>(
+    banned: T,
+    until: Option<Instant>,
+    now: Instant,
+    now_unix: SystemTime,
+) -> PersistedBan<T> {
+    PersistedBan { banned, until: until.map(|until| instant_to_unix_secs(until, now, now_unix)) }
+}
+
+/// Converts the persisted end of a ban to its deadline, which is `None` if the ban is indefinite.
+///
+/// Returns `None` if the ban already expired.
+pub(crate) fn restore_ban_deadline(
+    until: Option<u64>,
+    now: Instant,
+    now_unix: SystemTime,
+) -> Option<Option<Instant>> {
+    match until {
+        Some(secs) => unix_secs_to_instant(secs, now, now_unix).map(Some),
+        None => Some(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::PeerId;
+
+    #[test]
+    fn instant_conversion() {
+        let now = Instant::now();
+        let now_unix = SystemTime::now();
+
+        let until = instant_to_unix_secs(now + Duration::from_secs(60), now, now_unix);
+        assert_eq!(until, unix_secs(now_unix) + 60);
+
+        let restored = unix_secs_to_instant(until, now, now_unix).unwrap();
+        assert!(
+            restored > now + Duration::from_secs(58) && restored <= now + Duration::from_secs(60)
+        );
+
+        // expired timestamps are dropped
+        assert_eq!(unix_secs_to_instant(unix_secs(now_unix) - 1, now, now_unix), None);
+
+        // indefinite bans are kept, expired bans are dropped
+        assert_eq!(restore_ban_deadline(None, now, now_unix), Some(None));
+        assert_eq!(restore_ban_deadline(Some(unix_secs(now_unix) - 1), now, now_unix), None);
+        assert!(restore_ban_deadline(Some(until), now, now_unix).unwrap().is_some());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn parse_peers_file() {
+        let record = NodeRecord::new("127.0.0.1:30303".parse().unwrap(), PeerId::random());
+        let mut peer = PersistedPeer::new(NodeRecord::new(
+            "10.0.0.1:30303".parse().unwrap(),
+            PeerId::random(),
+        ));
+        peer.reputation = -1024;
+        peer.capabilities = vec![Capability::eth_68()];
+
+        // the list of node records written by older versions
+        let file = serde_json::json!([record]).to_string();
+        let peers: PersistedPeers = serde_json::from_str::<PeersFile>(&file).unwrap().into();
+        assert_eq!(
+            peers,
+            PersistedPeers { peers: vec![PersistedPeer::new(record)], ..Default::default() }
+        );
+
+        let persisted = PersistedPeers {
+            peers: vec![PersistedPeer::new(record), peer],
+            banned_peers: vec![PersistedBan { banned: record.id, until: Some(1_700_000_000) }],
+            banned_ips: vec![PersistedBan { banned: "10.0.0.2".parse().unwrap(), until: None }],
+            banned_ip_ranges: vec![PersistedBan {
+                banned: "10.1.0.0/16".parse().unwrap(),
+                until: Some(1_700_000_000),
+            }],
+        };
+        let file = serde_json::to_string(&persisted).unwrap();
+        let peers: PersistedPeers = serde_json::from_str::<PeersFile>(&file).unwrap().into();
+        assert_eq!(peers, persisted);
+    }
+}
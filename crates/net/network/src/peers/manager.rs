@@ -1,6 +1,10 @@
 use crate::{
     error::{BackoffKind, SessionError},
     peers::{
+        book::{
+            instant_to_unix_secs, persist_ban, restore_ban_deadline, unix_secs,
+            unix_secs_to_instant, PeersFile, PersistedBan, PersistedPeer, PersistedPeers,
+        },
         reputation::{
             is_banned_reputation, DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE,
         },
@@ -11,9 +15,11 @@ use crate::{
     swarm::NetworkConnectionState,
 };
 use futures::StreamExt;
-use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
-use reth_net_common::ban_list::BanList;
-use reth_network_api::{PeerKind, ReputationChangeKind};
+use reth_eth_wire::{
+    capability::Capabilities, errors::EthStreamError, Capability, DisconnectReason,
+};
+use reth_net_common::ban_list::{BanList, IpRange};
+use reth_network_api::{PeerKind, PeerScore, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
@@ -22,7 +28,7 @@ use std::{
     net::{IpAddr, SocketAddr},
     path::Path,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 use tokio::{
@@ -76,6 +82,60 @@ impl PeersHandle {
 
         rx.await.unwrap_or_default()
     }
+
+    /// Returns the state of the peerset that should be persisted across restarts.
+    ///
+    /// Returns `None` if the [`PeersManager`] was dropped.
+    pub async fn persisted_peers(&self) -> Option<PersistedPeers> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetPersistedPeers(tx));
+
+        rx.await.ok()
+    }
+
+    /// Bans the peer for the given duration, or indefinitely if `None`.
+    pub fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+        self.send(PeerCommand::BanPeer(peer_id, duration));
+    }
+
+    /// Lifts the ban of the peer.
+    pub fn unban_peer(&self, peer_id: PeerId) {
+        self.send(PeerCommand::UnbanPeer(peer_id));
+    }
+
+    /// Bans all addresses of the ip range for the given duration, or indefinitely if `None`.
+    pub fn ban_ip_range(&self, range: IpRange, duration: Option<Duration>) {
+        self.send(PeerCommand::BanIpRange(range, duration));
+    }
+
+    /// Lifts the ban of the ip range.
+    pub fn unban_ip_range(&self, range: IpRange) {
+        self.send(PeerCommand::UnbanIpRange(range));
+    }
+
+    /// Returns the currently banned peers and ip addresses.
+    pub async fn ban_list(&self) -> BanList {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetBanList(tx));
+
+        rx.await.unwrap_or_default()
+    }
+
+    /// Changes the maximum number of inbound and outbound connections, limits that are `None` are
+    /// kept.
+    pub fn set_peer_limits(&self, max_inbound: Option<usize>, max_outbound: Option<usize>) {
+        self.send(PeerCommand::SetPeerLimits { max_inbound, max_outbound });
+    }
+
+    /// Toggles whether only trusted peers are accepted and connected to.
+    pub fn set_trusted_nodes_only(&self, trusted_only: bool) {
+        self.send(PeerCommand::SetTrustedNodesOnly(trusted_only));
+    }
+
+    /// Resets the reputation of all known peers to the default value.
+    pub fn reset_reputations(&self) {
+        self.send(PeerCommand::ResetReputations);
+    }
 }
 
 /// Maintains the state of _all_ the peers known to the network.
@@ -125,6 +185,8 @@ pub struct PeersManager {
     max_backoff_count: u8,
     /// Tracks the connection state of the node
     net_connection_state: NetworkConnectionState,
+    /// When to rotate out peers because of their [`PeerScore`].
+    scoring: PeerScoringConfig,
 }
 
 impl PeersManager {
@@ -134,13 +196,15 @@ impl PeersManager {
             refill_slots_interval,
             connection_info,
             reputation_weights,
-            ban_list,
+            mut ban_list,
             ban_duration,
             backoff_durations,
             trusted_nodes,
             trusted_nodes_only,
             basic_nodes,
             max_backoff_count,
+            scoring,
+            persisted_peers,
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
         let now = Instant::now();
@@ -160,6 +224,59 @@ impl PeersManager {
             peers.entry(id).or_insert_with(|| Peer::new(SocketAddr::from((address, tcp_port))));
         }
 
+        // restore the state of the persisted peers, dropping bans and backoffs that expired in the
+        // meantime
+        let mut backed_off_peers = HashMap::new();
+        let now_unix = SystemTime::now();
+        let restored_at = std::time::Instant::now();
+        for PersistedBan { banned, until } in persisted_peers.banned_peers {
+            match restore_ban_deadline(until, restored_at, now_unix) {
+                Some(Some(until)) => ban_list.ban_peer_until(banned, until),
+                Some(None) => ban_list.ban_peer(banned),
+                None => {}
+            }
+        }
+        for PersistedBan { banned, until } in persisted_peers.banned_ips {
+            match restore_ban_deadline(until, restored_at, now_unix) {
+                Some(Some(until)) => ban_list.ban_ip_until(banned, until),
+                Some(None) => ban_list.ban_ip(banned),
+                None => {}
+            }
+        }
+        for PersistedBan { banned, until } in persisted_peers.banned_ip_ranges {
+            if let Some(until) = restore_ban_deadline(until, restored_at, now_unix) {
+                ban_list.ban_ip_range_with(banned, until);
+            }
+        }
+        for persisted in persisted_peers.peers {
+            let peer_id = persisted.record.id;
+            let peer =
+                peers.entry(peer_id).or_insert_with(|| Peer::new(persisted.record.tcp_addr()));
+
+            peer.reputation = if is_banned_reputation(persisted.reputation) &&
+                !ban_list.is_banned_peer(&peer_id)
+            {
+                // the peer was banned because of its reputation, and is unbanned after the ban
+                // expired
+                DEFAULT_REPUTATION
+            } else {
+                persisted.reputation
+            };
+
+            if let Some(until) = persisted
+                .backed_off_until
+                .and_then(|until| unix_secs_to_instant(until, restored_at, now_unix))
+            {
+                peer.backed_off = true;
+                backed_off_peers.insert(peer_id, until);
+            }
+
+            peer.severe_backoff_counter = persisted.severe_backoff_counter;
+            peer.fork_id = persisted.fork_id;
+            peer.last_seen = persisted.last_seen.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+            peer.capabilities = persisted.capabilities;
+        }
+
         Self {
             peers,
             trusted_peer_ids,
@@ -171,13 +288,14 @@ impl PeersManager {
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
             connection_info,
             ban_list,
-            backed_off_peers: Default::default(),
+            backed_off_peers,
             ban_duration,
             backoff_durations,
             trusted_nodes_only,
             last_tick: Instant::now(),
             max_backoff_count,
             net_connection_state: NetworkConnectionState::default(),
+            scoring,
         }
     }
 
@@ -197,6 +315,63 @@ impl PeersManager {
         self.peers.iter().map(|(peer_id, v)| NodeRecord::new(v.addr, *peer_id))
     }
 
+    /// Returns the state of the peer set that should be persisted across restarts.
+    pub(crate) fn persisted_peers(&self) -> PersistedPeers {
+        let now_unix = SystemTime::now();
+        let now = std::time::Instant::now();
+        let peers = self
+            .peers
+            .iter()
+            .map(|(peer_id, peer)| {
+                let last_seen =
+                    if peer.state.is_connected() { Some(now_unix) } else { peer.last_seen };
+                PersistedPeer {
+                    record: NodeRecord::new(peer.addr, *peer_id),
+                    reputation: peer.reputation,
+                    last_seen: last_seen.map(unix_secs),
+                    backed_off_until: self
+                        .backed_off_peers
+                        .get(peer_id)
+                        .map(|until| instant_to_unix_secs(*until, now, now_unix)),
+                    severe_backoff_counter: peer.severe_backoff_counter,
+                    fork_id: peer.fork_id,
+                    capabilities: peer.capabilities.clone(),
+                }
+            })
+            .collect();
+
+        PersistedPeers {
+            peers,
+            banned_peers: self
+                .ban_list
+                .banned_peers()
+                .map(|(peer_id, until)| persist_ban(peer_id, until, now, now_unix))
+                .collect(),
+            banned_ips: self
+                .ban_list
+                .banned_ips()
+                .map(|(ip, until)| persist_ban(ip, until, now, now_unix))
+                .collect(),
+            banned_ip_ranges: self
+                .ban_list
+                .banned_ip_ranges()
+                .map(|(range, until)| persist_ban(range, until, now, now_unix))
+                .collect(),
+        }
+    }
+
+    /// Records the capabilities the peer announced for a newly established session.
+    pub(crate) fn on_session_capabilities(
+        &mut self,
+        peer_id: &PeerId,
+        capabilities: &Capabilities,
+    ) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.capabilities = capabilities.capabilities().to_vec();
+            peer.last_seen = Some(SystemTime::now());
+        }
+    }
+
     /// Returns an iterator over all peer ids for peers with the given kind
     pub(crate) fn peers_by_kind(&self, kind: PeerKind) -> impl Iterator<Item = PeerId> + '_ {
         self.peers.iter().filter_map(move |(peer_id, peer)| (peer.kind == kind).then_some(*peer_id))
@@ -387,6 +562,103 @@ impl PeersManager {
         self.queued_actions.push_back(PeerAction::UnBanPeer { peer_id });
     }
 
+    /// Bans the peer for the given duration, or indefinitely if `None`, and disconnects it if it is
+    /// currently connected.
+    ///
+    /// Durations that exceed the range of [std::time::Instant] result in an indefinite ban.
+    ///
+    /// Unlike bans caused by the reputation of the peer, this also applies to trusted peers.
+    pub(crate) fn ban_peer_for(&mut self, peer_id: PeerId, duration: Option<Duration>) {
+        trace!(target: "net::peers", ?peer_id, ?duration, "banning peer");
+
+        self.ban_list.ban_peer_with(peer_id, ban_deadline(duration));
+        self.queued_actions.push_back(PeerAction::BanPeer { peer_id });
+        self.disconnect_peers_where(|id, _| *id == peer_id);
+    }
+
+    /// Lifts the ban of the peer, which also resets its reputation if it was banned because of its
+    /// reputation.
+    pub(crate) fn lift_peer_ban(&mut self, peer_id: PeerId) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if peer.is_banned() {
+                peer.unban();
+            }
+        }
+        self.unban_peer(peer_id);
+    }
+
+    /// Bans all addresses of the ip range for the given duration, or indefinitely if `None`, and
+    /// disconnects all connected peers with an address in the range.
+    ///
+    /// Durations that exceed the range of [std::time::Instant] result in an indefinite ban.
+    pub(crate) fn ban_ip_range_for(&mut self, range: IpRange, duration: Option<Duration>) {
+        trace!(target: "net::peers", %range, ?duration, "banning ip range");
+
+        self.ban_list.ban_ip_range_with(range, ban_deadline(duration));
+        self.disconnect_peers_where(|_, peer| range.contains(&peer.addr.ip()));
+    }
+
+    /// Lifts the ban of the ip range.
+    pub(crate) fn unban_ip_range(&mut self, range: &IpRange) {
+        self.ban_list.unban_ip_range(range);
+    }
+
+    /// Changes the maximum number of inbound and outbound connections.
+    ///
+    /// Lowering a limit does not disconnect any established sessions, but no new sessions are
+    /// accepted until the number of sessions dropped below the new limit.
+    pub(crate) fn set_peer_limits(
+        &mut self,
+        max_inbound: Option<usize>,
+        max_outbound: Option<usize>,
+    ) {
+        if let Some(max_inbound) = max_inbound {
+            self.connection_info.max_inbound = max_inbound;
+        }
+        if let Some(max_outbound) = max_outbound {
+            self.connection_info.max_outbound = max_outbound;
+        }
+        self.fill_outbound_slots();
+    }
+
+    /// Toggles whether only trusted peers are accepted and connected to.
+    ///
+    /// When enabled, all connected peers that are not trusted are disconnected.
+    pub(crate) fn set_trusted_nodes_only(&mut self, trusted_only: bool) {
+        self.trusted_nodes_only = trusted_only;
+        if trusted_only {
+            let trusted_peer_ids = std::mem::take(&mut self.trusted_peer_ids);
+            self.disconnect_peers_where(|peer_id, peer| {
+                !peer.is_trusted() && !trusted_peer_ids.contains(peer_id)
+            });
+            self.trusted_peer_ids = trusted_peer_ids;
+        } else {
+            self.fill_outbound_slots();
+        }
+    }
+
+    /// Resets the reputation of all known peers to the default value.
+    ///
+    /// This does not lift any bans.
+    pub(crate) fn reset_reputations(&mut self) {
+        for peer in self.peers.values_mut() {
+            peer.reset_reputation();
+        }
+    }
+
+    /// Disconnects all connected peers that match the predicate.
+    fn disconnect_peers_where(&mut self, mut f: impl FnMut(&PeerId, &Peer) -> bool) {
+        for (peer_id, peer) in self.peers.iter_mut() {
+            if peer.state.is_connected() && f(peer_id, peer) {
+                peer.state.disconnect();
+                self.queued_actions.push_back(PeerAction::Disconnect {
+                    peer_id: *peer_id,
+                    reason: Some(DisconnectReason::DisconnectRequested),
+                });
+            }
+        }
+    }
+
     /// Tick function to update reputation of all connected peers.
     /// Peers are rewarded with reputation increases for the time they are connected since the last
     /// tick. This is to prevent peers from being disconnected eventually due to slashed
@@ -467,6 +739,30 @@ impl PeersManager {
         }
     }
 
+    /// Invoked when the [`PeerScore`] of a connected peer changed after a response.
+    ///
+    /// Non-trusted peers whose score dropped below the configured minimum are disconnected and
+    /// backed off, so that their slot can be used by a more useful peer.
+    pub(crate) fn on_peer_score_update(&mut self, peer_id: PeerId, score: &PeerScore) {
+        if !self.scoring.is_low_score(score) {
+            return
+        }
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+        if peer.is_trusted() || !peer.state.is_connected() {
+            return
+        }
+
+        trace!(target: "net::peers", ?peer_id, score=%score.score(), requests=%score.requests(), "disconnecting low scoring peer");
+
+        peer.state.disconnect();
+        self.queued_actions.push_back(PeerAction::Disconnect {
+            peer_id,
+            reason: Some(DisconnectReason::UselessPeer),
+        });
+        let backoff_until = self.backoff_durations.backoff_until(BackoffKind::Medium, 0);
+        self.backoff_peer_until(peer_id, backoff_until);
+    }
+
     /// Gracefully disconnected a pending _outgoing_ session
     pub(crate) fn on_outgoing_pending_session_gracefully_closed(&mut self, peer_id: &PeerId) {
         if let Some(peer) = self.peers.get_mut(peer_id) {
@@ -770,9 +1066,10 @@ impl PeersManager {
     ///
     /// Returns `None` if no peer is available.
     fn best_unconnected(&mut self) -> Option<(PeerId, &mut Peer)> {
-        let mut unconnected = self.peers.iter_mut().filter(|(_, peer)| {
+        let mut unconnected = self.peers.iter_mut().filter(|(peer_id, peer)| {
             !peer.is_backed_off() &&
                 !peer.is_banned() &&
+                !self.ban_list.is_banned(peer_id, &peer.addr.ip()) &&
                 peer.state.is_unconnected() &&
                 (!self.trusted_nodes_only || peer.is_trusted())
         });
@@ -872,6 +1169,25 @@ impl PeersManager {
                     PeerCommand::GetPeers(tx) => {
                         let _ = tx.send(self.iter_peers().collect());
                     }
+                    PeerCommand::GetPersistedPeers(tx) => {
+                        let _ = tx.send(self.persisted_peers());
+                    }
+                    PeerCommand::BanPeer(peer_id, duration) => self.ban_peer_for(peer_id, duration),
+                    PeerCommand::UnbanPeer(peer_id) => self.lift_peer_ban(peer_id),
+                    PeerCommand::BanIpRange(range, duration) => {
+                        self.ban_ip_range_for(range, duration)
+                    }
+                    PeerCommand::UnbanIpRange(range) => self.unban_ip_range(&range),
+                    PeerCommand::GetBanList(tx) => {
+                        let _ = tx.send(self.ban_list.clone());
+                    }
+                    PeerCommand::SetPeerLimits { max_inbound, max_outbound } => {
+                        self.set_peer_limits(max_inbound, max_outbound)
+                    }
+                    PeerCommand::SetTrustedNodesOnly(trusted_only) => {
+                        self.set_trusted_nodes_only(trusted_only)
+                    }
+                    PeerCommand::ResetReputations => self.reset_reputations(),
                 }
             }
 
@@ -1032,6 +1348,10 @@ pub struct Peer {
     backed_off: bool,
     /// Counts number of times the peer was backed off due to a severe [BackoffKind].
     severe_backoff_counter: u8,
+    /// When a session to the peer was last established.
+    last_seen: Option<SystemTime>,
+    /// The capabilities the peer announced in its last session.
+    capabilities: Vec<Capability>,
 }
 
 // === impl Peer ===
@@ -1060,6 +1380,8 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            last_seen: None,
+            capabilities: Vec::new(),
         }
     }
 
@@ -1209,6 +1531,29 @@ pub(crate) enum PeerCommand {
     GetPeer(PeerId, oneshot::Sender<Option<Peer>>),
     /// Get node information on all peers
     GetPeers(oneshot::Sender<Vec<NodeRecord>>),
+    /// Get the state of all peers that should be persisted
+    GetPersistedPeers(oneshot::Sender<PersistedPeers>),
+    /// Ban a peer for the given duration, or indefinitely
+    BanPeer(PeerId, Option<Duration>),
+    /// Lift the ban of a peer
+    UnbanPeer(PeerId),
+    /// Ban an ip range for the given duration, or indefinitely
+    BanIpRange(IpRange, Option<Duration>),
+    /// Lift the ban of an ip range
+    UnbanIpRange(IpRange),
+    /// Get the banned peers and ip addresses
+    GetBanList(oneshot::Sender<BanList>),
+    /// Change the connection limits
+    SetPeerLimits {
+        /// The new maximum number of inbound connections, if it should change.
+        max_inbound: Option<usize>,
+        /// The new maximum number of outbound connections, if it should change.
+        max_outbound: Option<usize>,
+    },
+    /// Toggle whether only trusted peers are accepted and connected to
+    SetTrustedNodesOnly(bool),
+    /// Reset the reputation of all peers
+    ResetReputations,
 }
 
 /// Actions the peer manager can trigger.
@@ -1308,6 +1653,13 @@ pub struct PeersConfig {
     ///
     /// The backoff duration increases with number of backoff attempts.
     pub backoff_durations: PeerBackoffDurations,
+    /// When to disconnect peers because of the low quality of their responses.
+    pub scoring: PeerScoringConfig,
+    /// The state of known peers and the bans that were persisted by a previous run.
+    ///
+    /// Bans and backoffs that have not expired yet are restored.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_peers: PersistedPeers,
 }
 
 impl Default for PeersConfig {
@@ -1324,6 +1676,8 @@ impl Default for PeersConfig {
             trusted_nodes_only: false,
             basic_nodes: Default::default(),
             max_backoff_count: 5,
+            scoring: Default::default(),
+            persisted_peers: Default::default(),
         }
     }
 }
@@ -1423,12 +1777,28 @@ impl PeersConfig {
         self
     }
 
+    /// Peers, their state and bans persisted by a previous run, see [`PersistedPeers`].
+    pub fn with_persisted_peers(mut self, peers: PersistedPeers) -> Self {
+        self.persisted_peers = peers;
+        self
+    }
+
+    /// Configures when to disconnect peers because of their [`PeerScore`].
+    pub fn with_scoring(mut self, scoring: PeerScoringConfig) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
     /// Returns the maximum number of peers, inbound and outbound.
     pub const fn max_peers(&self) -> usize {
         self.connection_info.max_outbound + self.connection_info.max_inbound
     }
 
-    /// Read from file nodes available at launch. Ignored if None.
+    /// Read from file nodes available at launch, together with their persisted state. Ignored if
+    /// None.
+    ///
+    /// The file either contains the [`PersistedPeers`] or, as written by older versions, a list of
+    /// [`NodeRecord`]s.
     pub fn with_basic_nodes_from_file(
         self,
         optional_file: Option<impl AsRef<Path>>,
@@ -1440,8 +1810,9 @@ impl PeersConfig {
             Err(e) => Err(e)?,
         };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+        let peers: PersistedPeers = serde_json::from_reader::<_, PeersFile>(reader)?.into();
+        let nodes = peers.peers.iter().map(|peer| peer.record).collect::<HashSet<_>>();
+        Ok(self.with_basic_nodes(nodes).with_persisted_peers(peers))
     }
 
     /// Returns settings for testing
@@ -1523,6 +1894,36 @@ impl Default for PeerBackoffDurations {
     }
 }
 
+/// Configures when peers are disconnected because of a low [`PeerScore`].
+///
+/// Once a peer served enough requests for its score to be meaningful, a score below the minimum
+/// means that the peer is persistently slow, unhelpful or faulty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PeerScoringConfig {
+    /// The score below which a peer is disconnected, on the `0..=100` scale of [`PeerScore`].
+    ///
+    /// A value of `0` disables disconnecting peers because of their score.
+    pub min_score: u8,
+    /// The number of requests a peer has to serve before it can be disconnected because of its
+    /// score.
+    pub min_requests: u64,
+}
+
+impl PeerScoringConfig {
+    /// Returns `true` if the peer should be rotated out because of the given score.
+    pub fn is_low_score(&self, score: &PeerScore) -> bool {
+        score.requests() >= self.min_requests && score.score() < self.min_score as f64
+    }
+}
+
+impl Default for PeerScoringConfig {
+    fn default() -> Self {
+        Self { min_score: 10, min_requests: 20 }
+    }
+}
+
 /// Error thrown when a incoming connection is rejected right away
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum InboundConnectionError {
@@ -1538,15 +1939,23 @@ impl Display for InboundConnectionError {
     }
 }
 
+/// Returns the deadline of a ban with the given duration.
+///
+/// Returns `None`, an indefinite ban, if there's no duration or the deadline would overflow.
+fn ban_deadline(duration: Option<Duration>) -> Option<std::time::Instant> {
+    duration.and_then(|duration| std::time::Instant::now().checked_add(duration))
+}
+
 #[cfg(test)]
 mod tests {
     use super::PeersManager;
     use crate::{
         error::BackoffKind,
         peers::{
+            book::unix_secs,
             manager::{ConnectionInfo, PeerBackoffDurations, PeerConnectionState},
-            reputation::DEFAULT_REPUTATION,
-            InboundConnectionError, PeerAction,
+            reputation::{BANNED_REPUTATION, DEFAULT_REPUTATION},
+            InboundConnectionError, PeerAction, PersistedBan, PersistedPeer, PersistedPeers,
         },
         session::PendingSessionHandshakeError,
         PeersConfig,
@@ -1556,8 +1965,8 @@ mod tests {
         errors::{EthHandshakeError, EthStreamError, P2PHandshakeError, P2PStreamError},
         DisconnectReason,
     };
-    use reth_net_common::ban_list::BanList;
-    use reth_network_api::{Direction, ReputationChangeKind};
+    use reth_net_common::ban_list::{BanList, IpRange};
+    use reth_network_api::{Direction, PeerScore, ReputationChangeKind};
     use reth_primitives::{PeerId, B512};
     use std::{
         collections::HashSet,
@@ -1566,7 +1975,7 @@ mod tests {
         net::{IpAddr, Ipv4Addr, SocketAddr},
         pin::Pin,
         task::{Context, Poll},
-        time::Duration,
+        time::{Duration, SystemTime},
     };
 
     struct PeerActionFuture<'a> {
@@ -2097,6 +2506,264 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_disconnect_low_score() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_peer(peer, socket_addr, None);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        peers.on_active_outgoing_established(peer);
+
+        // a few failures are not enough to rotate the peer out
+        let mut score = PeerScore::default();
+        for _ in 0..5 {
+            score.on_timeout();
+        }
+        peers.on_peer_score_update(peer, &score);
+        assert!(peers.queued_actions.is_empty());
+
+        for _ in 0..20 {
+            score.on_timeout();
+        }
+        assert!(peers.scoring.is_low_score(&score));
+        peers.on_peer_score_update(peer, &score);
+
+        let p = peers.peers.get(&peer).unwrap();
+        assert_eq!(p.state, PeerConnectionState::DisconnectingOut);
+        assert!(p.is_backed_off());
+
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, reason } => {
+                assert_eq!(peer_id, peer);
+                assert_eq!(reason, Some(DisconnectReason::UselessPeer));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_ban_peer() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_trusted_peer(peer, socket_addr);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        peers.on_active_outgoing_established(peer);
+
+        // trusted peers can be banned manually
+        peers.ban_peer_for(peer, None);
+        assert!(peers.ban_list.is_banned_peer(&peer));
+        assert_eq!(peers.peers.get(&peer).unwrap().state, PeerConnectionState::DisconnectingOut);
+        assert!(matches!(event!(peers), PeerAction::BanPeer { .. }));
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, reason } => {
+                assert_eq!(peer_id, peer);
+                assert_eq!(reason, Some(DisconnectReason::DisconnectRequested));
+            }
+            _ => unreachable!(),
+        }
+
+        // banned peers are not dialed again
+        peers.on_active_session_gracefully_closed(peer);
+        assert!(peers.best_unconnected().is_none());
+
+        peers.lift_peer_ban(peer);
+        assert!(!peers.ban_list.is_banned_peer(&peer));
+        assert!(matches!(event!(peers), PeerAction::UnBanPeer { .. }));
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_ban_ip_range() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test());
+        assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
+        peers.on_incoming_session_established(peer, socket_addr);
+        assert!(matches!(event!(peers), PeerAction::PeerAdded(_)));
+
+        let range: IpRange = "127.0.0.0/16".parse().unwrap();
+        peers.ban_ip_range_for(range, None);
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::new(127, 0, 3, 4))),
+            Err(InboundConnectionError::IpBanned)
+        );
+        assert!(peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::new(127, 1, 0, 1))).is_ok());
+
+        peers.unban_ip_range(&range);
+        assert!(peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::new(127, 0, 3, 4))).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_admin_ban_overflowing_duration() {
+        let peer = PeerId::random();
+        let mut peers = PeersManager::new(PeersConfig::test());
+
+        // durations beyond the range of `Instant` result in an indefinite ban
+        peers.ban_peer_for(peer, Some(Duration::from_secs(u64::MAX)));
+        assert!(peers.ban_list.is_banned_peer(&peer));
+        assert_eq!(peers.ban_list.peer_banned_until(&peer), None);
+
+        let range: IpRange = "127.0.0.0/16".parse().unwrap();
+        peers.ban_ip_range_for(range, Some(Duration::from_secs(u64::MAX)));
+        assert_eq!(
+            peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::new(127, 0, 3, 4))),
+            Err(InboundConnectionError::IpBanned)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_admin_trusted_nodes_only() {
+        let trusted = PeerId::random();
+        let basic = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_trusted_peer_id(trusted);
+        for peer in [trusted, basic] {
+            assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
+            peers.on_incoming_session_established(peer, socket_addr);
+            assert!(matches!(event!(peers), PeerAction::PeerAdded(_)));
+        }
+
+        peers.set_trusted_nodes_only(true);
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, .. } => {
+                assert_eq!(peer_id, basic);
+            }
+            _ => unreachable!(),
+        }
+        assert!(peers.queued_actions.is_empty());
+        assert!(peers.trusted_peer_ids.contains(&trusted));
+
+        peers.set_peer_limits(Some(0), Some(0));
+        assert!(!peers.connection_info.has_in_capacity());
+        assert!(!peers.connection_info.has_out_capacity());
+    }
+
+    #[tokio::test]
+    async fn test_restore_persisted_peers() {
+        let now = unix_secs(SystemTime::now());
+        let record = |port: u16| NodeRecord::new(([127, 0, 1, 2], port).into(), PeerId::random());
+
+        let banned = PersistedPeer {
+            reputation: BANNED_REPUTATION - 1,
+            ..PersistedPeer::new(record(30303))
+        };
+        let ban_expired = PersistedPeer {
+            reputation: BANNED_REPUTATION - 1,
+            ..PersistedPeer::new(record(30304))
+        };
+        let backed_off = PersistedPeer {
+            reputation: -1024,
+            backed_off_until: Some(now + 3600),
+            severe_backoff_counter: 2,
+            last_seen: Some(now - 60),
+            ..PersistedPeer::new(record(30305))
+        };
+        let backoff_expired =
+            PersistedPeer { backed_off_until: Some(now - 10), ..PersistedPeer::new(record(30306)) };
+        let banned_indefinitely = PeerId::random();
+        let banned_ip = IpAddr::V4(Ipv4Addr::new(127, 0, 1, 3));
+        let ban_expired_ip = IpAddr::V4(Ipv4Addr::new(127, 0, 1, 4));
+        let banned_ip_range: IpRange = "10.1.0.0/16".parse().unwrap();
+
+        let peers = PeersManager::new(PeersConfig::test().with_persisted_peers(PersistedPeers {
+            peers: vec![
+                banned.clone(),
+                ban_expired.clone(),
+                backed_off.clone(),
+                backoff_expired.clone(),
+            ],
+            banned_peers: vec![
+                PersistedBan { banned: banned.record.id, until: Some(now + 3600) },
+                PersistedBan { banned: ban_expired.record.id, until: Some(now - 10) },
+                PersistedBan { banned: banned_indefinitely, until: None },
+            ],
+            banned_ips: vec![
+                PersistedBan { banned: banned_ip, until: None },
+                PersistedBan { banned: ban_expired_ip, until: Some(now - 10) },
+            ],
+            banned_ip_ranges: vec![PersistedBan { banned: banned_ip_range, until: Some(now + 60) }],
+        }));
+        assert_eq!(peers.num_known_peers(), 4);
+
+        let peer = &peers.peers[&banned.record.id];
+        assert!(peer.is_banned());
+        assert!(peers.ban_list.is_banned_peer(&banned.record.id));
+
+        let peer = &peers.peers[&ban_expired.record.id];
+        assert!(!peer.is_banned());
+        assert_eq!(peer.reputation, DEFAULT_REPUTATION);
+        assert!(!peers.ban_list.is_banned_peer(&ban_expired.record.id));
+
+        assert!(peers.ban_list.is_banned_peer(&banned_indefinitely));
+        assert!(peers.ban_list.is_banned_ip(&banned_ip));
+        assert!(!peers.ban_list.is_banned_ip(&ban_expired_ip));
+        assert!(peers.ban_list.is_banned_ip(&IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3))));
+
+        let peer = &peers.peers[&backed_off.record.id];
+        assert!(peer.is_backed_off());
+        assert_eq!(peer.reputation, -1024);
+        assert_eq!(peer.severe_backoff_counter, 2);
+        assert!(peers.backed_off_peers.contains_key(&backed_off.record.id));
+
+        assert!(!peers.peers[&backoff_expired.record.id].is_backed_off());
+
+        // the restored state is persisted again
+        let persisted = peers.persisted_peers();
+        let restored =
+            persisted.peers.iter().find(|peer| peer.record == backed_off.record).unwrap();
+        assert!(restored.backed_off_until.unwrap().abs_diff(now + 3600) <= 1);
+        assert_eq!(restored.last_seen, backed_off.last_seen);
+        assert_eq!(restored.severe_backoff_counter, 2);
+
+        let ban = persisted.banned_peers.iter().find(|ban| ban.banned == banned.record.id).unwrap();
+        assert!(ban.until.unwrap().abs_diff(now + 3600) <= 1);
+        assert!(persisted
+            .banned_peers
+            .contains(&PersistedBan { banned: banned_indefinitely, until: None }));
+        assert!(!persisted.banned_peers.iter().any(|ban| ban.banned == ban_expired.record.id));
+        assert_eq!(persisted.banned_ips, vec![PersistedBan { banned: banned_ip, until: None }]);
+        assert_eq!(persisted.banned_ip_ranges.len(), 1);
+        assert_eq!(persisted.banned_ip_ranges[0].banned, banned_ip_range);
+    }
+
     #[tokio::test]
     async fn accept_incoming_trusted_unknown_peer_address() {
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 99)), 8008);
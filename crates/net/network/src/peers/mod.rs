@@ -1,10 +1,14 @@
 //! Peer related implementations
 
+mod book;
 mod manager;
 mod reputation;
 
+pub use book::{PersistedBan, PersistedPeer, PersistedPeers};
 pub(crate) use manager::InboundConnectionError;
-pub use manager::{ConnectionInfo, Peer, PeerAction, PeersConfig, PeersHandle, PeersManager};
+pub use manager::{
+    ConnectionInfo, Peer, PeerAction, PeerScoringConfig, PeersConfig, PeersHandle, PeersManager,
+};
 pub use reputation::ReputationChangeWeights;
 pub use reth_network_api::PeerKind;
 
@@ -1,6 +1,7 @@
 //! Represents an established session.
 
 use crate::{
+    bandwidth::SessionBandwidth,
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerResponse, PeerResponseResult},
     session::{
         config::INITIAL_REQUEST_TIMEOUT,
@@ -95,6 +96,8 @@ pub(crate) struct ActiveSession {
     pub(crate) protocol_breach_request_timeout: Duration,
     /// Used to reserve a slot to guarantee that the termination message is delivered
     pub(crate) terminate_message: Option<(PollSender<ActiveSessionMessage>, ActiveSessionMessage)>,
+    /// Counts the bytes exchanged with the peer, shared with the session's handle.
+    pub(crate) bandwidth: Arc<SessionBandwidth>,
 }
 
 impl ActiveSession {
@@ -120,6 +123,8 @@ impl ActiveSession {
     ///
     /// Returns an error if the message is considered to be in violation of the protocol.
     fn on_incoming_message(&mut self, msg: EthMessage) -> OnIncomingMessageOutcome {
+        self.bandwidth.on_inbound(&msg);
+
         /// A macro that handles an incoming request
         /// This creates a new channel and tries to send the sender half to the session while
         /// storing the receiver half internally so the pending response can be polled.
@@ -552,8 +557,14 @@ impl Future for ActiveSession {
                 if let Some(msg) = this.queued_outgoing.pop_front() {
                     progress = true;
                     let res = match msg {
-                        OutgoingMessage::Eth(msg) => this.conn.start_send_unpin(msg),
-                        OutgoingMessage::Broadcast(msg) => this.conn.start_send_broadcast(msg),
+                        OutgoingMessage::Eth(msg) => {
+                            this.bandwidth.on_outbound(&msg);
+                            this.conn.start_send_unpin(msg)
+                        }
+                        OutgoingMessage::Broadcast(msg) => {
+                            this.bandwidth.on_outbound_broadcast(&msg);
+                            this.conn.start_send_broadcast(msg)
+                        }
                     };
                     if let Err(err) = res {
                         debug!(target: "net::session", %err, remote_peer_id=?this.remote_peer_id, "failed to send message");
@@ -763,12 +774,14 @@ mod tests {
         config::PROTOCOL_BREACH_REQUEST_TIMEOUT, handle::PendingSessionEvent,
         start_pending_incoming_session,
     };
+    use alloy_rlp::Encodable;
     use reth_ecies::stream::ECIESStream;
     use reth_eth_wire::{
-        EthStream, GetBlockBodies, HelloMessageWithProtocols, P2PStream, Status, StatusBuilder,
-        UnauthedEthStream, UnauthedP2PStream,
+        EthMessageID, EthStream, GetBlockBodies, HelloMessageWithProtocols, P2PStream, Status,
+        StatusBuilder, UnauthedEthStream, UnauthedP2PStream,
     };
     use reth_net_common::bandwidth_meter::{BandwidthMeter, MeteredStream};
+    use reth_network_api::ByteCount;
     use reth_primitives::{pk2id, ForkFilter, Hardfork, MAINNET};
     use secp256k1::{SecretKey, SECP256K1};
     use tokio::{
@@ -862,6 +875,7 @@ mod tests {
                     peer_id,
                     capabilities,
                     conn,
+                    bandwidth,
                     ..
                 } => {
                     let (_to_session_tx, messages_rx) = mpsc::channel(10);
@@ -895,6 +909,7 @@ mod tests {
                         )),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
+                        bandwidth: Arc::new(bandwidth),
                     }
                 }
                 ev => {
@@ -989,7 +1004,7 @@ mod tests {
         let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
         let local_addr = listener.local_addr().unwrap();
 
-        let num_messages = 100;
+        let num_messages = 100u64;
 
         let fut = builder.with_client_stream(local_addr, move |mut client_stream| async move {
             for _ in 0..num_messages {
@@ -1005,14 +1020,24 @@ mod tests {
         tokio::task::spawn(async move {
             let (incoming, _) = listener.accept().await.unwrap();
             let session = builder.connect_incoming(incoming).await;
+            let bandwidth = Arc::clone(&session.bandwidth);
             session.await;
 
-            tx.send(()).unwrap();
+            tx.send(bandwidth.snapshot()).unwrap();
         });
 
         tokio::task::spawn(fut);
 
-        rx.await.unwrap();
+        let bandwidth = rx.await.unwrap();
+        let msg = EthMessage::NewPooledTransactionHashes66(Vec::new().into());
+        let size = 1 + msg.length() as u64;
+        assert_eq!(
+            bandwidth.eth,
+            vec![(
+                EthMessageID::NewPooledTransactionHashes,
+                ByteCount { inbound: num_messages * size, outbound: 0 }
+            )]
+        );
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -1,6 +1,7 @@
 //! Session handles.
 
 use crate::{
+    bandwidth::SessionBandwidth,
     message::PeerMessage,
     session::{conn::EthRlpxConnection, Direction, SessionId},
     PendingSessionHandshakeError,
@@ -75,6 +76,8 @@ pub struct ActiveSessionHandle {
     pub(crate) local_addr: Option<SocketAddr>,
     /// The Status message the peer sent for the `eth` handshake
     pub(crate) status: Arc<Status>,
+    /// The bytes exchanged over the session.
+    pub(crate) bandwidth: Arc<SessionBandwidth>,
 }
 
 // === impl ActiveSessionHandle ===
@@ -147,6 +150,8 @@ impl ActiveSessionHandle {
             eth_version: self.version,
             status: self.status.clone(),
             session_established: self.established,
+            score: None,
+            bandwidth: self.bandwidth.snapshot(),
         }
     }
 }
@@ -179,6 +184,8 @@ pub enum PendingSessionEvent {
         direction: Direction,
         /// The remote node's user agent, usually containing the client name and version
         client_id: String,
+        /// The byte counters of the session, including those of the installed subprotocols
+        bandwidth: SessionBandwidth,
     },
     /// Handshake unsuccessful, session was disconnected.
     Disconnected {
@@ -1,6 +1,7 @@
 //! Support for handling peer sessions.
 
 use crate::{
+    bandwidth::{MeteredProtocolStream, SessionBandwidth},
     message::PeerMessage,
     metrics::SessionManagerMetrics,
     session::{active::ActiveSession, config::SessionCounter},
@@ -432,6 +433,7 @@ impl SessionManager {
                 status,
                 direction,
                 client_id,
+                bandwidth,
             } => {
                 // move from pending to established.
                 self.remove_pending_session(&session_id);
@@ -473,6 +475,8 @@ impl SessionManager {
                 // negotiated version
                 let version = conn.version();
 
+                let bandwidth = Arc::new(bandwidth);
+
                 let session = ActiveSession {
                     next_id: 0,
                     remote_peer_id: peer_id,
@@ -493,6 +497,7 @@ impl SessionManager {
                     internal_request_timeout: Arc::clone(&timeout),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
+                    bandwidth: Arc::clone(&bandwidth),
                 };
 
                 self.spawn(session);
@@ -510,6 +515,7 @@ impl SessionManager {
                     client_version: Arc::clone(&client_version),
                     remote_addr,
                     local_addr,
+                    bandwidth,
                 };
 
                 self.active_sessions.insert(peer_id, handle);
@@ -997,6 +1003,8 @@ async fn authenticate_stream(
         }
     };
 
+    let mut bandwidth = SessionBandwidth::new();
+
     let (conn, their_status) = if p2p_stream.shared_capabilities().len() == 1 {
         // if the hello handshake was successful we can try status handshake
         //
@@ -1022,15 +1030,22 @@ async fn authenticate_stream(
             .await
             .unwrap();
 
-        // install additional handlers
+        // install additional handlers, metering their messages
         for handler in extra_handlers.into_iter() {
             let cap = handler.protocol().cap;
             let remote_peer_id = their_hello.id;
-            multiplex_stream
+            let counter = bandwidth.add_protocol(cap.clone());
+            let installed_cap = cap.clone();
+            if multiplex_stream
                 .install_protocol(&cap, move |conn| {
-                    handler.into_connection(direction, remote_peer_id, conn)
+                    let conn = conn.with_received_bytes_counter(counter.inbound());
+                    let conn = handler.into_connection(direction, remote_peer_id, conn);
+                    MeteredProtocolStream::new(conn, &installed_cap, counter)
                 })
-                .ok();
+                .is_err()
+            {
+                bandwidth.remove_protocol(&cap);
+            }
         }
 
         (multiplex_stream.into(), their_status)
@@ -1046,5 +1061,6 @@ async fn authenticate_stream(
         conn,
         direction,
         client_id: their_hello.client_version,
+        bandwidth,
     }
 }
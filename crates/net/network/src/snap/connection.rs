@@ -0,0 +1,293 @@
+//! The `snap/1` RLPx sub-protocol handler and the per-peer connection.
+
+use super::{snap_protocol, IncomingSnapRequest, SnapPeerHandle, SnapPeerRequest, SnapPeers};
+use crate::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use alloy_rlp::Encodable;
+use futures::{stream::FuturesUnordered, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol,
+    snap::SnapMessageId, SnapProtocolMessage,
+};
+use reth_interfaces::p2p::error::{RequestError, RequestResult};
+use reth_network_api::Direction;
+use reth_primitives::{BytesMut, PeerId};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+};
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    oneshot,
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{debug, trace};
+
+/// The [`ProtocolHandler`] of the `snap/1` protocol.
+///
+/// Announces `snap/1` on every connection and registers the established connections in the
+/// shared registry of `snap` peers.
+#[derive(Debug, Clone)]
+pub struct SnapProtocolHandler {
+    /// The registry of connected `snap` peers.
+    peers: SnapPeers,
+    /// Sender half of the channel to the [`SnapRequestHandler`](super::SnapRequestHandler).
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+}
+
+impl SnapProtocolHandler {
+    /// Creates a new handler that delegates incoming requests to the given channel.
+    pub(crate) fn new(
+        peers: SnapPeers,
+        to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+    ) -> Self {
+        Self { peers, to_request_handler }
+    }
+
+    fn connection_handler(&self) -> SnapConnectionHandler {
+        SnapConnectionHandler {
+            peers: self.peers.clone(),
+            to_request_handler: self.to_request_handler.clone(),
+        }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// The [`ConnectionHandler`] of the `snap/1` protocol.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    peers: SnapPeers,
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        snap_protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (to_connection, from_peers) = mpsc::unbounded_channel();
+        let inflight = Arc::new(AtomicUsize::new(0));
+        self.peers.insert(
+            peer_id,
+            SnapPeerHandle { to_connection: to_connection.clone(), inflight: inflight.clone() },
+        );
+
+        SnapConnection {
+            peer_id,
+            conn,
+            peers: self.peers,
+            to_connection,
+            requests: UnboundedReceiverStream::new(from_peers),
+            inflight_requests: HashMap::new(),
+            inflight,
+            next_request_id: 0,
+            to_request_handler: self.to_request_handler,
+            pending_responses: FuturesUnordered::new(),
+        }
+    }
+}
+
+/// A request sent to the peer that awaits a response.
+#[derive(Debug)]
+struct InflightSnapRequest {
+    /// The message id of the expected response.
+    response_id: SnapMessageId,
+    /// The channel to send the response to.
+    response: oneshot::Sender<RequestResult<SnapProtocolMessage>>,
+}
+
+/// A `snap/1` connection to a peer.
+///
+/// Sends the requests of the [`FetchClient`](crate::FetchClient) to the peer and matches the
+/// responses by request id, and delegates the requests of the peer to the
+/// [`SnapRequestHandler`](super::SnapRequestHandler).
+///
+/// The connection is closed if the peer sends an invalid message.
+#[derive(Debug)]
+pub struct SnapConnection {
+    /// The remote peer.
+    peer_id: PeerId,
+    /// The raw messages of the peer.
+    conn: ProtocolConnection,
+    /// The registry of connected `snap` peers, this connection is removed from on drop.
+    peers: SnapPeers,
+    /// The sender half of `requests`, identifies this connection in the registry.
+    to_connection: mpsc::UnboundedSender<SnapPeerRequest>,
+    /// Requests to send to the peer.
+    requests: UnboundedReceiverStream<SnapPeerRequest>,
+    /// Requests sent to the peer, by request id.
+    inflight_requests: HashMap<u64, InflightSnapRequest>,
+    /// The number of requests sent to the peer that await a response, shared with the registry.
+    inflight: Arc<AtomicUsize>,
+    /// The request id of the next request sent to the peer.
+    next_request_id: u64,
+    /// Sender half of the channel to the [`SnapRequestHandler`](super::SnapRequestHandler).
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+    /// Responses to requests of the peer that are being served.
+    pending_responses: FuturesUnordered<oneshot::Receiver<SnapProtocolMessage>>,
+}
+
+impl SnapConnection {
+    /// Assigns a request id to the request and returns the encoded request.
+    fn on_request_to_peer(&mut self, request: SnapPeerRequest) -> Option<BytesMut> {
+        let SnapPeerRequest { mut request, response } = request;
+        let response_id = match &mut request {
+            SnapProtocolMessage::GetAccountRange(req) => {
+                req.request_id = self.next_request_id;
+                SnapMessageId::AccountRange
+            }
+            SnapProtocolMessage::GetStorageRanges(req) => {
+                req.request_id = self.next_request_id;
+                SnapMessageId::StorageRanges
+            }
+            SnapProtocolMessage::GetByteCodes(req) => {
+                req.request_id = self.next_request_id;
+                SnapMessageId::ByteCodes
+            }
+            SnapProtocolMessage::GetTrieNodes(req) => {
+                req.request_id = self.next_request_id;
+                SnapMessageId::TrieNodes
+            }
+            _ => {
+                let _ = response.send(Err(RequestError::BadResponse));
+                return None
+            }
+        };
+
+        // requests the client stopped waiting for will not be answered anymore
+        self.inflight_requests.retain(|_, req| !req.response.is_closed());
+        self.inflight_requests
+            .insert(self.next_request_id, InflightSnapRequest { response_id, response });
+        self.inflight.store(self.inflight_requests.len(), Ordering::Relaxed);
+        self.next_request_id += 1;
+
+        Some(encode_message(&request))
+    }
+
+    /// Delegates a request of the peer to the request handler.
+    fn on_request_from_peer(&mut self, request: SnapProtocolMessage) {
+        let (tx, rx) = oneshot::channel();
+        match self.to_request_handler.try_send(IncomingSnapRequest {
+            peer_id: self.peer_id,
+            request,
+            response: tx,
+        }) {
+            Ok(()) => self.pending_responses.push(rx),
+            Err(TrySendError::Full(_)) => {
+                trace!(target: "net::snap", peer_id=?self.peer_id, "Dropping request, request handler is busy");
+            }
+            Err(TrySendError::Closed(_)) => {
+                trace!(target: "net::snap", peer_id=?self.peer_id, "Dropping request, request handler is gone");
+            }
+        }
+    }
+
+    /// Matches a response of the peer with the inflight request.
+    fn on_response_from_peer(&mut self, response: SnapProtocolMessage) {
+        let Some(request) = self.inflight_requests.remove(&response.request_id()) else {
+            // the request may have been abandoned by the client
+            trace!(target: "net::snap", peer_id=?self.peer_id, request_id=response.request_id(), "Received response to unknown request");
+            return
+        };
+        self.inflight.store(self.inflight_requests.len(), Ordering::Relaxed);
+
+        if request.response_id == response.message_id() {
+            let _ = request.response.send(Ok(response));
+        } else {
+            let _ = request.response.send(Err(RequestError::BadResponse));
+        }
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(request)) = this.requests.poll_next_unpin(cx) {
+                if let Some(msg) = this.on_request_to_peer(request) {
+                    return Poll::Ready(Some(msg))
+                }
+                continue
+            }
+
+            match this.pending_responses.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(response))) => {
+                    return Poll::Ready(Some(encode_message(&response)))
+                }
+                // the request handler dropped the request
+                Poll::Ready(Some(Err(_))) => continue,
+                _ => {}
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else {
+                // connection closed
+                return Poll::Ready(None)
+            };
+
+            match SnapProtocolMessage::decode_message(&mut &msg[..]) {
+                Ok(message) if message.is_request() => this.on_request_from_peer(message),
+                Ok(message) => this.on_response_from_peer(message),
+                Err(err) => {
+                    debug!(target: "net::snap", peer_id=?this.peer_id, %err, "Received invalid message, disconnecting");
+                    return Poll::Ready(None)
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SnapConnection {
+    fn drop(&mut self) {
+        self.peers.remove(&self.peer_id, &self.to_connection);
+        for (_, request) in self.inflight_requests.drain() {
+            let _ = request.response.send(Err(RequestError::ConnectionDropped));
+        }
+    }
+}
+
+/// Encodes the message, prefixed by its message id.
+fn encode_message(message: &SnapProtocolMessage) -> BytesMut {
+    let mut buf = BytesMut::with_capacity(message.length());
+    message.encode(&mut buf);
+    buf
+}
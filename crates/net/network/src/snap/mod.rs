@@ -0,0 +1,93 @@
+//! Support for the `snap/1` protocol: <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+//!
+//! The protocol runs as an additional RLPx sub-protocol next to `eth`. The
+//! [`SnapProtocolHandler`] establishes a [`SnapConnection`] with every peer that supports `snap/1`.
+//! Incoming requests are delegated to the [`SnapRequestHandler`], which serves them from the
+//! database, outgoing requests are sent via the [`FetchClient`](crate::FetchClient).
+
+use parking_lot::RwLock;
+use reth_eth_wire::{
+    capability::Capability, protocol::Protocol, snap::SNAP_PROTOCOL_MESSAGE_COUNT,
+    SnapProtocolMessage,
+};
+use reth_interfaces::p2p::error::RequestResult;
+use reth_primitives::PeerId;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+mod connection;
+mod server;
+
+pub use connection::{SnapConnection, SnapConnectionHandler, SnapProtocolHandler};
+pub use server::{IncomingSnapRequest, SnapRequestHandler};
+
+/// Returns the `snap/1` capability.
+pub const fn snap_capability() -> Capability {
+    Capability::new_static("snap", 1)
+}
+
+/// Returns the `snap/1` protocol.
+pub fn snap_protocol() -> Protocol {
+    Protocol::new(snap_capability(), SNAP_PROTOCOL_MESSAGE_COUNT)
+}
+
+/// A request to send to a peer over its [`SnapConnection`].
+#[derive(Debug)]
+pub(crate) struct SnapPeerRequest {
+    /// The request to send, the request id is assigned by the connection.
+    pub(crate) request: SnapProtocolMessage,
+    /// The channel to send the response of the peer to.
+    pub(crate) response: oneshot::Sender<RequestResult<SnapProtocolMessage>>,
+}
+
+/// The handle to an established [`SnapConnection`].
+#[derive(Debug, Clone)]
+pub(crate) struct SnapPeerHandle {
+    /// Sender half of the channel to the connection.
+    pub(crate) to_connection: UnboundedSender<SnapPeerRequest>,
+    /// The number of requests to the peer that are awaiting a response.
+    pub(crate) inflight: Arc<AtomicUsize>,
+}
+
+/// The registry of connected peers that support `snap/1`.
+///
+/// This is shared between the [`SnapConnection`]s and the [`FetchClient`](crate::FetchClient).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SnapPeers {
+    peers: Arc<RwLock<HashMap<PeerId, SnapPeerHandle>>>,
+}
+
+impl SnapPeers {
+    /// Registers the connection to a peer.
+    pub(crate) fn insert(&self, peer_id: PeerId, handle: SnapPeerHandle) {
+        self.peers.write().insert(peer_id, handle);
+    }
+
+    /// Removes the connection to the peer, if it is the given connection.
+    pub(crate) fn remove(&self, peer_id: &PeerId, to_connection: &UnboundedSender<SnapPeerRequest>) {
+        let mut peers = self.peers.write();
+        if peers.get(peer_id).is_some_and(|peer| peer.to_connection.same_channel(to_connection)) {
+            peers.remove(peer_id);
+        }
+    }
+
+    /// Returns the number of connected `snap` peers.
+    pub(crate) fn len(&self) -> usize {
+        self.peers.read().len()
+    }
+
+    /// Returns the peer with the fewest inflight requests.
+    pub(crate) fn idlest(&self) -> Option<(PeerId, SnapPeerHandle)> {
+        self.peers
+            .read()
+            .iter()
+            .min_by_key(|(_, peer)| peer.inflight.load(Ordering::Relaxed))
+            .map(|(peer_id, peer)| (*peer_id, peer.clone()))
+    }
+}
@@ -0,0 +1,562 @@
+//! Serves `snap/1` requests from the database.
+
+use crate::{budget::DEFAULT_BUDGET_TRY_DRAIN_STREAM, poll_nested_stream_with_budget};
+use alloy_rlp::Encodable;
+use futures::StreamExt;
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    tables,
+    transaction::DbTx,
+    DatabaseError,
+};
+use reth_eth_wire::{
+    snap::{
+        AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+        GetTrieNodes, SlimAccount, StorageData, StorageRanges, TrieNodes,
+    },
+    SnapProtocolMessage,
+};
+use reth_interfaces::trie::StateRootError;
+use reth_primitives::{trie::Nibbles, Bytes, PeerId, StorageEntry, B256, KECCAK_EMPTY};
+use reth_provider::{
+    BlockNumReader, DatabaseProviderFactory, HeaderProvider, ProviderError, ProviderResult,
+};
+use reth_tasks::pool::BlockingTaskPool;
+use reth_trie::proof::Proof;
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::{mpsc::Receiver, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::debug;
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/v1.13.14/eth/protocols/snap/handler.go#L34-L53>
+
+/// Maximum size of replies to data retrievals.
+const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Maximum number of bytecodes to look up.
+///
+/// Used to limit lookups.
+const MAX_CODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of trie nodes to look up.
+///
+/// Used to limit lookups.
+const MAX_TRIE_NODE_LOOKUPS: usize = 1024;
+
+/// A `snap/1` request of a peer, delegated by its [`SnapConnection`](super::SnapConnection).
+#[derive(Debug)]
+pub struct IncomingSnapRequest {
+    /// The peer that sent the request.
+    pub peer_id: PeerId,
+    /// The request.
+    pub request: SnapProtocolMessage,
+    /// The channel sender for the response.
+    pub response: oneshot::Sender<SnapProtocolMessage>,
+}
+
+/// Serves `snap/1` requests of peers.
+///
+/// Requests are only served for the state root of the latest block, all other requests are
+/// answered with empty responses, which tells the peer that the state is not available.
+///
+/// Serving a request walks the tries and computes storage roots and proofs, so requests are
+/// served on a [`BlockingTaskPool`].
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct SnapRequestHandler<DB, C> {
+    /// The client type that can access the database.
+    client: C,
+    /// Incoming requests from the [`SnapConnection`](super::SnapConnection)s.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// The pool that serves the requests.
+    blocking_task_pool: BlockingTaskPool,
+    _db: PhantomData<DB>,
+}
+
+// === impl SnapRequestHandler ===
+
+impl<DB, C> SnapRequestHandler<DB, C> {
+    /// Create a new instance
+    pub fn new(
+        client: C,
+        incoming: Receiver<IncomingSnapRequest>,
+        blocking_task_pool: BlockingTaskPool,
+    ) -> Self {
+        Self {
+            client,
+            incoming_requests: ReceiverStream::new(incoming),
+            blocking_task_pool,
+            _db: PhantomData,
+        }
+    }
+}
+
+impl<DB, C> SnapRequestHandler<DB, C>
+where
+    DB: Database,
+    C: DatabaseProviderFactory<DB>,
+{
+    fn on_request(
+        client: &C,
+        peer_id: PeerId,
+        request: SnapProtocolMessage,
+    ) -> SnapProtocolMessage {
+        let response = match &request {
+            SnapProtocolMessage::GetAccountRange(req) => {
+                Self::with_state(client, req.root_hash, |tx| serve_account_range(tx, req))
+                    .map(|res| res.map(SnapProtocolMessage::AccountRange))
+            }
+            SnapProtocolMessage::GetStorageRanges(req) => {
+                Self::with_state(client, req.root_hash, |tx| serve_storage_ranges(tx, req))
+                    .map(|res| res.map(SnapProtocolMessage::StorageRanges))
+            }
+            SnapProtocolMessage::GetByteCodes(req) => {
+                // bytecodes are not tied to a state root
+                client
+                    .database_provider_ro()
+                    .and_then(|provider| serve_byte_codes(provider.tx_ref(), req))
+                    .map(|res| Some(SnapProtocolMessage::ByteCodes(res)))
+            }
+            SnapProtocolMessage::GetTrieNodes(req) => {
+                Self::with_state(client, req.root_hash, |tx| serve_trie_nodes(tx, req))
+                    .map(|res| res.map(SnapProtocolMessage::TrieNodes))
+            }
+            _ => Ok(None),
+        };
+
+        match response {
+            Ok(Some(response)) => response,
+            Ok(None) => empty_response(&request),
+            Err(err) => {
+                debug!(target: "net::snap", ?peer_id, %err, "Failed to serve request");
+                empty_response(&request)
+            }
+        }
+    }
+
+    /// Serves a request with the database transaction, if the given state root is the root of
+    /// the latest state.
+    fn with_state<T>(
+        client: &C,
+        state_root: B256,
+        f: impl FnOnce(&<DB as Database>::TX) -> ProviderResult<T>,
+    ) -> ProviderResult<Option<T>> {
+        let provider = client.database_provider_ro()?;
+        let best_block = provider.best_block_number()?;
+        let Some(header) = provider.header_by_number(best_block)? else { return Ok(None) };
+        if header.state_root != state_root {
+            return Ok(None)
+        }
+        f(provider.tx_ref()).map(Some)
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<DB, C> Future for SnapRequestHandler<DB, C>
+where
+    DB: Database + 'static,
+    C: DatabaseProviderFactory<DB> + Clone + Send + Unpin + 'static,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let maybe_more_incoming_requests = poll_nested_stream_with_budget!(
+            "net::snap",
+            "Incoming snap requests stream",
+            DEFAULT_BUDGET_TRY_DRAIN_STREAM,
+            this.incoming_requests.poll_next_unpin(cx),
+            |incoming| {
+                let IncomingSnapRequest { peer_id, request, response } = incoming;
+                let client = this.client.clone();
+                // the response is sent from the pool, the handle isn't needed
+                let _ = this.blocking_task_pool.spawn(move || {
+                    let _ = response.send(Self::on_request(&client, peer_id, request));
+                });
+            },
+        );
+
+        // stream is fully drained and import futures pending
+        if maybe_more_incoming_requests {
+            // make sure we're woken up again
+            cx.waker().wake_by_ref();
+            return Poll::Pending
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Returns the empty response to the given request.
+fn empty_response(request: &SnapProtocolMessage) -> SnapProtocolMessage {
+    let request_id = request.request_id();
+    match request {
+        SnapProtocolMessage::GetStorageRanges(_) => {
+            SnapProtocolMessage::StorageRanges(StorageRanges { request_id, ..Default::default() })
+        }
+        SnapProtocolMessage::GetByteCodes(_) => {
+            SnapProtocolMessage::ByteCodes(ByteCodes { request_id, ..Default::default() })
+        }
+        SnapProtocolMessage::GetTrieNodes(_) => {
+            SnapProtocolMessage::TrieNodes(TrieNodes { request_id, ..Default::default() })
+        }
+        _ => SnapProtocolMessage::AccountRange(AccountRange { request_id, ..Default::default() }),
+    }
+}
+
+/// Returns the soft limit of the response size.
+fn response_limit(response_bytes: u64) -> usize {
+    usize::try_from(response_bytes).unwrap_or(usize::MAX).min(SOFT_RESPONSE_LIMIT)
+}
+
+/// Converts a trie error into a [`ProviderError`].
+fn trie_error(err: impl Into<StateRootError>) -> ProviderError {
+    DatabaseError::from(err.into()).into()
+}
+
+/// Returns the accounts starting at the requested hash, up to and including the first account at
+/// or after the limit hash, and the proof of the starting hash and the last returned account.
+fn serve_account_range<TX: DbTx>(
+    tx: &TX,
+    request: &GetAccountRange,
+) -> ProviderResult<AccountRange> {
+    let limit = response_limit(request.response_bytes);
+    let proof = Proof::new(tx);
+
+    let mut accounts = Vec::new();
+    let mut size = 0;
+
+    let mut cursor = tx.cursor_read::<tables::HashedAccounts>()?;
+    let mut entry = cursor.seek(request.starting_hash)?;
+    while let Some((hash, account)) = entry {
+        let storage_root = proof.storage_root(hash).map_err(trie_error)?;
+        let data = AccountData {
+            hash,
+            body: SlimAccount {
+                nonce: account.nonce,
+                balance: account.balance,
+                storage_root,
+                code_hash: account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+            },
+        };
+        size += data.length();
+        accounts.push(data);
+
+        if hash >= request.limit_hash || size >= limit {
+            break
+        }
+        entry = cursor.next()?;
+    }
+
+    let mut targets = vec![request.starting_hash];
+    targets.extend(accounts.last().map(|account| account.hash));
+    let proof = proof.account_multiproof(&targets).map_err(trie_error)?;
+
+    Ok(AccountRange { request_id: request.request_id, accounts, proof })
+}
+
+/// Returns the storage slots of the requested accounts.
+///
+/// Serving stops at the first account whose range is incomplete, either because it starts after
+/// the first slot, ends before the last slot or the response limit was reached. The proof of the
+/// boundaries of that range is attached.
+fn serve_storage_ranges<TX: DbTx>(
+    tx: &TX,
+    request: &GetStorageRanges,
+) -> ProviderResult<StorageRanges> {
+    let limit = response_limit(request.response_bytes);
+    let starting_hash = request.starting_hash();
+    let limit_hash = request.limit_hash();
+
+    let mut slots = Vec::new();
+    let mut proof = Vec::new();
+    let mut size = 0;
+
+    let mut cursor = tx.cursor_dup_read::<tables::HashedStorages>()?;
+    for (idx, hashed_address) in request.account_hashes.iter().enumerate() {
+        if size >= limit {
+            break
+        }
+
+        // the starting hash only applies to the first account, the limit hash only to the last
+        let origin = if idx == 0 { starting_hash } else { B256::ZERO };
+        let last = if idx == request.account_hashes.len() - 1 {
+            limit_hash
+        } else {
+            B256::repeat_byte(0xff)
+        };
+
+        let mut account_slots = Vec::new();
+        let mut incomplete = origin != B256::ZERO;
+
+        let mut entry = cursor.seek_by_key_subkey(*hashed_address, origin)?;
+        while let Some(StorageEntry { key, value }) = entry {
+            if size >= limit {
+                incomplete = true;
+                break
+            }
+
+            let data = StorageData { hash: key, data: alloy_rlp::encode(value).into() };
+            size += data.length();
+            account_slots.push(data);
+
+            if key >= last {
+                incomplete |= last != B256::repeat_byte(0xff);
+                break
+            }
+            entry = cursor.next_dup_val()?;
+        }
+
+        if incomplete {
+            let mut targets = vec![origin];
+            targets.extend(account_slots.last().map(|slot| slot.hash));
+            let (_, nodes) =
+                Proof::new(tx).storage_multiproof(*hashed_address, &targets).map_err(trie_error)?;
+            proof = nodes;
+        }
+
+        slots.push(account_slots);
+
+        if incomplete {
+            break
+        }
+    }
+
+    Ok(StorageRanges { request_id: request.request_id, slots, proof })
+}
+
+/// Returns the requested bytecodes, skipping unknown ones.
+fn serve_byte_codes<TX: DbTx>(tx: &TX, request: &GetByteCodes) -> ProviderResult<ByteCodes> {
+    let limit = response_limit(request.response_bytes);
+
+    let mut codes = Vec::new();
+    let mut size = 0;
+
+    for hash in request.hashes.iter().take(MAX_CODE_LOOKUPS) {
+        let code = if *hash == KECCAK_EMPTY {
+            Bytes::new()
+        } else if let Some(bytecode) = tx.get::<tables::Bytecodes>(*hash)? {
+            bytecode.original_bytes()
+        } else {
+            continue
+        };
+
+        size += code.len();
+        codes.push(code);
+
+        if size >= limit {
+            break
+        }
+    }
+
+    Ok(ByteCodes { request_id: request.request_id, codes })
+}
+
+/// Returns the requested trie nodes.
+///
+/// Serving stops at the first unknown node, since nodes must not be skipped.
+fn serve_trie_nodes<TX: DbTx>(tx: &TX, request: &GetTrieNodes) -> ProviderResult<TrieNodes> {
+    let limit = response_limit(request.response_bytes);
+    let proof = Proof::new(tx);
+
+    let mut nodes = Vec::new();
+    let mut size = 0;
+
+    'paths: for path_set in &request.paths {
+        let (requested, found) = match path_set.0.as_slice() {
+            [] => break,
+            [account_path] => {
+                let Some(path) = decode_compact_path(account_path) else { break };
+                let found = proof.account_trie_nodes(vec![path.clone()]).map_err(trie_error)?;
+                (vec![path], found)
+            }
+            [hashed_address, storage_paths @ ..] => {
+                if hashed_address.len() != B256::len_bytes() {
+                    break
+                }
+                let Some(paths) = storage_paths
+                    .iter()
+                    .map(|path| decode_compact_path(path))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    break
+                };
+                let (_, found) = proof
+                    .storage_trie_nodes(B256::from_slice(hashed_address), paths.clone())
+                    .map_err(trie_error)?;
+                (paths, found)
+            }
+        };
+
+        for path in requested {
+            let Some(node) = found.get(&path) else { break 'paths };
+            size += node.len();
+            nodes.push(node.clone());
+
+            if size >= limit || nodes.len() >= MAX_TRIE_NODE_LOOKUPS {
+                break 'paths
+            }
+        }
+    }
+
+    Ok(TrieNodes { request_id: request.request_id, nodes })
+}
+
+/// Decodes a compact (hex-prefix) encoded trie path.
+fn decode_compact_path(path: &[u8]) -> Option<Nibbles> {
+    let (flags, rest) = path.split_first()?;
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    match flags >> 4 {
+        // even length, with or without terminator
+        0 | 2 => {}
+        // odd length, with or without terminator
+        1 | 3 => nibbles.push(flags & 0x0f),
+        _ => return None,
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+    Some(Nibbles::from_nibbles_unchecked(nibbles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::transaction::DbTxMut;
+    use reth_primitives::{keccak256, Account, U256};
+    use reth_provider::test_utils::create_test_provider_factory;
+    use reth_trie::StateRoot;
+
+    #[test]
+    fn decode_compact_paths() {
+        assert_eq!(decode_compact_path(&[0x00]), Some(Nibbles::default()));
+        assert_eq!(
+            decode_compact_path(&[0x00, 0x12]),
+            Some(Nibbles::from_nibbles_unchecked([0x1, 0x2]))
+        );
+        assert_eq!(
+            decode_compact_path(&[0x13, 0x45]),
+            Some(Nibbles::from_nibbles_unchecked([0x3, 0x4, 0x5]))
+        );
+        assert_eq!(decode_compact_path(&[0x40]), None);
+        assert_eq!(decode_compact_path(&[]), None);
+    }
+
+    #[test]
+    fn serve_state_ranges() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let accounts = (1..=10u64)
+            .map(|i| {
+                let account = Account { nonce: i, balance: U256::from(i), bytecode_hash: None };
+                (keccak256(B256::with_last_byte(i as u8)), account)
+            })
+            .collect::<std::collections::BTreeMap<_, _>>();
+        for (hashed_address, account) in &accounts {
+            tx.put::<tables::HashedAccounts>(*hashed_address, *account).unwrap();
+        }
+        let (first, _) = accounts.first_key_value().unwrap();
+        for slot in 1..=5u8 {
+            let entry = StorageEntry { key: keccak256([slot]), value: U256::from(slot) };
+            tx.put::<tables::HashedStorages>(*first, entry).unwrap();
+        }
+
+        let (root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+
+        // the full range is served, with the proof of the boundaries
+        let range = serve_account_range(
+            tx,
+            &GetAccountRange {
+                request_id: 1,
+                root_hash: root,
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: 1024 * 1024,
+            },
+        )
+        .unwrap();
+        assert_eq!(range.request_id, 1);
+        assert_eq!(range.accounts.len(), accounts.len());
+        assert!(range.accounts.iter().map(|account| account.hash).eq(accounts.keys().copied()));
+        assert_eq!(keccak256(&range.proof[0]), root);
+
+        // the response is cut after the limit hash
+        let third = *accounts.keys().nth(2).unwrap();
+        let range = serve_account_range(
+            tx,
+            &GetAccountRange {
+                request_id: 2,
+                root_hash: root,
+                starting_hash: B256::ZERO,
+                limit_hash: third,
+                response_bytes: 1024 * 1024,
+            },
+        )
+        .unwrap();
+        assert_eq!(range.accounts.len(), 3);
+
+        // the storage range of the first account is complete
+        let ranges = serve_storage_ranges(
+            tx,
+            &GetStorageRanges {
+                request_id: 3,
+                root_hash: root,
+                account_hashes: vec![*first],
+                starting_hash: Bytes::new(),
+                limit_hash: Bytes::new(),
+                response_bytes: 1024 * 1024,
+            },
+        )
+        .unwrap();
+        assert_eq!(ranges.slots.len(), 1);
+        assert_eq!(ranges.slots[0].len(), 5);
+        assert!(ranges.proof.is_empty());
+
+        // a partial storage range comes with a proof
+        let ranges = serve_storage_ranges(
+            tx,
+            &GetStorageRanges {
+                request_id: 4,
+                root_hash: root,
+                account_hashes: vec![*first],
+                starting_hash: ranges.slots[0][1].hash.into(),
+                limit_hash: Bytes::new(),
+                response_bytes: 1024 * 1024,
+            },
+        )
+        .unwrap();
+        assert_eq!(ranges.slots[0].len(), 4);
+        assert!(!ranges.proof.is_empty());
+
+        // the root node of the account trie is served at the empty path
+        let nodes = serve_trie_nodes(
+            tx,
+            &GetTrieNodes {
+                request_id: 5,
+                root_hash: root,
+                paths: vec![reth_eth_wire::snap::TrieNodePathSet(vec![Bytes::from_static(&[
+                    0x00,
+                ])])],
+                response_bytes: 1024 * 1024,
+            },
+        )
+        .unwrap();
+        assert_eq!(nodes.nodes.len(), 1);
+        assert_eq!(keccak256(&nodes.nodes[0]), root);
+    }
+}
@@ -17,7 +17,7 @@ use rand::seq::SliceRandom;
 use reth_eth_wire::{
     capability::Capabilities, BlockHashNumber, DisconnectReason, NewBlockHashes, Status,
 };
-use reth_network_api::PeerKind;
+use reth_network_api::{PeerKind, PeerScore};
 use reth_primitives::{ForkId, PeerId, B256};
 use reth_provider::BlockNumReader;
 use std::{
@@ -110,6 +110,11 @@ where
         self.state_fetcher.client()
     }
 
+    /// Returns the [`PeerScore`] of the connected peer.
+    pub(crate) fn peer_score(&self, peer_id: &PeerId) -> Option<PeerScore> {
+        self.state_fetcher.peer_score(peer_id)
+    }
+
     /// How many peers we're currently connected to.
     pub fn num_active_peers(&self) -> usize {
         self.active_peers.len()
@@ -382,17 +387,22 @@ where
     /// instruction that needs to be handled in [Self::on_block_response_outcome]. This could be
     /// a follow-up request or an instruction to slash the peer's reputation.
     fn on_eth_response(&mut self, peer: PeerId, resp: PeerResponseResult) -> Option<StateAction> {
-        match resp {
+        let outcome = match resp {
             PeerResponseResult::BlockHeaders(res) => {
-                let outcome = self.state_fetcher.on_block_headers_response(peer, res)?;
-                self.on_block_response_outcome(outcome)
+                self.state_fetcher.on_block_headers_response(peer, res)
             }
             PeerResponseResult::BlockBodies(res) => {
-                let outcome = self.state_fetcher.on_block_bodies_response(peer, res)?;
-                self.on_block_response_outcome(outcome)
+                self.state_fetcher.on_block_bodies_response(peer, res)
             }
-            _ => None,
+            _ => return None,
+        };
+
+        // the response changed the peer's score, which may get it rotated out
+        if let Some(score) = self.state_fetcher.peer_score(&peer) {
+            self.peers_manager.on_peer_score_update(peer, &score);
         }
+
+        self.on_block_response_outcome(outcome?)
     }
 
     /// Advances the state
@@ -2,6 +2,7 @@ use super::{
     DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
     SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
+use crate::bandwidth::ServeLimits;
 use derive_more::Constructor;
 
 /// Configuration for managing transactions within the network.
@@ -10,6 +11,10 @@ use derive_more::Constructor;
 pub struct TransactionsManagerConfig {
     /// Configuration for fetching transactions.
     pub transaction_fetcher_config: TransactionFetcherConfig,
+    /// Limits on the bandwidth used for serving
+    /// [`PooledTransactions`](reth_eth_wire::PooledTransactions) responses.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub serve_limits: ServeLimits,
 }
 
 /// Configuration for fetching transactions.
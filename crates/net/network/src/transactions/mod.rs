@@ -1,6 +1,7 @@
 //! Transactions management for the p2p network.
 
 use crate::{
+    bandwidth::ServeRateLimiter,
     budget::{
         DEFAULT_BUDGET_TRY_DRAIN_NETWORK_TRANSACTION_EVENTS,
         DEFAULT_BUDGET_TRY_DRAIN_PENDING_POOL_IMPORTS, DEFAULT_BUDGET_TRY_DRAIN_POOL_IMPORTS,
@@ -14,6 +15,7 @@ use crate::{
     metrics::{TransactionsManagerMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE},
     NetworkEvents, NetworkHandle,
 };
+use alloy_rlp::Encodable;
 use futures::{stream::FuturesUnordered, Future, StreamExt};
 use reth_eth_wire::{
     EthVersion, GetPooledTransactions, HandleMempoolData, HandleVersionedMempoolData,
@@ -244,6 +246,8 @@ pub struct TransactionsManager<Pool> {
     pending_transactions: ReceiverStream<TxHash>,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedMeteredReceiver<NetworkTransactionEvent>,
+    /// Limits the bandwidth used for serving [`PooledTransactions`] responses.
+    serve_limiter: ServeRateLimiter,
     /// TransactionsManager metrics
     metrics: TransactionsManagerMetrics,
 }
@@ -296,6 +300,7 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
                 from_network,
                 NETWORK_POOL_TRANSACTIONS_SCOPE,
             ),
+            serve_limiter: ServeRateLimiter::new(transactions_manager_config.serve_limits),
             metrics,
         }
     }
@@ -355,11 +360,22 @@ where
                 let _ = response.send(Ok(PooledTransactions::default()));
                 return
             }
+            let soft_limit =
+                self.transaction_fetcher.info.soft_limit_byte_size_pooled_transactions_response;
+            let size_limit = match self.serve_limiter.available(&peer_id) {
+                Some(available) if (available as usize) < soft_limit => {
+                    self.metrics.throttled_pooled_transactions_responses.increment(1);
+                    if available == 0 {
+                        let _ = response.send(Ok(PooledTransactions::default()));
+                        return
+                    }
+                    available as usize
+                }
+                _ => soft_limit,
+            };
             let transactions = self.pool.get_pooled_transaction_elements(
                 request.0,
-                GetPooledTransactionLimit::ResponseSizeSoftLimit(
-                    self.transaction_fetcher.info.soft_limit_byte_size_pooled_transactions_response,
-                ),
+                GetPooledTransactionLimit::ResponseSizeSoftLimit(size_limit),
             );
 
             // we sent a response at which point we assume that the peer is aware of the
@@ -367,6 +383,9 @@ where
             peer.seen_transactions.extend(transactions.iter().map(|tx| *tx.hash()));
 
             let resp = PooledTransactions(transactions);
+            if !self.serve_limiter.is_unlimited() {
+                self.serve_limiter.on_served(peer_id, resp.length() as u64);
+            }
             let _ = response.send(Ok(resp));
         }
     }
@@ -395,8 +414,14 @@ where
 
         // This fetches all transaction from the pool, including the 4844 blob transactions but
         // __without__ their sidecar, because 4844 transactions are only ever announced as hashes.
+        // Private and conditional transactions are never announced.
         let propagated = self.propagate_transactions(
-            self.pool.get_all(hashes).into_iter().map(PropagateTransaction::new).collect(),
+            self.pool
+                .get_all(hashes)
+                .into_iter()
+                .filter(|tx| tx.is_shareable())
+                .map(PropagateTransaction::new)
+                .collect(),
         );
 
         // notify pool so events get fired
@@ -443,7 +468,10 @@ where
                     //  via `GetPooledTransactions`.
                     //
                     // From: <https://eips.ethereum.org/EIPS/eip-4844#networking>
-                    if !tx.transaction.is_eip4844() {
+                    //
+                    // Additional transaction types can opt out of full broadcasts as well, see
+                    // [PoolTransaction::is_broadcastable_in_full].
+                    if tx.broadcastable_in_full {
                         full_transactions.push(tx);
                     }
                 }
@@ -513,7 +541,7 @@ where
             .pool
             .get_all(txs)
             .into_iter()
-            .filter(|tx| !tx.transaction.is_eip4844())
+            .filter(|tx| tx.transaction.is_broadcastable_in_full() && tx.is_shareable())
             .map(PropagateTransaction::new);
 
         // Iterate through the transactions to propagate and fill the hashes and full transaction
@@ -555,8 +583,13 @@ where
                 return
             };
 
-            let to_propagate: Vec<PropagateTransaction> =
-                self.pool.get_all(hashes).into_iter().map(PropagateTransaction::new).collect();
+            let to_propagate: Vec<PropagateTransaction> = self
+                .pool
+                .get_all(hashes)
+                .into_iter()
+                .filter(|tx| tx.is_shareable())
+                .map(PropagateTransaction::new)
+                .collect();
 
             let mut propagated = PropagatedTransactions::default();
 
@@ -1370,6 +1403,8 @@ where
 /// A transaction that's about to be propagated to multiple peers.
 struct PropagateTransaction {
     size: usize,
+    /// Whether the full transaction can be broadcast, otherwise it's only announced.
+    broadcastable_in_full: bool,
     transaction: Arc<TransactionSigned>,
 }
 
@@ -1383,8 +1418,9 @@ impl PropagateTransaction {
     /// Create a new instance from a pooled transaction
     fn new<T: PoolTransaction>(tx: Arc<ValidPoolTransaction<T>>) -> Self {
         let size = tx.encoded_length();
+        let broadcastable_in_full = tx.transaction.is_broadcastable_in_full();
         let transaction = Arc::new(tx.transaction.to_recovered_transaction().into_signed());
-        Self { size, transaction }
+        Self { size, broadcastable_in_full, transaction }
     }
 }
 
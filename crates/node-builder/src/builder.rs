@@ -30,7 +30,7 @@ use reth_db::{
 };
 use reth_exex::{ExExContext, ExExHandle, ExExManager, ExExManagerHandle};
 use reth_interfaces::{consensus::Consensus, p2p::either::EitherDownloader};
-use reth_network::{NetworkBuilder, NetworkConfig, NetworkEvents, NetworkHandle};
+use reth_network::{FetchClient, NetworkBuilder, NetworkConfig, NetworkEvents, NetworkHandle};
 use reth_node_api::{
     FullNodeComponents, FullNodeComponentsAdapter, FullNodeTypes, FullNodeTypesAdapter, NodeTypes,
 };
@@ -43,7 +43,7 @@ use reth_node_core::{
     init::init_genesis,
     node_config::NodeConfig,
     primitives::{kzg::KzgSettings, Head},
-    utils::write_peers_to_file,
+    utils::{PeersFileWriter, PERSIST_PEERS_INTERVAL},
 };
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
 use reth_primitives::{constants::eip4844::MAINNET_KZG_TRUSTED_SETUP, format_ether, ChainSpec};
@@ -54,7 +54,7 @@ use reth_prune::PrunerBuilder;
 use reth_revm::EvmProcessorFactory;
 use reth_rpc_engine_api::EngineApi;
 use reth_static_file::StaticFileProducer;
-use reth_tasks::TaskExecutor;
+use reth_tasks::{pool::BlockingTaskPool, TaskExecutor};
 use reth_tracing::tracing::{debug, error, info};
 use reth_transaction_pool::{PoolConfig, TransactionPool};
 use std::{cmp::max, str::FromStr, sync::Arc, thread::available_parallelism};
@@ -754,6 +754,7 @@ where
                 &config,
                 &reth_config.stages,
                 client.clone(),
+                None::<FetchClient>,
                 Arc::clone(&consensus),
                 provider_factory.clone(),
                 &executor,
@@ -777,6 +778,7 @@ where
                 &config,
                 &reth_config.stages,
                 network_client.clone(),
+                config.snap_sync.snap_sync.then(|| network_client.clone()),
                 Arc::clone(&consensus),
                 provider_factory.clone(),
                 &executor,
@@ -1343,12 +1345,21 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     /// to that network.
     pub fn start_network<Pool>(
         &self,
-        builder: NetworkBuilder<Node::Provider, (), ()>,
+        mut builder: NetworkBuilder<Node::Provider, (), ()>,
         pool: Pool,
     ) -> NetworkHandle
     where
         Pool: TransactionPool + Unpin + 'static,
     {
+        // snap sync requests the state from `snap` peers
+        if self.config.network.enable_snap || self.config.snap_sync.snap_sync {
+            let blocking_task_pool =
+                BlockingTaskPool::build().expect("failed to build snap request pool");
+            let snap = builder
+                .snap_request_handler::<Node::DB, _>(self.provider().clone(), blocking_task_pool);
+            self.executor.spawn_critical("p2p snap request handler", snap);
+        }
+
         let (handle, network, txpool, eth) = builder
             .transactions(pool, Default::default())
             .request_handler(self.provider().clone())
@@ -1359,11 +1370,36 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
 
         let default_peers_path = self.data_dir().known_peers_path();
         let known_peers_file = self.config.network.persistent_peers_file(default_peers_path);
+
+        let peers_file_writer = known_peers_file.map(PeersFileWriter::new);
+
+        // persist the peers periodically, so their state survives an unclean shutdown
+        if let Some(peers_file_writer) = peers_file_writer.clone() {
+            let peers = network.peers_handle();
+            self.executor.spawn(async move {
+                let mut interval = tokio::time::interval(PERSIST_PEERS_INTERVAL);
+                // the first tick completes immediately
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    // stop once the network is gone or its final state was written on shutdown
+                    let Some(persisted) = peers.persisted_peers().await else { break };
+                    let writer = peers_file_writer.clone();
+                    match tokio::task::spawn_blocking(move || writer.write(&persisted)).await {
+                        Ok(true) => {}
+                        Ok(false) | Err(_) => break,
+                    }
+                }
+            });
+        }
+
         self.executor.spawn_critical_with_graceful_shutdown_signal(
             "p2p network task",
             |shutdown| {
                 network.run_until_graceful_shutdown(shutdown, |network| {
-                    write_peers_to_file(network, known_peers_file)
+                    if let Some(peers_file_writer) = peers_file_writer {
+                        peers_file_writer.write_final(&network.persisted_peers());
+                    }
                 })
             },
         );
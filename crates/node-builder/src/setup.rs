@@ -13,11 +13,12 @@ use reth_interfaces::{
     p2p::{
         bodies::{client::BodiesClient, downloader::BodyDownloader},
         headers::{client::HeadersClient, downloader::HeaderDownloader},
+        snap::client::SnapClient,
     },
 };
 use reth_node_core::{
     node_config::NodeConfig,
-    primitives::{BlockNumber, B256},
+    primitives::{stage::StageId, BlockNumber, B256},
 };
 use reth_provider::{HeaderSyncMode, ProviderFactory};
 use reth_revm::stack::{Hook, InspectorStackConfig};
@@ -25,8 +26,8 @@ use reth_stages::{
     prelude::DefaultStages,
     stages::{
         AccountHashingStage, ExecutionStage, ExecutionStageThresholds, IndexAccountHistoryStage,
-        IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage, StorageHashingStage,
-        TransactionLookupStage,
+        IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage, SnapSyncStage,
+        StorageHashingStage, TransactionLookupStage,
     },
     Pipeline, StageSet,
 };
@@ -37,11 +38,14 @@ use std::sync::Arc;
 use tokio::sync::watch;
 
 /// Constructs a [Pipeline] that's wired to the network
+///
+/// If a `snap` client is given, the state is snap synced with it, see [SnapSyncStage].
 #[allow(clippy::too_many_arguments)]
-pub async fn build_networked_pipeline<DB, Client, EvmConfig>(
+pub async fn build_networked_pipeline<DB, Client, Snap, EvmConfig>(
     node_config: &NodeConfig,
     config: &StageConfig,
     client: Client,
+    snap_client: Option<Snap>,
     consensus: Arc<dyn Consensus>,
     provider_factory: ProviderFactory<DB>,
     task_executor: &TaskExecutor,
@@ -55,6 +59,7 @@ pub async fn build_networked_pipeline<DB, Client, EvmConfig>(
 where
     DB: Database + Unpin + Clone + 'static,
     Client: HeadersClient + BodiesClient + Clone + 'static,
+    Snap: SnapClient + Clone + 'static,
     EvmConfig: ConfigureEvm + Clone + 'static,
 {
     // building network downloaders using the fetch client
@@ -72,6 +77,7 @@ where
         config,
         header_downloader,
         body_downloader,
+        snap_client,
         consensus,
         max_block,
         metrics_tx,
@@ -86,13 +92,16 @@ where
 }
 
 /// Builds the [Pipeline] with the given [ProviderFactory] and downloaders.
+///
+/// If a `snap` client is given, the [SnapSyncStage] is added after the bodies stage.
 #[allow(clippy::too_many_arguments)]
-pub async fn build_pipeline<DB, H, B, EvmConfig>(
+pub async fn build_pipeline<DB, H, B, Snap, EvmConfig>(
     node_config: &NodeConfig,
     provider_factory: ProviderFactory<DB>,
     stage_config: &StageConfig,
     header_downloader: H,
     body_downloader: B,
+    snap_client: Option<Snap>,
     consensus: Arc<dyn Consensus>,
     max_block: Option<u64>,
     metrics_tx: reth_stages::MetricEventsSender,
@@ -105,6 +114,7 @@ where
     DB: Database + Clone + 'static,
     H: HeaderDownloader + 'static,
     B: BodyDownloader + 'static,
+    Snap: SnapClient + Clone + 'static,
     EvmConfig: ConfigureEvm + Clone + 'static,
 {
     let mut builder = Pipeline::builder();
@@ -139,68 +149,85 @@ where
     } else {
         HeaderSyncMode::Tip(tip_rx)
     };
+    let mut stages = DefaultStages::new(
+        provider_factory.clone(),
+        header_mode,
+        Arc::clone(&consensus),
+        header_downloader,
+        body_downloader,
+        factory.clone(),
+        stage_config.etl.clone(),
+    )
+    .set(SenderRecoveryStage { commit_threshold: stage_config.sender_recovery.commit_threshold })
+    .set(
+        ExecutionStage::new(
+            factory,
+            ExecutionStageThresholds {
+                max_blocks: stage_config.execution.max_blocks,
+                max_changes: stage_config.execution.max_changes,
+                max_cumulative_gas: stage_config.execution.max_cumulative_gas,
+                max_duration: stage_config.execution.max_duration,
+            },
+            stage_config
+                .merkle
+                .clean_threshold
+                .max(stage_config.account_hashing.clean_threshold)
+                .max(stage_config.storage_hashing.clean_threshold),
+            prune_modes.clone(),
+            exex_manager_handle,
+        )
+        .with_metrics_tx(metrics_tx.clone()),
+    )
+    .set(AccountHashingStage::new(
+        stage_config.account_hashing.clean_threshold,
+        stage_config.account_hashing.commit_threshold,
+        stage_config.etl.clone(),
+    ))
+    .set(StorageHashingStage::new(
+        stage_config.storage_hashing.clean_threshold,
+        stage_config.storage_hashing.commit_threshold,
+        stage_config.etl.clone(),
+    ))
+    .set(MerkleStage::from_config(stage_config.merkle, provider_factory.clone()))
+    .set(TransactionLookupStage::new(
+        stage_config.transaction_lookup.chunk_size,
+        stage_config.etl.clone(),
+        prune_modes.transaction_lookup,
+    ))
+    .set(IndexAccountHistoryStage::new(
+        stage_config.index_account_history.commit_threshold,
+        prune_modes.account_history,
+        stage_config.etl.clone(),
+    ))
+    .set(IndexStorageHistoryStage::new(
+        stage_config.index_storage_history.commit_threshold,
+        prune_modes.storage_history,
+        stage_config.etl.clone(),
+    ));
+
+    if let Some(snap_client) = snap_client {
+        let preimages = node_config
+            .snap_sync
+            .preimages
+            .clone()
+            .ok_or_else(|| eyre::eyre!("snap sync requires a preimage file"))?;
+        if !preimages.is_file() {
+            eyre::bail!(
+                "snap sync preimage file {preimages:?} does not exist, export the preimages of a \
+                 geth node that was synced with --cache.preimages using `geth db export preimage`"
+            );
+        }
+        stages = stages.add_after(
+            SnapSyncStage::new(snap_client, preimages, stage_config.etl.clone())
+                .with_pivot_distance(node_config.snap_sync.pivot_distance),
+            StageId::Bodies,
+        );
+    }
+
     let pipeline = builder
         .with_tip_sender(tip_tx)
-        .with_metrics_tx(metrics_tx.clone())
-        .add_stages(
-            DefaultStages::new(
-                provider_factory.clone(),
-                header_mode,
-                Arc::clone(&consensus),
-                header_downloader,
-                body_downloader,
-                factory.clone(),
-                stage_config.etl.clone(),
-            )
-            .set(SenderRecoveryStage {
-                commit_threshold: stage_config.sender_recovery.commit_threshold,
-            })
-            .set(
-                ExecutionStage::new(
-                    factory,
-                    ExecutionStageThresholds {
-                        max_blocks: stage_config.execution.max_blocks,
-                        max_changes: stage_config.execution.max_changes,
-                        max_cumulative_gas: stage_config.execution.max_cumulative_gas,
-                        max_duration: stage_config.execution.max_duration,
-                    },
-                    stage_config
-                        .merkle
-                        .clean_threshold
-                        .max(stage_config.account_hashing.clean_threshold)
-                        .max(stage_config.storage_hashing.clean_threshold),
-                    prune_modes.clone(),
-                    exex_manager_handle,
-                )
-                .with_metrics_tx(metrics_tx),
-            )
-            .set(AccountHashingStage::new(
-                stage_config.account_hashing.clean_threshold,
-                stage_config.account_hashing.commit_threshold,
-                stage_config.etl.clone(),
-            ))
-            .set(StorageHashingStage::new(
-                stage_config.storage_hashing.clean_threshold,
-                stage_config.storage_hashing.commit_threshold,
-                stage_config.etl.clone(),
-            ))
-            .set(MerkleStage::new_execution(stage_config.merkle.clean_threshold))
-            .set(TransactionLookupStage::new(
-                stage_config.transaction_lookup.chunk_size,
-                stage_config.etl.clone(),
-                prune_modes.transaction_lookup,
-            ))
-            .set(IndexAccountHistoryStage::new(
-                stage_config.index_account_history.commit_threshold,
-                prune_modes.account_history,
-                stage_config.etl.clone(),
-            ))
-            .set(IndexStorageHistoryStage::new(
-                stage_config.index_storage_history.commit_threshold,
-                prune_modes.storage_history,
-                stage_config.etl.clone(),
-            )),
-        )
+        .with_metrics_tx(metrics_tx)
+        .add_stages(stages)
         .build(provider_factory, static_file_producer);
 
     Ok(pipeline)
@@ -2,7 +2,7 @@
 
 /// NetworkArg struct for configuring the network
 mod network_args;
-pub use network_args::{DiscoveryArgs, NetworkArgs};
+pub use network_args::{DiscoveryArgs, EthRequestsArgs, NetworkArgs};
 
 /// RpcServerArg struct for configuring the RPC
 mod rpc_server_args;
@@ -51,6 +51,10 @@ pub use dev_args::DevArgs;
 mod pruning_args;
 pub use pruning_args::PruningArgs;
 
+/// SnapSyncArgs for configuring snap sync
+mod snap_sync_args;
+pub use snap_sync_args::SnapSyncArgs;
+
 pub mod utils;
 
 pub mod types;
@@ -7,14 +7,15 @@ use reth_discv4::{
     DEFAULT_DISCOVERY_ADDR, DEFAULT_DISCOVERY_PORT, DEFAULT_DISCOVERY_V5_ADDR,
     DEFAULT_DISCOVERY_V5_PORT,
 };
-use reth_net_nat::NatResolver;
+use reth_net_nat::{NatResolver, PortMappingConfig, PortMappingMethod};
 use reth_network::{
+    bandwidth::{BandwidthLimit, ServeLimits},
     transactions::{
         TransactionFetcherConfig, TransactionsManagerConfig,
         DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
         SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
     },
-    HelloMessageWithProtocols, NetworkConfigBuilder, SessionsConfig,
+    EthRequestsConfig, HelloMessageWithProtocols, NetworkConfigBuilder, SessionsConfig,
 };
 use reth_primitives::{mainnet_nodes, ChainSpec, NodeRecord};
 use secp256k1::SecretKey;
@@ -28,6 +29,14 @@ pub struct NetworkArgs {
     #[command(flatten)]
     pub discovery: DiscoveryArgs,
 
+    /// Arguments to configure how `eth` requests of peers are served.
+    #[command(flatten)]
+    pub eth_requests: EthRequestsArgs,
+
+    /// Arguments to configure how pooled transactions are served to peers.
+    #[command(flatten)]
+    pub tx_serve: TransactionsServeArgs,
+
     /// Comma separated enode URLs of trusted peers for P2P connections.
     ///
     /// --trusted-peers enode://abcd@192.168.0.1:30303
@@ -44,8 +53,9 @@ pub struct NetworkArgs {
     #[arg(long, value_delimiter = ',')]
     pub bootnodes: Option<Vec<NodeRecord>>,
 
-    /// The path to the known peers file. Connected peers are dumped to this file on nodes
-    /// shutdown, and read on startup. Cannot be used with `--no-persist-peers`.
+    /// The path to the known peers file. Known peers and their reputation, bans and backoffs are
+    /// dumped to this file periodically and on node shutdown, and read on startup. Cannot be used
+    /// with `--no-persist-peers`.
     #[arg(long, value_name = "FILE", verbatim_doc_comment, conflicts_with = "no_persist_peers")]
     pub peers_file: Option<PathBuf>,
 
@@ -68,6 +78,12 @@ pub struct NetworkArgs {
     #[arg(long, default_value = "any")]
     pub nat: NatResolver,
 
+    /// Map the p2p and discovery ports on the local gateway (any|upnp|natpmp)
+    ///
+    /// Mappings are renewed while the node is running and removed on shutdown.
+    #[arg(id = "nat.port-mapping", long = "nat.port-mapping", value_name = "METHOD")]
+    pub port_mapping: Option<PortMappingMethod>,
+
     /// Network listening address
     #[arg(long = "addr", value_name = "ADDR", default_value_t = DEFAULT_DISCOVERY_ADDR)]
     pub addr: IpAddr,
@@ -98,6 +114,12 @@ pub struct NetworkArgs {
     /// is 128 KiB.
     #[arg(long = "pooled-tx-pack-soft-limit", value_name = "BYTES", default_value_t = DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ)]
     pub soft_limit_byte_size_pooled_transactions_response_on_pack_request: usize,
+
+    /// Serve the state to peers via the `snap/1` protocol.
+    ///
+    /// The protocol is also enabled by `--snap-sync`, which requests the state from `snap` peers.
+    #[arg(long)]
+    pub enable_snap: bool,
 }
 
 impl NetworkArgs {
@@ -129,6 +151,7 @@ impl NetworkArgs {
                 self.soft_limit_byte_size_pooled_transactions_response,
                 self.soft_limit_byte_size_pooled_transactions_response_on_pack_request,
             ),
+            serve_limits: self.tx_serve.apply(config.transactions.serve_limits),
         };
 
         // Configure basic network stack
@@ -140,7 +163,14 @@ impl NetworkArgs {
             .peer_config(peers_config)
             .boot_nodes(self.bootnodes.clone().unwrap_or(chain_bootnodes))
             .chain_spec(chain_spec)
-            .transactions_manager_config(transactions_manager_config);
+            .transactions_manager_config(transactions_manager_config)
+            .eth_requests_config(self.eth_requests.apply(config.eth_requests.clone()));
+
+        // Configure port mapping on the local gateway
+        if let Some(method) = self.port_mapping {
+            network_config_builder =
+                network_config_builder.port_mapping(PortMappingConfig::new(method));
+        }
 
         // Configure node identity
         let peer_id = network_config_builder.get_peer_id();
@@ -180,6 +210,8 @@ impl Default for NetworkArgs {
     fn default() -> Self {
         Self {
             discovery: DiscoveryArgs::default(),
+            eth_requests: EthRequestsArgs::default(),
+            tx_serve: TransactionsServeArgs::default(),
             trusted_peers: vec![],
             trusted_only: false,
             bootnodes: None,
@@ -188,6 +220,7 @@ impl Default for NetworkArgs {
             p2p_secret_key: None,
             no_persist_peers: false,
             nat: NatResolver::Any,
+            port_mapping: None,
             addr: DEFAULT_DISCOVERY_ADDR,
             port: DEFAULT_DISCOVERY_PORT,
             max_outbound_peers: None,
@@ -195,7 +228,121 @@ impl Default for NetworkArgs {
             soft_limit_byte_size_pooled_transactions_response:
                 SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
             soft_limit_byte_size_pooled_transactions_response_on_pack_request: DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
+            enable_snap: false,
+        }
+    }
+}
+
+/// Arguments to configure how `eth` requests for headers, bodies and receipts are served.
+///
+/// Unset values fall back to the `[eth_requests]` section of the config file.
+#[derive(Debug, Clone, Default, Args, PartialEq, Eq)]
+pub struct EthRequestsArgs {
+    /// Maximum number of headers served per request. default: 1024
+    #[arg(
+        id = "eth-requests.max-headers",
+        long = "eth-requests.max-headers",
+        value_name = "COUNT"
+    )]
+    pub max_headers: Option<usize>,
+
+    /// Maximum number of block bodies served per request. default: 1024
+    #[arg(id = "eth-requests.max-bodies", long = "eth-requests.max-bodies", value_name = "COUNT")]
+    pub max_bodies: Option<usize>,
+
+    /// Maximum number of block receipts served per request. default: 1024
+    #[arg(
+        id = "eth-requests.max-receipts",
+        long = "eth-requests.max-receipts",
+        value_name = "COUNT"
+    )]
+    pub max_receipts: Option<usize>,
+
+    /// Number of blocks below the tip that are served before older history.
+    ///
+    /// Requests for older history are queued and served at a limited rate.
+    #[arg(
+        id = "eth-requests.recent-blocks",
+        long = "eth-requests.recent-blocks",
+        value_name = "BLOCKS"
+    )]
+    pub recent_blocks: Option<u64>,
+
+    /// Maximum number of queued requests for older history that are served per 100ms.
+    /// default: 8
+    #[arg(
+        id = "eth-requests.history-requests",
+        long = "eth-requests.history-requests",
+        value_name = "COUNT"
+    )]
+    pub history_requests: Option<usize>,
+
+    /// Maximum bandwidth used for serving the requests of a single peer, in bytes per second.
+    #[arg(id = "eth-requests.peer-quota", long = "eth-requests.peer-quota", value_name = "BYTES")]
+    pub peer_quota: Option<u64>,
+
+    /// Maximum bandwidth used for serving the requests of all peers, in bytes per second.
+    #[arg(
+        id = "eth-requests.total-quota",
+        long = "eth-requests.total-quota",
+        value_name = "BYTES"
+    )]
+    pub total_quota: Option<u64>,
+}
+
+impl EthRequestsArgs {
+    /// Applies the arguments to the given [`EthRequestsConfig`].
+    pub fn apply(&self, mut config: EthRequestsConfig) -> EthRequestsConfig {
+        if let Some(max_headers) = self.max_headers {
+            config = config.with_max_headers(max_headers);
+        }
+        if let Some(max_bodies) = self.max_bodies {
+            config = config.with_max_bodies(max_bodies);
+        }
+        if let Some(max_receipts) = self.max_receipts {
+            config = config.with_max_receipts(max_receipts);
+        }
+        if let Some(recent_blocks) = self.recent_blocks {
+            config = config.with_recent_blocks(recent_blocks);
+        }
+        if let Some(history_requests) = self.history_requests {
+            config = config.with_history_requests_per_interval(history_requests);
+        }
+        if let Some(quota) = self.peer_quota {
+            config.serve_limits = config.serve_limits.with_per_peer(BandwidthLimit::new(quota));
+        }
+        if let Some(quota) = self.total_quota {
+            config.serve_limits = config.serve_limits.with_total(BandwidthLimit::new(quota));
+        }
+        config
+    }
+}
+
+/// Arguments to configure how pooled transactions are served to peers.
+///
+/// Unset values fall back to the `[transactions]` section of the config file.
+#[derive(Debug, Clone, Default, Args, PartialEq, Eq)]
+pub struct TransactionsServeArgs {
+    /// Maximum bandwidth used for serving pooled transactions to a single peer, in bytes per
+    /// second.
+    #[arg(id = "tx-serve.peer-quota", long = "tx-serve.peer-quota", value_name = "BYTES")]
+    pub peer_quota: Option<u64>,
+
+    /// Maximum bandwidth used for serving pooled transactions to all peers, in bytes per second.
+    #[arg(id = "tx-serve.total-quota", long = "tx-serve.total-quota", value_name = "BYTES")]
+    pub total_quota: Option<u64>,
+}
+
+impl TransactionsServeArgs {
+    /// Applies the arguments to the given [`ServeLimits`].
+    pub fn apply(&self, mut limits: ServeLimits) -> ServeLimits {
+        if let Some(quota) = self.peer_quota {
+            limits = limits.with_per_peer(BandwidthLimit::new(quota));
+        }
+        if let Some(quota) = self.total_quota {
+            limits = limits.with_total(BandwidthLimit::new(quota));
         }
+        limits
     }
 }
 
@@ -303,6 +450,65 @@ mod tests {
         assert_eq!(args.nat, NatResolver::ExternalIp("0.0.0.0".parse().unwrap()));
     }
 
+    #[test]
+    fn parse_port_mapping_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.port_mapping, None);
+
+        let args =
+            CommandParser::<NetworkArgs>::parse_from(["reth", "--nat.port-mapping", "natpmp"]).args;
+        assert_eq!(args.port_mapping, Some(PortMappingMethod::NatPmp));
+    }
+
+    #[test]
+    fn parse_eth_requests_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--eth-requests.max-receipts",
+            "128",
+            "--eth-requests.recent-blocks",
+            "8192",
+            "--eth-requests.history-requests",
+            "16",
+            "--eth-requests.peer-quota",
+            "1048576",
+        ])
+        .args;
+        assert_eq!(args.eth_requests.max_receipts, Some(128));
+        assert_eq!(args.eth_requests.recent_blocks, Some(8192));
+
+        let config = args.eth_requests.apply(EthRequestsConfig::default());
+        assert_eq!(config.max_receipts, 128);
+        assert_eq!(config.max_headers, EthRequestsConfig::default().max_headers);
+        assert_eq!(config.recent_blocks, Some(8192));
+        assert_eq!(config.history_requests_per_interval, 16);
+        assert_eq!(config.serve_limits.per_peer, Some(BandwidthLimit::new(1048576)));
+        assert_eq!(config.serve_limits.total, None);
+    }
+
+    #[test]
+    fn parse_tx_serve_args() {
+        let args =
+            CommandParser::<NetworkArgs>::parse_from(["reth", "--tx-serve.total-quota", "4194304"])
+                .args;
+        assert_eq!(args.tx_serve.total_quota, Some(4194304));
+
+        // unset values fall back to the config file
+        let configured = ServeLimits::default().with_per_peer(BandwidthLimit::new(1024));
+        let limits = args.tx_serve.apply(configured);
+        assert_eq!(limits.total, Some(BandwidthLimit::new(4194304)));
+        assert_eq!(limits.per_peer, Some(BandwidthLimit::new(1024)));
+
+        // the eth requests quotas don't apply to pooled transactions
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--eth-requests.peer-quota",
+            "1048576",
+        ])
+        .args;
+        assert!(args.tx_serve.apply(ServeLimits::default()).is_unlimited());
+    }
+
     #[test]
     fn parse_peer_args() {
         let args =
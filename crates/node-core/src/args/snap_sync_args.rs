@@ -0,0 +1,77 @@
+//! clap [Args](clap::Args) for snap sync configuration
+
+use clap::Args;
+use std::path::PathBuf;
+
+/// Parameters for snap sync
+#[derive(Debug, Args, PartialEq, Eq, Clone)]
+#[command(next_help_heading = "Snap sync")]
+pub struct SnapSyncArgs {
+    /// Bootstrap a fresh node from the state of a recent block served by `snap` peers, instead of
+    /// executing all blocks from genesis.
+    ///
+    /// Requires the preimages of all hashed addresses and storage keys, see
+    /// `--snap-sync.preimages`. Without them the plain state can't be written and the sync fails
+    /// once the state is downloaded.
+    #[arg(long = "snap-sync", requires = "preimages")]
+    pub snap_sync: bool,
+
+    /// The file with the preimages of hashed addresses and storage keys, as exported by
+    /// `geth db export preimage`.
+    ///
+    /// Geth only records preimages when it is run with `--cache.preimages`, which is disabled by
+    /// default. The file must cover every address and storage key of the state at the pivot
+    /// block.
+    #[arg(long = "snap-sync.preimages", value_name = "PATH")]
+    pub preimages: Option<PathBuf>,
+
+    /// The distance of the pivot block, whose state is downloaded, to the sync target.
+    #[arg(long = "snap-sync.pivot-distance", default_value_t = 64)]
+    pub pivot_distance: u64,
+}
+
+impl Default for SnapSyncArgs {
+    fn default() -> Self {
+        Self { snap_sync: false, preimages: None, pivot_distance: 64 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_snap_sync_args() {
+        let args = CommandParser::<SnapSyncArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, SnapSyncArgs::default());
+
+        let args = CommandParser::<SnapSyncArgs>::parse_from([
+            "reth",
+            "--snap-sync",
+            "--snap-sync.preimages",
+            "preimages.rlp",
+            "--snap-sync.pivot-distance",
+            "128",
+        ])
+        .args;
+        assert_eq!(
+            args,
+            SnapSyncArgs {
+                snap_sync: true,
+                preimages: Some("preimages.rlp".into()),
+                pivot_distance: 128
+            }
+        );
+
+        // the preimages are required
+        assert!(CommandParser::<SnapSyncArgs>::try_parse_from(["reth", "--snap-sync"]).is_err());
+    }
+}
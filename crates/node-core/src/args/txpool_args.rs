@@ -1,14 +1,20 @@
 //! Transaction pool arguments
 
-use crate::cli::config::RethTransactionPoolConfig;
+use crate::{args::utils::parse_duration_from_secs, cli::config::RethTransactionPoolConfig};
 use clap::Args;
 use reth_primitives::Address;
 use reth_transaction_pool::{
-    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS, validate::DEFAULT_MAX_TX_INPUT_BYTES,
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-    TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS,
+    journal::{PoolJournalConfig, DEFAULT_JOURNAL_SNAPSHOT_INTERVAL},
+    validate::DEFAULT_MAX_TX_INPUT_BYTES,
+    BlobPoolConfig, LocalTransactionConfig, OrderingConfig, PoolConfig, PriceBumpConfig,
+    SubPoolLimit, TransactionLifetimes, TransactionOrderingKind, DEFAULT_PRICE_BUMP,
+    REPLACE_BLOB_PRICE_BUMP, TXPOOL_BLOB_POOL_FEE_EVICTION_HORIZON_DEFAULT,
+    TXPOOL_BLOB_POOL_MAX_BLOBS_DEFAULT, TXPOOL_BLOB_POOL_MAX_SIZE_MB_DEFAULT,
+    TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+    TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
+use std::{path::PathBuf, time::Duration};
 /// Parameters for debugging purposes
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "TxPool")]
@@ -46,6 +52,20 @@ pub struct TxPoolArgs {
     #[arg(long = "blobpool.pricebump", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
     pub blob_transaction_price_bump: u128,
 
+    /// Max number of blobs of all transactions in the blob sub-pool.
+    #[arg(long = "blobpool.max-blobs", default_value_t = TXPOOL_BLOB_POOL_MAX_BLOBS_DEFAULT)]
+    pub blob_pool_max_blobs: usize,
+    /// Max size of the blob sub-pool in megabytes, including blob sidecars.
+    #[arg(long = "blobpool.max-size", default_value_t = TXPOOL_BLOB_POOL_MAX_SIZE_MB_DEFAULT)]
+    pub blob_pool_max_size: usize,
+    /// Number of blocks within which a blob transaction must be able to pay the blob fee, assuming
+    /// the blob fee drops as fast as possible. Other blob transactions are evicted.
+    #[arg(long = "blobpool.fee-eviction-horizon", default_value_t = TXPOOL_BLOB_POOL_FEE_EVICTION_HORIZON_DEFAULT, value_name = "BLOCKS")]
+    pub blob_fee_eviction_horizon: u64,
+    /// Flag to disable the eviction of blob transactions based on their blob fee cap.
+    #[arg(long = "blobpool.no-fee-eviction")]
+    pub no_blob_fee_eviction: bool,
+
     /// Max size in bytes of a single transaction allowed to enter the pool
     #[arg(long = "txpool.max-tx-input-bytes", alias = "txpool.max_tx_input_bytes", default_value_t = DEFAULT_MAX_TX_INPUT_BYTES)]
     pub max_tx_input_bytes: usize,
@@ -63,6 +83,54 @@ pub struct TxPoolArgs {
     /// Flag to toggle local transaction propagation.
     #[arg(long = "txpool.no-local-transactions-propagation")]
     pub no_local_transactions_propagation: bool,
+
+    /// Persist all transactions of the pool in a journal and restore them on startup.
+    ///
+    /// This replaces the backup of local transactions, existing backups are moved into the
+    /// journal. The sub-pool of a transaction is not restored, it's determined from the state at
+    /// startup like for new transactions.
+    #[arg(long = "txpool.journal")]
+    pub journal: bool,
+    /// Interval in seconds at which the journal is rewritten from a snapshot of the pool.
+    #[arg(long = "txpool.journal-snapshot-interval", value_parser = parse_duration_from_secs, default_value = "3600", value_name = "SECONDS", requires = "journal")]
+    pub journal_snapshot_interval: Duration,
+
+    /// Record all transactions added to the pool and all canonical block updates to the given
+    /// file, which can be replayed with `reth txpool replay`.
+    #[arg(long = "txpool.record", value_name = "PATH")]
+    pub record: Option<PathBuf>,
+
+    /// Max time in seconds since submission a transaction in the pending sub-pool may stay in the
+    /// pool.
+    #[arg(long = "txpool.pending-lifetime", value_parser = parse_duration_from_secs, value_name = "SECONDS")]
+    pub pending_lifetime: Option<Duration>,
+    /// Max time in seconds since submission a transaction in the basefee sub-pool may stay in the
+    /// pool.
+    #[arg(long = "txpool.basefee-lifetime", value_parser = parse_duration_from_secs, value_name = "SECONDS")]
+    pub basefee_lifetime: Option<Duration>,
+    /// Max time in seconds since submission a transaction in the queued sub-pool may stay in the
+    /// pool.
+    #[arg(long = "txpool.queued-lifetime", value_parser = parse_duration_from_secs, value_name = "SECONDS")]
+    pub queued_lifetime: Option<Duration>,
+    /// Max time in seconds since submission a transaction in the blob sub-pool may stay in the
+    /// pool.
+    #[arg(long = "txpool.blob-lifetime", value_parser = parse_duration_from_secs, value_name = "SECONDS")]
+    pub blob_lifetime: Option<Duration>,
+    /// Max time in seconds since submission a local transaction may stay in the pool.
+    ///
+    /// This overrides the sub-pool lifetimes for local transactions.
+    #[arg(long = "txpool.local-lifetime", value_parser = parse_duration_from_secs, value_name = "SECONDS")]
+    pub local_lifetime: Option<Duration>,
+
+    /// How pending transactions are ordered for block production.
+    ///
+    /// One of `coinbase-tip`, `fifo` (by arrival) or `effective-fee` (by total tip, including the
+    /// pending blob fee of blob transactions).
+    #[arg(long = "txpool.ordering", default_value_t = TransactionOrderingKind::CoinbaseTip)]
+    pub ordering: TransactionOrderingKind,
+    /// Senders whose transactions are ordered before all other pending transactions.
+    #[arg(long = "txpool.priority-senders", value_delimiter = ',', value_name = "ADDRESS")]
+    pub priority_senders: Vec<Address>,
 }
 
 impl Default for TxPoolArgs {
@@ -77,15 +145,38 @@ impl Default for TxPoolArgs {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bump: DEFAULT_PRICE_BUMP,
             blob_transaction_price_bump: REPLACE_BLOB_PRICE_BUMP,
+            blob_pool_max_blobs: TXPOOL_BLOB_POOL_MAX_BLOBS_DEFAULT,
+            blob_pool_max_size: TXPOOL_BLOB_POOL_MAX_SIZE_MB_DEFAULT,
+            blob_fee_eviction_horizon: TXPOOL_BLOB_POOL_FEE_EVICTION_HORIZON_DEFAULT,
+            no_blob_fee_eviction: false,
             max_tx_input_bytes: DEFAULT_MAX_TX_INPUT_BYTES,
             max_cached_entries: DEFAULT_MAX_CACHED_BLOBS,
             no_locals: false,
             locals: Default::default(),
             no_local_transactions_propagation: false,
+            journal: false,
+            journal_snapshot_interval: DEFAULT_JOURNAL_SNAPSHOT_INTERVAL,
+            record: None,
+            pending_lifetime: None,
+            basefee_lifetime: None,
+            queued_lifetime: None,
+            blob_lifetime: None,
+            local_lifetime: None,
+            ordering: TransactionOrderingKind::CoinbaseTip,
+            priority_senders: Default::default(),
         }
     }
 }
 
+impl TxPoolArgs {
+    /// Returns the configuration of the transaction pool journal at the given path, if enabled.
+    pub fn journal_config(&self, path: PathBuf) -> Option<PoolJournalConfig> {
+        self.journal.then(|| {
+            PoolJournalConfig::new(path).with_snapshot_interval(self.journal_snapshot_interval)
+        })
+    }
+}
+
 impl RethTransactionPoolConfig for TxPoolArgs {
     /// Returns transaction pool configuration.
     fn pool_config(&self) -> PoolConfig {
@@ -111,11 +202,28 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 max_txs: self.queued_max_count,
                 max_size: self.queued_max_size * 1024 * 1024,
             },
+            blob_pool: BlobPoolConfig {
+                max_blobs: self.blob_pool_max_blobs,
+                max_size: self.blob_pool_max_size * 1024 * 1024,
+                fee_eviction_horizon: (!self.no_blob_fee_eviction)
+                    .then_some(self.blob_fee_eviction_horizon),
+            },
             max_account_slots: self.max_account_slots,
             price_bumps: PriceBumpConfig {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
             },
+            transaction_lifetimes: TransactionLifetimes {
+                pending: self.pending_lifetime,
+                basefee: self.basefee_lifetime,
+                queued: self.queued_lifetime,
+                blob: self.blob_lifetime,
+                local: self.local_lifetime,
+            },
+            ordering: OrderingConfig {
+                kind: self.ordering,
+                priority_senders: self.priority_senders.iter().copied().collect(),
+            },
         }
     }
 }
@@ -132,6 +240,100 @@ mod tests {
         args: T,
     }
 
+    #[test]
+    fn parse_journal_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.journal_config(PathBuf::from("journal.rlp")), None);
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.journal",
+            "--txpool.journal-snapshot-interval",
+            "60",
+        ])
+        .args;
+        assert_eq!(
+            args.journal_config(PathBuf::from("journal.rlp")),
+            Some(
+                PoolJournalConfig::new(PathBuf::from("journal.rlp"))
+                    .with_snapshot_interval(Duration::from_secs(60))
+            )
+        );
+    }
+
+    #[test]
+    fn parse_lifetime_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
+        assert!(args.pool_config().transaction_lifetimes.is_unlimited());
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.queued-lifetime",
+            "10800",
+            "--txpool.local-lifetime",
+            "86400",
+        ])
+        .args;
+        assert_eq!(
+            args.pool_config().transaction_lifetimes,
+            TransactionLifetimes {
+                queued: Some(Duration::from_secs(10800)),
+                local: Some(Duration::from_secs(86400)),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_ordering_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.pool_config().ordering, OrderingConfig::default());
+
+        let sender = Address::with_last_byte(1);
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.ordering",
+            "effective-fee",
+            "--txpool.priority-senders",
+            &sender.to_string(),
+        ])
+        .args;
+        assert_eq!(
+            args.pool_config().ordering,
+            OrderingConfig {
+                kind: TransactionOrderingKind::EffectiveFee,
+                priority_senders: [sender].into_iter().collect(),
+            }
+        );
+
+        assert!(CommandParser::<TxPoolArgs>::try_parse_from(["reth", "--txpool.ordering", "lifo"])
+            .is_err());
+    }
+
+    #[test]
+    fn parse_blob_pool_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.pool_config().blob_pool, BlobPoolConfig::default());
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--blobpool.max-blobs",
+            "100",
+            "--blobpool.max-size",
+            "50",
+            "--blobpool.no-fee-eviction",
+        ])
+        .args;
+        assert_eq!(
+            args.pool_config().blob_pool,
+            BlobPoolConfig {
+                max_blobs: 100,
+                max_size: 50 * 1024 * 1024,
+                fee_eviction_horizon: None
+            }
+        );
+    }
+
     #[test]
     fn txpool_args_default_sanity_test() {
         let default_args = TxPoolArgs::default();
@@ -3,7 +3,7 @@
 use crate::{
     args::{
         get_secret_key, DatabaseArgs, DebugArgs, DevArgs, DiscoveryArgs, NetworkArgs,
-        PayloadBuilderArgs, PruningArgs, RpcServerArgs, TxPoolArgs,
+        PayloadBuilderArgs, PruningArgs, RpcServerArgs, SnapSyncArgs, TxPoolArgs,
     },
     dirs::{ChainPath, DataDirPath},
     metrics::prometheus_exporter,
@@ -143,6 +143,9 @@ pub struct NodeConfig {
 
     /// All pruning related arguments
     pub pruning: PruningArgs,
+
+    /// All snap sync related arguments with --snap-sync prefix
+    pub snap_sync: SnapSyncArgs,
 }
 
 impl NodeConfig {
@@ -231,6 +234,12 @@ impl NodeConfig {
         self
     }
 
+    /// Set the snap sync args for the node
+    pub fn with_snap_sync(mut self, snap_sync: SnapSyncArgs) -> Self {
+        self.snap_sync = snap_sync;
+        self
+    }
+
     /// Get the network secret from the given data dir
     pub fn network_secret(&self, data_dir: &ChainPath<DataDirPath>) -> eyre::Result<SecretKey> {
         let network_secret_path =
@@ -464,7 +473,14 @@ impl NodeConfig {
                 self.network.discovery.port + self.instance - 1,
             ));
 
-        let config = cfg_builder.build(client);
+        // receipts of pruned blocks are not served to peers
+        let prune_config = self.prune_config().ok().flatten().or_else(|| config.prune.clone());
+
+        let mut config = cfg_builder.build(client);
+        if let Some(prune_config) = prune_config {
+            config.eth_requests_config =
+                config.eth_requests_config.clone().with_prune_modes(&prune_config.segments);
+        }
 
         if !self.network.discovery.enable_discv5_discovery {
             return config
@@ -515,6 +531,7 @@ impl Default for NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            snap_sync: SnapSyncArgs::default(),
         }
     }
 }
@@ -8,7 +8,7 @@ use reth_interfaces::p2p::{
     headers::client::{HeadersClient, HeadersRequest},
     priority::Priority,
 };
-use reth_network::NetworkManager;
+use reth_network::{peers::PersistedPeers, NetworkManager};
 use reth_primitives::{
     fs, BlockHashOrNumber, ChainSpec, HeadersDirection, SealedBlock, SealedHeader,
 };
@@ -17,7 +17,8 @@ use reth_rpc::{JwtError, JwtSecret};
 use std::{
     env::VarError,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
 };
 use tracing::{debug, info, trace, warn};
 
@@ -38,6 +39,9 @@ pub fn get_or_create_jwt_secret_from_path(path: &Path) -> Result<JwtSecret, JwtE
     }
 }
 
+/// How often the peers are written to the persistent peers file while the node is running.
+pub const PERSIST_PEERS_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
 /// Collect the peers from the [NetworkManager] and write them to the given `persistent_peers_file`,
 /// if configured.
 pub fn write_peers_to_file<C>(network: &NetworkManager<C>, persistent_peers_file: Option<PathBuf>)
@@ -45,19 +49,77 @@ where
     C: BlockReader + Unpin,
 {
     if let Some(file_path) = persistent_peers_file {
-        let known_peers = network.all_peers().collect::<Vec<_>>();
-        if let Ok(known_peers) = serde_json::to_string_pretty(&known_peers) {
-            trace!(target: "reth::cli", peers_file =?file_path, num_peers=%known_peers.len(), "Saving current peers");
-            let parent_dir = file_path.parent().map(fs::create_dir_all).transpose();
-            match parent_dir.and_then(|_| fs::write(&file_path, known_peers)) {
-                Ok(_) => {
-                    info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
-                }
-                Err(err) => {
-                    warn!(target: "reth::cli", %err, peers_file=?file_path, "Failed to write network peers to file");
-                }
-            }
+        write_persisted_peers(&network.persisted_peers(), &file_path);
+    }
+}
+
+/// Writes the state of the peers to the given file.
+///
+/// The peers are written to a temporary file that then replaces the file, so that an interrupted
+/// write does not corrupt the previously persisted peers.
+pub fn write_persisted_peers(peers: &PersistedPeers, file_path: &Path) {
+    if try_write_persisted_peers(peers, file_path) {
+        info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
+    }
+}
+
+/// Writes the state of the peers to the given file, returns false if the write failed.
+fn try_write_persisted_peers(peers: &PersistedPeers, file_path: &Path) -> bool {
+    let Ok(known_peers) = serde_json::to_string_pretty(peers) else { return false };
+    trace!(target: "reth::cli", peers_file =?file_path, num_peers=%peers.peers.len(), "Saving current peers");
+    let tmp_path = file_path.with_extension("tmp");
+    let parent_dir = file_path.parent().map(fs::create_dir_all).transpose();
+    match parent_dir
+        .and_then(|_| fs::write(&tmp_path, known_peers))
+        .and_then(|_| fs::rename(&tmp_path, file_path))
+    {
+        Ok(_) => true,
+        Err(err) => {
+            warn!(target: "reth::cli", %err, peers_file=?file_path, "Failed to write network peers to file");
+            false
+        }
+    }
+}
+
+/// Writes the state of the peers to the persistent peers file, both periodically while the node is
+/// running and on shutdown.
+///
+/// Writes are serialized, and once the final state was written on shutdown all later writes are
+/// skipped, so that a periodic write can't replace it with an older state.
+#[derive(Debug, Clone)]
+pub struct PeersFileWriter {
+    /// The persistent peers file.
+    file_path: PathBuf,
+    /// Whether the final state was written.
+    finished: Arc<Mutex<bool>>,
+}
+
+impl PeersFileWriter {
+    /// Creates a new writer for the given peers file.
+    pub fn new(file_path: PathBuf) -> Self {
+        Self { file_path, finished: Default::default() }
+    }
+
+    /// Writes the peers to the file, unless the final state was already written.
+    ///
+    /// Returns false if the write was skipped. This blocks on file IO and must not be called from
+    /// an async task.
+    pub fn write(&self, peers: &PersistedPeers) -> bool {
+        let finished = self.finished.lock().unwrap_or_else(PoisonError::into_inner);
+        if *finished {
+            return false
+        }
+        if try_write_persisted_peers(peers, &self.file_path) {
+            debug!(target: "reth::cli", peers_file=?self.file_path, "Wrote network peers to file");
         }
+        true
+    }
+
+    /// Writes the final state of the peers to the file and skips all later writes.
+    pub fn write_final(&self, peers: &PersistedPeers) {
+        let mut finished = self.finished.lock().unwrap_or_else(PoisonError::into_inner);
+        write_persisted_peers(peers, &self.file_path);
+        *finished = true;
     }
 }
 
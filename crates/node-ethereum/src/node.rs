@@ -12,7 +12,7 @@ use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_provider::CanonStateSubscriptions;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, EthTransactionPool, TransactionPool,
+    blobstore::DiskFileBlobStore, ConfiguredEthTransactionPool, TransactionPool,
     TransactionValidationTaskExecutor,
 };
 
@@ -79,7 +79,7 @@ impl<Node> PoolBuilder<Node> for EthereumPoolBuilder
 where
     Node: FullNodeTypes,
 {
-    type Pool = EthTransactionPool<Node::Provider, DiskFileBlobStore>;
+    type Pool = ConfiguredEthTransactionPool<Node::Provider, DiskFileBlobStore>;
 
     async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
         let data_dir = ctx.data_dir();
@@ -94,8 +94,11 @@ where
                 blob_store.clone(),
             );
 
-        let transaction_pool =
-            reth_transaction_pool::Pool::eth_pool(validator, blob_store, ctx.pool_config());
+        let transaction_pool = reth_transaction_pool::Pool::configured_eth_pool(
+            validator,
+            blob_store,
+            ctx.pool_config(),
+        );
         info!(target: "reth::cli", "Transaction pool initialized");
         let transactions_path = data_dir.txpool_transactions_path();
 
@@ -107,16 +110,48 @@ where
             let transactions_backup_config =
                 reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
 
-            ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
-                "local transactions backup task",
-                |shutdown| {
-                    reth_transaction_pool::maintain::backup_local_transactions_task(
-                        shutdown,
-                        pool.clone(),
-                        transactions_backup_config,
-                    )
-                },
-            );
+            if let Some(journal_config) =
+                ctx.config().txpool.journal_config(data_dir.txpool_journal_path())
+            {
+                // the journal includes local transactions, and takes over the ones that were
+                // backed up while it was disabled
+                let journal_config = journal_config
+                    .with_local_transactions_backup(data_dir.txpool_transactions_path());
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "transaction pool journal task",
+                    |shutdown| {
+                        reth_transaction_pool::journal::journal_transactions_task(
+                            shutdown,
+                            pool.clone(),
+                            journal_config,
+                        )
+                    },
+                );
+            } else {
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "local transactions backup task",
+                    |shutdown| {
+                        reth_transaction_pool::maintain::backup_local_transactions_task(
+                            shutdown,
+                            pool.clone(),
+                            transactions_backup_config,
+                        )
+                    },
+                );
+            }
+
+            if let Some(record_path) = ctx.config().txpool.record.clone() {
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "transaction pool recorder task",
+                    |shutdown| {
+                        reth_transaction_pool::replay::record_pool_task(
+                            shutdown,
+                            pool.clone(),
+                            record_path,
+                        )
+                    },
+                );
+            }
 
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
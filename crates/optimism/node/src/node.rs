@@ -2,7 +2,7 @@
 
 use crate::{
     args::RollupArgs,
-    txpool::{OpTransactionPool, OpTransactionValidator},
+    txpool::{ConfiguredOpTransactionPool, OpTransactionValidator},
     OptimismEngineTypes, OptimismEvmConfig,
 };
 use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
@@ -16,8 +16,7 @@ use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_provider::CanonStateSubscriptions;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, CoinbaseTipOrdering, TransactionPool,
-    TransactionValidationTaskExecutor,
+    blobstore::DiskFileBlobStore, TransactionPool, TransactionValidationTaskExecutor,
 };
 
 /// Type configuration for a regular Optimism node.
@@ -88,7 +87,7 @@ impl<Node> PoolBuilder<Node> for OptimismPoolBuilder
 where
     Node: FullNodeTypes,
 {
-    type Pool = OpTransactionPool<Node::Provider, DiskFileBlobStore>;
+    type Pool = ConfiguredOpTransactionPool<Node::Provider, DiskFileBlobStore>;
 
     async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
         let data_dir = ctx.data_dir();
@@ -105,11 +104,12 @@ where
             )
             .map(OpTransactionValidator::new);
 
+        let pool_config = ctx.pool_config();
         let transaction_pool = reth_transaction_pool::Pool::new(
             validator,
-            CoinbaseTipOrdering::default(),
+            pool_config.ordering.ordering(),
             blob_store,
-            ctx.pool_config(),
+            pool_config,
         );
         info!(target: "reth::cli", "Transaction pool initialized");
         let transactions_path = data_dir.txpool_transactions_path();
@@ -122,16 +122,48 @@ where
             let transactions_backup_config =
                 reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
 
-            ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
-                "local transactions backup task",
-                |shutdown| {
-                    reth_transaction_pool::maintain::backup_local_transactions_task(
-                        shutdown,
-                        pool.clone(),
-                        transactions_backup_config,
-                    )
-                },
-            );
+            if let Some(journal_config) =
+                ctx.config().txpool.journal_config(data_dir.txpool_journal_path())
+            {
+                // the journal includes local transactions, and takes over the ones that were
+                // backed up while it was disabled
+                let journal_config = journal_config
+                    .with_local_transactions_backup(data_dir.txpool_transactions_path());
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "transaction pool journal task",
+                    |shutdown| {
+                        reth_transaction_pool::journal::journal_transactions_task(
+                            shutdown,
+                            pool.clone(),
+                            journal_config,
+                        )
+                    },
+                );
+            } else {
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "local transactions backup task",
+                    |shutdown| {
+                        reth_transaction_pool::maintain::backup_local_transactions_task(
+                            shutdown,
+                            pool.clone(),
+                            transactions_backup_config,
+                        )
+                    },
+                );
+            }
+
+            if let Some(record_path) = ctx.config().txpool.record.clone() {
+                ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                    "transaction pool recorder task",
+                    |shutdown| {
+                        reth_transaction_pool::replay::record_pool_task(
+                            shutdown,
+                            pool.clone(),
+                            record_path,
+                        )
+                    },
+                );
+            }
 
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
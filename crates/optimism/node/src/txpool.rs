@@ -4,9 +4,9 @@ use reth_primitives::{Block, ChainSpec, GotExpected, InvalidTransactionError, Se
 use reth_provider::{BlockReaderIdExt, StateProviderFactory};
 use reth_revm::{optimism::RethL1BlockInfo, L1BlockInfo};
 use reth_transaction_pool::{
-    CoinbaseTipOrdering, EthPoolTransaction, EthPooledTransaction, EthTransactionValidator, Pool,
-    TransactionOrigin, TransactionValidationOutcome, TransactionValidationTaskExecutor,
-    TransactionValidator,
+    CoinbaseTipOrdering, ConfiguredOrdering, EthPoolTransaction, EthPooledTransaction,
+    EthTransactionValidator, Pool, TransactionOrigin, TransactionValidationOutcome,
+    TransactionValidationTaskExecutor, TransactionValidator,
 };
 use std::sync::{
     atomic::{AtomicU64, Ordering},
@@ -20,6 +20,14 @@ pub type OpTransactionPool<Client, S> = Pool<
     S,
 >;
 
+/// Type alias for the optimism transaction pool that uses the ordering selected in
+/// [PoolConfig::ordering](reth_transaction_pool::PoolConfig::ordering)
+pub type ConfiguredOpTransactionPool<Client, S> = Pool<
+    TransactionValidationTaskExecutor<OpTransactionValidator<Client, EthPooledTransaction>>,
+    ConfiguredOrdering<EthPooledTransaction>,
+    S,
+>;
+
 /// Validator for Optimism transactions.
 #[derive(Debug, Clone)]
 pub struct OpTransactionValidator<Client, Tx> {
@@ -257,10 +257,16 @@ where
     let base_fee = initialized_block_env.basefee.to::<u64>();
 
     let mut executed_txs = Vec::new();
-    let mut best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
-        base_fee,
-        initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
-    ));
+    let mut best_txs = pool.best_transactions_with_attributes(
+        BestTransactionsAttributes::new(
+            base_fee,
+            initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
+        )
+        .with_block(
+            initialized_block_env.number.to::<u64>(),
+            initialized_block_env.timestamp.to::<u64>(),
+        ),
+    );
 
     let mut total_fees = U256::ZERO;
 
@@ -405,6 +411,13 @@ where
                 ))
             }
 
+            // skip conditional transactions whose known accounts don't match the parent state
+            if !pool_tx.matches_known_accounts(&*state_provider) {
+                trace!(target: "payload_builder", tx=?pool_tx.hash(), "skipping conditional transaction with mismatching known accounts");
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+
             // check if the job was cancelled, if so we can exit early
             if cancel.is_cancelled() {
                 return Ok(BuildOutcome::Cancelled)
@@ -1,6 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{AnyNode, NodeRecord};
-use reth_rpc_types::{admin::NodeInfo, PeerInfo};
+use reth_rpc_types::{admin::NodeInfo, BansInfo, ScoredPeerInfo};
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
@@ -31,8 +31,10 @@ pub trait AdminApi {
     /// connected remote nodes at the networking granularity. These include general information
     /// about the nodes themselves as participants of the devp2p P2P overlay protocol, as well as
     /// specialized information added by each of the running application protocols
+    ///
+    /// In addition to Geth's format, each peer includes the score of the responses it served.
     #[method(name = "peers")]
-    async fn peers(&self) -> RpcResult<Vec<PeerInfo>>;
+    async fn peers(&self) -> RpcResult<Vec<ScoredPeerInfo>>;
 
     /// Creates an RPC subscription which serves events received from the network.
     #[subscription(
@@ -45,4 +47,49 @@ pub trait AdminApi {
     /// Returns the ENR of the node.
     #[method(name = "nodeInfo")]
     async fn node_info(&self) -> RpcResult<NodeInfo>;
+
+    /// Bans a remote node for the given number of seconds, or indefinitely if no duration is
+    /// given, and disconnects it if the connection exists.
+    #[method(name = "banPeer")]
+    fn ban_peer(&self, record: AnyNode, duration: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts the ban of a remote node.
+    #[method(name = "unbanPeer")]
+    fn unban_peer(&self, record: AnyNode) -> RpcResult<bool>;
+
+    /// Bans an ip range in CIDR notation, e.g. `10.0.0.0/8`, or a single ip address for the given
+    /// number of seconds, or indefinitely if no duration is given.
+    ///
+    /// All connected remote nodes with an address in the range are disconnected.
+    #[method(name = "banIpRange")]
+    fn ban_ip_range(&self, range: String, duration: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts the ban of an ip range.
+    #[method(name = "unbanIpRange")]
+    fn unban_ip_range(&self, range: String) -> RpcResult<bool>;
+
+    /// Returns the banned remote nodes and ip ranges.
+    #[method(name = "bans")]
+    async fn bans(&self) -> RpcResult<BansInfo>;
+
+    /// Changes the maximum number of inbound and outbound connections. Limits that are not given
+    /// are kept.
+    ///
+    /// Established connections are kept when a limit is lowered.
+    #[method(name = "setPeerLimits")]
+    fn set_peer_limits(
+        &self,
+        max_inbound: Option<usize>,
+        max_outbound: Option<usize>,
+    ) -> RpcResult<bool>;
+
+    /// Toggles whether only trusted nodes are accepted and connected to.
+    ///
+    /// When enabled, all connected remote nodes that are not trusted are disconnected.
+    #[method(name = "setTrustedNodesOnly")]
+    fn set_trusted_nodes_only(&self, enabled: bool) -> RpcResult<bool>;
+
+    /// Resets the reputation of the remote node, or of all known remote nodes if no node is given.
+    #[method(name = "resetReputation")]
+    fn reset_reputation(&self, record: Option<AnyNode>) -> RpcResult<bool>;
 }
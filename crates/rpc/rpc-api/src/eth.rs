@@ -5,8 +5,8 @@ use reth_primitives::{
 };
 use reth_rpc_types::{
     state::StateOverride, AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
-    EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index, RichBlock,
-    StateContext, SyncStatus, Transaction, TransactionRequest, Work,
+    ConditionalOptions, EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index,
+    RichBlock, StateContext, SyncStatus, Transaction, TransactionRequest, Work,
 };
 
 /// Eth rpc interface: <https://ethereum.github.io/execution-apis/api-documentation/>
@@ -286,6 +286,26 @@ pub trait EthApi {
     #[method(name = "sendRawTransaction")]
     async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<B256>;
 
+    /// Sends signed transaction that may only be included in a block if the given conditions
+    /// hold, returning its hash.
+    #[method(name = "sendRawTransactionConditional")]
+    async fn send_raw_transaction_conditional(
+        &self,
+        bytes: Bytes,
+        options: ConditionalOptions,
+    ) -> RpcResult<B256>;
+
+    /// Sends signed transaction that is only included in blocks built by this node and never
+    /// gossiped to peers, returning its hash.
+    ///
+    /// The transaction is dropped once the chain advances past the given block number.
+    #[method(name = "sendPrivateRawTransaction")]
+    async fn send_private_raw_transaction(
+        &self,
+        bytes: Bytes,
+        max_block_number: Option<U64>,
+    ) -> RpcResult<B256>;
+
     /// Returns an Ethereum specific signature with: sign(keccak256("\x19Ethereum Signed Message:\n"
     /// + len(message) + message))).
     #[method(name = "sign")]
@@ -1,5 +1,6 @@
 use jsonrpsee::proc_macros::rpc;
-use reth_rpc_types::pubsub::{Params, SubscriptionKind};
+use reth_primitives::TxHash;
+use reth_rpc_types::{pubsub::SubscriptionKind, EthSubscriptionParams};
 
 /// Ethereum pub-sub rpc interface.
 #[rpc(server, namespace = "eth")]
@@ -13,6 +14,21 @@ pub trait EthPubSubApi {
     async fn subscribe(
         &self,
         kind: SubscriptionKind,
-        params: Option<Params>,
+        params: Option<EthSubscriptionParams>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
+    /// Create a subscription for transactions that are dropped from the pool because they were
+    /// replaced, discarded, evicted or became invalid.
+    ///
+    /// If transaction hashes are given, only these transactions are tracked. The subscription is
+    /// rejected if none of them are in the pool.
+    #[subscription(
+        name = "subscribeDroppedTransactions" => "droppedTransactions",
+        unsubscribe = "unsubscribeDroppedTransactions",
+        item = reth_rpc_types::DroppedTransaction
+    )]
+    async fn subscribe_dropped_transactions(
+        &self,
+        hashes: Option<Vec<TxHash>>,
     ) -> jsonrpsee::core::SubscriptionResult;
 }
@@ -54,7 +54,7 @@ pub mod servers {
         reth::RethApiServer,
         rpc::RpcApiServer,
         trace::TraceApiServer,
-        txpool::TxPoolApiServer,
+        txpool::{TxPoolAdminApiServer, TxPoolApiServer},
         validation::BlockSubmissionValidationApiServer,
         web3::Web3ApiServer,
     };
@@ -82,7 +82,7 @@ pub mod clients {
         otterscan::OtterscanClient,
         rpc::RpcApiServer,
         trace::TraceApiClient,
-        txpool::TxPoolApiClient,
+        txpool::{TxPoolAdminApiClient, TxPoolApiClient},
         validation::BlockSubmissionValidationApiClient,
         web3::Web3ApiClient,
     };
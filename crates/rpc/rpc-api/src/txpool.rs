@@ -1,6 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::Address;
-use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
+use reth_primitives::{Address, Bytes, B256};
+use reth_rpc_types::{
+    txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus},
+    TxpoolSubpool, TxpoolTransactionState,
+};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
@@ -34,3 +37,39 @@ pub trait TxPoolApi {
     #[method(name = "content")]
     async fn txpool_content(&self) -> RpcResult<TxpoolContent>;
 }
+
+/// Txpool admin rpc interface, to manage the transactions in the pool.
+///
+/// This namespace is only served by the authenticated server.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpoolAdmin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "txpoolAdmin"))]
+pub trait TxPoolAdminApi {
+    /// Removes the transactions with the given hashes from the pool, returning the hashes of the
+    /// removed transactions.
+    #[method(name = "removeTransactions")]
+    async fn remove_transactions(&self, hashes: Vec<B256>) -> RpcResult<Vec<B256>>;
+
+    /// Removes all transactions of the given sender from the pool, returning the hashes of the
+    /// removed transactions.
+    #[method(name = "removeSenderTransactions")]
+    async fn remove_sender_transactions(&self, sender: Address) -> RpcResult<Vec<B256>>;
+
+    /// Removes all transactions in the given sub-pool, including their descendants, returning the
+    /// hashes of the removed transactions.
+    #[method(name = "clearSubpool")]
+    async fn clear_subpool(&self, subpool: TxpoolSubpool) -> RpcResult<Vec<B256>>;
+
+    /// Replaces the transaction with the given hash by the given signed transaction of the same
+    /// sender and nonce, returning the hash of the replacement.
+    ///
+    /// Unlike a replacement submitted through `eth_sendRawTransaction`, the replacement doesn't
+    /// need to pay a higher fee than the replaced transaction. If the replacement is rejected by
+    /// the pool, the replaced transaction is restored.
+    #[method(name = "replaceTransaction")]
+    async fn replace_transaction(&self, hash: B256, replacement: Bytes) -> RpcResult<B256>;
+
+    /// Returns the state of the transaction with the given hash, which explains why it is parked,
+    /// or `null` if it's not in the pool.
+    #[method(name = "transactionState")]
+    async fn transaction_state(&self, hash: B256) -> RpcResult<Option<TxpoolTransactionState>>;
+}
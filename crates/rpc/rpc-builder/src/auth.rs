@@ -28,7 +28,7 @@ use reth_rpc::{
         FeeHistoryCacheConfig,
     },
     AuthLayer, Claims, EngineEthApi, EthApi, EthFilter, EthSubscriptionIdProvider,
-    JwtAuthValidator, JwtSecret,
+    JwtAuthValidator, JwtSecret, TxPoolAdminApi,
 };
 use reth_rpc_api::servers::*;
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner};
@@ -103,6 +103,8 @@ where
 }
 
 /// Configure and launch a _standalone_ auth server with existing EthApi implementation.
+///
+/// The server also serves the `txpoolAdmin` namespace for the pool of the EthApi.
 pub async fn launch_with_eth_api<Provider, Pool, Network, EngineApi, EngineT, EvmConfig>(
     eth_api: EthApi<Provider, Pool, Network, EvmConfig>,
     eth_filter: EthFilter<Provider, Pool>,
@@ -128,6 +130,8 @@ where
     // Configure the module and start the server.
     let mut module = RpcModule::new(());
     module.merge(engine_api.into_rpc()).expect("No conflicting methods");
+    let txpool_admin = TxPoolAdminApi::new(eth_api.pool().clone());
+    module.merge(txpool_admin.into_rpc()).expect("No conflicting methods");
     let engine_eth = EngineEthApi::new(eth_api, eth_filter);
     module.merge(engine_eth.into_rpc()).expect("No conflicting methods");
 
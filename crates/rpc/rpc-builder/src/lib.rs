@@ -187,7 +187,7 @@ use reth_rpc::{
     },
     AdminApi, AuthLayer, Claims, DebugApi, EngineEthApi, EthApi, EthFilter, EthPubSub,
     EthSubscriptionIdProvider, JwtAuthValidator, JwtSecret, NetApi, OtterscanApi, RPCApi, RethApi,
-    TraceApi, TxPoolApi, Web3Api,
+    TraceApi, TxPoolAdminApi, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_tasks::{
@@ -1128,6 +1128,7 @@ where
     /// Configures the auth module that includes the
     ///   * `engine_` namespace
     ///   * `api_` namespace
+    ///   * `txpoolAdmin_` namespace
     ///
     /// Note: This does _not_ register the `engine_` in this registry.
     pub fn create_auth_module<EngineApi, EngineT>(&mut self, engine_api: EngineApi) -> AuthRpcModule
@@ -1144,6 +1145,10 @@ where
         let engine_eth = EngineEthApi::new(eth_handlers.api.clone(), eth_handlers.filter);
         module.merge(engine_eth.into_rpc()).expect("No conflicting methods");
 
+        // the pool admin methods modify the pool and are only served by the auth server
+        let txpool_admin = TxPoolAdminApi::new(self.pool.clone());
+        module.merge(txpool_admin.into_rpc()).expect("No conflicting methods");
+
         AuthRpcModule { inner: module }
     }
 
@@ -3,10 +3,13 @@
 use crate::utils::launch_auth;
 use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
 use reth_node_ethereum::EthEngineTypes;
-use reth_primitives::{Block, U64};
+use reth_primitives::{Address, Block, Bytes, B256, U64};
 use reth_rpc::JwtSecret;
-use reth_rpc_api::clients::EngineApiClient;
-use reth_rpc_types::engine::{ForkchoiceState, PayloadId, TransitionConfiguration};
+use reth_rpc_api::clients::{EngineApiClient, TxPoolAdminApiClient};
+use reth_rpc_types::{
+    engine::{ForkchoiceState, PayloadId, TransitionConfiguration},
+    TxpoolSubpool,
+};
 use reth_rpc_types_compat::engine::payload::{
     convert_block_to_payload_input_v2, try_block_to_payload_v1,
 };
@@ -29,13 +32,37 @@ where
     EngineApiClient::exchange_capabilities(client, vec![]).await;
 }
 
+async fn test_txpool_admin_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    let removed =
+        TxPoolAdminApiClient::remove_transactions(client, vec![B256::random()]).await.unwrap();
+    assert!(removed.is_empty());
+    let removed =
+        TxPoolAdminApiClient::remove_sender_transactions(client, Address::random()).await.unwrap();
+    assert!(removed.is_empty());
+    for subpool in
+        [TxpoolSubpool::Pending, TxpoolSubpool::BaseFee, TxpoolSubpool::Blob, TxpoolSubpool::Queued]
+    {
+        let removed = TxPoolAdminApiClient::clear_subpool(client, subpool).await.unwrap();
+        assert!(removed.is_empty());
+    }
+    let state = TxPoolAdminApiClient::transaction_state(client, B256::random()).await.unwrap();
+    assert!(state.is_none());
+    let replaced =
+        TxPoolAdminApiClient::replace_transaction(client, B256::random(), Bytes::default()).await;
+    assert!(replaced.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_auth_endpoints_http() {
     reth_tracing::init_test_tracing();
     let secret = JwtSecret::random();
     let handle = launch_auth(secret).await;
     let client = handle.http_client();
-    test_basic_engine_calls(&client).await
+    test_basic_engine_calls(&client).await;
+    test_txpool_admin_calls(&client).await
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -44,5 +71,6 @@ async fn test_auth_endpoints_ws() {
     let secret = JwtSecret::random();
     let handle = launch_auth(secret).await;
     let client = handle.ws_client().await;
-    test_basic_engine_calls(&client).await
+    test_basic_engine_calls(&client).await;
+    test_txpool_admin_calls(&client).await
 }
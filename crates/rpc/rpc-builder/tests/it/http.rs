@@ -144,6 +144,15 @@ where
     AdminApiClient::add_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::remove_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::node_info(client).await.unwrap();
+    AdminApiClient::ban_peer(client, node.into(), Some(60)).await.unwrap();
+    AdminApiClient::unban_peer(client, node.into()).await.unwrap();
+    AdminApiClient::ban_ip_range(client, "10.3.0.0/16".to_string(), None).await.unwrap();
+    AdminApiClient::unban_ip_range(client, "10.3.0.0/16".to_string()).await.unwrap();
+    assert!(AdminApiClient::ban_ip_range(client, "10.3.0.0/33".to_string(), None).await.is_err());
+    AdminApiClient::bans(client).await.unwrap();
+    AdminApiClient::set_peer_limits(client, Some(10), None).await.unwrap();
+    AdminApiClient::set_trusted_nodes_only(client, false).await.unwrap();
+    AdminApiClient::reset_reputation(client, None).await.unwrap();
 }
 
 async fn test_basic_eth_calls<C>(client: &C)
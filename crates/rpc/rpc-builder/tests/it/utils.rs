@@ -4,7 +4,8 @@ use reth_node_ethereum::{EthEngineTypes, EthEvmConfig};
 use reth_payload_builder::test_utils::spawn_test_payload_service;
 use reth_primitives::MAINNET;
 use reth_provider::test_utils::{NoopProvider, TestCanonStateSubscriptions};
-use reth_rpc::JwtSecret;
+use reth_rpc::{JwtSecret, TxPoolAdminApi};
+use reth_rpc_api::TxPoolAdminApiServer;
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerConfig, AuthServerHandle},
     RpcModuleBuilder, RpcModuleSelection, RpcServerConfig, RpcServerHandle,
@@ -33,7 +34,9 @@ pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
         spawn_test_payload_service().into(),
         Box::<TokioTaskExecutor>::default(),
     );
-    let module = AuthRpcModule::new(engine_api);
+    let mut module = AuthRpcModule::new(engine_api);
+    let txpool_admin = TxPoolAdminApi::new(TestPool::from(TestPoolBuilder::default()));
+    module.merge_auth_methods(txpool_admin.into_rpc()).unwrap();
     module.start_server(config).await.unwrap()
 }
 
@@ -1,5 +1,6 @@
 //! Ethereum related types
 
+pub mod pubsub;
 pub mod transaction;
 
 // re-export
@@ -0,0 +1,190 @@
+//! Reth specific parameters and items of `eth` subscriptions.
+
+use alloy_primitives::{Address, Bytes, B256, U128, U8};
+use alloy_rpc_types::pubsub::Params;
+use serde::{Deserialize, Serialize};
+
+/// Parameters of an `eth_subscribe` call.
+///
+/// This extends the standard [Params] with a [PendingTransactionFilter] for the
+/// `newPendingTransactions` subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum EthSubscriptionParams {
+    /// Server side filter for the `newPendingTransactions` subscription.
+    PendingTransactions(PendingTransactionFilter),
+    /// Standard subscription parameters.
+    Eth(Params),
+}
+
+impl From<Params> for EthSubscriptionParams {
+    fn from(params: Params) -> Self {
+        Self::Eth(params)
+    }
+}
+
+impl From<PendingTransactionFilter> for EthSubscriptionParams {
+    fn from(filter: PendingTransactionFilter) -> Self {
+        Self::PendingTransactions(filter)
+    }
+}
+
+/// Server side filter for the `newPendingTransactions` subscription.
+///
+/// A transaction is sent to the subscriber if it matches all of the configured conditions, unset
+/// conditions match all transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PendingTransactionFilter {
+    /// Whether full transaction objects are sent instead of transaction hashes.
+    #[serde(default)]
+    pub full_transactions: bool,
+    /// Senders of matching transactions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub from: Vec<Address>,
+    /// Recipients of matching transactions. Contract creations never match.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub to: Vec<Address>,
+    /// Prefixes of the input of matching transactions, usually 4 byte method selectors.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub method_selectors: Vec<Bytes>,
+    /// The minimum max priority fee per gas, or gas price for legacy transactions, of matching
+    /// transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_priority_fee: Option<U128>,
+    /// Types of matching transactions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tx_types: Vec<U8>,
+}
+
+impl PendingTransactionFilter {
+    /// Returns `true` if the filter doesn't restrict the matching transactions.
+    pub fn is_unrestricted(&self) -> bool {
+        self.from.is_empty() &&
+            self.to.is_empty() &&
+            self.method_selectors.is_empty() &&
+            self.min_priority_fee.is_none() &&
+            self.tx_types.is_empty()
+    }
+
+    /// Returns `true` if a transaction with the given properties matches the filter.
+    ///
+    /// The `priority_fee` is the max priority fee per gas of the transaction, or the gas price
+    /// for legacy transactions.
+    pub fn matches(
+        &self,
+        from: Address,
+        to: Option<Address>,
+        input: &[u8],
+        priority_fee: u128,
+        tx_type: u8,
+    ) -> bool {
+        (self.from.is_empty() || self.from.contains(&from)) &&
+            (self.to.is_empty() || to.map_or(false, |to| self.to.contains(&to))) &&
+            (self.method_selectors.is_empty() ||
+                self.method_selectors.iter().any(|selector| input.starts_with(selector))) &&
+            self.min_priority_fee.map_or(true, |min| U128::from(priority_fee) >= min) &&
+            (self.tx_types.is_empty() || self.tx_types.contains(&U8::from(tx_type)))
+    }
+}
+
+/// The reason a transaction was dropped from the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropReason {
+    /// The transaction was replaced by another transaction with the same sender and nonce.
+    Replaced,
+    /// The transaction was discarded because the pool exceeded its limits.
+    Discarded,
+    /// The transaction exceeded its configured lifetime.
+    Evicted,
+    /// The transaction became invalid.
+    Invalid,
+}
+
+/// An item of the `eth_subscribeDroppedTransactions` subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedTransaction {
+    /// The hash of the dropped transaction.
+    pub hash: B256,
+    /// Why the transaction was dropped.
+    pub reason: DropReason,
+    /// The hash of the replacement, if the transaction was replaced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<B256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_subscription_params() {
+        let params: EthSubscriptionParams = serde_json::from_str("true").unwrap();
+        assert_eq!(params, EthSubscriptionParams::Eth(Params::Bool(true)));
+
+        let params: EthSubscriptionParams = serde_json::from_str(
+            r#"{"address":"0x0000000000000000000000000000000000000001","topics":[]}"#,
+        )
+        .unwrap();
+        assert!(matches!(params, EthSubscriptionParams::Eth(Params::Logs(_))));
+
+        let params: EthSubscriptionParams = serde_json::from_str(
+            r#"{"fullTransactions":true,"to":["0x0000000000000000000000000000000000000001"],"methodSelectors":["0xa9059cbb"],"minPriorityFee":"0x3b9aca00","txTypes":["0x2"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            params,
+            EthSubscriptionParams::PendingTransactions(PendingTransactionFilter {
+                full_transactions: true,
+                to: vec![Address::with_last_byte(1)],
+                method_selectors: vec![Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb])],
+                min_priority_fee: Some(U128::from(1_000_000_000u64)),
+                tx_types: vec![U8::from(2)],
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn pending_transaction_filter_matches() {
+        let from = Address::with_last_byte(1);
+        let to = Address::with_last_byte(2);
+        let input = [0xa9, 0x05, 0x9c, 0xbb, 0x00];
+
+        let filter = PendingTransactionFilter::default();
+        assert!(filter.is_unrestricted());
+        assert!(filter.matches(from, None, &[], 0, 0));
+
+        let filter = PendingTransactionFilter {
+            to: vec![to],
+            method_selectors: vec![Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb])],
+            min_priority_fee: Some(U128::from(10)),
+            tx_types: vec![U8::from(2)],
+            ..Default::default()
+        };
+        assert!(!filter.is_unrestricted());
+        assert!(filter.matches(from, Some(to), &input, 10, 2));
+        assert!(!filter.matches(from, None, &input, 10, 2));
+        assert!(!filter.matches(from, Some(from), &input, 10, 2));
+        assert!(!filter.matches(from, Some(to), &input[1..], 10, 2));
+        assert!(!filter.matches(from, Some(to), &input, 9, 2));
+        assert!(!filter.matches(from, Some(to), &input, 10, 0));
+    }
+
+    #[test]
+    fn serde_dropped_transaction() {
+        let s = r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000001","reason":"replaced","replacedBy":"0x0000000000000000000000000000000000000000000000000000000000000002"}"#;
+        let dropped: DroppedTransaction = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            dropped,
+            DroppedTransaction {
+                hash: B256::with_last_byte(1),
+                reason: DropReason::Replaced,
+                replaced_by: Some(B256::with_last_byte(2)),
+            }
+        );
+        assert_eq!(serde_json::to_string(&dropped).unwrap(), s);
+    }
+}
@@ -0,0 +1,78 @@
+//! Options of `eth_sendRawTransactionConditional`, used by ERC-4337 bundlers to submit bundles
+//! that are only valid under specific conditions.
+
+use alloy_primitives::{Address, B256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The expected storage of an account in [`ConditionalOptions::known_accounts`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AccountStorage {
+    /// The expected root of the account's storage trie.
+    RootHash(B256),
+    /// The expected values of individual storage slots.
+    Slots(HashMap<B256, B256>),
+}
+
+/// Conditions under which a transaction submitted via `eth_sendRawTransactionConditional` may be
+/// included in a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalOptions {
+    /// The expected storage of accounts at the parent of the including block.
+    #[serde(default)]
+    pub known_accounts: HashMap<Address, AccountStorage>,
+    /// The minimum number of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_min: Option<U64>,
+    /// The maximum number of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_max: Option<U64>,
+    /// The minimum timestamp of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_min: Option<U64>,
+    /// The maximum timestamp of the including block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_max: Option<U64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn deserialize_conditional_options() {
+        let s = r#"{
+            "knownAccounts": {
+                "0x000000000000000000000000000000000000dead": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+                "0x000000000000000000000000000000000000beef": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+                }
+            },
+            "blockNumberMax": "0x64",
+            "timestampMin": "0x65f1f1a0"
+        }"#;
+        let options: ConditionalOptions = serde_json::from_str(s).unwrap();
+        assert_eq!(options.known_accounts.len(), 2);
+        assert!(matches!(
+            options.known_accounts[&address!("000000000000000000000000000000000000dead")],
+            AccountStorage::RootHash(_)
+        ));
+        assert_eq!(
+            options.known_accounts[&address!("000000000000000000000000000000000000beef")],
+            AccountStorage::Slots(HashMap::from([(
+                B256::with_last_byte(1),
+                B256::with_last_byte(2)
+            )]))
+        );
+        assert_eq!(options.block_number_min, None);
+        assert_eq!(options.block_number_max, Some(U64::from(100)));
+        assert_eq!(options.timestamp_min, Some(U64::from(0x65f1f1a0)));
+
+        let serialized = serde_json::to_string(&options).unwrap();
+        let deserialized: ConditionalOptions = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(options, deserialized);
+    }
+}
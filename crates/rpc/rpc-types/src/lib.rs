@@ -16,6 +16,7 @@ mod net;
 mod peer;
 pub mod relay;
 mod rpc;
+mod txpool_admin;
 
 // re-export for convenience
 pub use alloy_rpc_types::serde_helpers;
@@ -37,10 +38,15 @@ pub use eth::{
     engine::{
         ExecutionPayload, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadError,
     },
-    transaction::{self, TransactionKind, TransactionRequest, TypedTransactionRequest},
+    pubsub::{DropReason, DroppedTransaction, EthSubscriptionParams, PendingTransactionFilter},
+    transaction::{
+        self, AccountStorage, ConditionalOptions, TransactionKind, TransactionRequest,
+        TypedTransactionRequest,
+    },
 };
 
 pub use mev::*;
 pub use net::*;
 pub use peer::*;
 pub use rpc::*;
+pub use txpool_admin::*;
@@ -1,11 +1,12 @@
 use crate::{pk_to_id, PeerId};
 use alloy_rlp::{RlpDecodable, RlpEncodable};
-use alloy_rpc_types::admin::EthProtocolInfo;
+use alloy_rpc_types::admin::{EthProtocolInfo, PeerInfo};
 use enr::Enr;
 use secp256k1::{SecretKey, SECP256K1};
 use serde::{Deserialize, Serialize};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
+    collections::BTreeMap,
     fmt,
     fmt::Write,
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -26,6 +27,97 @@ pub struct NetworkStatus {
     pub eth_protocol_info: EthProtocolInfo,
 }
 
+/// A connected peer, as returned by `admin_peers`.
+///
+/// Extends the Geth compatible [`PeerInfo`] with the score of the responses the peer served and
+/// the bytes exchanged with the peer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScoredPeerInfo {
+    /// The Geth compatible info about the peer.
+    #[serde(flatten)]
+    pub info: PeerInfo,
+    /// The score of the peer, if any request was sent to the peer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<PeerScoreInfo>,
+    /// The bytes exchanged with the peer over its session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bandwidth: Option<PeerBandwidthInfo>,
+}
+
+/// How useful a peer is when serving requests.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerScoreInfo {
+    /// The moving average of the quality of the peer's responses, from 0 to 100.
+    pub score: f64,
+    /// The moving average of the response latency in milliseconds, if any request was answered.
+    pub latency_ms: Option<u64>,
+    /// Total number of bytes the peer served.
+    pub bytes_served: u64,
+    /// Number of scored requests.
+    pub requests: u64,
+    /// Number of requests that timed out.
+    pub timeouts: u64,
+    /// Number of requests that were answered with a bad response.
+    pub bad_responses: u64,
+}
+
+/// The bytes exchanged with a peer over its session.
+///
+/// Sizes are the uncompressed sizes of the messages.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerBandwidthInfo {
+    /// Total number of bytes received from the peer.
+    pub inbound: u64,
+    /// Total number of bytes sent to the peer.
+    pub outbound: u64,
+    /// Bytes of `eth` messages by message type, e.g. `BlockBodies`.
+    pub eth: BTreeMap<String, ByteCountInfo>,
+    /// Bytes of additional subprotocols by capability, e.g. `snap/1`.
+    pub protocols: BTreeMap<String, ByteCountInfo>,
+}
+
+/// Number of bytes exchanged with a peer in each direction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ByteCountInfo {
+    /// Bytes received from the peer.
+    pub inbound: u64,
+    /// Bytes sent to the peer.
+    pub outbound: u64,
+}
+
+/// The banned peers and ip addresses, as returned by `admin_bans`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BansInfo {
+    /// The banned peers.
+    pub peers: Vec<PeerBanInfo>,
+    /// The banned ip addresses and ip ranges.
+    pub ip_ranges: Vec<IpRangeBanInfo>,
+}
+
+/// A banned peer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerBanInfo {
+    /// The id of the peer.
+    pub id: PeerId,
+    /// Seconds until the ban expires, or `None` if the peer is banned indefinitely.
+    pub expires_in: Option<u64>,
+}
+
+/// A banned ip range.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpRangeBanInfo {
+    /// The ip range in CIDR notation, e.g. `10.0.0.0/8`.
+    pub range: String,
+    /// Seconds until the ban expires, or `None` if the range is banned indefinitely.
+    pub expires_in: Option<u64>,
+}
+
 /// Represents a ENR in discovery.
 ///
 /// Note: this is only an excerpt of the [`NodeRecord`] data structure.
@@ -207,9 +299,66 @@ impl TryFrom<&Enr<SecretKey>> for NodeRecord {
 mod tests {
     use super::*;
     use alloy_rlp::Decodable;
+    use alloy_rpc_types::admin::{PeerNetworkInfo, PeerProtocolsInfo};
     use rand::{thread_rng, Rng, RngCore};
     use std::net::Ipv6Addr;
 
+    #[test]
+    fn scored_peer_info_serde_roundtrip() {
+        let info = PeerInfo {
+            id: Some("44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d".to_string()),
+            name: "reth".to_string(),
+            caps: vec!["eth/68".to_string()],
+            network: PeerNetworkInfo {
+                remote_address: "127.0.0.1:30303".to_string(),
+                local_address: "127.0.0.1:30304".to_string(),
+            },
+            protocols: PeerProtocolsInfo { eth: None, pip: None },
+        };
+        let score = PeerScoreInfo {
+            score: 87.5,
+            latency_ms: Some(120),
+            bytes_served: 4096,
+            requests: 8,
+            timeouts: 1,
+            bad_responses: 0,
+        };
+
+        let bandwidth = PeerBandwidthInfo {
+            inbound: 300,
+            outbound: 50,
+            eth: BTreeMap::from([(
+                "BlockBodies".to_string(),
+                ByteCountInfo { inbound: 200, outbound: 50 },
+            )]),
+            protocols: BTreeMap::from([(
+                "snap/1".to_string(),
+                ByteCountInfo { inbound: 100, outbound: 0 },
+            )]),
+        };
+
+        let peer = ScoredPeerInfo {
+            info: info.clone(),
+            score: Some(score.clone()),
+            bandwidth: Some(bandwidth.clone()),
+        };
+        let json = serde_json::to_value(&peer).unwrap();
+        assert_eq!(json["name"], "reth");
+        assert_eq!(json["score"]["latencyMs"], 120);
+        assert_eq!(json["score"]["bytesServed"], 4096);
+        assert_eq!(json["bandwidth"]["eth"]["BlockBodies"]["inbound"], 200);
+        assert_eq!(json["bandwidth"]["protocols"]["snap/1"]["inbound"], 100);
+
+        let decoded: ScoredPeerInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.info.name, "reth");
+        assert_eq!(decoded.score, Some(score));
+        assert_eq!(decoded.bandwidth, Some(bandwidth));
+
+        // peers without a score serialize like a plain `PeerInfo`
+        let peer = ScoredPeerInfo { info: info.clone(), score: None, bandwidth: None };
+        assert_eq!(serde_json::to_value(&peer).unwrap(), serde_json::to_value(&info).unwrap());
+    }
+
     #[test]
     fn test_mapped_ipv6() {
         let mut rng = thread_rng();
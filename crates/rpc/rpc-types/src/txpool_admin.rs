@@ -0,0 +1,60 @@
+//! Types for the authenticated `txpoolAdmin` namespace.
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// A sub-pool of the transaction pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxpoolSubpool {
+    /// Transactions that are ready to be included in the next block.
+    Pending,
+    /// Transactions that don't meet the base fee of the next block.
+    BaseFee,
+    /// Blob transactions that are not pending.
+    Blob,
+    /// Transactions with missing or queued ancestors, or whose sender lacks funds.
+    Queued,
+}
+
+/// The state of a transaction in the pool, as returned by `txpoolAdmin_transactionState`.
+///
+/// A transaction is parked in a sub-pool other than [`TxpoolSubpool::Pending`] if any of the flags
+/// required for pending transactions is unset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolTransactionState {
+    /// The hash of the transaction.
+    pub hash: B256,
+    /// The sub-pool that currently contains the transaction.
+    pub subpool: TxpoolSubpool,
+    /// Whether all ancestor transactions of the sender are pending.
+    pub no_parked_ancestors: bool,
+    /// Whether there are no nonce gaps between the on chain nonce and the transaction.
+    pub no_nonce_gaps: bool,
+    /// Whether the sender's balance covers the cost of the transaction and all prior transactions.
+    pub enough_balance: bool,
+    /// Whether the gas limit of the transaction doesn't exceed the block's gas limit.
+    pub not_too_much_gas: bool,
+    /// Whether the max fee per gas of the transaction meets the base fee of the next block.
+    pub enough_fee_cap_block: bool,
+    /// Whether the max fee per blob gas of the transaction meets the blob fee of the next block.
+    pub enough_blob_fee_cap_block: bool,
+    /// Whether the transaction is an EIP-4844 blob transaction.
+    pub blob_transaction: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_transaction_state() {
+        let s = r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000001","subpool":"baseFee","noParkedAncestors":true,"noNonceGaps":true,"enoughBalance":true,"notTooMuchGas":true,"enoughFeeCapBlock":false,"enoughBlobFeeCapBlock":true,"blobTransaction":false}"#;
+        let state: TxpoolTransactionState = serde_json::from_str(s).unwrap();
+        assert_eq!(state.hash, B256::with_last_byte(1));
+        assert_eq!(state.subpool, TxpoolSubpool::BaseFee);
+        assert!(!state.enough_fee_cap_block);
+        assert_eq!(serde_json::to_string(&state).unwrap(), s);
+    }
+}
@@ -1,15 +1,21 @@
-use crate::result::ToRpcResult;
+use crate::result::{invalid_params_rpc_err, ToRpcResult};
 use alloy_primitives::B256;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use reth_network_api::{NetworkInfo, PeerKind, Peers};
+use reth_network_api::{
+    ByteCount, IpRange, NetworkInfo, PeerBandwidth, PeerKind, Peers, ReputationChangeKind,
+};
 use reth_primitives::{AnyNode, ChainSpec, NodeRecord};
 use reth_rpc_api::AdminApiServer;
 use reth_rpc_types::{
     admin::{EthProtocolInfo, NodeInfo, Ports, ProtocolInfo},
-    PeerEthProtocolInfo, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
+    BansInfo, ByteCountInfo, IpRangeBanInfo, PeerBanInfo, PeerBandwidthInfo, PeerEthProtocolInfo,
+    PeerInfo, PeerNetworkInfo, PeerProtocolsInfo, PeerScoreInfo, ScoredPeerInfo,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use std::sync::Arc;
 
 /// `admin` API implementation.
 ///
@@ -60,29 +66,45 @@ where
         Ok(true)
     }
 
-    async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
+    async fn peers(&self) -> RpcResult<Vec<ScoredPeerInfo>> {
         let peers = self.network.get_all_peers().await.to_rpc_result()?;
         let peers = peers
             .into_iter()
-            .map(|peer| PeerInfo {
-                id: Some(peer.remote_id.to_string()),
-                name: peer.client_version.to_string(),
-                caps: peer.capabilities.capabilities().iter().map(|cap| cap.to_string()).collect(),
-                network: PeerNetworkInfo {
-                    remote_address: peer.remote_addr.to_string(),
-                    local_address: peer
-                        .local_addr
-                        .unwrap_or_else(|| self.network.local_addr())
-                        .to_string(),
-                },
-                protocols: PeerProtocolsInfo {
-                    eth: Some(PeerEthProtocolInfo {
-                        difficulty: Some(peer.status.total_difficulty),
-                        head: peer.status.blockhash.to_string(),
-                        version: peer.status.version as u32,
-                    }),
-                    pip: None,
+            .map(|peer| ScoredPeerInfo {
+                info: PeerInfo {
+                    id: Some(peer.remote_id.to_string()),
+                    name: peer.client_version.to_string(),
+                    caps: peer
+                        .capabilities
+                        .capabilities()
+                        .iter()
+                        .map(|cap| cap.to_string())
+                        .collect(),
+                    network: PeerNetworkInfo {
+                        remote_address: peer.remote_addr.to_string(),
+                        local_address: peer
+                            .local_addr
+                            .unwrap_or_else(|| self.network.local_addr())
+                            .to_string(),
+                    },
+                    protocols: PeerProtocolsInfo {
+                        eth: Some(PeerEthProtocolInfo {
+                            difficulty: Some(peer.status.total_difficulty),
+                            head: peer.status.blockhash.to_string(),
+                            version: peer.status.version as u32,
+                        }),
+                        pip: None,
+                    },
                 },
+                score: peer.score.map(|score| PeerScoreInfo {
+                    score: score.score(),
+                    latency_ms: score.latency().map(|latency| latency.as_millis() as u64),
+                    bytes_served: score.bytes_served(),
+                    requests: score.requests(),
+                    timeouts: score.timeouts(),
+                    bad_responses: score.bad_responses(),
+                }),
+                bandwidth: Some(bandwidth_info(&peer.bandwidth)),
             })
             .collect();
 
@@ -118,6 +140,82 @@ where
         Ok(node_info)
     }
 
+    /// Handler for `admin_banPeer`
+    fn ban_peer(&self, record: AnyNode, duration: Option<u64>) -> RpcResult<bool> {
+        self.network.ban_peer(record.peer_id(), duration.map(Duration::from_secs));
+        Ok(true)
+    }
+
+    /// Handler for `admin_unbanPeer`
+    fn unban_peer(&self, record: AnyNode) -> RpcResult<bool> {
+        self.network.unban_peer(record.peer_id());
+        Ok(true)
+    }
+
+    /// Handler for `admin_banIpRange`
+    fn ban_ip_range(&self, range: String, duration: Option<u64>) -> RpcResult<bool> {
+        self.network.ban_ip_range(parse_ip_range(&range)?, duration.map(Duration::from_secs));
+        Ok(true)
+    }
+
+    /// Handler for `admin_unbanIpRange`
+    fn unban_ip_range(&self, range: String) -> RpcResult<bool> {
+        self.network.unban_ip_range(parse_ip_range(&range)?);
+        Ok(true)
+    }
+
+    /// Handler for `admin_bans`
+    async fn bans(&self) -> RpcResult<BansInfo> {
+        let ban_list = self.network.ban_list().await.to_rpc_result()?;
+        let now = Instant::now();
+        let expires_in = |until: Option<Instant>| {
+            until.map(|until| until.saturating_duration_since(now).as_secs())
+        };
+
+        let peers = ban_list
+            .banned_peers()
+            .map(|(id, until)| PeerBanInfo { id, expires_in: expires_in(until) })
+            .collect();
+        let ip_ranges = ban_list
+            .banned_ips()
+            .map(|(ip, until)| (IpRange::from(ip), until))
+            .chain(ban_list.banned_ip_ranges())
+            .map(|(range, until)| IpRangeBanInfo {
+                range: range.to_string(),
+                expires_in: expires_in(until),
+            })
+            .collect();
+
+        Ok(BansInfo { peers, ip_ranges })
+    }
+
+    /// Handler for `admin_setPeerLimits`
+    fn set_peer_limits(
+        &self,
+        max_inbound: Option<usize>,
+        max_outbound: Option<usize>,
+    ) -> RpcResult<bool> {
+        self.network.set_peer_limits(max_inbound, max_outbound);
+        Ok(true)
+    }
+
+    /// Handler for `admin_setTrustedNodesOnly`
+    fn set_trusted_nodes_only(&self, enabled: bool) -> RpcResult<bool> {
+        self.network.set_trusted_nodes_only(enabled);
+        Ok(true)
+    }
+
+    /// Handler for `admin_resetReputation`
+    fn reset_reputation(&self, record: Option<AnyNode>) -> RpcResult<bool> {
+        match record {
+            Some(record) => {
+                self.network.reputation_change(record.peer_id(), ReputationChangeKind::Reset)
+            }
+            None => self.network.reset_reputations(),
+        }
+        Ok(true)
+    }
+
     /// Handler for `admin_peerEvents`
     async fn subscribe_peer_events(
         &self,
@@ -127,6 +225,28 @@ where
     }
 }
 
+/// Parses an ip range in CIDR notation, or a single ip address.
+fn parse_ip_range(range: &str) -> RpcResult<IpRange> {
+    range.parse().map_err(|err| invalid_params_rpc_err(format!("invalid ip range {range}: {err}")))
+}
+
+/// Converts the bandwidth of a peer's session into its rpc representation.
+fn bandwidth_info(bandwidth: &PeerBandwidth) -> PeerBandwidthInfo {
+    let count =
+        |count: &ByteCount| ByteCountInfo { inbound: count.inbound, outbound: count.outbound };
+    let total = bandwidth.total();
+    PeerBandwidthInfo {
+        inbound: total.inbound,
+        outbound: total.outbound,
+        eth: bandwidth.eth.iter().map(|(id, bytes)| (format!("{id:?}"), count(bytes))).collect(),
+        protocols: bandwidth
+            .protocols
+            .iter()
+            .map(|(cap, bytes)| (cap.to_string(), count(bytes)))
+            .collect(),
+    }
+}
+
 impl<N> std::fmt::Debug for AdminApi<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AdminApi").finish_non_exhaustive()
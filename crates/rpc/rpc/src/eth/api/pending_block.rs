@@ -63,10 +63,13 @@ impl PendingBlockEnv {
 
         let mut executed_txs = Vec::new();
         let mut senders = Vec::new();
-        let mut best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
-            base_fee,
-            block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
-        ));
+        let mut best_txs = pool.best_transactions_with_attributes(
+            BestTransactionsAttributes::new(
+                base_fee,
+                block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
+            )
+            .with_block(block_number, block_env.timestamp.to::<u64>()),
+        );
 
         let (withdrawals, withdrawals_root) = match origin {
             PendingBlockEnvOrigin::ActualPending(ref block) => {
@@ -113,6 +116,12 @@ impl PendingBlockEnv {
                 continue
             }
 
+            // skip conditional transactions whose known accounts don't match the parent state
+            if !pool_tx.matches_known_accounts(&*state_provider) {
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+
             // convert tx to a signed transaction
             let tx = pool_tx.to_recovered_transaction();
 
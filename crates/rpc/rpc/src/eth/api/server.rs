@@ -18,8 +18,8 @@ use reth_provider::{
 use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
     state::StateOverride, AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
-    EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index, RichBlock,
-    StateContext, SyncStatus, TransactionRequest, Work,
+    ConditionalOptions, EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index,
+    RichBlock, StateContext, SyncStatus, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 
@@ -400,6 +400,31 @@ where
         Ok(EthTransactions::send_raw_transaction(self, tx).await?)
     }
 
+    /// Handler for: `eth_sendRawTransactionConditional`
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        options: ConditionalOptions,
+    ) -> Result<B256> {
+        trace!(target: "rpc::eth", ?tx, ?options, "Serving eth_sendRawTransactionConditional");
+        Ok(EthTransactions::send_raw_transaction_conditional(self, tx, options).await?)
+    }
+
+    /// Handler for: `eth_sendPrivateRawTransaction`
+    async fn send_private_raw_transaction(
+        &self,
+        tx: Bytes,
+        max_block_number: Option<U64>,
+    ) -> Result<B256> {
+        trace!(target: "rpc::eth", ?tx, ?max_block_number, "Serving eth_sendPrivateRawTransaction");
+        Ok(EthTransactions::send_private_raw_transaction(
+            self,
+            tx,
+            max_block_number.map(|n| n.to()),
+        )
+        .await?)
+    }
+
     /// Handler for: `eth_sign`
     async fn sign(&self, address: Address, message: Bytes) -> Result<Bytes> {
         trace!(target: "rpc::eth", ?address, ?message, "Serving eth_sign");
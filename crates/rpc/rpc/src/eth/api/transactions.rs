@@ -4,7 +4,9 @@ use crate::{
         api::pending_block::PendingBlockEnv,
         error::{EthApiError, EthResult, RpcInvalidTransactionError, SignError},
         revm_utils::{prepare_call_env, EvmOverrides},
-        utils::recover_raw_transaction,
+        utils::{
+            recover_raw_transaction, transaction_conditional, DEFAULT_PRIVATE_TRANSACTION_EXPIRY,
+        },
     },
     EthApi, EthApiSpec,
 };
@@ -14,13 +16,14 @@ use reth_network_api::NetworkInfo;
 use reth_primitives::{
     eip4844::calc_blob_gasprice,
     revm::env::{fill_block_env_with_coinbase, tx_env_with_recovered},
-    Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredPooledTransaction, Header,
+    Address, BlockId, BlockNumber, BlockNumberOrTag, Bytes, FromRecoveredPooledTransaction, Header,
     IntoRecoveredTransaction, Receipt, SealedBlock, SealedBlockWithSenders,
     TransactionKind::{Call, Create},
     TransactionMeta, TransactionSigned, TransactionSignedEcRecovered, B256, U256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+    BlockNumReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox,
+    StateProviderFactory,
 };
 use reth_revm::{
     database::StateProviderDatabase,
@@ -31,12 +34,12 @@ use reth_rpc_types::{
         EIP1559TransactionRequest, EIP2930TransactionRequest, EIP4844TransactionRequest,
         LegacyTransactionRequest,
     },
-    AnyReceiptEnvelope, AnyTransactionReceipt, Index, Log, ReceiptWithBloom, Transaction,
-    TransactionInfo, TransactionKind as RpcTransactionKind, TransactionReceipt, TransactionRequest,
-    TypedTransactionRequest, WithOtherFields,
+    AnyReceiptEnvelope, AnyTransactionReceipt, ConditionalOptions, Index, Log, ReceiptWithBloom,
+    Transaction, TransactionInfo, TransactionKind as RpcTransactionKind, TransactionReceipt,
+    TransactionRequest, TypedTransactionRequest, WithOtherFields,
 };
 use reth_rpc_types_compat::transaction::from_recovered_with_block_context;
-use reth_transaction_pool::{TransactionOrigin, TransactionPool};
+use reth_transaction_pool::{TransactionConditional, TransactionOrigin, TransactionPool};
 use revm::{
     db::CacheDB,
     primitives::{
@@ -268,6 +271,31 @@ pub trait EthTransactions: Send + Sync {
     /// Returns the hash of the transaction.
     async fn send_raw_transaction(&self, tx: Bytes) -> EthResult<B256>;
 
+    /// Decodes and recovers the transaction and submits it to the pool, if the given conditions
+    /// hold for the next block.
+    ///
+    /// The pool drops the transaction once its conditions can no longer be satisfied.
+    ///
+    /// Returns the hash of the transaction.
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        options: ConditionalOptions,
+    ) -> EthResult<B256>;
+
+    /// Decodes and recovers the transaction and submits it to the pool as a private transaction.
+    ///
+    /// Private transactions are included in blocks built by this node, but are never announced or
+    /// served to peers. The pool drops the transaction once the chain advances past the given
+    /// block number, which defaults to 25 blocks after the latest block.
+    ///
+    /// Returns the hash of the transaction.
+    async fn send_private_raw_transaction(
+        &self,
+        tx: Bytes,
+        max_block_number: Option<BlockNumber>,
+    ) -> EthResult<B256>;
+
     /// Signs transaction with a matching signer, if any and submits the transaction to the pool.
     /// Returns the hash of the signed transaction.
     async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256>;
@@ -862,6 +890,87 @@ where
         Ok(hash)
     }
 
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        options: ConditionalOptions,
+    ) -> EthResult<B256> {
+        // the sequencer endpoint only accepts unconditional raw transactions
+        if self.inner.raw_transaction_forwarder.is_some() {
+            return Err(EthApiError::Unsupported(
+                "conditional transactions can't be forwarded to the sequencer",
+            ))
+        }
+
+        let conditional = transaction_conditional(options)?;
+        let recovered = recover_raw_transaction(tx)?;
+
+        // the conditions must hold for the next block, on top of the latest state
+        let conditional = self
+            .on_blocking_task(|this| async move {
+                let latest =
+                    this.provider().latest_header()?.ok_or(EthApiError::UnknownBlockNumber)?;
+                if conditional.is_expired(latest.number + 1, latest.timestamp + 1) {
+                    return Err(EthApiError::ConditionsNotMet)
+                }
+                let state = this.state_at_hash(latest.hash())?;
+                if !conditional.matches_state(&*state)? {
+                    return Err(EthApiError::ConditionsNotMet)
+                }
+                Ok(conditional)
+            })
+            .await?;
+
+        let pool_transaction = <Pool::Transaction>::from_recovered_pooled_transaction(recovered);
+
+        // submit the transaction to the pool with a `Local` origin
+        let hash = self
+            .pool()
+            .add_conditional_transaction(TransactionOrigin::Local, pool_transaction, conditional)
+            .await?;
+
+        Ok(hash)
+    }
+
+    async fn send_private_raw_transaction(
+        &self,
+        tx: Bytes,
+        max_block_number: Option<BlockNumber>,
+    ) -> EthResult<B256> {
+        // private transactions are only included in blocks built by this node
+        if self.inner.raw_transaction_forwarder.is_some() {
+            return Err(EthApiError::Unsupported(
+                "private transactions can't be forwarded to the sequencer",
+            ))
+        }
+
+        let latest = self.provider().best_block_number()?;
+        let max_block_number =
+            max_block_number.unwrap_or(latest + DEFAULT_PRIVATE_TRANSACTION_EXPIRY);
+        if max_block_number <= latest {
+            return Err(EthApiError::InvalidParams(format!(
+                "maxBlockNumber {max_block_number} is not after the latest block {latest}"
+            )))
+        }
+
+        let recovered = recover_raw_transaction(tx)?;
+        let pool_transaction = <Pool::Transaction>::from_recovered_pooled_transaction(recovered);
+
+        // the transaction is dropped from the pool once the expiry block has been mined
+        let conditional = TransactionConditional {
+            block_number_max: Some(max_block_number),
+            ..Default::default()
+        };
+
+        // submit the transaction to the pool with a `Private` origin
+        let hash = self
+            .pool()
+            .add_conditional_transaction(TransactionOrigin::Private, pool_transaction, conditional)
+            .await?;
+
+        Ok(hash)
+    }
+
     async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<B256> {
         let from = match request.from {
             Some(from) => from,
@@ -38,6 +38,9 @@ pub enum EthApiError {
     /// Errors related to the transaction pool
     #[error(transparent)]
     PoolError(RpcPoolError),
+    /// When the conditions of a conditional transaction don't hold
+    #[error("transaction conditions not met")]
+    ConditionsNotMet,
     /// When an unknown block number is encountered
     #[error("unknown block number")]
     UnknownBlockNumber,
@@ -156,6 +159,9 @@ impl From<EthApiError> for ErrorObject<'static> {
             EthApiError::UnknownSafeOrFinalizedBlock => {
                 rpc_error_with_code(EthRpcErrorCode::UnknownBlock.code(), error.to_string())
             }
+            EthApiError::ConditionsNotMet => {
+                rpc_error_with_code(EthRpcErrorCode::TransactionRejected.code(), error.to_string())
+            }
             EthApiError::Unsupported(msg) => internal_rpc_err(msg),
             EthApiError::InternalJsTracerError(msg) => internal_rpc_err(msg),
             EthApiError::InvalidParams(msg) => invalid_params_rpc_err(msg),
@@ -4,7 +4,7 @@ use crate::{
     eth::logs_utils,
     result::{internal_rpc_err, invalid_params_rpc_err},
 };
-use futures::StreamExt;
+use futures::{future::Either, StreamExt};
 use jsonrpsee::{
     server::SubscriptionMessage, types::ErrorObject, PendingSubscriptionSink, SubscriptionSink,
 };
@@ -17,10 +17,14 @@ use reth_rpc_types::{
         Params, PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
         SyncStatusMetadata,
     },
-    FilteredParams, Header, Log,
+    DropReason, DroppedTransaction, EthSubscriptionParams, FilteredParams, Header, Log,
+    PendingTransactionFilter,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::{NewTransactionEvent, TransactionPool};
+use reth_transaction_pool::{
+    FullTransactionEvent, NewTransactionEvent, PoolTransaction, TransactionEvent,
+    TransactionListenerKind, TransactionPool,
+};
 use serde::Serialize;
 use std::sync::Arc;
 use tokio_stream::{
@@ -82,7 +86,7 @@ where
         &self,
         pending: PendingSubscriptionSink,
         kind: SubscriptionKind,
-        params: Option<Params>,
+        params: Option<EthSubscriptionParams>,
     ) -> jsonrpsee::core::SubscriptionResult {
         let sink = pending.accept().await?;
         let pubsub = self.inner.clone();
@@ -92,6 +96,34 @@ where
 
         Ok(())
     }
+
+    /// Handler for `eth_subscribeDroppedTransactions`
+    async fn subscribe_dropped_transactions(
+        &self,
+        pending: PendingSubscriptionSink,
+        hashes: Option<Vec<TxHash>>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let stream = match hashes {
+            Some(hashes) if !hashes.is_empty() => {
+                // the subscription would end immediately
+                let Some(stream) = self.inner.dropped_transactions_stream_for(hashes) else {
+                    pending
+                        .reject(invalid_params_rpc_err("None of the transactions are in the pool"))
+                        .await;
+                    return Ok(())
+                };
+                Either::Left(stream)
+            }
+            _ => Either::Right(self.inner.dropped_transactions_stream()),
+        };
+
+        let sink = pending.accept().await?;
+        self.subscription_task_spawner.spawn(Box::pin(async move {
+            let _ = pipe_from_stream(sink, stream).await;
+        }));
+
+        Ok(())
+    }
 }
 
 /// The actual handler for an accepted [`EthPubSub::subscribe`] call.
@@ -99,7 +131,7 @@ async fn handle_accepted<Provider, Pool, Events, Network>(
     pubsub: Arc<EthPubSubInner<Provider, Pool, Events, Network>>,
     accepted_sink: SubscriptionSink,
     kind: SubscriptionKind,
-    params: Option<Params>,
+    params: Option<EthSubscriptionParams>,
 ) -> Result<(), ErrorObject<'static>>
 where
     Provider: BlockReader + EvmEnvProvider + Clone + 'static,
@@ -117,8 +149,15 @@ where
         SubscriptionKind::Logs => {
             // if no params are provided, used default filter params
             let filter = match params {
-                Some(Params::Logs(filter)) => FilteredParams::new(Some(*filter)),
-                Some(Params::Bool(_)) => {
+                Some(EthSubscriptionParams::Eth(Params::Logs(filter))) => {
+                    FilteredParams::new(Some(*filter))
+                }
+                Some(EthSubscriptionParams::Eth(Params::Bool(_))) => {
+                    return Err(invalid_params_rpc_err("Invalid params for logs"))
+                }
+                Some(EthSubscriptionParams::PendingTransactions(filter))
+                    if !filter.is_unrestricted() || filter.full_transactions =>
+                {
                     return Err(invalid_params_rpc_err("Invalid params for logs"))
                 }
                 _ => FilteredParams::default(),
@@ -128,33 +167,40 @@ where
             pipe_from_stream(accepted_sink, stream).await
         }
         SubscriptionKind::NewPendingTransactions => {
-            if let Some(params) = params {
-                match params {
-                    Params::Bool(true) => {
-                        // full transaction objects requested
-                        let stream = pubsub.full_pending_transaction_stream().map(|tx| {
-                            EthSubscriptionResult::FullTransaction(Box::new(
-                                reth_rpc_types_compat::transaction::from_recovered(
-                                    tx.transaction.to_recovered_transaction(),
-                                ),
-                            ))
-                        });
-                        return pipe_from_stream(accepted_sink, stream).await
-                    }
-                    Params::Bool(false) | Params::None => {
-                        // only hashes requested
-                    }
-                    Params::Logs(_) => {
-                        return Err(invalid_params_rpc_err(
-                            "Invalid params for newPendingTransactions",
-                        ))
-                    }
+            let filter = match params {
+                Some(EthSubscriptionParams::Eth(Params::Bool(true))) => {
+                    // full transaction objects requested
+                    PendingTransactionFilter { full_transactions: true, ..Default::default() }
+                }
+                Some(EthSubscriptionParams::PendingTransactions(filter)) => filter,
+                Some(EthSubscriptionParams::Eth(Params::Logs(_))) => {
+                    return Err(invalid_params_rpc_err("Invalid params for newPendingTransactions"))
+                }
+                Some(EthSubscriptionParams::Eth(Params::Bool(false) | Params::None)) | None => {
+                    // only hashes requested
+                    PendingTransactionFilter::default()
                 }
+            };
+
+            if filter.is_unrestricted() && !filter.full_transactions {
+                let stream = pubsub
+                    .pending_transaction_hashes_stream()
+                    .map(EthSubscriptionResult::TransactionHash);
+                return pipe_from_stream(accepted_sink, stream).await
             }
 
-            let stream = pubsub
-                .pending_transaction_hashes_stream()
-                .map(EthSubscriptionResult::TransactionHash);
+            let full_transactions = filter.full_transactions;
+            let stream = pubsub.full_pending_transaction_stream(filter).map(move |tx| {
+                if full_transactions {
+                    EthSubscriptionResult::FullTransaction(Box::new(
+                        reth_rpc_types_compat::transaction::from_recovered(
+                            tx.transaction.to_recovered_transaction(),
+                        ),
+                    ))
+                } else {
+                    EthSubscriptionResult::TransactionHash(*tx.transaction.hash())
+                }
+            });
             pipe_from_stream(accepted_sink, stream).await
         }
         SubscriptionKind::Syncing => {
@@ -292,18 +338,126 @@ where
     Pool: TransactionPool + 'static,
 {
     /// Returns a stream that yields all transaction hashes emitted by the txpool.
+    ///
+    /// Only transactions that may be shared with peers are included, private and conditional
+    /// transactions are never revealed.
     fn pending_transaction_hashes_stream(&self) -> impl Stream<Item = TxHash> {
-        ReceiverStream::new(self.pool.pending_transactions_listener())
+        let pool = self.pool.clone();
+        ReceiverStream::new(self.pool.pending_transactions_listener()).filter(move |hash| {
+            // transactions that are no longer in the pool are skipped
+            futures::future::ready(pool.get(hash).map_or(false, |tx| tx.is_shareable()))
+        })
     }
 
-    /// Returns a stream that yields all transactions emitted by the txpool.
+    /// Returns a stream that yields all transactions emitted by the txpool that match the given
+    /// filter.
+    ///
+    /// Only transactions that may be shared with peers are included, private and conditional
+    /// transactions are never revealed.
     fn full_pending_transaction_stream(
         &self,
+        filter: PendingTransactionFilter,
     ) -> impl Stream<Item = NewTransactionEvent<<Pool as TransactionPool>::Transaction>> {
-        self.pool.new_pending_pool_transactions_listener()
+        self.pool.new_pending_pool_transactions_listener().filter(move |event| {
+            let tx = &event.transaction.transaction;
+            futures::future::ready(
+                event.transaction.is_shareable() &&
+                    filter.matches(
+                        tx.sender(),
+                        tx.to(),
+                        tx.input(),
+                        tx.priority_fee_or_price(),
+                        tx.tx_type(),
+                    ),
+            )
+        })
+    }
+
+    /// Returns a stream that yields all transactions that are dropped from the txpool.
+    ///
+    /// Only transactions that are allowed to be propagated are included, private and conditional
+    /// transactions are never revealed.
+    fn dropped_transactions_stream(&self) -> impl Stream<Item = DroppedTransaction> {
+        let events =
+            self.pool.all_transactions_event_listener_for(TransactionListenerKind::PropagateOnly);
+        events.filter_map(|event| {
+            let dropped = match event {
+                FullTransactionEvent::Replaced { transaction, replaced_by } => {
+                    Some(DroppedTransaction {
+                        hash: *transaction.hash(),
+                        reason: DropReason::Replaced,
+                        replaced_by: Some(replaced_by),
+                    })
+                }
+                FullTransactionEvent::Discarded(hash) => {
+                    Some(dropped_transaction(hash, DropReason::Discarded))
+                }
+                FullTransactionEvent::Evicted(hash) => {
+                    Some(dropped_transaction(hash, DropReason::Evicted))
+                }
+                FullTransactionEvent::Invalid(hash) => {
+                    Some(dropped_transaction(hash, DropReason::Invalid))
+                }
+                _ => None,
+            };
+            futures::future::ready(dropped)
+        })
+    }
+
+    /// Returns a stream that yields the given transactions once they're dropped from the txpool.
+    ///
+    /// Transactions that are not in the pool are ignored, `None` is returned if none of them are.
+    fn dropped_transactions_stream_for(
+        &self,
+        hashes: Vec<TxHash>,
+    ) -> Option<impl Stream<Item = DroppedTransaction>> {
+        let streams = hashes.into_iter().filter_map(|hash| {
+            let events = self.pool.transaction_event_listener(hash)?;
+            let pool = self.pool.clone();
+            Some(events.filter_map(move |event| {
+                let dropped = match event {
+                    // don't reveal a replacement that is not allowed to be propagated
+                    TransactionEvent::Replaced(replaced_by) => {
+                        if pool
+                            .get(&replaced_by)
+                            .is_some_and(|tx| tx.propagate && tx.is_shareable())
+                        {
+                            Some(DroppedTransaction {
+                                hash,
+                                reason: DropReason::Replaced,
+                                replaced_by: Some(replaced_by),
+                            })
+                        } else {
+                            Some(dropped_transaction(hash, DropReason::Discarded))
+                        }
+                    }
+                    TransactionEvent::Discarded => {
+                        Some(dropped_transaction(hash, DropReason::Discarded))
+                    }
+                    TransactionEvent::Evicted => {
+                        Some(dropped_transaction(hash, DropReason::Evicted))
+                    }
+                    TransactionEvent::Invalid => {
+                        Some(dropped_transaction(hash, DropReason::Invalid))
+                    }
+                    _ => None,
+                };
+                futures::future::ready(dropped)
+            }))
+        });
+        let streams = streams.collect::<Vec<_>>();
+        if streams.is_empty() {
+            return None
+        }
+        Some(futures::stream::select_all(streams))
     }
 }
 
+/// Creates a [DroppedTransaction] for a transaction that wasn't replaced.
+const fn dropped_transaction(hash: TxHash, reason: DropReason) -> DroppedTransaction {
+    DroppedTransaction { hash, reason, replaced_by: None }
+}
+
 impl<Provider, Pool, Events, Network> EthPubSubInner<Provider, Pool, Events, Network>
 where
     Provider: BlockReader + EvmEnvProvider + 'static,
@@ -339,3 +493,33 @@ where
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionConditional, TransactionOrigin,
+    };
+
+    #[tokio::test]
+    async fn pending_streams_skip_conditional_transactions() {
+        let pool = testing_pool();
+        let pubsub =
+            EthPubSubInner { pool: pool.clone(), provider: (), chain_events: (), network: () };
+        let mut hashes = Box::pin(pubsub.pending_transaction_hashes_stream());
+        let mut full = Box::pin(pubsub.full_pending_transaction_stream(Default::default()));
+
+        let conditional = MockTransaction::eip1559();
+        let conditions =
+            TransactionConditional { block_number_max: Some(100), ..Default::default() };
+        pool.add_conditional_transaction(TransactionOrigin::Local, conditional, conditions)
+            .await
+            .unwrap();
+        let transaction = MockTransaction::eip1559();
+        let hash = pool.add_transaction(TransactionOrigin::Local, transaction).await.unwrap();
+
+        assert_eq!(hashes.next().await, Some(hash));
+        assert_eq!(*full.next().await.unwrap().transaction.hash(), hash);
+    }
+}
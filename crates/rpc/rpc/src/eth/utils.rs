@@ -2,6 +2,16 @@
 
 use crate::eth::error::{EthApiError, EthResult};
 use reth_primitives::{Bytes, PooledTransactionsElement, PooledTransactionsElementEcRecovered};
+use reth_rpc_types::{AccountStorage, ConditionalOptions};
+use reth_transaction_pool::{KnownAccountState, TransactionConditional};
+
+/// The max number of storage roots and slots the `knownAccounts` of a conditional transaction may
+/// contain.
+pub(crate) const MAX_CONDITIONAL_KNOWN_ACCOUNTS_COST: usize = 1000;
+
+/// The number of blocks after the latest block a private transaction is kept in the pool for, if
+/// no expiry is given.
+pub(crate) const DEFAULT_PRIVATE_TRANSACTION_EXPIRY: u64 = 25;
 
 /// Recovers a [PooledTransactionsElementEcRecovered] from an enveloped encoded byte stream.
 ///
@@ -18,3 +28,38 @@ pub(crate) fn recover_raw_transaction(
 
     transaction.try_into_ecrecovered().or(Err(EthApiError::InvalidTransactionSignature))
 }
+
+/// Converts the options of `eth_sendRawTransactionConditional` into the [TransactionConditional]
+/// tracked by the pool.
+///
+/// Returns an error if the `knownAccounts` contain more than
+/// [MAX_CONDITIONAL_KNOWN_ACCOUNTS_COST] storage roots and slots.
+pub(crate) fn transaction_conditional(
+    options: ConditionalOptions,
+) -> EthResult<TransactionConditional> {
+    let conditional = TransactionConditional {
+        known_accounts: options
+            .known_accounts
+            .into_iter()
+            .map(|(address, storage)| {
+                let state = match storage {
+                    AccountStorage::RootHash(root) => KnownAccountState::StorageRoot(root),
+                    AccountStorage::Slots(slots) => KnownAccountState::Slots(slots),
+                };
+                (address, state)
+            })
+            .collect(),
+        block_number_min: options.block_number_min.map(|n| n.to()),
+        block_number_max: options.block_number_max.map(|n| n.to()),
+        timestamp_min: options.timestamp_min.map(|t| t.to()),
+        timestamp_max: options.timestamp_max.map(|t| t.to()),
+    };
+
+    if conditional.cost() > MAX_CONDITIONAL_KNOWN_ACCOUNTS_COST {
+        return Err(EthApiError::InvalidParams(format!(
+            "knownAccounts exceed the limit of {MAX_CONDITIONAL_KNOWN_ACCOUNTS_COST} entries"
+        )))
+    }
+
+    Ok(conditional)
+}
@@ -47,6 +47,6 @@ pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
-pub use txpool::TxPoolApi;
+pub use txpool::{TxPoolAdminApi, TxPoolApi};
 pub use web3::Web3Api;
 pub mod result;
@@ -1,14 +1,21 @@
+use crate::eth::{error::EthApiError, utils::recover_raw_transaction};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult as Result;
-use reth_primitives::Address;
-use reth_rpc_api::TxPoolApiServer;
+use reth_primitives::{
+    Address, Bytes, FromRecoveredPooledTransaction, IntoRecoveredTransaction,
+    PooledTransactionsElementEcRecovered, B256,
+};
+use reth_rpc_api::{TxPoolAdminApiServer, TxPoolApiServer};
 use reth_rpc_types::{
     txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
-    Transaction,
+    Transaction, TxpoolSubpool, TxpoolTransactionState,
+};
+use reth_transaction_pool::{
+    AllPoolTransactions, PoolResult, PoolTransaction, SubPool, TransactionConditional,
+    TransactionOrigin, TransactionPool, ValidPoolTransaction,
 };
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
 use std::collections::BTreeMap;
-use tracing::trace;
+use tracing::{trace, warn};
 
 /// `txpool` API implementation.
 ///
@@ -139,3 +146,149 @@ impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
         f.debug_struct("TxpoolApi").finish_non_exhaustive()
     }
 }
+
+/// `txpoolAdmin` API implementation.
+///
+/// This type provides the functionality for handling `txpoolAdmin` related requests, which modify
+/// the pool and must only be served by the authenticated server.
+#[derive(Clone)]
+pub struct TxPoolAdminApi<Pool> {
+    /// An interface to interact with the pool
+    pool: Pool,
+}
+
+impl<Pool> TxPoolAdminApi<Pool> {
+    /// Creates a new instance of `TxPoolAdminApi`.
+    pub fn new(pool: Pool) -> Self {
+        TxPoolAdminApi { pool }
+    }
+}
+
+impl<Pool> TxPoolAdminApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Returns a copy of the pool transaction that can be added to the pool again, including the
+    /// sidecar of blob transactions.
+    fn readd_copy(
+        &self,
+        transaction: &ValidPoolTransaction<Pool::Transaction>,
+    ) -> Option<Pool::Transaction> {
+        let recovered = transaction.to_recovered_transaction();
+        let pooled = if recovered.is_eip4844() {
+            let sidecar = self.pool.get_blob(*transaction.hash()).ok().flatten()?;
+            PooledTransactionsElementEcRecovered::try_from_blob_transaction(recovered, sidecar)
+                .ok()?
+        } else {
+            PooledTransactionsElementEcRecovered::try_from(recovered).ok()?
+        };
+        Some(Pool::Transaction::from_recovered_pooled_transaction(pooled))
+    }
+
+    /// Adds the transaction to the pool, with its conditions if any.
+    async fn add_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Pool::Transaction,
+        conditional: Option<TransactionConditional>,
+    ) -> PoolResult<B256> {
+        match conditional {
+            Some(conditional) => {
+                self.pool.add_conditional_transaction(origin, transaction, conditional).await
+            }
+            None => self.pool.add_transaction(origin, transaction).await,
+        }
+    }
+}
+
+#[async_trait]
+impl<Pool> TxPoolAdminApiServer for TxPoolAdminApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Handler for `txpoolAdmin_removeTransactions`
+    async fn remove_transactions(&self, hashes: Vec<B256>) -> Result<Vec<B256>> {
+        trace!(target: "rpc::txpool", ?hashes, "Serving txpoolAdmin_removeTransactions");
+        Ok(self.pool.remove_transactions(hashes).iter().map(|tx| *tx.hash()).collect())
+    }
+
+    /// Handler for `txpoolAdmin_removeSenderTransactions`
+    async fn remove_sender_transactions(&self, sender: Address) -> Result<Vec<B256>> {
+        trace!(target: "rpc::txpool", ?sender, "Serving txpoolAdmin_removeSenderTransactions");
+        Ok(self.pool.remove_transactions_by_sender(sender).iter().map(|tx| *tx.hash()).collect())
+    }
+
+    /// Handler for `txpoolAdmin_clearSubpool`
+    async fn clear_subpool(&self, subpool: TxpoolSubpool) -> Result<Vec<B256>> {
+        trace!(target: "rpc::txpool", ?subpool, "Serving txpoolAdmin_clearSubpool");
+        let subpool = match subpool {
+            TxpoolSubpool::Pending => SubPool::Pending,
+            TxpoolSubpool::BaseFee => SubPool::BaseFee,
+            TxpoolSubpool::Blob => SubPool::Blob,
+            TxpoolSubpool::Queued => SubPool::Queued,
+        };
+        Ok(self.pool.remove_transactions_by_subpool(subpool).iter().map(|tx| *tx.hash()).collect())
+    }
+
+    /// Handler for `txpoolAdmin_replaceTransaction`
+    async fn replace_transaction(&self, hash: B256, replacement: Bytes) -> Result<B256> {
+        trace!(target: "rpc::txpool", ?hash, "Serving txpoolAdmin_replaceTransaction");
+        let existing = self.pool.get(&hash).ok_or(EthApiError::TransactionNotFound)?;
+        let recovered = recover_raw_transaction(replacement)?;
+        let replacement = Pool::Transaction::from_recovered_pooled_transaction(recovered);
+        if replacement.sender() != existing.sender() || replacement.nonce() != existing.nonce() {
+            return Err(EthApiError::InvalidParams(
+                "replacement must have the same sender and nonce as the replaced transaction"
+                    .to_string(),
+            )
+            .into())
+        }
+
+        let restore = self
+            .readd_copy(&existing)
+            .ok_or(EthApiError::Unsupported("the replaced transaction can't be restored"))?;
+
+        // the replaced transaction is removed first, so the replacement is not subject to the
+        // price bump of regular replacements
+        let conditional = existing.conditional().cloned();
+        self.pool.remove_transactions(vec![hash]);
+        match self.add_transaction(existing.origin, replacement, conditional.clone()).await {
+            Ok(hash) => Ok(hash),
+            Err(err) => {
+                if let Err(restore_err) =
+                    self.add_transaction(existing.origin, restore, conditional).await
+                {
+                    warn!(target: "rpc::txpool", err = %restore_err, ?hash, "Failed to restore replaced transaction");
+                }
+                Err(EthApiError::from(err).into())
+            }
+        }
+    }
+
+    /// Handler for `txpoolAdmin_transactionState`
+    async fn transaction_state(&self, hash: B256) -> Result<Option<TxpoolTransactionState>> {
+        trace!(target: "rpc::txpool", ?hash, "Serving txpoolAdmin_transactionState");
+        Ok(self.pool.transaction_state(hash).map(|state| TxpoolTransactionState {
+            hash,
+            subpool: match state.subpool {
+                SubPool::Pending => TxpoolSubpool::Pending,
+                SubPool::BaseFee => TxpoolSubpool::BaseFee,
+                SubPool::Blob => TxpoolSubpool::Blob,
+                SubPool::Queued => TxpoolSubpool::Queued,
+            },
+            no_parked_ancestors: state.no_parked_ancestors,
+            no_nonce_gaps: state.no_nonce_gaps,
+            enough_balance: state.enough_balance,
+            not_too_much_gas: state.not_too_much_gas,
+            enough_fee_cap_block: state.enough_fee_cap_block,
+            enough_blob_fee_cap_block: state.enough_blob_fee_cap_block,
+            blob_transaction: state.blob_transaction,
+        }))
+    }
+}
+
+impl<Pool> std::fmt::Debug for TxPoolAdminApi<Pool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TxPoolAdminApi").finish_non_exhaustive()
+    }
+}
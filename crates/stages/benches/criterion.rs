@@ -109,7 +109,7 @@ fn merkle(c: &mut Criterion, runtime: &Runtime) {
 
     let db = setup::txs_testdata(DEFAULT_NUM_BLOCKS);
 
-    let stage = MerkleStage::Both { clean_threshold: u64::MAX };
+    let stage: MerkleStage = MerkleStage::Both { clean_threshold: u64::MAX, parallel: None };
     measure_stage(
         runtime,
         &mut group,
@@ -120,7 +120,7 @@ fn merkle(c: &mut Criterion, runtime: &Runtime) {
         "Merkle-incremental".to_string(),
     );
 
-    let stage = MerkleStage::Both { clean_threshold: 0 };
+    let stage: MerkleStage = MerkleStage::Both { clean_threshold: 0, parallel: None };
     measure_stage(
         runtime,
         &mut group,
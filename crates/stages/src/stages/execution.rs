@@ -8,13 +8,13 @@ use reth_primitives::{
     stage::{
         CheckpointBlockRange, EntitiesCheckpoint, ExecutionCheckpoint, StageCheckpoint, StageId,
     },
-    BlockNumber, Header, PruneModes, StaticFileSegment,
+    BlockNumber, Header, PruneModes, PruneSegment, StaticFileSegment,
 };
 use reth_provider::{
     providers::{StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
     BlockReader, CanonStateNotification, Chain, DatabaseProviderRW, ExecutorFactory,
-    HeaderProvider, LatestStateProviderRef, OriginalValuesKnown, ProviderError, StatsReader,
-    TransactionVariant,
+    HeaderProvider, LatestStateProviderRef, OriginalValuesKnown, ProviderError,
+    PruneCheckpointReader, StatsReader, TransactionVariant,
 };
 use reth_stages_api::{
     BlockErrorKind, ExecInput, ExecOutput, MetricEvent, MetricEventsSender, Stage, StageError,
@@ -552,10 +552,17 @@ where
 
     // Get next expected receipt number in static files
     let static_file_provider = provider.static_file_provider();
-    let next_static_file_receipt_num = static_file_provider
-        .get_highest_static_file_tx(StaticFileSegment::Receipts)
-        .map(|num| num + 1)
-        .unwrap_or(0);
+    let next_static_file_receipt_num =
+        match static_file_provider.get_highest_static_file_tx(StaticFileSegment::Receipts) {
+            Some(num) => num + 1,
+            // The receipts below a snap sync pivot were never produced, the receipts prune
+            // checkpoint marks them as pruned.
+            None => provider
+                .get_prune_checkpoint(PruneSegment::Receipts)?
+                .and_then(|checkpoint| checkpoint.tx_number)
+                .map(|num| num + 1)
+                .unwrap_or(0),
+        };
 
     let mut static_file_producer =
         static_file_provider.get_writer(start_block, StaticFileSegment::Receipts)?;
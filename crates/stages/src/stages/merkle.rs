@@ -1,4 +1,5 @@
 use reth_codecs::Compact;
+use reth_config::config::MerkleConfig;
 use reth_db::{
     database::Database,
     tables,
@@ -11,13 +12,14 @@ use reth_primitives::{
     BlockNumber, GotExpected, SealedHeader, B256,
 };
 use reth_provider::{
-    DatabaseProviderRW, HeaderProvider, ProviderError, StageCheckpointReader,
-    StageCheckpointWriter, StatsReader,
+    DatabaseProviderFactory, DatabaseProviderRO, DatabaseProviderRW, HeaderProvider, ProviderError,
+    ProviderResult, StageCheckpointReader, StageCheckpointWriter, StatsReader,
 };
 use reth_stages_api::{
     BlockErrorKind, ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput,
 };
 use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
+use reth_trie_parallel::tx_root::{TxParallelStateRoot, DEFAULT_STORAGE_ROOT_BATCH_SIZE};
 use std::fmt::Debug;
 use tracing::*;
 
@@ -25,6 +27,9 @@ use tracing::*;
 /// of changes to whole rebuild.
 pub const MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD: u64 = 5_000;
 
+/// The default number of accounts for which storage roots are computed in parallel.
+pub const MERKLE_STAGE_DEFAULT_PARALLEL_BATCH_SIZE: usize = DEFAULT_STORAGE_ROOT_BATCH_SIZE;
+
 /// The merkle hashing stage uses input from
 /// [`AccountHashingStage`][crate::stages::AccountHashingStage] and
 /// [`StorageHashingStage`][crate::stages::AccountHashingStage] to calculate intermediate hashes
@@ -46,13 +51,21 @@ pub const MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD: u64 = 5_000;
 /// - [`AccountHashingStage`][crate::stages::AccountHashingStage]
 /// - [`StorageHashingStage`][crate::stages::StorageHashingStage]
 /// - [`MerkleStage::Execution`]
+///
+/// During execution, storage roots can be computed in parallel in batches of accounts on
+/// read-only transactions of a provider factory, see [`TxParallelStateRoot`]. Intermediate
+/// progress of full rebuilds is then saved after a batch. The read-only transactions only see
+/// committed state, so the stage falls back to computing the state root serially if the hashed
+/// state or the tries have uncommitted changes.
 #[derive(Debug, Clone)]
-pub enum MerkleStage {
+pub enum MerkleStage<Provider = NoParallelProvider> {
     /// The execution portion of the merkle stage.
     Execution {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// The parallel storage root computation, or `None` to compute the state root serially.
+        parallel: Option<MerkleParallelism<Provider>>,
     },
     /// The unwind portion of the merkle stage.
     Unwind,
@@ -62,13 +75,36 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// The parallel storage root computation, or `None` to compute the state root serially.
+        parallel: Option<MerkleParallelism<Provider>>,
     },
 }
 
+/// Parallel storage root computation of the [`MerkleStage`].
+#[derive(Debug, Clone)]
+pub struct MerkleParallelism<Provider> {
+    /// The factory of the read-only transactions storage roots are computed on.
+    pub provider: Provider,
+    /// The number of accounts for which storage roots are computed in parallel at once.
+    pub batch_size: usize,
+}
+
+/// Provider factory of a [`MerkleStage`] that always computes the state root serially.
+///
+/// The type has no values, so the stage can never be configured to run in parallel with it.
+#[derive(Debug, Clone, Copy)]
+pub enum NoParallelProvider {}
+
+impl<DB: Database> DatabaseProviderFactory<DB> for NoParallelProvider {
+    fn database_provider_ro(&self) -> ProviderResult<DatabaseProviderRO<DB>> {
+        match *self {}
+    }
+}
+
 impl MerkleStage {
     /// Stage default for the [MerkleStage::Execution].
     pub fn default_execution() -> Self {
-        Self::Execution { clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD }
+        Self::new_execution(MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD)
     }
 
     /// Stage default for the [MerkleStage::Unwind].
@@ -76,9 +112,24 @@ impl MerkleStage {
         Self::Unwind
     }
 
-    /// Create new instance of [MerkleStage::Execution].
+    /// Create new instance of [MerkleStage::Execution] that computes the state root serially.
     pub fn new_execution(clean_threshold: u64) -> Self {
-        Self::Execution { clean_threshold }
+        Self::Execution { clean_threshold, parallel: None }
+    }
+}
+
+impl<Provider> MerkleStage<Provider> {
+    /// Create new instance of [MerkleStage::Execution] from the stage configuration.
+    ///
+    /// If enabled in the configuration, storage roots are computed in parallel on read-only
+    /// transactions of the given provider factory.
+    pub fn from_config(config: MerkleConfig, provider: Provider) -> Self {
+        Self::Execution {
+            clean_threshold: config.clean_threshold,
+            parallel: config
+                .parallel
+                .then_some(MerkleParallelism { provider, batch_size: config.parallel_batch_size }),
+        }
     }
 
     /// Gets the hashing progress
@@ -99,7 +150,7 @@ impl MerkleStage {
 
     /// Saves the hashing progress
     pub fn save_execution_checkpoint<DB: Database>(
-        &mut self,
+        &self,
         provider: &DatabaseProviderRW<DB>,
         checkpoint: Option<MerkleCheckpoint>,
     ) -> Result<(), StageError> {
@@ -116,7 +167,11 @@ impl MerkleStage {
     }
 }
 
-impl<DB: Database> Stage<DB> for MerkleStage {
+impl<DB, Provider> Stage<DB> for MerkleStage<Provider>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync,
+{
     /// Return the id of the stage
     fn id(&self) -> StageId {
         match self {
@@ -133,14 +188,31 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         provider: &DatabaseProviderRW<DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        let threshold = match self {
+        let (threshold, parallel) = match self {
             MerkleStage::Unwind => {
                 info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
                 return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
             }
-            MerkleStage::Execution { clean_threshold } => *clean_threshold,
+            MerkleStage::Execution { clean_threshold, parallel } => {
+                (*clean_threshold, parallel.as_ref())
+            }
             #[cfg(any(test, feature = "test-utils"))]
-            MerkleStage::Both { clean_threshold } => *clean_threshold,
+            MerkleStage::Both { clean_threshold, parallel } => {
+                (*clean_threshold, parallel.as_ref())
+            }
+        };
+
+        // storage roots are computed on read-only transactions, which only see committed state
+        let parallel = match parallel {
+            Some(parallel) if is_committed(provider, &parallel.provider)? => Some(parallel),
+            Some(_) => {
+                debug!(
+                    target: "sync::stages::merkle::exec",
+                    "Hashed state or tries have uncommitted changes, computing state root serially"
+                );
+                None
+            }
+            None => None,
         };
 
         let range = input.next_block_range();
@@ -193,10 +265,20 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             });
 
             let tx = provider.tx_ref();
-            let progress = StateRoot::from_tx(tx)
-                .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
-                .root_with_progress()
-                .map_err(|e| StageError::Fatal(Box::new(e)))?;
+            let intermediate_state = checkpoint.map(IntermediateStateRootState::from);
+            let progress = match parallel {
+                // the cleared storage tries may not be committed yet
+                Some(parallel) => TxParallelStateRoot::from_tx(tx, &parallel.provider)
+                    .with_batch_size(parallel.batch_size)
+                    .with_rebuilt_storage_tries()
+                    .with_intermediate_state(intermediate_state)
+                    .root_with_progress()
+                    .map_err(|e| StageError::Fatal(Box::new(e)))?,
+                None => StateRoot::from_tx(tx)
+                    .with_intermediate_state(intermediate_state)
+                    .root_with_progress()
+                    .map_err(|e| StageError::Fatal(Box::new(e)))?,
+            };
             match progress {
                 StateRootProgress::Progress(state, hashed_entries_walked, updates) => {
                     updates.flush(tx)?;
@@ -228,9 +310,18 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             }
         } else {
             debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
-            let (root, updates) =
-                StateRoot::incremental_root_with_updates(provider.tx_ref(), range)
-                    .map_err(|e| StageError::Fatal(Box::new(e)))?;
+            let tx = provider.tx_ref();
+            let (root, updates) = match parallel {
+                Some(parallel) => {
+                    TxParallelStateRoot::incremental_root_calculator(tx, &parallel.provider, range)
+                        .and_then(|calculator| {
+                            calculator.with_batch_size(parallel.batch_size).root_with_updates()
+                        })
+                        .map_err(|e| StageError::Fatal(Box::new(e)))?
+                }
+                None => StateRoot::incremental_root_with_updates(tx, range)
+                    .map_err(|e| StageError::Fatal(Box::new(e)))?,
+            };
             updates.flush(provider.tx_ref())?;
 
             let total_hashed_entries = (provider.count_entries::<tables::HashedAccounts>()? +
@@ -315,6 +406,33 @@ impl<DB: Database> Stage<DB> for MerkleStage {
     }
 }
 
+/// Returns `true` if read-only transactions of the provider factory see the same hashed state and
+/// tries as the given provider, judging by the checkpoints of the stages that write them.
+fn is_committed<DB, Provider>(
+    provider: &DatabaseProviderRW<DB>,
+    factory: &Provider,
+) -> Result<bool, StageError>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB>,
+{
+    let provider_ro = factory.database_provider_ro()?;
+    for stage_id in [
+        StageId::AccountHashing,
+        StageId::StorageHashing,
+        StageId::MerkleUnwind,
+        StageId::MerkleExecute,
+    ] {
+        if provider.get_stage_checkpoint(stage_id)? != provider_ro.get_stage_checkpoint(stage_id)? ||
+            provider.get_stage_checkpoint_progress(stage_id)? !=
+                provider_ro.get_stage_checkpoint_progress(stage_id)?
+        {
+            return Ok(false)
+        }
+    }
+    Ok(true)
+}
+
 /// Check that the computed state root matches the root in the expected header.
 #[inline]
 fn validate_state_root(
@@ -343,7 +461,11 @@ mod tests {
         TestRunnerError, TestStageDB, UnwindStageTestRunner,
     };
     use assert_matches::assert_matches;
-    use reth_db::cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO};
+    use reth_db::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+        test_utils::TempDatabase,
+        DatabaseEnv,
+    };
     use reth_interfaces::test_utils::{
         generators,
         generators::{
@@ -353,9 +475,11 @@ mod tests {
     use reth_primitives::{
         keccak256, stage::StageUnitCheckpoint, SealedBlock, StaticFileSegment, StorageEntry, U256,
     };
-    use reth_provider::providers::StaticFileWriter;
+    use reth_provider::{
+        providers::StaticFileWriter, test_utils::create_test_provider_factory, ProviderFactory,
+    };
     use reth_trie::test_utils::{state_root, state_root_prehashed};
-    use std::collections::BTreeMap;
+    use std::{collections::BTreeMap, sync::Arc};
 
     stage_test_suite_ext!(MerkleTestRunner, merkle);
 
@@ -440,26 +564,86 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    /// Merkelize whole state and update small trie with parallel storage roots
+    #[tokio::test]
+    async fn execute_merkle_parallel() {
+        for (previous_stage, stage_progress) in [(500, 0), (2, 1)] {
+            // Set up the runner
+            let mut runner =
+                MerkleTestRunner { parallel_batch_size: Some(3), ..Default::default() };
+            let input = ExecInput {
+                target: Some(previous_stage),
+                checkpoint: Some(StageCheckpoint::new(stage_progress)),
+            };
+
+            runner.seed_execution(input).expect("failed to seed execution");
+
+            let rx = runner.execute(input);
+
+            // Assert the successful result
+            let result = rx.await.unwrap();
+            assert_matches!(
+                result,
+                Ok(ExecOutput { checkpoint: StageCheckpoint { block_number, .. }, done: true })
+                    if block_number == previous_stage
+            );
+
+            // Validate the stage execution
+            assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
+        }
+    }
+
+    #[test]
+    fn parallel_execution_config() {
+        let factory = create_test_provider_factory();
+
+        assert_matches!(
+            MerkleStage::default_execution(),
+            MerkleStage::Execution { parallel: None, .. }
+        );
+        assert_matches!(
+            MerkleStage::from_config(
+                MerkleConfig { parallel: false, ..Default::default() },
+                factory.clone()
+            ),
+            MerkleStage::Execution { parallel: None, .. }
+        );
+        assert_matches!(
+            MerkleStage::from_config(
+                MerkleConfig { parallel: true, parallel_batch_size: 10, ..Default::default() },
+                factory
+            ),
+            MerkleStage::Execution { parallel: Some(MerkleParallelism { batch_size: 10, .. }), .. }
+        );
+    }
+
     struct MerkleTestRunner {
         db: TestStageDB,
         clean_threshold: u64,
+        parallel_batch_size: Option<usize>,
     }
 
     impl Default for MerkleTestRunner {
         fn default() -> Self {
-            Self { db: TestStageDB::default(), clean_threshold: 10000 }
+            Self { db: TestStageDB::default(), clean_threshold: 10000, parallel_batch_size: None }
         }
     }
 
     impl StageTestRunner for MerkleTestRunner {
-        type S = MerkleStage;
+        type S = MerkleStage<ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>>;
 
         fn db(&self) -> &TestStageDB {
             &self.db
         }
 
         fn stage(&self) -> Self::S {
-            Self::S::Both { clean_threshold: self.clean_threshold }
+            Self::S::Both {
+                clean_threshold: self.clean_threshold,
+                parallel: self.parallel_batch_size.map(|batch_size| MerkleParallelism {
+                    provider: self.db.factory.clone(),
+                    batch_size,
+                }),
+            }
         }
     }
 
@@ -0,0 +1,426 @@
+//! Downloads of state ranges and trie nodes from `snap` peers.
+
+use super::{heal::encode_compact_path, proof::verify_range_proof};
+use alloy_rlp::Decodable;
+use reth_interfaces::p2p::{
+    error::RequestError,
+    snap::client::{
+        GetAccountRange, GetByteCodes, GetStorageRanges, GetTrieNodes, SnapClient, SnapFut,
+        TrieNodePathSet,
+    },
+};
+use reth_primitives::{
+    keccak256,
+    trie::{Nibbles, TrieAccount},
+    Account, Bytecode, Bytes, StorageEntry, WithPeerId, B256, EMPTY_ROOT_HASH, KECCAK_EMPTY, U256,
+};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    time::Duration,
+};
+use tracing::*;
+
+/// The soft limit of the size of responses requested from peers.
+const RESPONSE_BYTES: u64 = 512 * 1024;
+
+/// The maximum number of accounts whose storage is requested at once.
+const MAX_STORAGE_ACCOUNTS: usize = 128;
+
+/// The maximum number of bytecodes requested at once.
+const MAX_BYTECODES: usize = 64;
+
+/// The maximum number of trie nodes requested at once.
+pub(super) const MAX_TRIE_NODES: usize = 128;
+
+/// The number of consecutive empty responses after which peers are considered to no longer serve
+/// the state root of the pivot.
+const MAX_EMPTY_RESPONSES: usize = 16;
+
+/// The time to wait before retrying a request if no `snap` peer is connected.
+const NO_PEERS_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The result of a download.
+#[derive(Debug)]
+pub(super) enum SnapDownload {
+    /// Accounts of a range of the state trie.
+    Accounts(AccountBatch),
+    /// Trie nodes of the state trie with their paths.
+    TrieNodes(Vec<(Nibbles, Bytes)>),
+    /// Peers no longer serve the state root of the pivot.
+    Stale,
+}
+
+/// The accounts of a range of the state trie, with their storage and bytecodes.
+#[derive(Debug)]
+pub(super) struct AccountBatch {
+    /// The first hash of the range.
+    pub(super) start: B256,
+    /// The last hash of the range covered by the batch.
+    pub(super) end: B256,
+    /// Whether the batch covers the remainder of the range.
+    pub(super) complete: bool,
+    /// The accounts, by hashed address.
+    pub(super) accounts: Vec<(B256, Account)>,
+    /// The storage slots of the accounts, by hashed address.
+    pub(super) storages: Vec<(B256, Vec<StorageEntry>)>,
+    /// The bytecodes of the accounts, by code hash.
+    pub(super) bytecodes: Vec<(B256, Bytecode)>,
+}
+
+/// The verdict on a response of a peer.
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict<T = ()> {
+    /// The response can be processed, with the result of its verification.
+    Valid(T),
+    /// The peer does not serve the requested state.
+    Empty,
+    /// The response violates the protocol.
+    Invalid,
+}
+
+/// Downloads the accounts in the range `start..=limit` of the state trie with the given root,
+/// up to the response limit of a single request, and their storage and bytecodes.
+pub(super) async fn download_accounts<C: SnapClient>(
+    client: C,
+    root: B256,
+    start: B256,
+    limit: B256,
+) -> SnapDownload {
+    let request = GetAccountRange {
+        request_id: 0,
+        root_hash: root,
+        starting_hash: start,
+        limit_hash: limit,
+        response_bytes: RESPONSE_BYTES,
+    };
+    let response = request_with_retry(
+        &client,
+        || client.get_account_range(request.clone()),
+        |response| {
+            if response.accounts.is_empty() && response.proof.is_empty() {
+                return Verdict::Empty
+            }
+
+            let leaves = response
+                .accounts
+                .iter()
+                .map(|account| {
+                    let body = account.body;
+                    let bytecode_hash = (body.code_hash != KECCAK_EMPTY).then_some(body.code_hash);
+                    let trie_account = TrieAccount::from((
+                        Account { nonce: body.nonce, balance: body.balance, bytecode_hash },
+                        body.storage_root,
+                    ));
+                    (account.hash, alloy_rlp::encode(trie_account))
+                })
+                .collect::<Vec<_>>();
+            // an empty range with a proof proves that there are no more accounts
+            match verify_range_proof(root, start, &leaves, &response.proof) {
+                Ok(more) => Verdict::Valid(more),
+                Err(err) => {
+                    debug!(target: "sync::stages::snap_sync", %err, "Invalid account range proof");
+                    Verdict::Invalid
+                }
+            }
+        },
+    )
+    .await;
+    let Some((response, more)) = response else { return SnapDownload::Stale };
+    let mut accounts = response.into_data().accounts;
+
+    // peers may serve one account past the limit to prove the end of the range
+    let end = accounts.last().map(|account| account.hash).filter(|hash| more && *hash < limit);
+    accounts.retain(|account| account.hash <= limit);
+
+    let storage_accounts = accounts
+        .iter()
+        .filter(|account| account.body.storage_root != EMPTY_ROOT_HASH)
+        .map(|account| (account.hash, account.body.storage_root))
+        .collect();
+    let Some(storages) = download_storages(&client, root, storage_accounts).await else {
+        return SnapDownload::Stale
+    };
+
+    let code_hashes = accounts
+        .iter()
+        .map(|account| account.body.code_hash)
+        .filter(|hash| *hash != KECCAK_EMPTY)
+        .collect();
+    let Some(bytecodes) = download_bytecodes(&client, code_hashes).await else {
+        return SnapDownload::Stale
+    };
+
+    let accounts = accounts
+        .into_iter()
+        .map(|account| {
+            let body = account.body;
+            let bytecode_hash = (body.code_hash != KECCAK_EMPTY).then_some(body.code_hash);
+            (account.hash, Account { nonce: body.nonce, balance: body.balance, bytecode_hash })
+        })
+        .collect();
+
+    SnapDownload::Accounts(AccountBatch {
+        start,
+        end: end.unwrap_or(limit),
+        complete: end.is_none(),
+        accounts,
+        storages,
+        bytecodes,
+    })
+}
+
+/// Downloads the storage of the given accounts of the state trie with the given root, and
+/// verifies it against the storage roots of the accounts.
+///
+/// Returns `None` if peers no longer serve the state root.
+async fn download_storages<C: SnapClient>(
+    client: &C,
+    root: B256,
+    mut accounts: VecDeque<(B256, B256)>,
+) -> Option<Vec<(B256, Vec<StorageEntry>)>> {
+    let mut storages = Vec::with_capacity(accounts.len());
+    // the slots of the first account and the hash to continue from, if its storage did not fit
+    // into a single response
+    let mut partial: Option<(Vec<StorageEntry>, B256)> = None;
+
+    while let Some(first) = accounts.front() {
+        let (requested, origin) = match &partial {
+            Some((_, origin)) => (vec![*first], *origin),
+            None => (accounts.iter().take(MAX_STORAGE_ACCOUNTS).copied().collect(), B256::ZERO),
+        };
+        let account_hashes = requested.iter().map(|(hash, _)| *hash).collect::<Vec<_>>();
+        let request = GetStorageRanges {
+            request_id: 0,
+            root_hash: root,
+            account_hashes: account_hashes.clone(),
+            starting_hash: match &partial {
+                Some(_) => Bytes::copy_from_slice(origin.as_slice()),
+                None => Bytes::new(),
+            },
+            limit_hash: Bytes::new(),
+            response_bytes: RESPONSE_BYTES,
+        };
+        let (response, more) = request_with_retry(
+            client,
+            || client.get_storage_ranges(request.clone()),
+            |response| {
+                if response.slots.len() > requested.len() {
+                    return Verdict::Invalid
+                }
+                if response.slots.is_empty() {
+                    return Verdict::Empty
+                }
+
+                // only the range of the last served account may be incomplete, which is proven by
+                // the attached proof, the ranges of all other accounts must be complete
+                let last = response.slots.len() - 1;
+                let mut more = false;
+                for (idx, (slots, (_, storage_root))) in
+                    response.slots.iter().zip(&requested).enumerate()
+                {
+                    if !slots.iter().all(|slot| decode_slot(&slot.data).is_some()) {
+                        return Verdict::Invalid
+                    }
+                    let leaves =
+                        slots.iter().map(|slot| (slot.hash, &slot.data)).collect::<Vec<_>>();
+                    let origin = if idx == 0 { origin } else { B256::ZERO };
+                    let proof = if idx == last { &response.proof[..] } else { &[][..] };
+                    match verify_range_proof(*storage_root, origin, &leaves, proof) {
+                        Ok(has_more) => more = has_more,
+                        Err(err) => {
+                            debug!(target: "sync::stages::snap_sync", %err, "Invalid storage range proof");
+                            return Verdict::Invalid
+                        }
+                    }
+                }
+                Verdict::Valid(more)
+            },
+        )
+        .await?;
+        let response = response.into_data();
+
+        let served = response.slots.len();
+        for (idx, slots) in response.slots.into_iter().enumerate() {
+            let mut entries = partial.take().map(|(entries, _)| entries).unwrap_or_default();
+            let origin = slots.last().and_then(|slot| next_hash(slot.hash));
+            entries.extend(slots.into_iter().filter_map(|slot| {
+                let value = decode_slot(&slot.data)?;
+                (value != U256::ZERO).then_some(StorageEntry { key: slot.hash, value })
+            }));
+
+            match origin.filter(|_| more && idx == served - 1) {
+                Some(origin) => partial = Some((entries, origin)),
+                None => {
+                    storages.push((account_hashes[idx], entries));
+                    accounts.pop_front();
+                }
+            }
+        }
+    }
+
+    Some(storages)
+}
+
+/// Downloads the bytecodes with the given hashes.
+///
+/// Returns `None` if peers no longer serve the bytecodes.
+async fn download_bytecodes<C: SnapClient>(
+    client: &C,
+    hashes: BTreeSet<B256>,
+) -> Option<Vec<(B256, Bytecode)>> {
+    let mut bytecodes = Vec::with_capacity(hashes.len());
+    let mut pending = hashes;
+
+    while !pending.is_empty() {
+        let hashes = pending.iter().take(MAX_BYTECODES).copied().collect::<Vec<_>>();
+        let request =
+            GetByteCodes { request_id: 0, hashes: hashes.clone(), response_bytes: RESPONSE_BYTES };
+        let response = request_with_retry(
+            client,
+            || client.get_byte_codes(request.clone()),
+            |response| {
+                if response.codes.len() > hashes.len() ||
+                    !response.codes.iter().all(|code| hashes.contains(&keccak256(code)))
+                {
+                    Verdict::Invalid
+                } else if response.codes.is_empty() {
+                    Verdict::Empty
+                } else {
+                    Verdict::Valid(())
+                }
+            },
+        )
+        .await?;
+
+        for code in response.0.into_data().codes {
+            let hash = keccak256(&code);
+            if pending.remove(&hash) {
+                bytecodes.push((hash, Bytecode::new_raw(code)));
+            }
+        }
+    }
+
+    Some(bytecodes)
+}
+
+/// Downloads the trie nodes of the state trie with the given root at the given paths, and
+/// verifies them against their expected hashes.
+pub(super) async fn download_trie_nodes<C: SnapClient>(
+    client: C,
+    root: B256,
+    paths: Vec<(Nibbles, B256)>,
+) -> SnapDownload {
+    let mut nodes = Vec::with_capacity(paths.len());
+    let mut pending = VecDeque::from(paths);
+
+    while !pending.is_empty() {
+        let expected =
+            pending.iter().take(MAX_TRIE_NODES).map(|(_, hash)| *hash).collect::<Vec<_>>();
+        let request = GetTrieNodes {
+            request_id: 0,
+            root_hash: root,
+            paths: pending
+                .iter()
+                .take(MAX_TRIE_NODES)
+                .map(|(path, _)| TrieNodePathSet(vec![encode_compact_path(path)]))
+                .collect(),
+            response_bytes: RESPONSE_BYTES,
+        };
+        let response = request_with_retry(
+            &client,
+            || client.get_trie_nodes(request.clone()),
+            |response| {
+                if response.nodes.len() > expected.len() ||
+                    !response
+                        .nodes
+                        .iter()
+                        .zip(&expected)
+                        .all(|(node, hash)| keccak256(node) == *hash)
+                {
+                    Verdict::Invalid
+                } else if response.nodes.is_empty() {
+                    Verdict::Empty
+                } else {
+                    Verdict::Valid(())
+                }
+            },
+        )
+        .await;
+        let Some((response, _)) = response else { return SnapDownload::Stale };
+
+        for node in response.into_data().nodes {
+            let (path, _) = pending.pop_front().expect("verified against pending paths");
+            nodes.push((path, node));
+        }
+    }
+
+    SnapDownload::TrieNodes(nodes)
+}
+
+/// Sends the request until a peer responds with a valid response.
+///
+/// Peers that respond with invalid responses are reported and the request is sent again. Returns
+/// the response with the result of its verification, or `None` if peers repeatedly respond that
+/// they do not serve the requested state.
+async fn request_with_retry<C, T, V>(
+    client: &C,
+    send: impl Fn() -> SnapFut<T>,
+    verdict: impl Fn(&T) -> Verdict<V>,
+) -> Option<(WithPeerId<T>, V)>
+where
+    C: SnapClient,
+{
+    let mut empty_responses = 0;
+    loop {
+        match send().await {
+            Ok(response) => match verdict(response.data()) {
+                Verdict::Valid(verified) => return Some((response, verified)),
+                Verdict::Empty => {
+                    trace!(target: "sync::stages::snap_sync", peer_id = ?response.peer_id(), "Peer does not serve the requested state");
+                    empty_responses += 1;
+                    if empty_responses >= MAX_EMPTY_RESPONSES {
+                        return None
+                    }
+                }
+                Verdict::Invalid => {
+                    debug!(target: "sync::stages::snap_sync", peer_id = ?response.peer_id(), "Invalid response");
+                    client.report_bad_message(response.peer_id());
+                }
+            },
+            Err(RequestError::UnsupportedCapability) => {
+                trace!(target: "sync::stages::snap_sync", "No snap peers connected");
+                tokio::time::sleep(NO_PEERS_BACKOFF).await;
+            }
+            Err(err) => {
+                debug!(target: "sync::stages::snap_sync", %err, "Request failed");
+            }
+        }
+    }
+}
+
+/// Decodes the RLP encoded value of a storage slot.
+fn decode_slot(data: &[u8]) -> Option<U256> {
+    U256::decode(&mut &data[..]).ok()
+}
+
+/// Returns the hash following the given hash, if any.
+pub(super) fn next_hash(hash: B256) -> Option<B256> {
+    U256::from_be_bytes(hash.0)
+        .checked_add(U256::from(1))
+        .map(|next| B256::from(next.to_be_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_hashes() {
+        assert_eq!(next_hash(B256::ZERO), Some(B256::with_last_byte(1)));
+        assert_eq!(
+            next_hash(B256::with_last_byte(0xff)),
+            Some(B256::from(U256::from(0x100).to_be_bytes()))
+        );
+        assert_eq!(next_hash(B256::repeat_byte(0xff)), None);
+    }
+}
@@ -0,0 +1,140 @@
+//! Helpers to compare the local state trie with the state trie of peers.
+
+use alloy_rlp::{Header, EMPTY_STRING_CODE};
+use reth_primitives::{trie::Nibbles, Bytes, B256};
+
+/// The references to the children of a branch node.
+pub(super) type BranchChildren<'a> = [&'a [u8]; 16];
+
+/// Returns the RLP encoded references to the children of the node, if it is a branch node.
+///
+/// A reference is either the hash of the child, an inlined child node or the empty string if
+/// there is no child.
+pub(super) fn branch_children(node: &[u8]) -> Option<BranchChildren<'_>> {
+    let mut buf = node;
+    let header = Header::decode(&mut buf).ok()?;
+    if !header.list || buf.len() < header.payload_length {
+        return None
+    }
+    let mut payload = &buf[..header.payload_length];
+
+    let mut children = [&[][..]; 16];
+    for child in &mut children {
+        let (item, rest) = split_item(payload)?;
+        *child = item;
+        payload = rest;
+    }
+
+    // the value of the branch is the last item, leaves and extensions only have two items
+    let (_, rest) = split_item(payload)?;
+    rest.is_empty().then_some(children)
+}
+
+/// Splits the first RLP item off the buffer.
+pub(super) fn split_item(buf: &[u8]) -> Option<(&[u8], &[u8])> {
+    let mut rest = buf;
+    let header = Header::decode(&mut rest).ok()?;
+    if rest.len() < header.payload_length {
+        return None
+    }
+    let len = buf.len() - rest.len() + header.payload_length;
+    Some(buf.split_at(len))
+}
+
+/// Returns true if the reference does not point to a child.
+pub(super) fn is_empty_reference(reference: &[u8]) -> bool {
+    matches!(reference, [EMPTY_STRING_CODE])
+}
+
+/// Returns the hash of the referenced child, if it is not inlined.
+pub(super) fn reference_hash(reference: &[u8]) -> Option<B256> {
+    match reference {
+        [0xa0, hash @ ..] if hash.len() == B256::len_bytes() => Some(B256::from_slice(hash)),
+        _ => None,
+    }
+}
+
+/// Returns the path of the child of the node at the given path.
+pub(super) fn child_path(path: &Nibbles, nibble: u8) -> Nibbles {
+    let mut child = path.clone();
+    child.push(nibble);
+    child
+}
+
+/// Encodes the trie path in compact (hex-prefix) form, as expected by `GetTrieNodes`.
+pub(super) fn encode_compact_path(path: &[u8]) -> Bytes {
+    let (flags, nibbles) = match path.split_first() {
+        Some((first, rest)) if path.len() % 2 == 1 => (0x10 | first, rest),
+        _ => (0x00, path),
+    };
+
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    encoded.push(flags);
+    encoded.extend(nibbles.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+    encoded.into()
+}
+
+/// Returns the first and the last hash with the given path as prefix.
+pub(super) fn prefix_range(path: &[u8]) -> (B256, B256) {
+    let mut first = B256::ZERO;
+    let mut last = B256::repeat_byte(0xff);
+    for (idx, nibble) in path.iter().enumerate() {
+        if idx % 2 == 0 {
+            first[idx / 2] = nibble << 4;
+            last[idx / 2] = nibble << 4 | 0x0f;
+        } else {
+            first[idx / 2] |= nibble;
+            last[idx / 2] = (last[idx / 2] & 0xf0) | nibble;
+        }
+    }
+    (first, last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::Encodable;
+    use reth_primitives::{b256, keccak256};
+
+    #[test]
+    fn compact_paths() {
+        assert_eq!(encode_compact_path(&[]), Bytes::from_static(&[0x00]));
+        assert_eq!(encode_compact_path(&[0x1]), Bytes::from_static(&[0x11]));
+        assert_eq!(encode_compact_path(&[0x1, 0x2]), Bytes::from_static(&[0x00, 0x12]));
+        assert_eq!(encode_compact_path(&[0xa, 0xb, 0xc]), Bytes::from_static(&[0x1a, 0xbc]));
+    }
+
+    #[test]
+    fn prefix_ranges() {
+        assert_eq!(prefix_range(&[]), (B256::ZERO, B256::repeat_byte(0xff)));
+        assert_eq!(
+            prefix_range(&[0xa, 0xb, 0xc]),
+            (
+                b256!("abc0000000000000000000000000000000000000000000000000000000000000"),
+                b256!("abcfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+            )
+        );
+    }
+
+    #[test]
+    fn decode_branch_children() {
+        let hash = keccak256([1]);
+        let mut items = vec![Bytes::new(); 17];
+        items[3] = hash.0.to_vec().into();
+        let mut node = Vec::new();
+        items.encode(&mut node);
+
+        let children = branch_children(&node).unwrap();
+        assert!(is_empty_reference(children[0]));
+        assert_eq!(reference_hash(children[3]), Some(hash));
+        assert!(children
+            .iter()
+            .enumerate()
+            .all(|(idx, child)| idx == 3 || is_empty_reference(child)));
+
+        // leaves and extensions are not branches
+        let mut leaf = Vec::new();
+        vec![Bytes::from_static(&[0x20]), Bytes::from_static(&[0x01])].encode(&mut leaf);
+        assert_eq!(branch_children(&leaf), None);
+    }
+}
@@ -0,0 +1,1036 @@
+use self::{
+    download::{download_accounts, download_trie_nodes, next_hash, SnapDownload, MAX_TRIE_NODES},
+    heal::{branch_children, child_path, is_empty_reference, prefix_range, reference_hash},
+    preimages::write_plain_state,
+};
+use futures_util::FutureExt;
+use reth_codecs::Compact;
+use reth_config::config::EtlConfig;
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRW},
+    database::Database,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_interfaces::p2p::snap::client::SnapClient;
+use reth_primitives::{
+    stage::{MerkleCheckpoint, StageCheckpoint, StageId},
+    trie::{Nibbles, StoredSubNode},
+    BlockNumber, Bytes, PruneCheckpoint, PruneMode, PruneSegment, StaticFileSegment, B256,
+};
+use reth_provider::{
+    providers::StaticFileWriter, BlockReader, DatabaseProviderRW, HeaderProvider, ProviderError,
+    PruneCheckpointWriter, StageCheckpointReader, StageCheckpointWriter,
+};
+use reth_stages_api::{ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use reth_trie::{proof::Proof, IntermediateStateRootState, StateRoot, StateRootProgress};
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tracing::*;
+
+mod download;
+mod heal;
+mod preimages;
+mod proof;
+
+/// The id of the [`SnapSyncStage`].
+///
+/// The stage is not part of [`StageId::ALL`], it only runs in pipelines that opt into snap sync.
+pub const SNAP_SYNC_STAGE_ID: StageId = StageId::Other("SnapSync");
+
+/// The id under which the progress of the state root computation of the [`SnapSyncStage`] is
+/// persisted.
+const SNAP_SYNC_VERIFICATION_ID: StageId = StageId::Other("SnapSyncVerification");
+
+/// The default distance of the pivot block to the sync target.
+pub const SNAP_SYNC_DEFAULT_PIVOT_DISTANCE: u64 = 64;
+
+/// The number of times the state root is verified before the stage gives up on healing the state.
+const MAX_VERIFICATIONS: usize = 8;
+
+/// The first and the last hash of the state trie.
+const FULL_RANGE: (B256, B256) = (B256::ZERO, B256::repeat_byte(0xff));
+
+/// The future of an in-progress download.
+type DownloadFut = Pin<Box<dyn Future<Output = SnapDownload> + Send + Sync>>;
+
+/// Errors of the [`SnapSyncStage`].
+#[derive(Debug, thiserror::Error)]
+pub enum SnapSyncError {
+    /// The state could not be healed to match the state root of the pivot.
+    #[error("state root mismatch at pivot block {block}: got {got}, expected {expected}")]
+    StateRootMismatch {
+        /// The pivot block.
+        block: BlockNumber,
+        /// The state root of the downloaded state.
+        got: B256,
+        /// The state root of the pivot block.
+        expected: B256,
+    },
+    /// Peers no longer serve the state of the pivot and there is no newer pivot to move to.
+    #[error("peers no longer serve the state of pivot block {0}")]
+    StalePivot(BlockNumber),
+    /// The preimages of hashed addresses or storage keys are missing.
+    #[error("missing preimages of {accounts} accounts and {slots} storage slots")]
+    MissingPreimages {
+        /// The number of accounts whose address is unknown.
+        accounts: usize,
+        /// The number of storage slots whose key is unknown.
+        slots: usize,
+    },
+    /// The preimage file is malformed.
+    #[error("invalid preimage file: {0}")]
+    InvalidPreimages(alloy_rlp::Error),
+    /// The preimage file cannot be read.
+    #[error("cannot read preimage file {path:?}: {error}")]
+    UnreadablePreimages {
+        /// The path of the preimage file.
+        path: PathBuf,
+        /// The error of opening the file.
+        error: std::io::Error,
+    },
+    /// The state below the pivot block was never executed and cannot be unwound to.
+    #[error("cannot unwind to block {unwind_to} below the snap sync pivot block {pivot}")]
+    UnwindBelowPivot {
+        /// The block to unwind to.
+        unwind_to: BlockNumber,
+        /// The pivot block.
+        pivot: BlockNumber,
+    },
+}
+
+/// The snap sync stage downloads the state at a recent pivot block from peers over the `snap`
+/// protocol, instead of executing all blocks from genesis.
+///
+/// The stage runs after the [`BodiesStage`][crate::stages::BodiesStage] and picks the block
+/// [`SNAP_SYNC_DEFAULT_PIVOT_DISTANCE`] blocks below the sync target as pivot. It then:
+///
+/// 1. Downloads the accounts of the state trie range by range, with their storage and bytecodes,
+///    into the hashed state tables.
+/// 2. Computes the state root of the downloaded state. Since peers advance their state while the
+///    download is running, ranges downloaded at different pivots may be inconsistent. The stage
+///    then heals the state by walking the state trie of peers from the root, and re-downloads the
+///    ranges below the nodes that differ from the local trie.
+/// 3. Once the state root matches, reconstructs the plain state from the preimage file and moves
+///    the checkpoints of the state stages to the pivot. The blocks above the pivot are then
+///    executed by the following stages.
+///
+/// If peers stop serving the state root of the pivot, the pivot is moved to the latest block
+/// that is far enough below the sync target.
+///
+/// Limitations:
+///
+/// - Only a node that has not executed any block is synced, otherwise the stage is a no-op.
+/// - The `snap` protocol serves hashed state only, the preimages of all addresses and storage keys
+///   must be provided in a file as exported by `geth db export preimage`. Geth only records
+///   preimages if it was synced with `--cache.preimages`, which is disabled by default, so the
+///   preimages must come from a node that was set up for this. The file is only checked to be
+///   readable when the sync starts; missing preimages are detected after the state is downloaded,
+///   which then fails the stage with [`SnapSyncError::MissingPreimages`].
+/// - There are no changesets or receipts below the pivot, unwinding below the pivot is not
+///   supported and the history below the pivot is reported as pruned.
+/// - Reth peers only serve the state at their latest block, the pivot must be close to the tip of
+///   the chain.
+/// - The tries are rebuilt from scratch on every verification. Like in the
+///   [`MerkleStage`][crate::stages::MerkleStage], the computation is split across transactions by
+///   an intermediate checkpoint.
+pub struct SnapSyncStage<C> {
+    /// The client to download state from.
+    client: C,
+    /// The distance of the pivot block to the sync target.
+    pivot_distance: u64,
+    /// The path of the file with the preimages of hashed addresses and storage keys.
+    preimages: PathBuf,
+    /// The ETL configuration used to reconstruct the plain state.
+    etl_config: EtlConfig,
+    /// The current pivot, if the sync is in progress.
+    pivot: Option<Pivot>,
+    /// The ranges of the state trie whose accounts remain to be downloaded.
+    ranges: VecDeque<(B256, B256)>,
+    /// The paths of the state trie to heal, with the expected hashes of their nodes.
+    heal: Vec<(Nibbles, B256)>,
+    /// The number of times the state root was verified at the current pivot.
+    verifications: usize,
+    /// The in-progress download.
+    download: Option<DownloadFut>,
+    /// The finished download that is yet to be written.
+    buffer: Option<SnapDownload>,
+}
+
+impl<C> fmt::Debug for SnapSyncStage<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapSyncStage")
+            .field("pivot_distance", &self.pivot_distance)
+            .field("preimages", &self.preimages)
+            .field("pivot", &self.pivot)
+            .field("ranges", &self.ranges.len())
+            .field("heal", &self.heal.len())
+            .field("verifications", &self.verifications)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C> SnapSyncStage<C> {
+    /// Create a new snap sync stage that reads the preimages of the state from the given file.
+    pub fn new(client: C, preimages: PathBuf, etl_config: EtlConfig) -> Self {
+        Self {
+            client,
+            pivot_distance: SNAP_SYNC_DEFAULT_PIVOT_DISTANCE,
+            preimages,
+            etl_config,
+            pivot: None,
+            ranges: VecDeque::new(),
+            heal: Vec::new(),
+            verifications: 0,
+            download: None,
+            buffer: None,
+        }
+    }
+
+    /// Set the distance of the pivot block to the sync target.
+    pub fn with_pivot_distance(mut self, pivot_distance: u64) -> Self {
+        self.pivot_distance = pivot_distance;
+        self
+    }
+
+    /// Loads the persisted progress of the sync, or starts a new sync if there is none.
+    ///
+    /// Returns `false` if the state is not snap synced.
+    fn start<DB: Database>(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        target: BlockNumber,
+    ) -> Result<bool, StageError> {
+        let progress = match load_progress(provider)? {
+            Some(progress) => progress,
+            None => {
+                let pivot = target.saturating_sub(self.pivot_distance);
+                let executed = provider
+                    .get_stage_checkpoint(StageId::Execution)?
+                    .unwrap_or_default()
+                    .block_number;
+                if pivot == 0 || executed > 0 {
+                    return Ok(false)
+                }
+
+                // Fail before downloading anything if the plain state can't be written later on.
+                if let Err(error) = std::fs::File::open(&self.preimages) {
+                    return Err(StageError::Fatal(Box::new(SnapSyncError::UnreadablePreimages {
+                        path: self.preimages.clone(),
+                        error,
+                    })))
+                }
+
+                // The genesis state is replaced by the downloaded state.
+                let tx = provider.tx_ref();
+                tx.clear::<tables::HashedAccounts>()?;
+                tx.clear::<tables::HashedStorages>()?;
+                tx.clear::<tables::AccountsTrie>()?;
+                tx.clear::<tables::StoragesTrie>()?;
+                save_verification_checkpoint(provider, None)?;
+
+                SnapSyncProgress { pivot, ranges: vec![FULL_RANGE] }
+            }
+        };
+
+        info!(target: "sync::stages::snap_sync", pivot = progress.pivot, ranges = progress.ranges.len(), "Starting snap sync");
+        self.set_pivot(provider, progress.pivot)?;
+        self.ranges = progress.ranges.into();
+        self.save_progress(provider)?;
+        Ok(true)
+    }
+
+    /// Sets the pivot to the given block.
+    fn set_pivot<DB: Database>(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        number: BlockNumber,
+    ) -> Result<(), StageError> {
+        let header = provider
+            .header_by_number(number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
+        self.pivot = Some(Pivot { number, state_root: header.state_root });
+        self.heal.clear();
+        self.verifications = 0;
+        Ok(())
+    }
+
+    /// Persists the pivot and the remaining ranges.
+    fn save_progress<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+    ) -> Result<(), StageError> {
+        if let Some(pivot) = &self.pivot {
+            let progress = SnapSyncProgress {
+                pivot: pivot.number,
+                ranges: self.ranges.iter().copied().collect(),
+            };
+            provider.save_stage_checkpoint_progress(SNAP_SYNC_STAGE_ID, progress.encode())?;
+        }
+        Ok(())
+    }
+
+    /// Compares the downloaded trie nodes with the local trie, and schedules the differing
+    /// subtries for healing or re-download.
+    fn heal_nodes<DB: Database>(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        nodes: Vec<(Nibbles, Bytes)>,
+    ) -> Result<(), StageError> {
+        let tx = provider.tx_ref();
+        let local = Proof::new(tx)
+            .account_trie_nodes(nodes.iter().map(|(path, _)| path.clone()).collect())
+            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+
+        for (path, node) in nodes {
+            let local_node = local.get(&path);
+            if local_node == Some(&node) {
+                continue
+            }
+
+            let Some((remote, local)) =
+                branch_children(&node).zip(local_node.and_then(|node| branch_children(node)))
+            else {
+                self.repair(tx, &path)?;
+                continue
+            };
+            for (nibble, (remote, local)) in remote.iter().zip(local.iter()).enumerate() {
+                if remote == local {
+                    continue
+                }
+                let child = child_path(&path, nibble as u8);
+                match reference_hash(remote) {
+                    Some(hash) if !is_empty_reference(local) => self.heal.push((child, hash)),
+                    _ if is_empty_reference(remote) => {
+                        let (start, end) = prefix_range(&child);
+                        delete_range(tx, start, end)?;
+                    }
+                    _ => self.repair(tx, &child)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the accounts below the path and schedules them for re-download.
+    fn repair<TX: DbTxMut + DbTx>(&mut self, tx: &TX, path: &[u8]) -> Result<(), DatabaseError> {
+        let (start, end) = prefix_range(path);
+        delete_range(tx, start, end)?;
+        self.ranges.push_back((start, end));
+        Ok(())
+    }
+
+    /// Computes the state root of the downloaded state, continuing from the persisted
+    /// intermediate checkpoint of the pivot, if any.
+    ///
+    /// Returns `None` if the computation is not finished yet, `true` if the root matches the state
+    /// root of the pivot, otherwise schedules healing from the root of the state trie.
+    fn verify<DB: Database>(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        pivot: Pivot,
+    ) -> Result<Option<bool>, StageError> {
+        let tx = provider.tx_ref();
+        let checkpoint = load_verification_checkpoint(provider)?
+            .filter(|checkpoint| checkpoint.target_block == pivot.number);
+        if let Some(checkpoint) = &checkpoint {
+            debug!(target: "sync::stages::snap_sync", pivot = pivot.number, last_account_key = ?checkpoint.last_account_key, "Continuing state root verification");
+        } else {
+            tx.clear::<tables::AccountsTrie>()?;
+            tx.clear::<tables::StoragesTrie>()?;
+        }
+
+        let progress = StateRoot::from_tx(tx)
+            .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
+            .root_with_progress()
+            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+        let root = match progress {
+            StateRootProgress::Progress(state, _, updates) => {
+                updates.flush(tx)?;
+                let checkpoint = MerkleCheckpoint::new(
+                    pivot.number,
+                    state.last_account_key,
+                    state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
+                    state.hash_builder.into(),
+                );
+                save_verification_checkpoint(provider, Some(checkpoint))?;
+                return Ok(None)
+            }
+            StateRootProgress::Complete(root, _, updates) => {
+                updates.flush(tx)?;
+                save_verification_checkpoint(provider, None)?;
+                root
+            }
+        };
+
+        if root == pivot.state_root {
+            return Ok(Some(true))
+        }
+
+        self.verifications += 1;
+        if self.verifications >= MAX_VERIFICATIONS {
+            return Err(StageError::Fatal(Box::new(SnapSyncError::StateRootMismatch {
+                block: pivot.number,
+                got: root,
+                expected: pivot.state_root,
+            })))
+        }
+
+        debug!(target: "sync::stages::snap_sync", pivot = pivot.number, ?root, expected = ?pivot.state_root, "Healing state");
+        self.heal = vec![(Nibbles::default(), pivot.state_root)];
+        Ok(Some(false))
+    }
+
+    /// Writes the plain state and moves the state of the node to the pivot.
+    fn finalize<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        pivot: BlockNumber,
+    ) -> Result<(), StageError> {
+        write_plain_state(provider, &self.preimages, &self.etl_config)?;
+
+        // There are no receipts and no history below the pivot.
+        let indices = provider
+            .block_body_indices(pivot)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(pivot))?;
+        let prune_mode = PruneMode::Before(pivot + 1);
+        provider.save_prune_checkpoint(
+            PruneSegment::Receipts,
+            PruneCheckpoint {
+                block_number: Some(pivot),
+                tx_number: indices.next_tx_num().checked_sub(1),
+                prune_mode,
+            },
+        )?;
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            provider.save_prune_checkpoint(
+                segment,
+                PruneCheckpoint { block_number: Some(pivot), tx_number: None, prune_mode },
+            )?;
+        }
+
+        let static_file_provider = provider.static_file_provider();
+        let next_block = static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::Receipts)
+            .map_or(0, |block| block + 1);
+        let mut receipts_writer =
+            static_file_provider.latest_writer(StaticFileSegment::Receipts)?;
+        for block in next_block..=pivot {
+            receipts_writer.increment_block(StaticFileSegment::Receipts, block)?;
+        }
+
+        for id in [
+            StageId::Execution,
+            StageId::MerkleUnwind,
+            StageId::AccountHashing,
+            StageId::StorageHashing,
+            StageId::MerkleExecute,
+            StageId::IndexStorageHistory,
+            StageId::IndexAccountHistory,
+        ] {
+            provider.save_stage_checkpoint(id, StageCheckpoint::new(pivot))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<DB, C> Stage<DB> for SnapSyncStage<C>
+where
+    DB: Database,
+    C: SnapClient + Clone + 'static,
+{
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        SNAP_SYNC_STAGE_ID
+    }
+
+    fn poll_execute_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        input: ExecInput,
+    ) -> Poll<Result<(), StageError>> {
+        if input.target_reached() || self.buffer.is_some() {
+            return Poll::Ready(Ok(()))
+        }
+        let Some(pivot) = self.pivot else { return Poll::Ready(Ok(())) };
+
+        if self.download.is_none() {
+            let client = self.client.clone();
+            let download: DownloadFut = if let Some((start, limit)) = self.ranges.front() {
+                Box::pin(download_accounts(client, pivot.state_root, *start, *limit))
+            } else if !self.heal.is_empty() {
+                let paths = self.heal.drain(..self.heal.len().min(MAX_TRIE_NODES)).collect();
+                Box::pin(download_trie_nodes(client, pivot.state_root, paths))
+            } else {
+                // All state is downloaded, the state root is verified next.
+                return Poll::Ready(Ok(()))
+            };
+            self.download = Some(download);
+        }
+
+        let download = self.download.as_mut().expect("download is set");
+        self.buffer = Some(ready!(download.poll_unpin(cx)));
+        self.download = None;
+        Poll::Ready(Ok(()))
+    }
+
+    /// Download the state at the pivot block and write it to the database.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        // The state was already synced, the blocks above are executed by the following stages.
+        if input.checkpoint().block_number > 0 {
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
+        let Some(pivot) = self.pivot else {
+            if !self.start(provider, input.target())? {
+                return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+            }
+            return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+        };
+
+        let tx = provider.tx_ref();
+        match self.buffer.take() {
+            Some(SnapDownload::Accounts(batch)) => {
+                trace!(target: "sync::stages::snap_sync", start = ?batch.start, end = ?batch.end, accounts = batch.accounts.len(), "Writing accounts");
+                delete_range(tx, batch.start, batch.end)?;
+
+                let mut account_cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+                for (hashed_address, account) in batch.accounts {
+                    account_cursor.upsert(hashed_address, account)?;
+                }
+                let mut storage_cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+                for (hashed_address, entries) in batch.storages {
+                    for entry in entries {
+                        storage_cursor.upsert(hashed_address, entry)?;
+                    }
+                }
+                let mut bytecode_cursor = tx.cursor_write::<tables::Bytecodes>()?;
+                for (hash, bytecode) in batch.bytecodes {
+                    bytecode_cursor.upsert(hash, bytecode)?;
+                }
+
+                match next_hash(batch.end).filter(|_| !batch.complete) {
+                    Some(next) => {
+                        if let Some(range) = self.ranges.front_mut() {
+                            range.0 = next;
+                        }
+                    }
+                    None => {
+                        self.ranges.pop_front();
+                    }
+                }
+            }
+            Some(SnapDownload::TrieNodes(nodes)) => self.heal_nodes(provider, nodes)?,
+            Some(SnapDownload::Stale) => {
+                let number = input.target().saturating_sub(self.pivot_distance);
+                if number <= pivot.number {
+                    return Err(StageError::Recoverable(Box::new(SnapSyncError::StalePivot(
+                        pivot.number,
+                    ))))
+                }
+                info!(target: "sync::stages::snap_sync", from = pivot.number, to = number, "Moving pivot");
+                self.set_pivot(provider, number)?;
+            }
+            None if !self.ranges.is_empty() || !self.heal.is_empty() => {
+                return Err(StageError::MissingDownloadBuffer)
+            }
+            None => {
+                if self.verify(provider, pivot)? == Some(true) {
+                    info!(target: "sync::stages::snap_sync", pivot = pivot.number, "State root verified, writing plain state");
+                    self.finalize(provider, pivot.number)?;
+                    self.save_progress(provider)?;
+                    self.pivot = None;
+                    return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+                }
+            }
+        }
+
+        self.save_progress(provider)?;
+        Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        if let Some(progress) = load_progress(provider)? {
+            if input.unwind_to < progress.pivot {
+                return Err(StageError::Fatal(Box::new(SnapSyncError::UnwindBelowPivot {
+                    unwind_to: input.unwind_to,
+                    pivot: progress.pivot,
+                })))
+            }
+        }
+
+        // The blocks above the pivot are unwound by the stages that processed them.
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+    }
+}
+
+/// The pivot block whose state is downloaded.
+#[derive(Debug, Clone, Copy)]
+struct Pivot {
+    /// The number of the block.
+    number: BlockNumber,
+    /// The state root of the block.
+    state_root: B256,
+}
+
+/// The persisted progress of the [`SnapSyncStage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SnapSyncProgress {
+    /// The pivot block.
+    pivot: BlockNumber,
+    /// The ranges of the state trie whose accounts remain to be downloaded.
+    ranges: Vec<(B256, B256)>,
+}
+
+impl SnapSyncProgress {
+    /// Encodes the progress as the big endian pivot, followed by the bounds of the ranges.
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(8 + self.ranges.len() * 64);
+        buf.extend_from_slice(&self.pivot.to_be_bytes());
+        for (start, end) in &self.ranges {
+            buf.extend_from_slice(start.as_slice());
+            buf.extend_from_slice(end.as_slice());
+        }
+        buf
+    }
+
+    /// Decodes the progress, returns `None` if it is malformed.
+    fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < 8 || (buf.len() - 8) % 64 != 0 {
+            return None
+        }
+        let (pivot, ranges) = buf.split_at(8);
+        Some(Self {
+            pivot: u64::from_be_bytes(pivot.try_into().ok()?),
+            ranges: ranges
+                .chunks_exact(64)
+                .map(|range| (B256::from_slice(&range[..32]), B256::from_slice(&range[32..])))
+                .collect(),
+        })
+    }
+}
+
+/// Loads the persisted progress of the [`SnapSyncStage`].
+fn load_progress<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+) -> Result<Option<SnapSyncProgress>, StageError> {
+    Ok(provider
+        .get_stage_checkpoint_progress(SNAP_SYNC_STAGE_ID)?
+        .and_then(|progress| SnapSyncProgress::decode(&progress)))
+}
+
+/// Loads the intermediate checkpoint of the state root computation of the [`SnapSyncStage`].
+fn load_verification_checkpoint<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+) -> Result<Option<MerkleCheckpoint>, StageError> {
+    let buf =
+        provider.get_stage_checkpoint_progress(SNAP_SYNC_VERIFICATION_ID)?.unwrap_or_default();
+    if buf.is_empty() {
+        return Ok(None)
+    }
+    let (checkpoint, _) = MerkleCheckpoint::from_compact(&buf, buf.len());
+    Ok(Some(checkpoint))
+}
+
+/// Saves the intermediate checkpoint of the state root computation of the [`SnapSyncStage`], or
+/// resets it if `None`.
+fn save_verification_checkpoint<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    checkpoint: Option<MerkleCheckpoint>,
+) -> Result<(), StageError> {
+    let mut buf = vec![];
+    if let Some(checkpoint) = checkpoint {
+        checkpoint.to_compact(&mut buf);
+    }
+    Ok(provider.save_stage_checkpoint_progress(SNAP_SYNC_VERIFICATION_ID, buf)?)
+}
+
+/// Deletes the hashed accounts in the range `start..=end` with their storage.
+fn delete_range<TX: DbTxMut + DbTx>(tx: &TX, start: B256, end: B256) -> Result<(), DatabaseError> {
+    let mut account_cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+    let mut storage_cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    let mut walker = account_cursor.walk_range(start..=end)?;
+    while let Some((hashed_address, _)) = walker.next().transpose()? {
+        walker.delete_current()?;
+        if storage_cursor.seek_exact(hashed_address)?.is_some() {
+            storage_cursor.delete_current_duplicates()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use alloy_rlp::Encodable;
+    use assert_matches::assert_matches;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_interfaces::{
+        p2p::{
+            download::DownloadClient,
+            snap::client::{
+                AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes,
+                GetStorageRanges, GetTrieNodes, SlimAccount, SnapFut, StorageData, StorageRanges,
+                TrieNodes,
+            },
+        },
+        test_utils::generators::{self, random_block_range, random_eoa_accounts},
+    };
+    use reth_primitives::{
+        keccak256, Account, Address, PeerId, StorageEntry, WithPeerId, KECCAK_EMPTY, U256,
+    };
+    use reth_provider::PruneCheckpointReader;
+    use reth_stages_api::StageExt;
+    use reth_trie::test_utils::{state_root, storage_root};
+    use std::{
+        collections::{BTreeMap, HashMap},
+        io::Write,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    type TestDB = Arc<TempDatabase<DatabaseEnv>>;
+
+    /// A [`SnapClient`] that serves a fixed state at a single state root, without proofs.
+    #[derive(Debug, Clone)]
+    struct TestSnapClient {
+        root: B256,
+        accounts: Arc<BTreeMap<B256, (SlimAccount, BTreeMap<B256, U256>)>>,
+        bytecodes: Arc<HashMap<B256, Bytes>>,
+        /// The number of account range responses that are still tampered with.
+        tampered_accounts: Arc<AtomicUsize>,
+        /// The number of storage range responses that are still tampered with.
+        tampered_storages: Arc<AtomicUsize>,
+        /// The number of reported responses.
+        bad_messages: Arc<AtomicUsize>,
+    }
+
+    impl TestSnapClient {
+        fn new(state: &BTreeMap<Address, (Account, Vec<StorageEntry>)>, code: Bytes) -> Self {
+            let accounts = state
+                .iter()
+                .map(|(address, (account, storage))| {
+                    let slim = SlimAccount {
+                        nonce: account.nonce,
+                        balance: account.balance,
+                        storage_root: storage_root(storage.iter().map(|e| (e.key, e.value))),
+                        code_hash: account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+                    };
+                    let slots =
+                        storage.iter().map(|entry| (keccak256(entry.key), entry.value)).collect();
+                    (keccak256(address), (slim, slots))
+                })
+                .collect();
+            Self {
+                root: state_root(state.iter().map(|(address, (account, storage))| {
+                    (*address, (*account, storage.iter().map(|e| (e.key, e.value))))
+                })),
+                accounts: Arc::new(accounts),
+                bytecodes: Arc::new(HashMap::from([(keccak256(&code), code)])),
+                tampered_accounts: Arc::default(),
+                tampered_storages: Arc::default(),
+                bad_messages: Arc::default(),
+            }
+        }
+    }
+
+    /// Returns true if the next response should be tampered with.
+    fn tamper(tampered: &AtomicUsize) -> bool {
+        tampered
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| left.checked_sub(1))
+            .is_ok()
+    }
+
+    fn respond<T: Send + Sync + 'static>(response: T) -> SnapFut<T> {
+        Box::pin(std::future::ready(Ok(WithPeerId::new(PeerId::ZERO, response))))
+    }
+
+    impl DownloadClient for TestSnapClient {
+        fn report_bad_message(&self, _peer_id: PeerId) {
+            self.bad_messages.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            1
+        }
+    }
+
+    impl SnapClient for TestSnapClient {
+        fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange> {
+            let mut accounts = if request.root_hash == self.root {
+                self.accounts
+                    .range(request.starting_hash..=request.limit_hash)
+                    .map(|(hash, (body, _))| AccountData { hash: *hash, body: *body })
+                    .collect::<Vec<_>>()
+            } else {
+                Vec::new()
+            };
+            if let Some(account) = accounts.first_mut().filter(|_| tamper(&self.tampered_accounts))
+            {
+                account.body.balance += U256::from(1);
+            }
+            respond(AccountRange { request_id: request.request_id, accounts, proof: Vec::new() })
+        }
+
+        fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges> {
+            let origin = B256::left_padding_from(&request.starting_hash);
+            let mut slots = request
+                .account_hashes
+                .iter()
+                .filter_map(|hash| self.accounts.get(hash))
+                .map(|(_, slots)| {
+                    slots
+                        .range(origin..)
+                        .map(|(hash, value)| StorageData {
+                            hash: *hash,
+                            data: alloy_rlp::encode(value).into(),
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            if let Some(slot) = slots.first_mut().and_then(|slots| slots.first_mut()) {
+                if tamper(&self.tampered_storages) {
+                    slot.data = alloy_rlp::encode(U256::MAX).into();
+                }
+            }
+            respond(StorageRanges { request_id: request.request_id, slots, proof: Vec::new() })
+        }
+
+        fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes> {
+            let codes = request
+                .hashes
+                .iter()
+                .filter_map(|hash| self.bytecodes.get(hash).cloned())
+                .collect();
+            respond(ByteCodes { request_id: request.request_id, codes })
+        }
+
+        fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes> {
+            respond(TrieNodes { request_id: request.request_id, nodes: Vec::new() })
+        }
+    }
+
+    /// Returns a state with a few accounts and a contract with storage, and the contract code.
+    fn test_state() -> (BTreeMap<Address, (Account, Vec<StorageEntry>)>, Bytes) {
+        let mut rng = generators::rng();
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        let mut state = random_eoa_accounts(&mut rng, 8)
+            .into_iter()
+            .map(|(address, account)| (address, (account, Vec::new())))
+            .collect::<BTreeMap<_, _>>();
+        state.insert(
+            Address::with_last_byte(0xaa),
+            (
+                Account { nonce: 1, balance: U256::from(1), bytecode_hash: Some(keccak256(&code)) },
+                (1..=3)
+                    .map(|slot| StorageEntry {
+                        key: B256::with_last_byte(slot),
+                        value: U256::from(slot),
+                    })
+                    .collect(),
+            ),
+        );
+        (state, code)
+    }
+
+    /// Writes the preimages of the addresses and storage keys of the state to a file.
+    fn write_preimages(
+        state: &BTreeMap<Address, (Account, Vec<StorageEntry>)>,
+    ) -> tempfile::NamedTempFile {
+        let mut buf = Vec::new();
+        for (address, (_, storage)) in state {
+            address.encode(&mut buf);
+            for entry in storage {
+                entry.key.encode(&mut buf);
+            }
+        }
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&buf).unwrap();
+        file
+    }
+
+    /// Inserts blocks up to the target, with the state root of the pivot block.
+    fn insert_blocks(db: &TestStageDB, target: BlockNumber, pivot: BlockNumber, root: B256) {
+        let mut rng = generators::rng();
+        let mut blocks = random_block_range(&mut rng, 0..=target, B256::ZERO, 0..2);
+        let mut header = blocks[pivot as usize].header.clone().unseal();
+        header.state_root = root;
+        blocks[pivot as usize].header = header.seal_slow();
+        db.insert_blocks(blocks.iter(), StorageKind::Static).unwrap();
+    }
+
+    async fn run(
+        stage: &mut SnapSyncStage<TestSnapClient>,
+        db: &TestStageDB,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        loop {
+            StageExt::<TestDB>::execute_ready(stage, input).await?;
+            let provider = db.factory.provider_rw()?;
+            let output = stage.execute(&provider, input)?;
+            db.factory.static_file_provider().commit()?;
+            provider.commit()?;
+            if output.done {
+                return Ok(output)
+            }
+        }
+    }
+
+    #[test]
+    fn progress_roundtrip() {
+        let progress = SnapSyncProgress {
+            pivot: 100,
+            ranges: vec![(B256::with_last_byte(1), B256::repeat_byte(0xaa)), FULL_RANGE],
+        };
+        assert_eq!(SnapSyncProgress::decode(&progress.encode()), Some(progress));
+        assert_eq!(SnapSyncProgress::decode(&[0; 7]), None);
+        assert_eq!(SnapSyncProgress::decode(&[0; 9]), None);
+    }
+
+    #[tokio::test]
+    async fn sync_state_at_pivot() {
+        let (state, code) = test_state();
+        let client = TestSnapClient::new(&state, code.clone());
+        let bad_messages = client.bad_messages.clone();
+        let preimages = write_preimages(&state);
+
+        let db = TestStageDB::default();
+        // the genesis state is replaced by the state of the pivot
+        db.insert_accounts_and_storages(
+            random_eoa_accounts(&mut generators::rng(), 2)
+                .into_iter()
+                .map(|(address, account)| (address, (account, Vec::<StorageEntry>::new()))),
+        )
+        .unwrap();
+        insert_blocks(&db, 10, 6, client.root);
+
+        let mut stage =
+            SnapSyncStage::new(client, preimages.path().to_path_buf(), EtlConfig::default())
+                .with_pivot_distance(4);
+        let input = ExecInput { target: Some(10), checkpoint: None };
+        let output = run(&mut stage, &db, input).await.unwrap();
+        assert_eq!(output, ExecOutput::done(StageCheckpoint::new(10)));
+        assert_eq!(bad_messages.load(Ordering::SeqCst), 0);
+
+        let plain_accounts = db.table::<tables::PlainAccountState>().unwrap();
+        assert_eq!(
+            plain_accounts,
+            state.iter().map(|(address, (account, _))| (*address, *account)).collect::<Vec<_>>()
+        );
+        let plain_storage = db.table::<tables::PlainStorageState>().unwrap();
+        assert_eq!(
+            plain_storage,
+            state
+                .iter()
+                .flat_map(|(address, (_, storage))| storage.iter().map(|entry| (*address, *entry)))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            db.table::<tables::Bytecodes>()
+                .unwrap()
+                .into_iter()
+                .map(|(hash, _)| hash)
+                .collect::<Vec<_>>(),
+            vec![keccak256(&code)]
+        );
+
+        let provider = db.factory.provider().unwrap();
+        assert_eq!(
+            provider.get_stage_checkpoint(StageId::Execution).unwrap(),
+            Some(StageCheckpoint::new(6))
+        );
+        assert_matches!(
+            provider.get_prune_checkpoint(PruneSegment::AccountHistory).unwrap(),
+            Some(PruneCheckpoint { block_number: Some(6), .. })
+        );
+        assert_eq!(
+            db.factory
+                .static_file_provider()
+                .get_highest_static_file_block(StaticFileSegment::Receipts),
+            Some(6)
+        );
+
+        // the state below the pivot was never executed
+        let unwind =
+            UnwindInput { checkpoint: StageCheckpoint::new(10), unwind_to: 5, bad_block: None };
+        let provider = db.factory.provider_rw().unwrap();
+        assert_matches!(
+            Stage::<TestDB>::unwind(&mut stage, &provider, unwind),
+            Err(StageError::Fatal(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_tampered_ranges() {
+        let (state, code) = test_state();
+        let client = TestSnapClient::new(&state, code);
+        // the first account range and the first storage range are tampered with
+        client.tampered_accounts.store(1, Ordering::SeqCst);
+        client.tampered_storages.store(1, Ordering::SeqCst);
+
+        let download =
+            download_accounts(client.clone(), client.root, FULL_RANGE.0, FULL_RANGE.1).await;
+        let SnapDownload::Accounts(batch) = download else { panic!("unexpected download") };
+        assert_eq!(client.bad_messages.load(Ordering::SeqCst), 2);
+
+        assert!(batch.complete);
+        assert_eq!(
+            batch.accounts,
+            state
+                .iter()
+                .map(|(address, (account, _))| (keccak256(address), *account))
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .collect::<Vec<_>>()
+        );
+        let (address, (_, storage)) =
+            state.iter().find(|(_, (_, storage))| !storage.is_empty()).unwrap();
+        let mut expected = storage
+            .iter()
+            .map(|entry| StorageEntry { key: keccak256(entry.key), value: entry.value })
+            .collect::<Vec<_>>();
+        expected.sort_unstable_by_key(|entry| entry.key);
+        assert_eq!(batch.storages, vec![(keccak256(address), expected)]);
+    }
+
+    #[tokio::test]
+    async fn stale_pivot() {
+        let (state, code) = test_state();
+        let client = TestSnapClient::new(&state, code);
+        let preimages = write_preimages(&state);
+
+        // peers do not serve the state root of the pivot, and there is no newer pivot
+        let db = TestStageDB::default();
+        insert_blocks(&db, 10, 6, B256::ZERO);
+
+        let mut stage =
+            SnapSyncStage::new(client, preimages.path().to_path_buf(), EtlConfig::default())
+                .with_pivot_distance(4);
+        let input = ExecInput { target: Some(10), checkpoint: None };
+        assert_matches!(run(&mut stage, &db, input).await, Err(StageError::Recoverable(_)));
+    }
+}
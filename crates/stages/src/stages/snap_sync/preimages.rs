@@ -0,0 +1,202 @@
+//! Reconstruction of the plain state from the hashed state and the preimages of the hashes.
+//!
+//! The `snap` protocol only serves hashed state, the addresses and storage keys are read from a
+//! preimage file instead, a sequence of RLP encoded byte strings as exported by
+//! `geth db export preimage`.
+
+use super::SnapSyncError;
+use alloy_rlp::Header;
+use reth_config::config::EtlConfig;
+use reth_db::{
+    codecs::CompactU256,
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    database::Database,
+    table::{Decode, Decompress},
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_etl::Collector;
+use reth_primitives::{keccak256, Account, Address, StorageEntry, B256};
+use reth_provider::DatabaseProviderRW;
+use reth_stages_api::StageError;
+use std::{fs::File, io, io::Read, iter::Peekable, path::Path};
+use tracing::*;
+
+/// The size of the chunks the preimage file is read in.
+const READ_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Writes the plain state for the hashed state, using the preimages of the given file.
+///
+/// Fails if the preimage of any hashed address or hashed storage key is missing.
+pub(super) fn write_plain_state<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    preimages: &Path,
+    etl_config: &EtlConfig,
+) -> Result<(), StageError> {
+    let tx = provider.tx_ref();
+    let (mut addresses, mut storage_keys) = read_preimages(preimages, etl_config)?;
+    info!(target: "sync::stages::snap_sync", addresses = addresses.len(), storage_keys = storage_keys.len(), "Read preimages");
+
+    let mut missing_accounts = 0;
+    let mut missing_slots = 0;
+
+    // Resolve the addresses, and collect the storage slots by hashed storage key with their
+    // address.
+    let mut plain_accounts =
+        Collector::<Address, Account>::new(etl_config.file_size, etl_config.dir.clone());
+    let mut hashed_slots =
+        Collector::<Vec<u8>, CompactU256>::new(etl_config.file_size, etl_config.dir.clone());
+    {
+        let mut addresses = SortedPreimages::new(addresses.iter()?);
+        let mut account_cursor = tx.cursor_read::<tables::HashedAccounts>()?;
+        let mut storage_cursor = tx.cursor_dup_read::<tables::HashedStorages>()?;
+        for entry in account_cursor.walk(None)? {
+            let (hashed_address, account) = entry?;
+            let Some(address) = addresses.get(hashed_address)? else {
+                missing_accounts += 1;
+                continue
+            };
+            let address = Address::decompress(address)?;
+            plain_accounts.insert(address, account)?;
+
+            let mut slot = storage_cursor.seek_exact(hashed_address)?;
+            while let Some((_, StorageEntry { key, value })) = slot {
+                hashed_slots.insert([key.as_slice(), address.as_slice()].concat(), value.into())?;
+                slot = storage_cursor.next_dup()?;
+            }
+        }
+    }
+
+    // Resolve the storage keys, and collect the storage slots by address and storage key.
+    let mut plain_slots =
+        Collector::<Vec<u8>, CompactU256>::new(etl_config.file_size, etl_config.dir.clone());
+    {
+        let mut storage_keys = SortedPreimages::new(storage_keys.iter()?);
+        for entry in hashed_slots.iter()? {
+            let (key, value) = entry?;
+            let Some(storage_key) = storage_keys.get(B256::from_slice(&key[..32]))? else {
+                missing_slots += 1;
+                continue
+            };
+            plain_slots
+                .insert([&key[32..], &storage_key[..]].concat(), CompactU256::decompress(value)?)?;
+        }
+    }
+
+    if missing_accounts > 0 || missing_slots > 0 {
+        return Err(StageError::Fatal(Box::new(SnapSyncError::MissingPreimages {
+            accounts: missing_accounts,
+            slots: missing_slots,
+        })))
+    }
+
+    tx.clear::<tables::PlainAccountState>()?;
+    tx.clear::<tables::PlainStorageState>()?;
+
+    let mut account_cursor = tx.cursor_write::<tables::PlainAccountState>()?;
+    for entry in plain_accounts.iter()? {
+        let (address, account) = entry?;
+        account_cursor.append(Address::decode(address)?, Account::decompress(account)?)?;
+    }
+
+    let mut storage_cursor = tx.cursor_dup_write::<tables::PlainStorageState>()?;
+    for entry in plain_slots.iter()? {
+        let (key, value) = entry?;
+        storage_cursor.append_dup(
+            Address::from_slice(&key[..20]),
+            StorageEntry {
+                key: B256::from_slice(&key[20..]),
+                value: CompactU256::decompress(value)?.into(),
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reads the preimages of the file into collectors of addresses and storage keys, by their hash.
+fn read_preimages(
+    path: &Path,
+    etl_config: &EtlConfig,
+) -> Result<(Collector<B256, Address>, Collector<B256, B256>), StageError> {
+    let mut addresses = Collector::new(etl_config.file_size, etl_config.dir.clone());
+    let mut storage_keys = Collector::new(etl_config.file_size, etl_config.dir.clone());
+
+    let mut file = File::open(path)?;
+    let mut buf = Vec::with_capacity(READ_CHUNK_SIZE);
+    let mut chunk = vec![0; READ_CHUNK_SIZE];
+    loop {
+        let read = file.read(&mut chunk)?;
+        buf.extend_from_slice(&chunk[..read]);
+
+        let mut remaining = &buf[..];
+        loop {
+            let mut payload = remaining;
+            let header = match Header::decode(&mut payload) {
+                Ok(header) if header.list => {
+                    return Err(invalid_preimages(alloy_rlp::Error::UnexpectedList))
+                }
+                Ok(header) if payload.len() >= header.payload_length => header,
+                Ok(_) | Err(alloy_rlp::Error::InputTooShort) => break,
+                Err(err) => return Err(invalid_preimages(err)),
+            };
+            let (preimage, rest) = payload.split_at(header.payload_length);
+            match preimage.len() {
+                20 => addresses.insert(keccak256(preimage), Address::from_slice(preimage))?,
+                32 => storage_keys.insert(keccak256(preimage), B256::from_slice(preimage))?,
+                _ => {}
+            }
+            remaining = rest;
+        }
+
+        let consumed = buf.len() - remaining.len();
+        buf.drain(..consumed);
+
+        if read == 0 {
+            if !buf.is_empty() {
+                return Err(invalid_preimages(alloy_rlp::Error::InputTooShort))
+            }
+            break
+        }
+    }
+
+    Ok((addresses, storage_keys))
+}
+
+fn invalid_preimages(err: alloy_rlp::Error) -> StageError {
+    StageError::Fatal(Box::new(SnapSyncError::InvalidPreimages(err)))
+}
+
+/// Preimages sorted by their hash, that are looked up in ascending order of the hashes.
+struct SortedPreimages<I: Iterator> {
+    preimages: Peekable<I>,
+}
+
+impl<I> SortedPreimages<I>
+where
+    I: Iterator<Item = io::Result<(Vec<u8>, Vec<u8>)>>,
+{
+    fn new(preimages: I) -> Self {
+        Self { preimages: preimages.peekable() }
+    }
+
+    /// Returns the encoded preimage of the hash.
+    ///
+    /// The hash must not be lower than the previously looked up hash.
+    fn get(&mut self, hash: B256) -> Result<Option<Vec<u8>>, StageError> {
+        loop {
+            match self.preimages.peek() {
+                Some(Ok((key, value))) => match key.as_slice().cmp(hash.as_slice()) {
+                    std::cmp::Ordering::Less => {}
+                    std::cmp::Ordering::Equal => return Ok(Some(value.clone())),
+                    std::cmp::Ordering::Greater => return Ok(None),
+                },
+                Some(Err(_)) => {
+                    return Err(self.preimages.next().and_then(Result::err).expect("peeked").into())
+                }
+                None => return Ok(None),
+            }
+            self.preimages.next();
+        }
+    }
+}
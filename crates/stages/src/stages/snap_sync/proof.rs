@@ -0,0 +1,604 @@
+//! Verification of the range proofs of `snap` responses.
+//!
+//! A range proof consists of the nodes on the paths of the first and the last key of a range.
+//! The partial trie built from these nodes is stripped of all references between the two paths,
+//! which are replaced by the leaves of the range. The range is valid if the resulting trie hashes
+//! to the expected root.
+//!
+//! See also <https://github.com/ethereum/go-ethereum/blob/master/trie/proof.go>.
+
+use super::heal::{encode_compact_path, is_empty_reference, reference_hash, split_item};
+use alloy_rlp::{Encodable, Header, EMPTY_STRING_CODE};
+use reth_primitives::{keccak256, Bytes, GotExpected, B256, EMPTY_ROOT_HASH};
+use std::{cmp::Ordering, collections::HashMap};
+
+/// An error that can occur when verifying a range proof.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub(super) enum RangeProofError {
+    /// The keys of the range are not ascending, or start before the origin.
+    #[error("range keys are not ascending from the origin")]
+    UnorderedKeys,
+    /// A node on the path of an edge of the range is missing from the proof.
+    #[error("missing proof node {0}")]
+    MissingNode(B256),
+    /// A node of the proof is malformed or not where it is expected.
+    #[error("invalid proof node")]
+    InvalidNode,
+    /// The value of a single key range does not match its proof.
+    #[error("value does not match the proof")]
+    ValueMismatch,
+    /// The edges of the range do not enclose any part of the trie.
+    #[error("range is not part of the trie")]
+    EmptyRange,
+    /// An empty range was served although there are more keys after the origin.
+    #[error("more keys available after the origin")]
+    MoreKeys,
+    /// The root of the trie built from the range and the proof does not match.
+    #[error("root mismatch: {0}")]
+    RootMismatch(GotExpected<B256>),
+}
+
+/// Verifies that the leaves are all the leaves of the trie with the given root, starting at the
+/// origin up to the last leaf.
+///
+/// The values are the RLP encoded leaf values of the trie. Without a proof, the leaves must be the
+/// entire trie.
+///
+/// Returns whether the trie contains more keys after the last leaf.
+pub(super) fn verify_range_proof<V: AsRef<[u8]>>(
+    root: B256,
+    origin: B256,
+    leaves: &[(B256, V)],
+    proof: &[Bytes],
+) -> Result<bool, RangeProofError> {
+    if leaves.first().is_some_and(|(key, _)| *key < origin) ||
+        !leaves.windows(2).all(|pair| pair[0].0 < pair[1].0)
+    {
+        return Err(RangeProofError::UnorderedKeys)
+    }
+
+    if proof.is_empty() {
+        let mut trie = Node::Empty;
+        for (key, value) in leaves {
+            trie.insert(&unpack(key), value.as_ref())?;
+        }
+        check_root(&trie, root)?;
+        return Ok(false)
+    }
+
+    let nodes =
+        proof.iter().map(|node| (keccak256(node), node.as_ref())).collect::<HashMap<_, _>>();
+    let mut trie = Node::Hash(root);
+    let origin = unpack(&origin);
+
+    let Some((last, _)) = leaves.last() else {
+        // an empty range proves that there are no keys at or after the origin
+        trie.resolve(&origin, &nodes)?;
+        if trie.get(&origin).is_some() || trie.has_right_element(&origin) {
+            return Err(RangeProofError::MoreKeys)
+        }
+        return Ok(false)
+    };
+    let last = unpack(last);
+
+    // a single leaf at the origin is proven by its own proof
+    if leaves.len() == 1 && origin == last {
+        trie.resolve(&origin, &nodes)?;
+        if trie.get(&origin) != Some(leaves[0].1.as_ref()) {
+            return Err(RangeProofError::ValueMismatch)
+        }
+        return Ok(trie.has_right_element(&origin))
+    }
+
+    trie.resolve(&origin, &nodes)?;
+    trie.resolve(&last, &nodes)?;
+    trie.unset_internal(&origin, &last)?;
+    for (key, value) in leaves {
+        trie.insert(&unpack(key), value.as_ref())?;
+    }
+    check_root(&trie, root)?;
+
+    Ok(trie.has_right_element(&last))
+}
+
+/// Checks that the trie hashes to the expected root.
+fn check_root(trie: &Node, root: B256) -> Result<(), RangeProofError> {
+    let got = trie.hash();
+    if got != root {
+        return Err(RangeProofError::RootMismatch(GotExpected { got, expected: root }))
+    }
+    Ok(())
+}
+
+/// Returns the nibbles of the key.
+fn unpack(key: &B256) -> Vec<u8> {
+    key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+/// Compares the key with the path, considering only the part of the key the path covers.
+fn compare_prefix(key: &[u8], path: &[u8]) -> Ordering {
+    key[..path.len().min(key.len())].cmp(path)
+}
+
+/// A node of a partial trie.
+///
+/// Values are only stored in leaves, since all keys of the state and storage tries have the same
+/// length.
+#[derive(Debug, Default)]
+enum Node {
+    /// No node.
+    #[default]
+    Empty,
+    /// A node that is only known by its hash.
+    Hash(B256),
+    /// A leaf with the remaining path of its key and its value.
+    Leaf(Vec<u8>, Vec<u8>),
+    /// An extension with its path and child.
+    Extension(Vec<u8>, Box<Node>),
+    /// A branch with its children.
+    Branch(Box<[Node; 16]>),
+}
+
+impl Node {
+    /// Decodes an RLP encoded node, whose children are either inlined or hashes.
+    fn decode(buf: &[u8]) -> Result<Self, RangeProofError> {
+        let items = list_items(buf).ok_or(RangeProofError::InvalidNode)?;
+        match items.as_slice() {
+            [children @ .., value] if children.len() == 16 => {
+                if !is_empty_reference(value) {
+                    return Err(RangeProofError::InvalidNode)
+                }
+                let mut branch: [Self; 16] = Default::default();
+                for (child, reference) in branch.iter_mut().zip(children) {
+                    *child = Self::decode_reference(reference)?;
+                }
+                Ok(Self::Branch(Box::new(branch)))
+            }
+            [path, child] => {
+                let path = string_payload(path).ok_or(RangeProofError::InvalidNode)?;
+                match decode_compact_path(path).ok_or(RangeProofError::InvalidNode)? {
+                    (path, true) => {
+                        let value = string_payload(child).ok_or(RangeProofError::InvalidNode)?;
+                        Ok(Self::Leaf(path, value.to_vec()))
+                    }
+                    (path, false) if !path.is_empty() => {
+                        Ok(Self::Extension(path, Box::new(Self::decode_reference(child)?)))
+                    }
+                    _ => Err(RangeProofError::InvalidNode),
+                }
+            }
+            _ => Err(RangeProofError::InvalidNode),
+        }
+    }
+
+    /// Decodes the reference to a child.
+    fn decode_reference(reference: &[u8]) -> Result<Self, RangeProofError> {
+        if is_empty_reference(reference) {
+            return Ok(Self::Empty)
+        }
+        if let Some(hash) = reference_hash(reference) {
+            return Ok(Self::Hash(hash))
+        }
+        // nodes shorter than a hash are inlined
+        if reference.len() < B256::len_bytes() {
+            return Self::decode(reference)
+        }
+        Err(RangeProofError::InvalidNode)
+    }
+
+    /// Replaces the hashes of the nodes on the path of the key with the nodes of the proof.
+    fn resolve(&mut self, key: &[u8], nodes: &HashMap<B256, &[u8]>) -> Result<(), RangeProofError> {
+        if let Self::Hash(hash) = *self {
+            *self = if hash == EMPTY_ROOT_HASH {
+                Self::Empty
+            } else {
+                Self::decode(nodes.get(&hash).ok_or(RangeProofError::MissingNode(hash))?)?
+            };
+        }
+
+        match self {
+            Self::Extension(path, child) if key.starts_with(path) => {
+                child.resolve(&key[path.len()..], nodes)
+            }
+            Self::Branch(children) => {
+                let (nibble, rest) = key.split_first().ok_or(RangeProofError::InvalidNode)?;
+                children[*nibble as usize].resolve(rest, nodes)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the value of the key, if it is in the resolved part of the trie.
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        match self {
+            Self::Leaf(path, value) if path == key => Some(value.as_slice()),
+            Self::Extension(path, child) => child.get(key.strip_prefix(path.as_slice())?),
+            Self::Branch(children) => {
+                let (nibble, rest) = key.split_first()?;
+                children[*nibble as usize].get(rest)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the trie contains a node to the right of the path of the key.
+    fn has_right_element(&self, key: &[u8]) -> bool {
+        match self {
+            Self::Branch(children) => {
+                let Some((nibble, rest)) = key.split_first() else { return false };
+                let nibble = *nibble as usize;
+                children[nibble + 1..].iter().any(|child| !matches!(child, Self::Empty)) ||
+                    children[nibble].has_right_element(rest)
+            }
+            Self::Extension(path, child) => match key.strip_prefix(path.as_slice()) {
+                Some(rest) => child.has_right_element(rest),
+                None => path.as_slice() > key,
+            },
+            Self::Leaf(path, _) => path.as_slice() > key,
+            Self::Empty | Self::Hash(_) => false,
+        }
+    }
+
+    /// Removes all references between the paths of the left and the right key, which must both be
+    /// resolved.
+    fn unset_internal(&mut self, left: &[u8], right: &[u8]) -> Result<(), RangeProofError> {
+        match self {
+            Self::Branch(children) => {
+                let (Some((&l, left)), Some((&r, right))) =
+                    (left.split_first(), right.split_first())
+                else {
+                    return Err(RangeProofError::InvalidNode)
+                };
+                let (l, r) = (l as usize, r as usize);
+
+                // step down until the paths fork
+                if l == r && !matches!(children[l], Self::Empty) {
+                    return children[l].unset_internal(left, right)
+                }
+                if l < r {
+                    children[l + 1..r].iter_mut().for_each(|child| *child = Self::Empty);
+                }
+                children[l].unset(left, false)?;
+                children[r].unset(right, true)
+            }
+            Self::Leaf(path, _) | Self::Extension(path, _) => {
+                let len = path.len();
+                match (compare_prefix(left, path), compare_prefix(right, path)) {
+                    (Ordering::Equal, Ordering::Equal) => match self {
+                        Self::Extension(_, child) => {
+                            child.unset_internal(&left[len..], &right[len..])
+                        }
+                        _ => Err(RangeProofError::UnorderedKeys),
+                    },
+                    // the node is entirely within the range
+                    (Ordering::Less, Ordering::Greater) => {
+                        *self = Self::Empty;
+                        Ok(())
+                    }
+                    // the leaf is an edge of the range
+                    (Ordering::Equal, Ordering::Greater) | (Ordering::Less, Ordering::Equal)
+                        if matches!(self, Self::Leaf(..)) =>
+                    {
+                        *self = Self::Empty;
+                        Ok(())
+                    }
+                    (Ordering::Equal, Ordering::Greater) => match self {
+                        Self::Extension(_, child) => child.unset(&left[len..], false),
+                        _ => Err(RangeProofError::InvalidNode),
+                    },
+                    (Ordering::Less, Ordering::Equal) => match self {
+                        Self::Extension(_, child) => child.unset(&right[len..], true),
+                        _ => Err(RangeProofError::InvalidNode),
+                    },
+                    _ => Err(RangeProofError::EmptyRange),
+                }
+            }
+            Self::Empty => Ok(()),
+            Self::Hash(_) => Err(RangeProofError::InvalidNode),
+        }
+    }
+
+    /// Removes all references to the left or the right of the path of the key, which must be
+    /// resolved.
+    fn unset(&mut self, key: &[u8], remove_left: bool) -> Result<(), RangeProofError> {
+        match self {
+            Self::Branch(children) => {
+                let (nibble, rest) = key.split_first().ok_or(RangeProofError::InvalidNode)?;
+                let nibble = *nibble as usize;
+                let removed =
+                    if remove_left { &mut children[..nibble] } else { &mut children[nibble + 1..] };
+                removed.iter_mut().for_each(|child| *child = Self::Empty);
+                children[nibble].unset(rest, remove_left)
+            }
+            Self::Leaf(path, _) | Self::Extension(path, _) if !key.starts_with(path) => {
+                // the node forks off the path, it is in the range if it is on the removed side
+                let ordering = path.as_slice().cmp(key);
+                if (remove_left && ordering.is_lt()) || (!remove_left && ordering.is_gt()) {
+                    *self = Self::Empty;
+                }
+                Ok(())
+            }
+            // the leaf at the edge is part of the range
+            Self::Leaf(..) => {
+                *self = Self::Empty;
+                Ok(())
+            }
+            Self::Extension(path, child) => child.unset(&key[path.len()..], remove_left),
+            Self::Empty => Ok(()),
+            Self::Hash(_) => Err(RangeProofError::InvalidNode),
+        }
+    }
+
+    /// Inserts the leaf into the resolved part of the trie.
+    fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<(), RangeProofError> {
+        match self {
+            Self::Empty => {
+                *self = Self::Leaf(key.to_vec(), value.to_vec());
+                Ok(())
+            }
+            Self::Leaf(path, existing) if *path == key => {
+                *existing = value.to_vec();
+                Ok(())
+            }
+            Self::Extension(path, child) if key.starts_with(path) => {
+                child.insert(&key[path.len()..], value)
+            }
+            Self::Leaf(path, _) | Self::Extension(path, _) => {
+                let common = path.iter().zip(key).take_while(|(a, b)| a == b).count();
+                if common >= path.len() || common >= key.len() {
+                    return Err(RangeProofError::InvalidNode)
+                }
+                let (existing_nibble, nibble) = (path[common] as usize, key[common] as usize);
+
+                let mut children: [Self; 16] = Default::default();
+                children[existing_nibble] = std::mem::take(self).strip_path(common + 1);
+                children[nibble] = Self::Leaf(key[common + 1..].to_vec(), value.to_vec());
+                let branch = Self::Branch(Box::new(children));
+                *self = if common == 0 {
+                    branch
+                } else {
+                    Self::Extension(key[..common].to_vec(), Box::new(branch))
+                };
+                Ok(())
+            }
+            Self::Branch(children) => {
+                let (nibble, rest) = key.split_first().ok_or(RangeProofError::InvalidNode)?;
+                children[*nibble as usize].insert(rest, value)
+            }
+            // the key is not covered by the proof
+            Self::Hash(_) => Err(RangeProofError::InvalidNode),
+        }
+    }
+
+    /// Removes the given number of nibbles from the start of the path of a leaf or an extension.
+    fn strip_path(self, len: usize) -> Self {
+        match self {
+            Self::Leaf(path, value) => Self::Leaf(path[len..].to_vec(), value),
+            Self::Extension(path, child) if path.len() == len => *child,
+            Self::Extension(path, child) => Self::Extension(path[len..].to_vec(), child),
+            node => node,
+        }
+    }
+
+    /// Returns the RLP encoding of the node, or `None` if it is empty or only known by its hash.
+    fn rlp(&self) -> Option<Vec<u8>> {
+        let mut payload = Vec::new();
+        match self {
+            Self::Empty | Self::Hash(_) => return None,
+            Self::Leaf(path, value) => {
+                let mut path = encode_compact_path(path).to_vec();
+                path[0] |= 0x20;
+                path.as_slice().encode(&mut payload);
+                value.as_slice().encode(&mut payload);
+            }
+            Self::Extension(path, child) => {
+                encode_compact_path(path).encode(&mut payload);
+                child.encode_reference(&mut payload);
+            }
+            Self::Branch(children) => {
+                for child in children.iter() {
+                    child.encode_reference(&mut payload);
+                }
+                payload.push(EMPTY_STRING_CODE);
+            }
+        }
+
+        let mut out = Vec::with_capacity(payload.len() + 3);
+        Header { list: true, payload_length: payload.len() }.encode(&mut out);
+        out.extend_from_slice(&payload);
+        Some(out)
+    }
+
+    /// Encodes the reference to the node as it appears in its parent.
+    fn encode_reference(&self, out: &mut Vec<u8>) {
+        match (self, self.rlp()) {
+            (Self::Hash(hash), _) => hash.encode(out),
+            (_, None) => out.push(EMPTY_STRING_CODE),
+            (_, Some(rlp)) if rlp.len() < B256::len_bytes() => out.extend_from_slice(&rlp),
+            (_, Some(rlp)) => keccak256(rlp).encode(out),
+        }
+    }
+
+    /// Returns the hash of the trie rooted at the node.
+    fn hash(&self) -> B256 {
+        match (self, self.rlp()) {
+            (Self::Hash(hash), _) => *hash,
+            (_, None) => EMPTY_ROOT_HASH,
+            (_, Some(rlp)) => keccak256(rlp),
+        }
+    }
+}
+
+/// Returns the items of an RLP list.
+fn list_items(buf: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut rest = buf;
+    let header = Header::decode(&mut rest).ok()?;
+    if !header.list || rest.len() != header.payload_length {
+        return None
+    }
+
+    let mut items = Vec::with_capacity(17);
+    while !rest.is_empty() {
+        let (item, remainder) = split_item(rest)?;
+        items.push(item);
+        rest = remainder;
+    }
+    Some(items)
+}
+
+/// Returns the payload of an RLP string.
+fn string_payload(item: &[u8]) -> Option<&[u8]> {
+    let mut payload = item;
+    let header = Header::decode(&mut payload).ok()?;
+    (!header.list && payload.len() == header.payload_length).then_some(payload)
+}
+
+/// Decodes a path in compact (hex-prefix) form, returning its nibbles and whether it is the path
+/// of a leaf.
+fn decode_compact_path(encoded: &[u8]) -> Option<(Vec<u8>, bool)> {
+    let (first, rest) = encoded.split_first()?;
+    let flags = first >> 4;
+    if flags > 3 {
+        return None
+    }
+
+    let mut path = Vec::with_capacity(rest.len() * 2 + 1);
+    if flags & 1 == 1 {
+        path.push(first & 0x0f);
+    }
+    path.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+    Some((path, flags & 2 == 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_primitives::trie::{HashBuilder, Nibbles};
+
+    /// Returns sorted leaves of a test trie.
+    fn leaves(len: u8) -> Vec<(B256, Vec<u8>)> {
+        let mut leaves = (0..len)
+            .map(|idx| (keccak256([idx]), alloy_rlp::encode(u64::from(idx) + 1)))
+            .collect::<Vec<_>>();
+        leaves.sort_unstable();
+        leaves
+    }
+
+    /// Returns the root of the trie with the given leaves and the proof of the given keys.
+    fn root_and_proof(leaves: &[(B256, Vec<u8>)], keys: &[B256]) -> (B256, Vec<Bytes>) {
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(keys.iter().map(Nibbles::unpack).collect());
+        for (key, value) in leaves {
+            hash_builder.add_leaf(Nibbles::unpack(key), value);
+        }
+        let root = hash_builder.root();
+        (root, hash_builder.take_proofs().into_values().collect())
+    }
+
+    #[test]
+    fn verify_ranges() {
+        let leaves = leaves(100);
+
+        // the entire trie without a proof
+        let (root, _) = root_and_proof(&leaves, &[]);
+        assert_eq!(verify_range_proof(root, B256::ZERO, &leaves, &[]), Ok(false));
+
+        // ranges in the middle and at the end of the trie
+        for (start, end) in [(0, 10), (10, 50), (50, 99), (98, 99)] {
+            let range = &leaves[start..=end];
+            let (_, proof) = root_and_proof(&leaves, &[range[0].0, range[range.len() - 1].0]);
+            assert_eq!(
+                verify_range_proof(root, range[0].0, range, &proof),
+                Ok(end < leaves.len() - 1),
+                "range {start}..={end}"
+            );
+        }
+
+        // a range starting at a non-existent origin
+        let range = &leaves[20..40];
+        let (_, proof) = root_and_proof(&leaves, &[B256::ZERO, range[range.len() - 1].0]);
+        assert_matches!(verify_range_proof(root, B256::ZERO, range, &proof), Err(_));
+        let range = &leaves[..40];
+        assert_eq!(verify_range_proof(root, B256::ZERO, range, &proof), Ok(true));
+
+        // a single leaf
+        let range = &leaves[30..31];
+        let (_, proof) = root_and_proof(&leaves, &[range[0].0]);
+        assert_eq!(verify_range_proof(root, range[0].0, range, &proof), Ok(true));
+
+        // no leaves after the origin
+        let origin = B256::repeat_byte(0xff);
+        let (_, proof) = root_and_proof(&leaves, &[origin]);
+        assert_eq!(verify_range_proof::<Vec<u8>>(root, origin, &[], &proof), Ok(false));
+        let (_, proof) = root_and_proof(&leaves, &[B256::ZERO]);
+        assert_eq!(
+            verify_range_proof::<Vec<u8>>(root, B256::ZERO, &[], &proof),
+            Err(RangeProofError::MoreKeys)
+        );
+    }
+
+    #[test]
+    fn reject_tampered_ranges() {
+        let leaves = leaves(100);
+        let range = &leaves[10..50];
+        let (root, proof) = root_and_proof(&leaves, &[range[0].0, range[range.len() - 1].0]);
+
+        // a modified value
+        let mut tampered = range.to_vec();
+        tampered[5].1 = alloy_rlp::encode(1000u64);
+        assert_matches!(
+            verify_range_proof(root, range[0].0, &tampered, &proof),
+            Err(RangeProofError::RootMismatch(_))
+        );
+
+        // a missing leaf
+        let mut tampered = range.to_vec();
+        tampered.remove(20);
+        assert_matches!(
+            verify_range_proof(root, range[0].0, &tampered, &proof),
+            Err(RangeProofError::RootMismatch(_))
+        );
+
+        // an additional leaf
+        let mut tampered = range.to_vec();
+        tampered.insert(1, (next_key(range[0].0), alloy_rlp::encode(1u64)));
+        assert_matches!(verify_range_proof(root, range[0].0, &tampered, &proof), Err(_));
+
+        // a missing proof node
+        assert_matches!(
+            verify_range_proof(root, range[0].0, range, &proof[1..]),
+            Err(RangeProofError::MissingNode(_))
+        );
+
+        // a partial range without a proof
+        assert_matches!(
+            verify_range_proof(root, range[0].0, range, &[]),
+            Err(RangeProofError::RootMismatch(_))
+        );
+
+        // unordered keys
+        let mut tampered = range.to_vec();
+        tampered.swap(3, 4);
+        assert_eq!(
+            verify_range_proof(root, range[0].0, &tampered, &proof),
+            Err(RangeProofError::UnorderedKeys)
+        );
+    }
+
+    fn next_key(key: B256) -> B256 {
+        let mut next = key;
+        next[31] = next[31].wrapping_add(1);
+        next
+    }
+
+    #[test]
+    fn compact_path_roundtrip() {
+        for path in [vec![], vec![0x1], vec![0x1, 0x2], vec![0xa, 0xb, 0xc]] {
+            let encoded = encode_compact_path(&path);
+            assert_eq!(decode_compact_path(&encoded), Some((path, false)));
+        }
+        assert_eq!(decode_compact_path(&[0x3a]), Some((vec![0xa], true)));
+        assert_eq!(decode_compact_path(&[0x40]), None);
+    }
+}
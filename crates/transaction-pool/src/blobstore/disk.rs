@@ -99,6 +99,10 @@ impl BlobStore for DiskFileBlobStore {
         stat
     }
 
+    fn hashes(&self) -> Result<Vec<B256>, BlobStoreError> {
+        Ok(self.inner.hashes()?)
+    }
+
     fn get(&self, tx: B256) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
         self.inner.get_one(tx)
     }
@@ -243,6 +247,22 @@ impl DiskFileBlobStoreInner {
         Ok(blob)
     }
 
+    /// Returns the hashes of all blob files on disk that are not scheduled for deletion.
+    fn hashes(&self) -> Result<Vec<B256>, DiskFileBlobStoreError> {
+        let entries = {
+            let _lock = self.file_lock.read();
+            fs::read_dir(&self.blob_dir)
+                .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+                .map_err(|e| DiskFileBlobStoreError::Open(self.blob_dir.clone(), e))?
+        };
+        let txs_to_delete = self.txs_to_delete.read();
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<B256>().ok())
+            .filter(|tx| !txs_to_delete.contains(tx))
+            .collect())
+    }
+
     /// Returns the path to the blob file for the given transaction hash.
     #[inline]
     fn blob_disk_file(&self, tx: B256) -> PathBuf {
@@ -496,4 +516,24 @@ mod tests {
         assert_eq!(store.data_size_hint(), Some(0));
         assert_eq!(store.inner.size_tracker.num_blobs.load(Ordering::Relaxed), 0);
     }
+
+    #[test]
+    fn disk_hashes() {
+        let (store, _dir) = tmp_store();
+
+        let blobs = rng_blobs(3);
+        let mut all_hashes = blobs.iter().map(|(tx, _)| *tx).collect::<Vec<_>>();
+        store.insert_all(blobs).unwrap();
+
+        let mut hashes = store.hashes().unwrap();
+        hashes.sort();
+        all_hashes.sort();
+        assert_eq!(hashes, all_hashes);
+
+        // blobs scheduled for deletion are excluded
+        store.delete(all_hashes[0]).unwrap();
+        let hashes = store.hashes().unwrap();
+        assert_eq!(hashes.len(), 2);
+        assert!(!hashes.contains(&all_hashes[0]));
+    }
 }
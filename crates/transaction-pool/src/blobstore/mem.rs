@@ -73,6 +73,10 @@ impl BlobStore for InMemoryBlobStore {
         BlobStoreCleanupStat::default()
     }
 
+    fn hashes(&self) -> Result<Vec<B256>, BlobStoreError> {
+        Ok(self.inner.store.read().keys().copied().collect())
+    }
+
     // Retrieves the decoded blob data for the given transaction hash.
     fn get(&self, tx: B256) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
         let store = self.inner.store.read();
@@ -41,6 +41,15 @@ pub trait BlobStore: fmt::Debug + Send + Sync + 'static {
     /// the store uses deferred cleanup: [DiskFileBlobStore]
     fn cleanup(&self) -> BlobStoreCleanupStat;
 
+    /// Returns the hashes of all transactions with a blob sidecar in the store.
+    ///
+    /// Sidecars that are already scheduled for deletion are not included.
+    ///
+    /// By default no hashes are returned, so no sidecars of the store are considered orphaned.
+    fn hashes(&self) -> Result<Vec<B256>, BlobStoreError> {
+        Ok(Vec::new())
+    }
+
     /// Retrieves the decoded blob data for the given transaction hash.
     fn get(&self, tx: B256) -> Result<Option<BlobTransactionSidecar>, BlobStoreError>;
 
@@ -46,6 +46,12 @@ impl BlobStoreCanonTracker {
         self.add_blocks(blob_txs);
     }
 
+    /// Returns `true` if the given blob transaction was included in a tracked block that is not
+    /// finalized yet.
+    pub fn contains(&self, tx: &B256) -> bool {
+        self.blob_txs_in_blocks.values().any(|txs| txs.contains(tx))
+    }
+
     /// Invoked when a block is finalized.
     ///
     /// This returns all blob transactions that were included in blocks that are now finalized.
@@ -92,7 +98,10 @@ mod tests {
         tracker.add_block(3, block3.clone());
 
         assert_eq!(tracker.on_finalized_block(0), BlobStoreUpdates::None);
-        assert_eq!(tracker.on_finalized_block(1), BlobStoreUpdates::Finalized(block1));
+        assert!(tracker.contains(&block1[0]));
+        assert_eq!(tracker.on_finalized_block(1), BlobStoreUpdates::Finalized(block1.clone()));
+        assert!(!tracker.contains(&block1[0]));
+        assert!(tracker.contains(&block2[0]));
         assert_eq!(
             tracker.on_finalized_block(3),
             BlobStoreUpdates::Finalized(block2.into_iter().chain(block3).collect::<Vec<_>>())
@@ -0,0 +1,145 @@
+//! Conditions of transactions submitted via `eth_sendRawTransactionConditional`.
+
+use reth_primitives::{Address, BlockNumber, B256};
+use reth_provider::{ProviderError, StateProvider};
+use std::collections::HashMap;
+
+/// The expected state of an account a conditional transaction depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnownAccountState {
+    /// The expected root of the account's storage trie.
+    StorageRoot(B256),
+    /// The expected values of individual storage slots of the account.
+    Slots(HashMap<B256, B256>),
+}
+
+/// Conditions that must hold for a transaction to be included in a block.
+///
+/// Transactions that violate their conditions are never included: they are skipped when building
+/// a block and removed from the pool once they can no longer be satisfied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionConditional {
+    /// The expected state of accounts at the parent of the including block.
+    pub known_accounts: HashMap<Address, KnownAccountState>,
+    /// The minimum number of the including block.
+    pub block_number_min: Option<BlockNumber>,
+    /// The maximum number of the including block.
+    pub block_number_max: Option<BlockNumber>,
+    /// The minimum timestamp of the including block.
+    pub timestamp_min: Option<u64>,
+    /// The maximum timestamp of the including block.
+    pub timestamp_max: Option<u64>,
+}
+
+impl TransactionConditional {
+    /// Returns the number of storage roots and slots that need to be checked to verify the
+    /// `known_accounts` condition.
+    pub fn cost(&self) -> usize {
+        self.known_accounts
+            .values()
+            .map(|state| match state {
+                KnownAccountState::StorageRoot(_) => 1,
+                KnownAccountState::Slots(slots) => slots.len(),
+            })
+            .sum()
+    }
+
+    /// Returns true if a block with the given number and timestamp satisfies the block number and
+    /// timestamp bounds.
+    pub fn is_within_block_bounds(&self, number: BlockNumber, timestamp: u64) -> bool {
+        self.block_number_min.map_or(true, |min| number >= min) &&
+            self.block_number_max.map_or(true, |max| number <= max) &&
+            self.timestamp_min.map_or(true, |min| timestamp >= min) &&
+            self.timestamp_max.map_or(true, |max| timestamp <= max)
+    }
+
+    /// Returns true if the upper bounds are already exceeded by a block with the given number and
+    /// timestamp, which means that no later block can satisfy them either.
+    pub fn is_expired(&self, number: BlockNumber, timestamp: u64) -> bool {
+        self.block_number_max.is_some_and(|max| number > max) ||
+            self.timestamp_max.is_some_and(|max| timestamp > max)
+    }
+
+    /// Returns true if the given state matches the `known_accounts` condition.
+    pub fn matches_state<S>(&self, state: &S) -> Result<bool, ProviderError>
+    where
+        S: StateProvider + ?Sized,
+    {
+        for (address, expected) in &self.known_accounts {
+            match expected {
+                KnownAccountState::StorageRoot(root) => {
+                    if state.proof(*address, &[])?.storage_root != *root {
+                        return Ok(false)
+                    }
+                }
+                KnownAccountState::Slots(slots) => {
+                    for (slot, value) in slots {
+                        let current = state.storage(*address, *slot)?.unwrap_or_default();
+                        if B256::from(current.to_be_bytes()) != *value {
+                            return Ok(false)
+                        }
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::U256;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    #[test]
+    fn block_bounds() {
+        let conditional = TransactionConditional {
+            block_number_min: Some(10),
+            block_number_max: Some(20),
+            timestamp_max: Some(1_000),
+            ..Default::default()
+        };
+
+        assert!(!conditional.is_within_block_bounds(9, 0));
+        assert!(conditional.is_within_block_bounds(10, 0));
+        assert!(conditional.is_within_block_bounds(20, 1_000));
+        assert!(!conditional.is_within_block_bounds(20, 1_001));
+
+        assert!(!conditional.is_expired(9, 0));
+        assert!(!conditional.is_expired(20, 1_000));
+        assert!(conditional.is_expired(21, 0));
+        assert!(conditional.is_expired(15, 1_001));
+    }
+
+    #[test]
+    fn known_account_slots() {
+        let address = Address::random();
+        let slot = B256::with_last_byte(1);
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage([(slot, U256::from(42))]),
+        );
+
+        let mut conditional = TransactionConditional::default();
+        conditional.known_accounts.insert(
+            address,
+            KnownAccountState::Slots(HashMap::from([(
+                slot,
+                B256::from(U256::from(42).to_be_bytes()),
+            )])),
+        );
+        assert_eq!(conditional.cost(), 1);
+        assert!(conditional.matches_state(&provider).unwrap());
+
+        conditional.known_accounts.insert(
+            address,
+            KnownAccountState::Slots(HashMap::from([(
+                slot,
+                B256::from(U256::from(43).to_be_bytes()),
+            )])),
+        );
+        assert!(!conditional.matches_state(&provider).unwrap());
+    }
+}
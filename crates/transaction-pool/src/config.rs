@@ -1,6 +1,11 @@
-use crate::{PoolSize, TransactionOrigin};
-use reth_primitives::{Address, EIP4844_TX_TYPE_ID};
-use std::collections::HashSet;
+use crate::{
+    ordering::{
+        BuiltinOrdering, ConfiguredOrdering, PriorityLaneOrdering, TransactionOrderingKind,
+    },
+    PoolSize, SubPool, TransactionOrigin,
+};
+use reth_primitives::{constants::eip4844::DATA_GAS_PER_BLOB, Address, EIP4844_TX_TYPE_ID};
+use std::{collections::HashSet, time::Duration};
 /// Guarantees max transactions for one sender, compatible with geth/erigon
 pub const TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER: usize = 16;
 
@@ -10,6 +15,20 @@ pub const TXPOOL_SUBPOOL_MAX_TXS_DEFAULT: usize = 10_000;
 /// The default maximum allowed size of the given subpool.
 pub const TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT: usize = 20;
 
+/// The default maximum number of blobs of all transactions in the blob sub-pool.
+pub const TXPOOL_BLOB_POOL_MAX_BLOBS_DEFAULT: usize = 2_048;
+
+/// The default maximum combined size of the blob sub-pool, including blob sidecars.
+pub const TXPOOL_BLOB_POOL_MAX_SIZE_MB_DEFAULT: usize = 320;
+
+/// The default number of blocks over which a blob transaction's `max_fee_per_blob_gas` must be
+/// able to satisfy the blob fee, see [BlobPoolConfig::fee_eviction_horizon].
+pub const TXPOOL_BLOB_POOL_FEE_EVICTION_HORIZON_DEFAULT: u64 = 64;
+
+/// The size (in bytes) accounted for each blob of a transaction in the blob sub-pool: the blob
+/// itself, its commitment and its proof.
+pub const BLOB_SIDECAR_SIZE_PER_BLOB: usize = DATA_GAS_PER_BLOB as usize + 48 + 48;
+
 /// Default price bump (in %) for the transaction pool underpriced check.
 pub const DEFAULT_PRICE_BUMP: u128 = 10;
 
@@ -29,6 +48,8 @@ pub struct PoolConfig {
     pub queued_limit: SubPoolLimit,
     /// Max number of transactions in the blob sub-pool
     pub blob_limit: SubPoolLimit,
+    /// Blob specific limits and eviction rules of the blob sub-pool.
+    pub blob_pool: BlobPoolConfig,
     /// Max number of executable transaction slots guaranteed per account
     pub max_account_slots: usize,
     /// Price bump (in %) for the transaction pool underpriced check.
@@ -36,6 +57,10 @@ pub struct PoolConfig {
     /// How to handle locally received transactions:
     /// [TransactionOrigin::Local](crate::TransactionOrigin).
     pub local_transactions_config: LocalTransactionConfig,
+    /// How long transactions may stay in the pool before they're evicted.
+    pub transaction_lifetimes: TransactionLifetimes,
+    /// How pending transactions are ordered for block production.
+    pub ordering: OrderingConfig,
 }
 
 impl PoolConfig {
@@ -56,10 +81,76 @@ impl Default for PoolConfig {
             basefee_limit: Default::default(),
             queued_limit: Default::default(),
             blob_limit: Default::default(),
+            blob_pool: Default::default(),
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bumps: Default::default(),
             local_transactions_config: Default::default(),
+            transaction_lifetimes: Default::default(),
+            ordering: Default::default(),
+        }
+    }
+}
+
+/// Selects the ordering of pending transactions of the default pools.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrderingConfig {
+    /// The built-in ordering to use.
+    pub kind: TransactionOrderingKind,
+    /// Senders whose transactions are ordered before all other transactions.
+    pub priority_senders: HashSet<Address>,
+}
+
+impl OrderingConfig {
+    /// Creates the configured ordering.
+    pub fn ordering<T>(&self) -> ConfiguredOrdering<T> {
+        PriorityLaneOrdering::new(BuiltinOrdering::new(self.kind), self.priority_senders.clone())
+    }
+}
+
+/// Maximum time transactions may stay in the pool before they're evicted, measured from their
+/// submission.
+///
+/// Transactions without a configured lifetime are never evicted because of their age.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionLifetimes {
+    /// Max lifetime of transactions in the pending sub-pool.
+    pub pending: Option<Duration>,
+    /// Max lifetime of transactions in the basefee sub-pool.
+    pub basefee: Option<Duration>,
+    /// Max lifetime of transactions in the queued sub-pool.
+    pub queued: Option<Duration>,
+    /// Max lifetime of transactions in the blob sub-pool.
+    pub blob: Option<Duration>,
+    /// Max lifetime of local transactions, regardless of the sub-pool that contains them.
+    ///
+    /// This only applies if local transactions are exempt from limits, see
+    /// [LocalTransactionConfig].
+    pub local: Option<Duration>,
+}
+
+impl TransactionLifetimes {
+    /// Returns the max lifetime of a transaction in the given sub-pool.
+    #[inline]
+    pub const fn max_lifetime(&self, subpool: SubPool, is_local: bool) -> Option<Duration> {
+        if is_local {
+            return self.local
         }
+        match subpool {
+            SubPool::Pending => self.pending,
+            SubPool::BaseFee => self.basefee,
+            SubPool::Queued => self.queued,
+            SubPool::Blob => self.blob,
+        }
+    }
+
+    /// Returns whether no lifetime is configured.
+    #[inline]
+    pub const fn is_unlimited(&self) -> bool {
+        self.pending.is_none() &&
+            self.basefee.is_none() &&
+            self.queued.is_none() &&
+            self.blob.is_none() &&
+            self.local.is_none()
     }
 }
 
@@ -95,6 +186,46 @@ impl Default for SubPoolLimit {
     }
 }
 
+/// Limits of the blob sub-pool that are expressed in blobs, and its fee based eviction rules.
+///
+/// These apply in addition to the [SubPoolLimit] of the blob sub-pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobPoolConfig {
+    /// Maximum number of blobs of all transactions in the blob sub-pool.
+    pub max_blobs: usize,
+    /// Maximum combined size (in bytes) of the transactions in the blob sub-pool and their blob
+    /// sidecars.
+    ///
+    /// Each blob accounts for [BLOB_SIDECAR_SIZE_PER_BLOB] bytes.
+    pub max_size: usize,
+    /// Number of blocks within which a blob transaction must be able to pay the blob fee.
+    ///
+    /// The blob fee can decrease by at most ~11% per block. Transactions with a
+    /// `max_fee_per_blob_gas` below the lowest blob fee reachable within this many blocks,
+    /// starting from the pending blob fee, are evicted from the blob sub-pool.
+    ///
+    /// If `None`, transactions are not evicted because of their blob fee cap.
+    pub fee_eviction_horizon: Option<u64>,
+}
+
+impl BlobPoolConfig {
+    /// Returns whether the blob count or size constraint is violated.
+    #[inline]
+    pub const fn is_exceeded(&self, blobs: usize, size: usize) -> bool {
+        self.max_blobs < blobs || self.max_size < size
+    }
+}
+
+impl Default for BlobPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_blobs: TXPOOL_BLOB_POOL_MAX_BLOBS_DEFAULT,
+            max_size: TXPOOL_BLOB_POOL_MAX_SIZE_MB_DEFAULT * 1024 * 1024,
+            fee_eviction_horizon: Some(TXPOOL_BLOB_POOL_FEE_EVICTION_HORIZON_DEFAULT),
+        }
+    }
+}
+
 /// Price bump config (in %) for the transaction pool underpriced check.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct PriceBumpConfig {
@@ -107,7 +238,7 @@ pub struct PriceBumpConfig {
 impl PriceBumpConfig {
     /// Returns the price bump required to replace the given transaction type.
     #[inline]
-    pub(crate) const fn price_bump(&self, tx_type: u8) -> u128 {
+    pub const fn price_bump(&self, tx_type: u8) -> u128 {
         if tx_type == EIP4844_TX_TYPE_ID {
             return self.replace_blob_tx_price_bump
         }
@@ -0,0 +1,731 @@
+//! Journal of all transactions in the pool, which restores the pool across restarts.
+//!
+//! The journal file consists of a sequence of RLP encoded [`JournalEntry`]s: a snapshot of the
+//! entire pool which is periodically rewritten, followed by the entries appended since the last
+//! snapshot. On startup the journal is replayed through the validator, preserving the origin,
+//! submission time and blob sidecars of the transactions.
+//!
+//! The sub-pool of a transaction is not journaled. Restored transactions are placed by the pool
+//! from the state at startup like any new transaction, so a transaction may end up in a different
+//! sub-pool than before the restart, e.g. if its sender's balance changed in the meantime.
+//!
+//! Conditional transactions are not journaled, since their conditions are only valid for a short
+//! time. Private transactions are the exception: their only condition is the block after which
+//! they expire, which is journaled together with them.
+
+use crate::{
+    maintain::load_and_reinsert_transactions, AllPoolTransactions, BlobStore, FullTransactionEvent,
+    Pool, PoolTransaction, TransactionConditional, TransactionOrdering, TransactionOrigin,
+    TransactionPool, TransactionValidator, ValidPoolTransaction,
+};
+use alloy_rlp::{BufMut, Decodable, Encodable, Header};
+use reth_primitives::{
+    fs::FsPathError, BlockNumber, FromRecoveredPooledTransaction, PooledTransactionsElement, TxHash,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, error, info, trace, warn};
+
+/// Default interval at which the journal is rewritten from a snapshot of the pool.
+pub const DEFAULT_JOURNAL_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Interval at which the buffered entries are appended to the journal.
+const JOURNAL_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Identifier of an inserted transaction entry.
+const INSERT_ENTRY: u8 = 0;
+/// Identifier of a removed transaction entry.
+const REMOVE_ENTRY: u8 = 1;
+
+/// Settings for the transaction pool journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolJournalConfig {
+    /// Path to the journal file.
+    pub path: PathBuf,
+    /// Interval at which the journal is rewritten from a snapshot of the pool.
+    pub snapshot_interval: Duration,
+    /// Path to the file of the local transactions backup, see
+    /// [`LocalTransactionBackupConfig`](crate::maintain::LocalTransactionBackupConfig).
+    ///
+    /// Local transactions that were backed up while the journal was disabled are reinserted on
+    /// startup and recorded in the journal, after which the backup file is removed.
+    pub local_transactions_backup: Option<PathBuf>,
+}
+
+impl PoolJournalConfig {
+    /// Creates a new config for a journal at the given path.
+    pub const fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            snapshot_interval: DEFAULT_JOURNAL_SNAPSHOT_INTERVAL,
+            local_transactions_backup: None,
+        }
+    }
+
+    /// Reinserts the local transactions backed up at the given path on startup.
+    pub fn with_local_transactions_backup(mut self, path: PathBuf) -> Self {
+        self.local_transactions_backup = Some(path);
+        self
+    }
+
+    /// Sets the interval at which the journal is rewritten from a snapshot of the pool.
+    pub const fn with_snapshot_interval(mut self, interval: Duration) -> Self {
+        self.snapshot_interval = interval;
+        self
+    }
+}
+
+/// Errors that can occur while reading or writing the journal.
+#[derive(Debug, thiserror::Error)]
+pub enum PoolJournalError {
+    /// Error accessing the journal file.
+    #[error("failed to access transaction pool journal: {0}")]
+    Io(#[from] std::io::Error),
+    /// Error reading the journal file.
+    #[error("failed to read transaction pool journal: {0}")]
+    FsPath(#[from] FsPathError),
+}
+
+/// A transaction recorded in the journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournaledTransaction {
+    /// Where the transaction originated from.
+    pub origin: TransactionOrigin,
+    /// Unix timestamp in milliseconds at which the transaction was submitted to the pool.
+    pub submitted_at: u64,
+    /// The transaction, including the blob sidecar of blob transactions.
+    pub transaction: PooledTransactionsElement,
+    /// The maximum number of the including block of private transactions.
+    pub max_block_number: Option<BlockNumber>,
+}
+
+impl JournaledTransaction {
+    /// Creates a new journal record for the pool transaction.
+    fn new<T: PoolTransaction>(
+        transaction: &ValidPoolTransaction<T>,
+        element: PooledTransactionsElement,
+    ) -> Self {
+        let submitted_at = SystemTime::now()
+            .checked_sub(transaction.timestamp.elapsed())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default()
+            .as_millis() as u64;
+        Self {
+            origin: transaction.origin,
+            submitted_at,
+            transaction: element,
+            max_block_number: transaction
+                .conditional()
+                .and_then(|conditional| conditional.block_number_max),
+        }
+    }
+
+    /// Returns the conditions the transaction was submitted with.
+    fn conditional(&self) -> Option<TransactionConditional> {
+        self.max_block_number
+            .map(|max| TransactionConditional { block_number_max: Some(max), ..Default::default() })
+    }
+
+    /// Returns the hash of the transaction.
+    pub fn hash(&self) -> &TxHash {
+        self.transaction.hash()
+    }
+
+    /// Returns the [`Instant`] corresponding to the submission time of the transaction.
+    fn submitted_instant(&self) -> Instant {
+        let submitted_at = UNIX_EPOCH + Duration::from_millis(self.submitted_at);
+        let elapsed = SystemTime::now().duration_since(submitted_at).unwrap_or_default();
+        Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now)
+    }
+}
+
+/// An entry of the journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalEntry {
+    /// A transaction was inserted into the pool.
+    Insert(JournaledTransaction),
+    /// A transaction was removed from the pool.
+    Remove(TxHash),
+}
+
+impl JournalEntry {
+    fn payload_length(&self) -> usize {
+        match self {
+            JournalEntry::Insert(tx) => {
+                INSERT_ENTRY.length() +
+                    origin_to_u8(tx.origin).length() +
+                    tx.submitted_at.length() +
+                    tx.transaction.length() +
+                    tx.max_block_number.map_or(0, |max| max.length())
+            }
+            JournalEntry::Remove(hash) => REMOVE_ENTRY.length() + hash.length(),
+        }
+    }
+}
+
+impl Encodable for JournalEntry {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        match self {
+            JournalEntry::Insert(tx) => {
+                INSERT_ENTRY.encode(out);
+                origin_to_u8(tx.origin).encode(out);
+                tx.submitted_at.encode(out);
+                tx.transaction.encode(out);
+                // optional trailing field
+                if let Some(max) = tx.max_block_number {
+                    max.encode(out);
+                }
+            }
+            JournalEntry::Remove(hash) => {
+                REMOVE_ENTRY.encode(out);
+                hash.encode(out);
+            }
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for JournalEntry {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let entry = match u8::decode(buf)? {
+            INSERT_ENTRY => JournalEntry::Insert(JournaledTransaction {
+                origin: origin_from_u8(u8::decode(buf)?)?,
+                submitted_at: u64::decode(buf)?,
+                transaction: PooledTransactionsElement::decode(buf)?,
+                max_block_number: if started_len - buf.len() < header.payload_length {
+                    Some(BlockNumber::decode(buf)?)
+                } else {
+                    None
+                },
+            }),
+            REMOVE_ENTRY => JournalEntry::Remove(TxHash::decode(buf)?),
+            _ => return Err(alloy_rlp::Error::Custom("unknown journal entry")),
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+        Ok(entry)
+    }
+}
+
+/// Returns the identifier of the [TransactionOrigin] used in the encoded entries.
+pub(crate) const fn origin_to_u8(origin: TransactionOrigin) -> u8 {
+    match origin {
+        TransactionOrigin::Local => 0,
+        TransactionOrigin::External => 1,
+        TransactionOrigin::Private => 2,
+    }
+}
+
+/// Returns the [TransactionOrigin] of the given identifier, see [origin_to_u8].
+pub(crate) const fn origin_from_u8(origin: u8) -> alloy_rlp::Result<TransactionOrigin> {
+    match origin {
+        0 => Ok(TransactionOrigin::Local),
+        1 => Ok(TransactionOrigin::External),
+        2 => Ok(TransactionOrigin::Private),
+        _ => Err(alloy_rlp::Error::Custom("unknown transaction origin")),
+    }
+}
+
+/// Returns true if the transaction can be recorded in the journal.
+///
+/// Conditional transactions are skipped, unless they are private transactions that only expire
+/// after a block.
+fn is_journaled<T: PoolTransaction>(transaction: &ValidPoolTransaction<T>) -> bool {
+    transaction.conditional().map_or(true, |conditional| {
+        transaction.origin.is_private() &&
+            *conditional ==
+                TransactionConditional {
+                    block_number_max: conditional.block_number_max,
+                    ..Default::default()
+                }
+    })
+}
+
+/// The journal file of the transaction pool.
+#[derive(Debug)]
+pub struct PoolJournal {
+    /// Path to the journal file.
+    path: PathBuf,
+    /// The journal file opened for appending, opened on the first append.
+    file: Option<File>,
+}
+
+impl PoolJournal {
+    /// Creates a new journal at the given path.
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path, file: None }
+    }
+
+    /// Reads all transactions recorded in the journal, ordered by their submission time.
+    ///
+    /// A corrupted tail, for example of an append that was interrupted by a crash, is skipped.
+    pub fn read(&self) -> Result<Vec<JournaledTransaction>, PoolJournalError> {
+        if !self.path.exists() {
+            return Ok(Vec::new())
+        }
+        let data = reth_primitives::fs::read(&self.path)?;
+        let mut buf = data.as_slice();
+
+        let mut transactions = HashMap::new();
+        while !buf.is_empty() {
+            match JournalEntry::decode(&mut buf) {
+                Ok(JournalEntry::Insert(tx)) => {
+                    transactions.insert(*tx.hash(), tx);
+                }
+                Ok(JournalEntry::Remove(hash)) => {
+                    transactions.remove(&hash);
+                }
+                Err(err) => {
+                    warn!(target: "txpool", %err, journal=?self.path, remaining=buf.len(), "Skipping corrupted transaction pool journal entries");
+                    break
+                }
+            }
+        }
+
+        let mut transactions = transactions.into_values().collect::<Vec<_>>();
+        transactions.sort_by_key(|tx| (tx.submitted_at, *tx.hash()));
+        Ok(transactions)
+    }
+
+    /// Replaces the journal with a snapshot of the given transactions.
+    pub fn write_snapshot(
+        &mut self,
+        transactions: impl IntoIterator<Item = JournaledTransaction>,
+    ) -> Result<(), PoolJournalError> {
+        let mut buf = Vec::new();
+        for tx in transactions {
+            JournalEntry::Insert(tx).encode(&mut buf);
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // write the snapshot to a temporary file first, so it's replaced atomically
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, buf)?;
+        self.file = None;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Appends the entries to the journal.
+    pub fn append(&mut self, entries: &[JournalEntry]) -> Result<(), PoolJournalError> {
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => {
+                let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+                self.file.insert(file)
+            }
+        };
+        let mut buf = Vec::with_capacity(entries.iter().map(Encodable::length).sum());
+        for entry in entries {
+            entry.encode(&mut buf);
+        }
+        file.write_all(&buf)?;
+        Ok(())
+    }
+}
+
+/// Returns the journal records of all transactions in the pool.
+fn pool_snapshot<V, T, S>(pool: &Pool<V, T, S>) -> Vec<JournaledTransaction>
+where
+    V: TransactionValidator,
+    T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
+    S: BlobStore,
+{
+    let AllPoolTransactions { pending, queued } = pool.all_transactions();
+    pending
+        .into_iter()
+        .chain(queued)
+        .filter(|tx| is_journaled(tx))
+        .filter_map(|tx| {
+            let element = pool.inner().to_pooled_transaction_element(&tx)?;
+            Some(JournaledTransaction::new(&tx, element))
+        })
+        .collect()
+}
+
+/// Writes a snapshot of the pool to the journal on a blocking thread.
+///
+/// Returns the hashes of the transactions in the snapshot.
+async fn write_pool_snapshot<V, T, S>(
+    pool: &Pool<V, T, S>,
+    journal: &mut PoolJournal,
+) -> HashSet<TxHash>
+where
+    V: TransactionValidator,
+    T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
+    S: BlobStore,
+{
+    let snapshot = pool_snapshot(pool);
+    let num_txs = snapshot.len();
+    let hashes = snapshot.iter().map(|tx| *tx.hash()).collect();
+
+    // the file opened for appending is replaced by the snapshot
+    journal.file = None;
+    let mut snapshot_journal = PoolJournal::new(journal.path.clone());
+    let result = tokio::task::spawn_blocking(move || snapshot_journal.write_snapshot(snapshot))
+        .await
+        .unwrap_or_else(|err| Err(std::io::Error::other(err).into()));
+    match result {
+        Ok(()) => {
+            debug!(target: "txpool", journal=?journal.path, %num_txs, "Wrote transaction pool journal snapshot");
+        }
+        Err(err) => {
+            warn!(target: "txpool", %err, journal=?journal.path, "Failed to write transaction pool journal snapshot");
+        }
+    }
+    hashes
+}
+
+/// Appends the buffered entries to the journal on a blocking thread.
+async fn append_entries(journal: &mut PoolJournal, entries: Vec<JournalEntry>) {
+    trace!(target: "txpool", num_entries = entries.len(), "Appending transaction pool journal entries");
+
+    // the journal is moved to the blocking thread together with its open file
+    let mut blocking_journal = std::mem::replace(journal, PoolJournal::new(journal.path.clone()));
+    let result = tokio::task::spawn_blocking(move || {
+        let result = blocking_journal.append(&entries);
+        (blocking_journal, result)
+    })
+    .await;
+    match result {
+        Ok((blocking_journal, result)) => {
+            *journal = blocking_journal;
+            if let Err(err) = result {
+                warn!(target: "txpool", %err, journal=?journal.path, "Failed to append to transaction pool journal");
+            }
+        }
+        Err(err) => {
+            warn!(target: "txpool", %err, journal=?journal.path, "Failed to append to transaction pool journal");
+        }
+    }
+}
+
+/// Validates the journaled transactions and reinserts them into the pool in the order they were
+/// submitted.
+///
+/// The sub-pool of each transaction is determined by the pool from the current state, like for
+/// any other new transaction.
+async fn reinsert_transactions<V, T, S>(
+    pool: &Pool<V, T, S>,
+    transactions: Vec<JournaledTransaction>,
+) where
+    V: TransactionValidator,
+    T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
+    S: BlobStore,
+{
+    let transactions = transactions
+        .into_iter()
+        .filter_map(|tx| {
+            let submitted_at = tx.submitted_instant();
+            let origin = tx.origin;
+            let conditional = tx.conditional();
+            let recovered = tx.transaction.try_into_ecrecovered().ok()?;
+            let transaction = V::Transaction::from_recovered_pooled_transaction(recovered);
+            Some((origin, submitted_at, transaction, conditional))
+        })
+        .collect::<Vec<_>>();
+    let num_txs = transactions.len();
+
+    let outcome = pool.add_transactions_with_timestamps(transactions).await;
+    let num_reinserted = outcome.iter().filter(|res| res.is_ok()).count();
+
+    info!(target: "txpool", %num_txs, %num_reinserted, "Reinserted transactions from transaction pool journal");
+}
+
+/// Task which records all transactions of the pool in a [`PoolJournal`].
+///
+/// Replays the journal on startup, then appends every inserted and removed transaction to it
+/// and rewrites it from a snapshot of the pool periodically and on shutdown. Entries are buffered
+/// and appended in batches, all file IO happens on blocking threads.
+///
+/// Insertions and removals are both derived from the pool's event stream, so they are appended
+/// in the order they happened.
+pub async fn journal_transactions_task<V, T, S>(
+    shutdown: reth_tasks::shutdown::GracefulShutdown,
+    pool: Pool<V, T, S>,
+    config: PoolJournalConfig,
+) where
+    V: TransactionValidator,
+    T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
+    S: BlobStore,
+{
+    let PoolJournalConfig { path, snapshot_interval, local_transactions_backup } = config;
+    let mut journal = PoolJournal::new(path);
+
+    let replay_journal = PoolJournal::new(journal.path.clone());
+    let replayed = tokio::task::spawn_blocking(move || replay_journal.read())
+        .await
+        .unwrap_or_else(|err| Err(std::io::Error::other(err).into()));
+    match replayed {
+        Ok(transactions) => reinsert_transactions(&pool, transactions).await,
+        Err(err) => error!(target: "txpool", %err, "Failed to replay transaction pool journal"),
+    }
+
+    // migrate the local transactions that were backed up while the journal was disabled
+    if let Some(backup_path) = local_transactions_backup {
+        if let Err(err) = load_and_reinsert_transactions(pool.clone(), &backup_path).await {
+            error!(target: "txpool", %err, "Failed to reinsert backed up local transactions");
+        }
+    }
+
+    // unbounded, so that no insertions or removals are missed
+    let mut events = pool.inner().add_unbounded_all_transactions_event_listener();
+
+    // compact the replayed journal
+    let mut journaled = write_pool_snapshot(&pool, &mut journal).await;
+
+    let mut interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + snapshot_interval,
+        snapshot_interval,
+    );
+    let mut flush_interval = tokio::time::interval(JOURNAL_FLUSH_INTERVAL);
+    let mut pending_entries = Vec::new();
+    let mut shutdown = std::pin::pin!(shutdown);
+
+    let graceful_guard = loop {
+        let entry = tokio::select! {
+            guard = &mut shutdown => break guard,
+            _ = interval.tick() => {
+                // the snapshot includes the changes of the buffered entries
+                pending_entries.clear();
+                journaled = write_pool_snapshot(&pool, &mut journal).await;
+                continue
+            }
+            _ = flush_interval.tick() => {
+                if !pending_entries.is_empty() {
+                    append_entries(&mut journal, std::mem::take(&mut pending_entries)).await;
+                }
+                continue
+            }
+            Some(event) = events.recv() => match event {
+                // new transactions are either pending or queued, moves between the sub-pools of
+                // journaled transactions are ignored
+                FullTransactionEvent::Pending(tx_hash) | FullTransactionEvent::Queued(tx_hash) => {
+                    if journaled.contains(&tx_hash) {
+                        continue
+                    }
+                    // the transaction may have been removed already, in which case it's skipped
+                    // together with its removal
+                    let Some(transaction) = pool.get(&tx_hash) else { continue };
+                    if !is_journaled(&transaction) {
+                        continue
+                    }
+                    let Some(element) = pool.inner().to_pooled_transaction_element(&transaction)
+                    else {
+                        continue
+                    };
+                    journaled.insert(tx_hash);
+                    JournalEntry::Insert(JournaledTransaction::new(&transaction, element))
+                }
+                FullTransactionEvent::Mined { tx_hash, .. } |
+                FullTransactionEvent::Discarded(tx_hash) |
+                FullTransactionEvent::Evicted(tx_hash) |
+                FullTransactionEvent::Invalid(tx_hash) => {
+                    if !journaled.remove(&tx_hash) {
+                        continue
+                    }
+                    JournalEntry::Remove(tx_hash)
+                }
+                FullTransactionEvent::Replaced { transaction, .. } => {
+                    if !journaled.remove(transaction.hash()) {
+                        continue
+                    }
+                    JournalEntry::Remove(*transaction.hash())
+                }
+                FullTransactionEvent::Propagated(_) => continue,
+            },
+        };
+
+        trace!(target: "txpool", ?entry, "Buffering transaction pool journal entry");
+        pending_entries.push(entry);
+    };
+
+    write_pool_snapshot(&pool, &mut journal).await;
+
+    drop(graceful_guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blobstore::InMemoryBlobStore, validate::EthTransactionValidatorBuilder,
+        CoinbaseTipOrdering, EthPooledTransaction,
+    };
+    use reth_primitives::{hex, MAINNET, U256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_tasks::TaskManager;
+
+    fn transaction() -> PooledTransactionsElement {
+        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+        PooledTransactionsElement::decode_enveloped(&mut &tx_bytes[..]).unwrap()
+    }
+
+    fn journaled(origin: TransactionOrigin, submitted_at: u64) -> JournaledTransaction {
+        JournaledTransaction {
+            origin,
+            submitted_at,
+            transaction: transaction(),
+            max_block_number: None,
+        }
+    }
+
+    #[test]
+    fn journal_entry_roundtrip() {
+        let entries = [
+            JournalEntry::Insert(journaled(TransactionOrigin::External, 1_700_000_000_000)),
+            JournalEntry::Insert(JournaledTransaction {
+                max_block_number: Some(100),
+                ..journaled(TransactionOrigin::Private, 1_700_000_000_000)
+            }),
+            JournalEntry::Remove(TxHash::random()),
+        ];
+        for entry in entries {
+            let mut buf = Vec::new();
+            entry.encode(&mut buf);
+            assert_eq!(buf.len(), entry.length());
+            assert_eq!(JournalEntry::decode(&mut buf.as_slice()).unwrap(), entry);
+        }
+    }
+
+    #[test]
+    fn read_journal_with_appended_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut journal = PoolJournal::new(temp_dir.path().join("txpool-journal.rlp"));
+        assert!(journal.read().unwrap().is_empty());
+
+        let tx = journaled(TransactionOrigin::External, 1);
+        journal.write_snapshot([tx.clone()]).unwrap();
+        assert_eq!(journal.read().unwrap(), vec![tx.clone()]);
+
+        journal.append(&[JournalEntry::Remove(*tx.hash())]).unwrap();
+        assert!(journal.read().unwrap().is_empty());
+
+        let tx = journaled(TransactionOrigin::Local, 2);
+        journal.append(&[JournalEntry::Insert(tx.clone())]).unwrap();
+        // interrupted append
+        OpenOptions::new().append(true).open(&journal.path).unwrap().write_all(&[0xf8]).unwrap();
+        assert_eq!(journal.read().unwrap(), vec![tx]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn appends_buffered_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut journal = PoolJournal::new(temp_dir.path().join("txpool-journal.rlp"));
+
+        let (tx, removed) = (journaled(TransactionOrigin::Local, 1), TxHash::random());
+        let entries = vec![JournalEntry::Insert(tx.clone()), JournalEntry::Remove(removed)];
+        append_entries(&mut journal, entries).await;
+        // the file stays open for the next batch
+        assert!(journal.file.is_some());
+        assert_eq!(journal.read().unwrap(), vec![tx.clone()]);
+
+        append_entries(&mut journal, vec![JournalEntry::Remove(*tx.hash())]).await;
+        assert!(journal.read().unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn restores_pool_from_journal() {
+        let provider = MockEthProvider::default();
+        let sender = hex!("1f9090aaE28b8a3dCeaDf281B0F12828e676c326").into();
+        provider.add_account(sender, ExtendedAccount::new(42, U256::MAX));
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, blob_store.clone());
+        let new_pool = || {
+            Pool::new(
+                validator.clone(),
+                CoinbaseTipOrdering::default(),
+                blob_store.clone(),
+                Default::default(),
+            )
+        };
+
+        let transaction = EthPooledTransaction::from_recovered_pooled_transaction(
+            transaction().try_into_ecrecovered().unwrap(),
+        );
+        let expiry = TransactionConditional { block_number_max: Some(100), ..Default::default() };
+        for (origin, conditional) in
+            [(TransactionOrigin::External, None), (TransactionOrigin::Private, Some(expiry))]
+        {
+            let pool = new_pool();
+            if let Some(conditional) = conditional.clone() {
+                pool.add_conditional_transaction(origin, transaction.clone(), conditional)
+                    .await
+                    .unwrap();
+            } else {
+                pool.add_transaction(origin, transaction.clone()).await.unwrap();
+            }
+            let snapshot = pool_snapshot(&pool);
+            assert_eq!(snapshot.len(), 1);
+
+            let restored = new_pool();
+            reinsert_transactions(&restored, snapshot).await;
+            let tx = restored.get(transaction.hash()).unwrap();
+            assert_eq!(tx.origin, origin);
+            assert_eq!(tx.conditional(), conditional.as_ref());
+            // submission time is preserved up to millisecond precision
+            let submitted = pool.get(transaction.hash()).unwrap().timestamp;
+            let drift = tx.timestamp.max(submitted) - tx.timestamp.min(submitted);
+            assert!(drift < Duration::from_secs(1));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn migrates_local_transactions_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let journal_path = temp_dir.path().join("txpool-journal.rlp");
+        let backup_path = temp_dir.path().join("txpool-transactions-backup.rlp");
+        let mut buf = Vec::new();
+        alloy_rlp::encode_list(&[transaction().into_transaction()], &mut buf);
+        std::fs::write(&backup_path, buf).unwrap();
+
+        let provider = MockEthProvider::default();
+        let sender = hex!("1f9090aaE28b8a3dCeaDf281B0F12828e676c326").into();
+        provider.add_account(sender, ExtendedAccount::new(42, U256::MAX));
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, blob_store.clone());
+        let pool =
+            Pool::new(validator, CoinbaseTipOrdering::default(), blob_store, Default::default());
+
+        let manager = TaskManager::new(tokio::runtime::Handle::current());
+        let config = PoolJournalConfig::new(journal_path.clone())
+            .with_local_transactions_backup(backup_path.clone());
+        manager.executor().spawn_critical_with_graceful_shutdown_signal("test task", |shutdown| {
+            journal_transactions_task(shutdown, pool.clone(), config)
+        });
+        manager.graceful_shutdown();
+
+        // the backed up transaction was moved into the journal
+        assert!(!backup_path.exists());
+        let journaled = PoolJournal::new(journal_path).read().unwrap();
+        assert_eq!(journaled.len(), 1);
+        assert_eq!(journaled[0].origin, TransactionOrigin::Local);
+        assert_eq!(journaled[0].hash(), transaction().hash());
+    }
+}
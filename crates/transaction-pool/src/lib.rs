@@ -154,22 +154,30 @@ use aquamarine as _;
 use reth_eth_wire::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash, U256};
 use reth_provider::StateProviderFactory;
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Instant};
 use tokio::sync::mpsc::Receiver;
 use tracing::{instrument, trace};
 
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
+    conditional::{KnownAccountState, TransactionConditional},
     config::{
-        LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-        REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-        TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+        BlobPoolConfig, LocalTransactionConfig, OrderingConfig, PoolConfig, PriceBumpConfig,
+        SubPoolLimit, TransactionLifetimes, BLOB_SIDECAR_SIZE_PER_BLOB, DEFAULT_PRICE_BUMP,
+        REPLACE_BLOB_PRICE_BUMP, TXPOOL_BLOB_POOL_FEE_EVICTION_HORIZON_DEFAULT,
+        TXPOOL_BLOB_POOL_MAX_BLOBS_DEFAULT, TXPOOL_BLOB_POOL_MAX_SIZE_MB_DEFAULT,
+        TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+        TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
-    ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
+    ordering::{
+        BuiltinOrdering, CoinbaseTipOrdering, ConfiguredOrdering, EffectiveFeeOrdering,
+        FifoOrdering, Priority, PriorityLaneOrdering, TransactionOrdering, TransactionOrderingKind,
+    },
     pool::{
-        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
-        TransactionEvent, TransactionEvents,
+        blob_tx_priority, fee_delta,
+        state::{SubPool, TxStateInfo},
+        AllTransactionsEvents, FullTransactionEvent, TransactionEvent, TransactionEvents,
     },
     traits::*,
     validate::{
@@ -179,13 +187,16 @@ pub use crate::{
 };
 
 pub mod error;
+pub mod journal;
 pub mod maintain;
 pub mod metrics;
 pub mod noop;
 pub mod pool;
+pub mod replay;
 pub mod validate;
 
 pub mod blobstore;
+mod conditional;
 mod config;
 mod identifier;
 mod ordering;
@@ -202,6 +213,14 @@ pub type EthTransactionPool<Client, S> = Pool<
     S,
 >;
 
+/// Type alias for the ethereum transaction pool that uses the ordering selected in
+/// [PoolConfig::ordering]
+pub type ConfiguredEthTransactionPool<Client, S> = Pool<
+    TransactionValidationTaskExecutor<EthTransactionValidator<Client, EthPooledTransaction>>,
+    ConfiguredOrdering<EthPooledTransaction>,
+    S,
+>;
+
 /// A shareable, generic, customizable `TransactionPool` implementation.
 #[derive(Debug)]
 pub struct Pool<V, T: TransactionOrdering, S> {
@@ -271,6 +290,33 @@ where
     pub fn is_exceeded(&self) -> bool {
         self.pool.is_exceeded()
     }
+
+    /// Validates the given transactions and adds them to the pool with the time they were
+    /// originally submitted at and their conditions, if any.
+    ///
+    /// This is used to restore transactions from the [journal](crate::journal).
+    pub async fn add_transactions_with_timestamps(
+        &self,
+        transactions: Vec<(
+            TransactionOrigin,
+            Instant,
+            V::Transaction,
+            Option<TransactionConditional>,
+        )>,
+    ) -> Vec<PoolResult<TxHash>> {
+        if transactions.is_empty() {
+            return Vec::new()
+        }
+        let validated = futures_util::future::join_all(transactions.into_iter().map(
+            |(origin, timestamp, tx, conditional)| async move {
+                let (_, outcome) = self.validate(origin, tx).await;
+                (origin, timestamp, outcome, conditional)
+            },
+        ))
+        .await;
+
+        self.pool.add_transactions_with_timestamps(validated)
+    }
 }
 
 impl<Client, S> EthTransactionPool<Client, S>
@@ -315,6 +361,26 @@ where
     }
 }
 
+impl<Client, S> ConfiguredEthTransactionPool<Client, S>
+where
+    Client: StateProviderFactory + reth_provider::BlockReaderIdExt + Clone + 'static,
+    S: BlobStore,
+{
+    /// Returns a new [Pool] that uses the default [TransactionValidationTaskExecutor] when
+    /// validating [EthPooledTransaction]s and orders them via the ordering selected in
+    /// [PoolConfig::ordering]
+    pub fn configured_eth_pool(
+        validator: TransactionValidationTaskExecutor<
+            EthTransactionValidator<Client, EthPooledTransaction>,
+        >,
+        blob_store: S,
+        config: PoolConfig,
+    ) -> Self {
+        let ordering = config.ordering.ordering();
+        Self::new(validator, ordering, blob_store, config)
+    }
+}
+
 /// implements the `TransactionPool` interface for various transaction pool API consumers.
 impl<V, T, S> TransactionPool for Pool<V, T, S>
 where
@@ -351,6 +417,16 @@ where
         results.pop().expect("result length is the same as the input")
     }
 
+    async fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let (_, tx) = self.validate(origin, transaction).await;
+        self.pool.add_conditional_transaction(origin, tx, conditional)
+    }
+
     async fn add_transactions(
         &self,
         origin: TransactionOrigin,
@@ -368,8 +444,11 @@ where
         self.pool.add_transaction_event_listener(tx_hash)
     }
 
-    fn all_transactions_event_listener(&self) -> AllTransactionsEvents<Self::Transaction> {
-        self.pool.add_all_transactions_event_listener()
+    fn all_transactions_event_listener_for(
+        &self,
+        kind: TransactionListenerKind,
+    ) -> AllTransactionsEvents<Self::Transaction> {
+        self.pool.add_all_transactions_event_listener(kind)
     }
 
     fn pending_transactions_listener_for(&self, kind: TransactionListenerKind) -> Receiver<TxHash> {
@@ -450,6 +529,10 @@ where
         self.pool.all_transactions()
     }
 
+    fn conditional_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.conditional_transactions()
+    }
+
     fn remove_transactions(
         &self,
         hashes: Vec<TxHash>,
@@ -457,6 +540,20 @@ where
         self.pool.remove_transactions(hashes)
     }
 
+    fn remove_transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.remove_transactions_by_sender(sender)
+    }
+
+    fn remove_transactions_by_subpool(
+        &self,
+        subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.remove_transactions_by_subpool(subpool)
+    }
+
     fn retain_unknown<A>(&self, announcement: &mut A)
     where
         A: HandleMempoolData,
@@ -464,6 +561,10 @@ where
         self.pool.retain_unknown(announcement)
     }
 
+    fn transaction_state(&self, tx_hash: TxHash) -> Option<TxStateInfo> {
+        self.pool.transaction_state(tx_hash)
+    }
+
     fn get(&self, tx_hash: &TxHash) -> Option<Arc<ValidPoolTransaction<Self::Transaction>>> {
         self.inner().get(tx_hash)
     }
@@ -543,6 +644,10 @@ where
         self.pool.update_accounts(accounts);
     }
 
+    fn evict_stale_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.evict_stale_transactions()
+    }
+
     fn delete_blob(&self, tx: TxHash) {
         self.pool.delete_blob(tx)
     }
@@ -554,6 +659,10 @@ where
     fn cleanup_blobs(&self) {
         self.pool.cleanup_blobs()
     }
+
+    fn orphaned_blobs(&self) -> Vec<TxHash> {
+        self.pool.orphaned_blobs()
+    }
 }
 
 impl<V, T: TransactionOrdering, S> Clone for Pool<V, T, S> {
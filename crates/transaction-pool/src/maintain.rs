@@ -4,8 +4,10 @@ use crate::{
     blobstore::{BlobStoreCanonTracker, BlobStoreUpdates},
     error::PoolError,
     metrics::MaintainPoolMetrics,
-    traits::{CanonicalStateUpdate, ChangedAccount, TransactionPool, TransactionPoolExt},
-    BlockInfo,
+    traits::{
+        CanonicalStateUpdate, ChangedAccount, PoolTransaction, TransactionPool, TransactionPoolExt,
+    },
+    BlockInfo, ValidPoolTransaction,
 };
 use futures_util::{
     future::{BoxFuture, Fuse, FusedFuture},
@@ -14,7 +16,7 @@ use futures_util::{
 use reth_primitives::{
     fs::FsPathError, Address, BlockHash, BlockNumber, BlockNumberOrTag,
     FromRecoveredPooledTransaction, IntoRecoveredTransaction, PooledTransactionsElementEcRecovered,
-    TransactionSigned, TryFromRecoveredTransaction,
+    TransactionSigned, TryFromRecoveredTransaction, TxHash,
 };
 use reth_provider::{
     BlockReaderIdExt, BundleStateWithReceipts, CanonStateNotification, ChainSpecProvider,
@@ -26,6 +28,8 @@ use std::{
     collections::HashSet,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, trace, warn};
@@ -42,11 +46,27 @@ pub struct MaintainPoolConfig {
     ///
     /// Default: 100
     pub max_reload_accounts: usize,
+    /// Interval at which transactions that exceeded their configured lifetime are evicted.
+    ///
+    /// Default: 60s
+    pub stale_eviction_interval: Duration,
+    /// Interval at which the blob store is checked for sidecars that don't belong to any
+    /// transaction in the pool or any mined, not yet finalized block.
+    ///
+    /// Sidecars are deleted if they're orphaned in two consecutive checks.
+    ///
+    /// Default: 5min
+    pub blob_store_check_interval: Duration,
 }
 
 impl Default for MaintainPoolConfig {
     fn default() -> Self {
-        Self { max_update_depth: 64, max_reload_accounts: 100 }
+        Self {
+            max_update_depth: 64,
+            max_reload_accounts: 100,
+            stale_eviction_interval: Duration::from_secs(60),
+            blob_store_check_interval: Duration::from_secs(5 * 60),
+        }
     }
 }
 
@@ -100,7 +120,12 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     Tasks: TaskSpawner + 'static,
 {
     let metrics = MaintainPoolMetrics::default();
-    let MaintainPoolConfig { max_update_depth, max_reload_accounts, .. } = config;
+    let MaintainPoolConfig {
+        max_update_depth,
+        max_reload_accounts,
+        stale_eviction_interval,
+        blob_store_check_interval,
+    } = config;
     // ensure the pool points to latest state
     if let Ok(Some(latest)) = client.header_by_number_or_tag(BlockNumberOrTag::Latest) {
         let latest = latest.seal_slow();
@@ -132,6 +157,24 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     // the future that reloads accounts from state
     let mut reload_accounts_fut = Fuse::terminated();
 
+    // periodically evicts transactions that exceeded their lifetime
+    let mut stale_eviction_interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + stale_eviction_interval,
+        stale_eviction_interval,
+    );
+
+    // periodically checks the blob store for sidecars without a transaction
+    let mut blob_store_check_interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + blob_store_check_interval,
+        blob_store_check_interval,
+    );
+
+    // sidecars that were orphaned during the last blob store check
+    let mut orphaned_blobs = HashSet::new();
+
+    // the future that lists the orphaned sidecars of the blob store
+    let mut orphaned_blobs_fut = Fuse::terminated();
+
     // The update loop that waits for new blocks and reorgs and performs pool updated
     // Listen for new chain events and derive the update action for the pool
     loop {
@@ -219,6 +262,41 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 }
                 event = ev;
             }
+            _ = stale_eviction_interval.tick() => {
+                let evicted = pool.evict_stale_transactions();
+                if !evicted.is_empty() {
+                    debug!(target: "txpool", num_txs=%evicted.len(), "evicted stale transactions");
+                    metrics.inc_evicted_stale_transactions(evicted.len());
+                }
+            }
+            _ = blob_store_check_interval.tick() => {
+                // listing the sidecars of the blob store may hit the disk
+                if orphaned_blobs_fut.is_terminated() {
+                    let (tx, rx) = oneshot::channel();
+                    let pool = pool.clone();
+                    orphaned_blobs_fut = rx.fuse();
+                    task_spawner.spawn_blocking(Box::pin(async move {
+                        let _ = tx.send(pool.orphaned_blobs());
+                    }));
+                }
+            }
+            Ok(orphaned) = &mut orphaned_blobs_fut => {
+                let orphaned = orphaned
+                    .into_iter()
+                    .filter(|tx| !blob_store_tracker.contains(tx))
+                    .collect::<HashSet<_>>();
+
+                // only delete sidecars that were already orphaned during the previous check, so
+                // that sidecars of transactions that are currently being inserted are kept
+                let to_delete =
+                    orphaned.intersection(&orphaned_blobs).copied().collect::<Vec<_>>();
+                if !to_delete.is_empty() {
+                    debug!(target: "txpool", num_blobs=%to_delete.len(), "deleting orphaned blob sidecars");
+                    metrics.inc_deleted_orphaned_blobs(to_delete.len());
+                    pool.delete_blobs(to_delete);
+                }
+                orphaned_blobs = orphaned;
+            }
         }
 
         // handle the result of the account reload
@@ -353,6 +431,17 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 };
                 pool.on_canonical_state_change(update);
 
+                // drop conditional transactions that can no longer be included
+                remove_violated_conditional_transactions(
+                    &client,
+                    &pool,
+                    &task_spawner,
+                    &metrics,
+                    new_tip.hash(),
+                    new_tip.number,
+                    new_tip.timestamp,
+                );
+
                 // all transactions that were mined in the old chain but not in the new chain need
                 // to be re-injected
                 //
@@ -434,6 +523,17 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 };
                 pool.on_canonical_state_change(update);
 
+                // drop conditional transactions that can no longer be included
+                remove_violated_conditional_transactions(
+                    &client,
+                    &pool,
+                    &task_spawner,
+                    &metrics,
+                    tip.hash(),
+                    tip.number,
+                    tip.timestamp,
+                );
+
                 // keep track of mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&blocks);
             }
@@ -553,6 +653,86 @@ where
     Ok(res)
 }
 
+/// Removes all conditional transactions from the pool whose conditions are violated by the state
+/// at the given tip, or whose bounds can't be satisfied by any block after the tip.
+///
+/// The conditions are checked on a blocking task, because checking storage roots requires state
+/// lookups.
+fn remove_violated_conditional_transactions<Client, P, Tasks>(
+    client: &Client,
+    pool: &P,
+    task_spawner: &Tasks,
+    metrics: &MaintainPoolMetrics,
+    tip_hash: BlockHash,
+    tip_number: BlockNumber,
+    tip_timestamp: u64,
+) where
+    Client: StateProviderFactory + Clone + 'static,
+    P: TransactionPool + 'static,
+    Tasks: TaskSpawner,
+{
+    let transactions = pool.conditional_transactions();
+    if transactions.is_empty() {
+        return
+    }
+
+    let client = client.clone();
+    let pool = pool.clone();
+    let metrics = metrics.clone();
+    task_spawner.spawn_blocking(Box::pin(async move {
+        let violated = violated_conditional_transactions(
+            &client,
+            transactions,
+            tip_hash,
+            tip_number,
+            tip_timestamp,
+        );
+        metrics.inc_removed_conditional_transactions(violated.len());
+        pool.remove_transactions(violated);
+    }));
+}
+
+/// Returns the hashes of the given conditional transactions whose conditions are violated by the
+/// state at the given tip, or whose bounds can't be satisfied by any block after the tip.
+fn violated_conditional_transactions<Client, T>(
+    client: &Client,
+    transactions: Vec<Arc<ValidPoolTransaction<T>>>,
+    tip_hash: BlockHash,
+    tip_number: BlockNumber,
+    tip_timestamp: u64,
+) -> Vec<TxHash>
+where
+    Client: StateProviderFactory,
+    T: PoolTransaction,
+{
+    let state = match client.history_by_block_hash(tip_hash) {
+        Ok(state) => state,
+        Err(err) => {
+            debug!(target: "txpool", %err, ?tip_hash, "failed to check conditional transactions");
+            return Vec::new()
+        }
+    };
+
+    transactions
+        .into_iter()
+        .filter(|tx| {
+            let Some(conditional) = tx.conditional() else { return false };
+            // the next block has a higher timestamp than the tip
+            if conditional.is_expired(tip_number + 1, tip_timestamp + 1) {
+                return true
+            }
+            match conditional.matches_state(&*state) {
+                Ok(matches) => !matches,
+                Err(err) => {
+                    debug!(target: "txpool", %err, tx=?tx.hash(), "failed to check known accounts");
+                    false
+                }
+            }
+        })
+        .map(|tx| *tx.hash())
+        .collect()
+}
+
 /// Extracts all changed accounts from the BundleState
 fn changed_accounts_iter(
     state: &BundleStateWithReceipts,
@@ -566,7 +746,7 @@ fn changed_accounts_iter(
 /// Loads transactions from a file, decodes them from the RLP format, and inserts them
 /// into the transaction pool on node boot up.
 /// The file is removed after the transactions have been successfully processed.
-async fn load_and_reinsert_transactions<P>(
+pub(crate) async fn load_and_reinsert_transactions<P>(
     pool: P,
     file_path: &Path,
 ) -> Result<(), TransactionsBackupError>
@@ -58,7 +58,7 @@ pub struct BlobStoreMetrics {
 }
 
 /// Transaction pool maintenance metrics
-#[derive(Metrics)]
+#[derive(Clone, Metrics)]
 #[metrics(scope = "transaction_pool")]
 pub struct MaintainPoolMetrics {
     /// Number of currently dirty addresses that need to be updated in the pool by fetching account
@@ -70,6 +70,12 @@ pub struct MaintainPoolMetrics {
     pub(crate) reinserted_transactions: Counter,
     /// Number of transactions finalized blob transactions we were tracking.
     pub(crate) deleted_tracked_finalized_blobs: Counter,
+    /// Number of transactions evicted because they exceeded their lifetime.
+    pub(crate) evicted_stale_transactions: Counter,
+    /// Number of blob sidecars deleted because they didn't belong to any tracked transaction.
+    pub(crate) deleted_orphaned_blobs: Counter,
+    /// Number of conditional transactions removed because their conditions were violated.
+    pub(crate) removed_conditional_transactions: Counter,
 }
 
 impl MaintainPoolMetrics {
@@ -88,6 +94,21 @@ impl MaintainPoolMetrics {
         self.deleted_tracked_finalized_blobs.increment(count as u64);
     }
 
+    #[inline]
+    pub(crate) fn inc_evicted_stale_transactions(&self, count: usize) {
+        self.evicted_stale_transactions.increment(count as u64);
+    }
+
+    #[inline]
+    pub(crate) fn inc_deleted_orphaned_blobs(&self, count: usize) {
+        self.deleted_orphaned_blobs.increment(count as u64);
+    }
+
+    #[inline]
+    pub(crate) fn inc_removed_conditional_transactions(&self, count: usize) {
+        self.removed_conditional_transactions.increment(count as u64);
+    }
+
     #[inline]
     pub(crate) fn inc_drift(&self) {
         self.drift_count.increment(1);
@@ -1,6 +1,9 @@
 use crate::traits::PoolTransaction;
-use reth_primitives::U256;
-use std::{fmt, marker::PhantomData};
+use reth_primitives::{
+    constants::eip4844::{BLOB_TX_MIN_BLOB_GASPRICE, DATA_GAS_PER_BLOB},
+    Address, U256,
+};
+use std::{collections::HashSet, fmt, marker::PhantomData, str::FromStr, sync::Arc};
 
 /// Priority of the transaction that can be missing.
 ///
@@ -39,6 +42,21 @@ pub trait TransactionOrdering: Send + Sync + 'static {
         transaction: &Self::Transaction,
         base_fee: u64,
     ) -> Priority<Self::PriorityValue>;
+
+    /// Returns the priority score for the given transaction, given the blob fee of the pending
+    /// block as well.
+    ///
+    /// This is what the pending sub-pool ranks its transactions by. By default the blob fee is
+    /// ignored.
+    fn priority_with_blob_fee(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+        blob_fee: u128,
+    ) -> Priority<Self::PriorityValue> {
+        let _ = blob_fee;
+        self.priority(transaction, base_fee)
+    }
 }
 
 /// Default ordering for the pool.
@@ -79,3 +97,380 @@ impl<T> Clone for CoinbaseTipOrdering<T> {
         Self::default()
     }
 }
+
+/// Orders transactions by their arrival.
+///
+/// All transactions that can pay the base fee have the same priority, so the pending sub-pool
+/// yields them in the order they became pending.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct FifoOrdering<T>(PhantomData<T>);
+
+impl<T> TransactionOrdering for FifoOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        transaction.effective_tip_per_gas(base_fee).map(|_| U256::ZERO).into()
+    }
+}
+
+impl<T> Default for FifoOrdering<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T> Clone for FifoOrdering<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// Orders transactions by the total fee they pay on top of the base fee.
+///
+/// Unlike [CoinbaseTipOrdering], which compares the tip per gas, this ranks a transaction by its
+/// effective tip times its gas limit. Blob transactions additionally count the blob fee of the
+/// pending block for their blob gas, so they are ranked by the block space they pay for.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct EffectiveFeeOrdering<T>(PhantomData<T>);
+
+impl<T> TransactionOrdering for EffectiveFeeOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    /// Assumes the minimum blob fee, see [Self::priority_with_blob_fee].
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        self.priority_with_blob_fee(transaction, base_fee, BLOB_TX_MIN_BLOB_GASPRICE)
+    }
+
+    fn priority_with_blob_fee(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+        blob_fee: u128,
+    ) -> Priority<Self::PriorityValue> {
+        transaction
+            .effective_tip_per_gas(base_fee)
+            .map(|tip| {
+                let blob_gas = transaction.blob_count() as u64 * DATA_GAS_PER_BLOB;
+                U256::from(tip) * U256::from(transaction.gas_limit()) +
+                    U256::from(blob_fee) * U256::from(blob_gas)
+            })
+            .into()
+    }
+}
+
+impl<T> Default for EffectiveFeeOrdering<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T> Clone for EffectiveFeeOrdering<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// Wraps another ordering and ranks the transactions of a set of priority senders before all
+/// other transactions.
+///
+/// Within each lane, transactions are ordered by the inner ordering.
+#[derive(Debug, Clone)]
+pub struct PriorityLaneOrdering<O> {
+    /// The ordering within each lane.
+    inner: O,
+    /// The senders whose transactions are ranked first.
+    senders: Arc<HashSet<Address>>,
+}
+
+impl<O> PriorityLaneOrdering<O> {
+    /// Creates a new ordering that ranks the transactions of the given senders first.
+    pub fn new(inner: O, senders: impl IntoIterator<Item = Address>) -> Self {
+        Self { inner, senders: Arc::new(senders.into_iter().collect()) }
+    }
+
+    /// Returns the senders whose transactions are ranked first.
+    pub fn senders(&self) -> &HashSet<Address> {
+        &self.senders
+    }
+
+    /// Pairs the priority of the inner ordering with whether the sender is in the priority lane.
+    fn in_lane(
+        &self,
+        transaction: &O::Transaction,
+        priority: Priority<O::PriorityValue>,
+    ) -> Priority<(bool, O::PriorityValue)>
+    where
+        O: TransactionOrdering,
+    {
+        match priority {
+            Priority::Value(value) => {
+                Priority::Value((self.senders.contains(&transaction.sender()), value))
+            }
+            Priority::None => Priority::None,
+        }
+    }
+}
+
+impl<O> TransactionOrdering for PriorityLaneOrdering<O>
+where
+    O: TransactionOrdering,
+{
+    type PriorityValue = (bool, O::PriorityValue);
+    type Transaction = O::Transaction;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        self.in_lane(transaction, self.inner.priority(transaction, base_fee))
+    }
+
+    fn priority_with_blob_fee(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+        blob_fee: u128,
+    ) -> Priority<Self::PriorityValue> {
+        self.in_lane(
+            transaction,
+            self.inner.priority_with_blob_fee(transaction, base_fee, blob_fee),
+        )
+    }
+}
+
+/// The built-in orderings that can be selected at runtime, see [BuiltinOrdering].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionOrderingKind {
+    /// Order by coinbase tip, see [CoinbaseTipOrdering].
+    #[default]
+    CoinbaseTip,
+    /// Order by arrival, see [FifoOrdering].
+    Fifo,
+    /// Order by effective fee per gas, see [EffectiveFeeOrdering].
+    EffectiveFee,
+}
+
+impl FromStr for TransactionOrderingKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "coinbase-tip" => Ok(Self::CoinbaseTip),
+            "fifo" => Ok(Self::Fifo),
+            "effective-fee" => Ok(Self::EffectiveFee),
+            _ => Err(format!(
+                "unknown transaction ordering {s}, expected one of coinbase-tip, fifo, effective-fee"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TransactionOrderingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CoinbaseTip => f.write_str("coinbase-tip"),
+            Self::Fifo => f.write_str("fifo"),
+            Self::EffectiveFee => f.write_str("effective-fee"),
+        }
+    }
+}
+
+/// One of the built-in orderings, selected at runtime.
+#[derive(Debug)]
+pub enum BuiltinOrdering<T> {
+    /// See [CoinbaseTipOrdering].
+    CoinbaseTip(CoinbaseTipOrdering<T>),
+    /// See [FifoOrdering].
+    Fifo(FifoOrdering<T>),
+    /// See [EffectiveFeeOrdering].
+    EffectiveFee(EffectiveFeeOrdering<T>),
+}
+
+impl<T> BuiltinOrdering<T> {
+    /// Creates the ordering of the given kind.
+    pub fn new(kind: TransactionOrderingKind) -> Self {
+        match kind {
+            TransactionOrderingKind::CoinbaseTip => Self::CoinbaseTip(Default::default()),
+            TransactionOrderingKind::Fifo => Self::Fifo(Default::default()),
+            TransactionOrderingKind::EffectiveFee => Self::EffectiveFee(Default::default()),
+        }
+    }
+}
+
+impl<T> Clone for BuiltinOrdering<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::CoinbaseTip(ordering) => Self::CoinbaseTip(ordering.clone()),
+            Self::Fifo(ordering) => Self::Fifo(ordering.clone()),
+            Self::EffectiveFee(ordering) => Self::EffectiveFee(ordering.clone()),
+        }
+    }
+}
+
+impl<T> Default for BuiltinOrdering<T> {
+    fn default() -> Self {
+        Self::new(TransactionOrderingKind::default())
+    }
+}
+
+impl<T> TransactionOrdering for BuiltinOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        match self {
+            Self::CoinbaseTip(ordering) => ordering.priority(transaction, base_fee),
+            Self::Fifo(ordering) => ordering.priority(transaction, base_fee),
+            Self::EffectiveFee(ordering) => ordering.priority(transaction, base_fee),
+        }
+    }
+
+    fn priority_with_blob_fee(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+        blob_fee: u128,
+    ) -> Priority<Self::PriorityValue> {
+        match self {
+            Self::CoinbaseTip(ordering) => {
+                ordering.priority_with_blob_fee(transaction, base_fee, blob_fee)
+            }
+            Self::Fifo(ordering) => {
+                ordering.priority_with_blob_fee(transaction, base_fee, blob_fee)
+            }
+            Self::EffectiveFee(ordering) => {
+                ordering.priority_with_blob_fee(transaction, base_fee, blob_fee)
+            }
+        }
+    }
+}
+
+/// The ordering used by the default pools, which is configured through
+/// [OrderingConfig](crate::OrderingConfig).
+pub type ConfiguredOrdering<T> = PriorityLaneOrdering<BuiltinOrdering<T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockTransaction;
+
+    #[test]
+    fn fifo_ordering_ignores_fees() {
+        let ordering = FifoOrdering::default();
+        let lo = MockTransaction::eip1559().with_priority_fee(1).with_max_fee(100);
+        let hi = MockTransaction::eip1559().with_priority_fee(10).with_max_fee(100);
+        assert_eq!(ordering.priority(&lo, 10), ordering.priority(&hi, 10));
+        assert_eq!(ordering.priority(&lo, 1_000), Priority::None);
+    }
+
+    #[test]
+    fn effective_fee_ordering_ranks_total_fee() {
+        let effective_fee = EffectiveFeeOrdering::default();
+        let coinbase_tip = CoinbaseTipOrdering::default();
+        let transfer = MockTransaction::eip1559()
+            .with_priority_fee(10)
+            .with_max_fee(100)
+            .with_gas_limit(21_000);
+        let call = MockTransaction::eip1559()
+            .with_priority_fee(5)
+            .with_max_fee(100)
+            .with_gas_limit(100_000);
+
+        // the call pays less per gas, but more in total
+        assert!(coinbase_tip.priority(&transfer, 10) > coinbase_tip.priority(&call, 10));
+        assert!(effective_fee.priority(&call, 10) > effective_fee.priority(&transfer, 10));
+        assert_eq!(effective_fee.priority(&call, 10), Priority::Value(U256::from(5u64 * 100_000)));
+        assert_eq!(effective_fee.priority(&call, 1_000), Priority::None);
+    }
+
+    #[test]
+    fn effective_fee_ordering_includes_blob_fee() {
+        let ordering = EffectiveFeeOrdering::default();
+        let tx = MockTransaction::eip1559()
+            .with_priority_fee(10)
+            .with_max_fee(100)
+            .with_gas_limit(21_000);
+        let blob = MockTransaction::eip4844()
+            .with_priority_fee(10)
+            .with_max_fee(100)
+            .with_gas_limit(21_000)
+            .with_blob_fee(100);
+        assert!(ordering.priority(&blob, 10) > ordering.priority(&tx, 10));
+
+        // the blob gas is priced at the pending blob fee, not the max fee per blob gas
+        assert_eq!(
+            ordering.priority_with_blob_fee(&blob, 10, 3),
+            Priority::Value(U256::from(10 * 21_000 + 3 * DATA_GAS_PER_BLOB))
+        );
+        assert_eq!(
+            ordering.priority_with_blob_fee(&tx, 10, 3),
+            Priority::Value(U256::from(10u64 * 21_000))
+        );
+
+        // a higher tip outweighs the blob fee at a low pending blob fee
+        let higher_tip = MockTransaction::eip1559()
+            .with_priority_fee(20)
+            .with_max_fee(100)
+            .with_gas_limit(21_000);
+        assert!(
+            ordering.priority_with_blob_fee(&higher_tip, 10, 0) >
+                ordering.priority_with_blob_fee(&blob, 10, 0)
+        );
+        assert!(
+            ordering.priority_with_blob_fee(&blob, 10, 2) >
+                ordering.priority_with_blob_fee(&higher_tip, 10, 2)
+        );
+    }
+
+    #[test]
+    fn priority_lane_ordering_ranks_senders_first() {
+        let lane = MockTransaction::eip1559().with_priority_fee(1).with_max_fee(100);
+        let other = MockTransaction::eip1559().with_priority_fee(10).with_max_fee(100);
+        let ordering = PriorityLaneOrdering::new(
+            BuiltinOrdering::new(TransactionOrderingKind::CoinbaseTip),
+            [lane.sender()],
+        );
+        assert!(ordering.priority(&lane, 10) > ordering.priority(&other, 10));
+
+        let no_lanes = PriorityLaneOrdering::new(BuiltinOrdering::default(), []);
+        assert!(no_lanes.priority(&lane, 10) < no_lanes.priority(&other, 10));
+    }
+
+    #[test]
+    fn parse_ordering_kind() {
+        for kind in [
+            TransactionOrderingKind::CoinbaseTip,
+            TransactionOrderingKind::Fifo,
+            TransactionOrderingKind::EffectiveFee,
+        ] {
+            assert_eq!(kind.to_string().parse::<TransactionOrderingKind>(), Ok(kind));
+        }
+        assert!("unknown".parse::<TransactionOrderingKind>().is_err());
+    }
+}
@@ -1,7 +1,11 @@
 use super::txpool::PendingFees;
 use crate::{
-    identifier::TransactionId, pool::size::SizeTracker, traits::BestTransactionsAttributes,
-    PoolTransaction, SubPoolLimit, ValidPoolTransaction,
+    config::BLOB_SIDECAR_SIZE_PER_BLOB, identifier::TransactionId, pool::size::SizeTracker,
+    traits::BestTransactionsAttributes, BlobPoolConfig, PoolTransaction, SubPoolLimit,
+    ValidPoolTransaction,
+};
+use reth_primitives::constants::eip4844::{
+    BLOB_GASPRICE_UPDATE_FRACTION, BLOB_TX_MIN_BLOB_GASPRICE, TARGET_DATA_GAS_PER_BLOCK,
 };
 use std::{
     cmp::Ordering,
@@ -16,6 +20,9 @@ use std::{
 ///
 /// This expects that certain constraints are met:
 ///   - blob transactions are always gap less
+///
+/// The transactions are kept in memory, only their sidecars are stored by the
+/// [BlobStore](crate::blobstore::BlobStore).
 pub(crate) struct BlobTransactions<T: PoolTransaction> {
     /// Keeps track of transactions inserted in the pool.
     ///
@@ -31,6 +38,8 @@ pub(crate) struct BlobTransactions<T: PoolTransaction> {
     ///
     /// See also [`PoolTransaction::size`].
     size_of: SizeTracker,
+    /// Keeps track of the number of blobs of all transactions in this pool.
+    blobs: usize,
 }
 
 // === impl BlobTransactions ===
@@ -50,6 +59,7 @@ impl<T: PoolTransaction> BlobTransactions<T> {
 
         // keep track of size
         self.size_of += tx.size();
+        self.blobs += tx.transaction.blob_count();
 
         // set transaction, which will also calculate priority based on current pending fees
         let transaction = BlobTransaction::new(tx, submission_id, &self.pending_fees);
@@ -76,6 +86,7 @@ impl<T: PoolTransaction> BlobTransactions<T> {
 
         // keep track of size
         self.size_of -= tx.transaction.size();
+        self.blobs -= tx.transaction.transaction.blob_count();
 
         Some(tx.transaction)
     }
@@ -124,11 +135,25 @@ impl<T: PoolTransaction> BlobTransactions<T> {
         limit.is_exceeded(self.len(), self.size())
     }
 
+    /// Returns true if the pool exceeds the given blob limits.
+    ///
+    /// The size of the pool includes the blob sidecars of all transactions, see
+    /// [BLOB_SIDECAR_SIZE_PER_BLOB].
+    #[inline]
+    pub(crate) fn exceeds_blob_limit(&self, config: &BlobPoolConfig) -> bool {
+        config.is_exceeded(self.blobs, self.size() + self.blobs * BLOB_SIDECAR_SIZE_PER_BLOB)
+    }
+
     /// The reported size of all transactions in this pool.
     pub(crate) fn size(&self) -> usize {
         self.size_of.into()
     }
 
+    /// Number of blobs of all transactions in the pool
+    pub(crate) const fn blob_count(&self) -> usize {
+        self.blobs
+    }
+
     /// Number of transactions in the entire pool
     pub(crate) fn len(&self) -> usize {
         self.by_id.len()
@@ -233,6 +258,51 @@ impl<T: PoolTransaction> BlobTransactions<T> {
         removed
     }
 
+    /// Removes transactions until the pool satisfies the blob limits of the [BlobPoolConfig].
+    ///
+    /// Transactions are removed in the same order as in [Self::truncate_pool].
+    pub(crate) fn truncate_blobs(
+        &mut self,
+        config: &BlobPoolConfig,
+    ) -> Vec<Arc<ValidPoolTransaction<T>>> {
+        let mut removed = Vec::new();
+
+        while self.exceeds_blob_limit(config) {
+            let tx = self.all.last().expect("pool is not empty");
+            let id = *tx.transaction.id();
+            removed.push(self.remove_transaction(&id).expect("transaction exists"));
+        }
+
+        removed
+    }
+
+    /// Removes all transactions with a `max_fee_per_blob_gas` that can't satisfy the blob fee
+    /// within the next `horizon` blocks, starting at the given pending `blob_fee`.
+    ///
+    /// See also [min_reachable_blob_fee].
+    ///
+    /// Note: this does not remove the descendants of the removed transactions.
+    pub(crate) fn remove_unviable(
+        &mut self,
+        blob_fee: u128,
+        horizon: u64,
+    ) -> Vec<Arc<ValidPoolTransaction<T>>> {
+        let min_blob_fee = min_reachable_blob_fee(blob_fee, horizon);
+        let to_remove = self
+            .by_id
+            .iter()
+            .filter(|(_, tx)| {
+                tx.transaction.max_fee_per_blob_gas().unwrap_or_default() < min_blob_fee
+            })
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        to_remove
+            .into_iter()
+            .map(|id| self.remove_transaction(&id).expect("transaction exists"))
+            .collect()
+    }
+
     /// Returns `true` if the transaction with the given id is already included in this pool.
     pub(crate) fn contains(&self, id: &TransactionId) -> bool {
         self.by_id.contains_key(id)
@@ -247,6 +317,11 @@ impl<T: PoolTransaction> BlobTransactions<T> {
     #[cfg(any(test, feature = "test-utils"))]
     pub(crate) fn assert_invariants(&self) {
         assert_eq!(self.by_id.len(), self.all.len(), "by_id.len() != all.len()");
+        assert_eq!(
+            self.blobs,
+            self.by_id.values().map(|tx| tx.transaction.transaction.blob_count()).sum::<usize>(),
+            "blobs != sum of blob counts"
+        );
     }
 }
 
@@ -258,6 +333,7 @@ impl<T: PoolTransaction> Default for BlobTransactions<T> {
             all: Default::default(),
             size_of: Default::default(),
             pending_fees: Default::default(),
+            blobs: 0,
         }
     }
 }
@@ -382,6 +458,18 @@ pub fn fee_delta(max_tx_fee: u128, current_fee: u128) -> i64 {
     }
 }
 
+/// Returns the lowest blob fee that can be reached within the given number of blocks, starting at
+/// the given blob fee.
+///
+/// The blob fee decreases the fastest if blocks don't contain any blobs, in which case the excess
+/// blob gas decreases by [TARGET_DATA_GAS_PER_BLOCK] per block. The blob fee never drops below
+/// [BLOB_TX_MIN_BLOB_GASPRICE].
+pub fn min_reachable_blob_fee(blob_fee: u128, blocks: u64) -> u128 {
+    let exponent =
+        -(blocks as f64 * TARGET_DATA_GAS_PER_BLOCK as f64) / BLOB_GASPRICE_UPDATE_FRACTION as f64;
+    ((blob_fee as f64 * exponent.exp()) as u128).max(BLOB_TX_MIN_BLOB_GASPRICE)
+}
+
 /// Returns the priority for the transaction, based on the "delta" blob fee and priority fee.
 pub fn blob_tx_priority(
     blob_fee_cap: u128,
@@ -694,4 +782,66 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn min_reachable_blob_fee_tests() {
+        // no blocks, no decrease
+        assert_eq!(min_reachable_blob_fee(1_000_000, 0), 1_000_000);
+
+        // the blob fee decreases by ~11% per empty block
+        let fee = min_reachable_blob_fee(1_000_000, 1);
+        assert!(fee > 880_000 && fee < 900_000, "unexpected fee {fee}");
+
+        // the blob fee never drops below the minimum
+        assert_eq!(min_reachable_blob_fee(1_000_000, 1_000), BLOB_TX_MIN_BLOB_GASPRICE);
+        assert_eq!(min_reachable_blob_fee(0, 0), BLOB_TX_MIN_BLOB_GASPRICE);
+    }
+
+    #[test]
+    fn remove_unviable_blob_transactions() {
+        let mut factory = MockTransactionFactory::default();
+        let mut pool = BlobTransactions::default();
+
+        let viable = MockTransaction::eip4844().with_blob_fee(900_000);
+        let unviable = MockTransaction::eip4844().with_blob_fee(800_000);
+        pool.add_transaction(factory.validated_arc(viable.clone()));
+        pool.add_transaction(factory.validated_arc(unviable.clone()));
+        assert_eq!(pool.blob_count(), 2);
+
+        let removed = pool.remove_unviable(1_000_000, 1);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].hash(), unviable.hash());
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.blob_count(), 1);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn truncate_blob_limit() {
+        let mut factory = MockTransactionFactory::default();
+        let mut pool = BlobTransactions::default();
+
+        for _ in 0..10 {
+            pool.add_transaction(factory.validated_arc(MockTransaction::eip4844()));
+        }
+        assert_eq!(pool.blob_count(), 10);
+
+        let config = BlobPoolConfig { max_blobs: 5, max_size: usize::MAX, ..Default::default() };
+        assert!(pool.exceeds_blob_limit(&config));
+        let removed = pool.truncate_blobs(&config);
+        assert_eq!(removed.len(), 5);
+        assert_eq!(pool.blob_count(), 5);
+        assert!(!pool.exceeds_blob_limit(&config));
+
+        // each blob accounts for its sidecar
+        let config = BlobPoolConfig {
+            max_blobs: usize::MAX,
+            max_size: pool.size() + 2 * BLOB_SIDECAR_SIZE_PER_BLOB,
+            ..Default::default()
+        };
+        assert!(pool.exceeds_blob_limit(&config));
+        pool.truncate_blobs(&config);
+        assert_eq!(pool.blob_count(), 2);
+        pool.assert_invariants();
+    }
 }
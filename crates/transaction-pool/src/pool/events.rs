@@ -30,6 +30,8 @@ pub enum FullTransactionEvent<T: PoolTransaction> {
     },
     /// Transaction was dropped due to configured limits.
     Discarded(TxHash),
+    /// Transaction was evicted because it exceeded its configured lifetime.
+    Evicted(TxHash),
     /// Transaction became invalid indefinitely.
     Invalid(TxHash),
     /// Transaction was propagated to peers.
@@ -48,6 +50,7 @@ impl<T: PoolTransaction> Clone for FullTransactionEvent<T> {
                 Self::Replaced { transaction: Arc::clone(transaction), replaced_by: *replaced_by }
             }
             Self::Discarded(hash) => Self::Discarded(*hash),
+            Self::Evicted(hash) => Self::Evicted(*hash),
             Self::Invalid(hash) => Self::Invalid(*hash),
             Self::Propagated(propagated) => Self::Propagated(Arc::clone(propagated)),
         }
@@ -70,6 +73,8 @@ pub enum TransactionEvent {
     Replaced(TxHash),
     /// Transaction was dropped due to configured limits.
     Discarded,
+    /// Transaction was evicted because it exceeded its configured lifetime.
+    Evicted,
     /// Transaction became invalid indefinitely.
     Invalid,
     /// Transaction was propagated to peers.
@@ -84,7 +89,8 @@ impl TransactionEvent {
             self,
             TransactionEvent::Replaced(_) |
                 TransactionEvent::Mined(_) |
-                TransactionEvent::Discarded
+                TransactionEvent::Discarded |
+                TransactionEvent::Evicted
         )
     }
 }
@@ -2,13 +2,13 @@
 
 use crate::{
     pool::events::{FullTransactionEvent, TransactionEvent},
-    traits::PropagateKind,
+    traits::{PropagateKind, TransactionListenerKind},
     PoolTransaction, ValidPoolTransaction,
 };
 use futures_util::Stream;
 use reth_primitives::{TxHash, B256};
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -78,6 +78,9 @@ pub(crate) struct PoolEventBroadcast<T: PoolTransaction> {
     all_events_broadcaster: AllPoolEventsBroadcaster<T>,
     /// All listeners for events for a certain transaction hash.
     broadcasters_by_hash: HashMap<TxHash, PoolEventBroadcaster>,
+    /// Hashes of the pooled transactions that are not allowed to be propagated, their events are
+    /// not sent to [TransactionListenerKind::PropagateOnly] listeners.
+    non_propagated: HashSet<TxHash>,
 }
 
 impl<T: PoolTransaction> Default for PoolEventBroadcast<T> {
@@ -85,6 +88,7 @@ impl<T: PoolTransaction> Default for PoolEventBroadcast<T> {
         Self {
             all_events_broadcaster: AllPoolEventsBroadcaster::default(),
             broadcasters_by_hash: HashMap::default(),
+            non_propagated: HashSet::default(),
         }
     }
 }
@@ -106,8 +110,24 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
             }
         }
 
+        let propagate_allowed = if event.is_final() {
+            !self.non_propagated.remove(hash)
+        } else {
+            !self.non_propagated.contains(hash)
+        };
+        let propagate_only_event = match &pool_event {
+            _ if !propagate_allowed => None,
+            // don't reveal a replacement that is not allowed to be propagated
+            FullTransactionEvent::Replaced { replaced_by, .. }
+                if self.non_propagated.contains(replaced_by) =>
+            {
+                Some(FullTransactionEvent::Discarded(*hash))
+            }
+            _ => Some(pool_event.clone()),
+        };
+
         // Broadcast to all listeners for all transactions.
-        self.all_events_broadcaster.broadcast(pool_event);
+        self.all_events_broadcaster.broadcast(pool_event, propagate_only_event);
     }
 
     /// Create a new subscription for the given transaction hash.
@@ -125,13 +145,45 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
         TransactionEvents { hash: tx_hash, events: rx }
     }
 
-    /// Create a new subscription for all transactions.
-    pub(crate) fn subscribe_all(&mut self) -> AllTransactionsEvents<T> {
+    /// Create a new subscription for all transactions depending on the given
+    /// [TransactionListenerKind].
+    ///
+    /// [TransactionListenerKind::PropagateOnly] listeners only receive events of transactions that
+    /// are allowed to be propagated, a replacement that is not allowed to be propagated is reported
+    /// as [FullTransactionEvent::Discarded].
+    pub(crate) fn subscribe_all(
+        &mut self,
+        kind: TransactionListenerKind,
+    ) -> AllTransactionsEvents<T> {
         let (tx, rx) = tokio::sync::mpsc::channel(TX_POOL_EVENT_CHANNEL_SIZE);
-        self.all_events_broadcaster.senders.push(tx);
+        match kind {
+            TransactionListenerKind::All => self.all_events_broadcaster.senders.push(tx),
+            TransactionListenerKind::PropagateOnly => {
+                self.all_events_broadcaster.propagate_only_senders.push(tx)
+            }
+        }
         AllTransactionsEvents::new(rx)
     }
 
+    /// Create a new unbounded subscription for all transactions.
+    ///
+    /// Unlike [Self::subscribe_all] this never drops events if the listener falls behind.
+    pub(crate) fn subscribe_all_unbounded(&mut self) -> UnboundedReceiver<FullTransactionEvent<T>> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        self.all_events_broadcaster.unbounded_senders.push(tx);
+        rx
+    }
+
+    /// Remembers the transaction if it is not allowed to be propagated, so that its events are not
+    /// sent to [TransactionListenerKind::PropagateOnly] listeners.
+    ///
+    /// This must be called before the transaction's first event is broadcast.
+    pub(crate) fn track(&mut self, tx: &ValidPoolTransaction<T>) {
+        if !tx.propagate || !tx.is_shareable() {
+            self.non_propagated.insert(*tx.hash());
+        }
+    }
+
     /// Notify listeners about a transaction that was added to the pending queue.
     pub(crate) fn pending(&mut self, tx: &TxHash, replaced: Option<Arc<ValidPoolTransaction<T>>>) {
         self.broadcast_event(tx, TransactionEvent::Pending, FullTransactionEvent::Pending(*tx));
@@ -172,6 +224,21 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
         self.broadcast_event(tx, TransactionEvent::Discarded, FullTransactionEvent::Discarded(*tx));
     }
 
+    /// Notify the listeners of the transaction about a transaction that failed validation.
+    ///
+    /// Listeners for all transactions are not notified, since the transaction never entered the
+    /// pool.
+    pub(crate) fn rejected(&mut self, tx: &TxHash) {
+        if let Some(mut sink) = self.broadcasters_by_hash.remove(tx) {
+            sink.broadcast(TransactionEvent::Discarded);
+        }
+    }
+
+    /// Notify listeners about a transaction that was evicted because of its age.
+    pub(crate) fn evicted(&mut self, tx: &TxHash) {
+        self.broadcast_event(tx, TransactionEvent::Evicted, FullTransactionEvent::Evicted(*tx));
+    }
+
     /// Notify listeners that the transaction was mined
     pub(crate) fn mined(&mut self, tx: &TxHash, block_hash: B256) {
         self.broadcast_event(
@@ -189,21 +256,48 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
 struct AllPoolEventsBroadcaster<T: PoolTransaction> {
     /// Corresponding sender half(s) for event listener channel
     senders: Vec<Sender<FullTransactionEvent<T>>>,
+    /// Corresponding sender half(s) for event listener channel of transactions that are allowed
+    /// to be propagated
+    propagate_only_senders: Vec<Sender<FullTransactionEvent<T>>>,
+    /// Corresponding sender half(s) for unbounded event listener channel
+    unbounded_senders: Vec<UnboundedSender<FullTransactionEvent<T>>>,
 }
 
 impl<T: PoolTransaction> Default for AllPoolEventsBroadcaster<T> {
     fn default() -> Self {
-        Self { senders: Vec::new() }
+        Self {
+            senders: Vec::new(),
+            propagate_only_senders: Vec::new(),
+            unbounded_senders: Vec::new(),
+        }
     }
 }
 
 impl<T: PoolTransaction> AllPoolEventsBroadcaster<T> {
-    // Broadcast an event to all listeners. Dropped listeners are silently evicted.
-    fn broadcast(&mut self, event: FullTransactionEvent<T>) {
-        self.senders.retain(|sender| match sender.try_send(event.clone()) {
+    // Broadcast an event to all listeners, and `propagate_only_event` to the listeners that only
+    // receive events of transactions that are allowed to be propagated. Dropped listeners are
+    // silently evicted.
+    fn broadcast(
+        &mut self,
+        event: FullTransactionEvent<T>,
+        propagate_only_event: Option<FullTransactionEvent<T>>,
+    ) {
+        Self::try_broadcast(&mut self.senders, &event);
+        if let Some(propagate_only_event) = propagate_only_event {
+            Self::try_broadcast(&mut self.propagate_only_senders, &propagate_only_event);
+        }
+        self.unbounded_senders.retain(|sender| sender.send(event.clone()).is_ok())
+    }
+
+    // Sends the event to the bounded listeners, skipping those that fell behind.
+    fn try_broadcast(
+        senders: &mut Vec<Sender<FullTransactionEvent<T>>>,
+        event: &FullTransactionEvent<T>,
+    ) {
+        senders.retain(|sender| match sender.try_send(event.clone()) {
             Ok(_) | Err(TrySendError::Full(_)) => true,
             Err(TrySendError::Closed(_)) => false,
-        })
+        });
     }
 }
 
@@ -70,15 +70,17 @@ use crate::{
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
         listener::PoolEventBroadcast,
-        state::SubPool,
+        state::{SubPool, TxStateInfo},
         txpool::{SenderInfo, TxPool},
     },
+    replay::{RecordedBlock, RecordedEvent, RecordedTransaction},
     traits::{
         AllPoolTransactions, BestTransactionsAttributes, BlockInfo, NewTransactionEvent, PoolSize,
         PoolTransaction, PropagatedTransactions, TransactionOrigin,
     },
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
-    CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering, TransactionValidator,
+    CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionConditional, TransactionOrdering,
+    TransactionValidator,
 };
 use best::BestTransactions;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard};
@@ -147,6 +149,8 @@ where
     transaction_listener: Mutex<Vec<TransactionListener<T::Transaction>>>,
     /// Listener for new blob transaction sidecars added to the pool.
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
+    /// Listeners for all transactions added to the pool and all canonical state updates.
+    recording_listener: Mutex<Vec<mpsc::UnboundedSender<RecordedEvent>>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
 }
@@ -169,6 +173,7 @@ where
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
             blob_transaction_sidecar_listener: Default::default(),
+            recording_listener: Default::default(),
             config,
             blob_store,
             blob_store_metrics: Default::default(),
@@ -269,11 +274,38 @@ where
             .then(|| self.event_listener.write().subscribe(tx_hash))
     }
 
-    /// Adds a listener for all transaction events.
+    /// Adds a listener for all transaction events depending on the given
+    /// [TransactionListenerKind].
     pub(crate) fn add_all_transactions_event_listener(
         &self,
+        kind: TransactionListenerKind,
     ) -> AllTransactionsEvents<T::Transaction> {
-        self.event_listener.write().subscribe_all()
+        self.event_listener.write().subscribe_all(kind)
+    }
+
+    /// Adds an unbounded listener for all transaction events that never misses an event.
+    pub(crate) fn add_unbounded_all_transactions_event_listener(
+        &self,
+    ) -> mpsc::UnboundedReceiver<FullTransactionEvent<T::Transaction>> {
+        self.event_listener.write().subscribe_all_unbounded()
+    }
+
+    /// Adds an unbounded listener for all transactions added to the pool and all canonical state
+    /// updates, in the order in which they were applied to the pool.
+    pub(crate) fn add_recording_listener(&self) -> mpsc::UnboundedReceiver<RecordedEvent> {
+        let (sender, rx) = mpsc::unbounded_channel();
+        self.recording_listener.lock().push(sender);
+        rx
+    }
+
+    /// Returns true if there are listeners for recorded events.
+    pub(crate) fn is_recording(&self) -> bool {
+        !self.recording_listener.lock().is_empty()
+    }
+
+    /// Sends the event to all recording listeners. Dropped listeners are evicted.
+    fn record(&self, event: RecordedEvent) {
+        self.recording_listener.lock().retain(|sender| sender.send(event.clone()).is_ok())
     }
 
     /// Returns a read lock to the pool's data.
@@ -309,6 +341,8 @@ where
     }
 
     /// Returns converted [PooledTransactionsElement] for the given transaction hashes.
+    ///
+    /// Private and conditional transactions are skipped, since they must never be served to peers.
     pub(crate) fn get_pooled_transaction_elements(
         &self,
         tx_hashes: Vec<TxHash>,
@@ -318,6 +352,9 @@ where
         let mut elements = Vec::with_capacity(transactions.len());
         let mut size = 0;
         for transaction in transactions {
+            if !transaction.is_shareable() {
+                continue
+            }
             let encoded_len = transaction.encoded_length();
             let tx = transaction.to_recovered_transaction().into_signed();
             let pooled = if tx.is_eip4844() {
@@ -352,18 +389,32 @@ where
     }
 
     /// Returns converted [PooledTransactionsElement] for the given transaction hash.
+    ///
+    /// Private and conditional transactions are skipped, since they must never be served to peers.
     pub(crate) fn get_pooled_transaction_element(
         &self,
         tx_hash: TxHash,
     ) -> Option<PooledTransactionsElement> {
-        self.get(&tx_hash).and_then(|transaction| {
-            let tx = transaction.to_recovered_transaction().into_signed();
-            if tx.is_eip4844() {
-                self.get_blob_transaction(tx).map(PooledTransactionsElement::BlobTransaction)
-            } else {
-                PooledTransactionsElement::try_from(tx).ok()
-            }
-        })
+        self.get(&tx_hash)
+            .filter(|transaction| transaction.is_shareable())
+            .and_then(|transaction| self.to_pooled_transaction_element(&transaction))
+    }
+
+    /// Converts the pool transaction to a [PooledTransactionsElement], including the sidecar of
+    /// blob transactions.
+    ///
+    /// Unlike [Self::get_pooled_transaction_element], this doesn't skip transactions that must not
+    /// be served to peers.
+    pub(crate) fn to_pooled_transaction_element(
+        &self,
+        transaction: &ValidPoolTransaction<T::Transaction>,
+    ) -> Option<PooledTransactionsElement> {
+        let tx = transaction.to_recovered_transaction().into_signed();
+        if tx.is_eip4844() {
+            self.get_blob_transaction(tx).map(PooledTransactionsElement::BlobTransaction)
+        } else {
+            PooledTransactionsElement::try_from(tx).ok()
+        }
     }
 
     /// Updates the entire pool after a new block was executed.
@@ -371,17 +422,23 @@ where
         trace!(target: "txpool", ?update, "updating pool on canonical state change");
 
         let block_info = update.block_info();
+        let recorded = self.is_recording().then(|| RecordedBlock::new(&update));
         let CanonicalStateUpdate { new_tip, changed_accounts, mined_transactions, .. } = update;
         self.validator.on_new_head_block(new_tip);
 
         let changed_senders = self.changed_senders(changed_accounts.into_iter());
 
         // update the pool
-        let outcome = self.pool.write().on_canonical_state_change(
-            block_info,
-            mined_transactions,
-            changed_senders,
-        );
+        let outcome = {
+            let mut pool = self.pool.write();
+            let outcome =
+                pool.on_canonical_state_change(block_info, mined_transactions, changed_senders);
+            // recorded while the pool is locked, so that updates are recorded in order
+            if let Some(recorded) = recorded {
+                self.record(RecordedEvent::Block(recorded));
+            }
+            outcome
+        };
 
         // This will discard outdated transactions based on the account's nonce
         self.delete_discarded_blobs(outcome.discarded.iter());
@@ -415,6 +472,8 @@ where
         &self,
         origin: TransactionOrigin,
         tx: TransactionValidationOutcome<T::Transaction>,
+        timestamp: Instant,
+        conditional: Option<Box<TransactionConditional>>,
     ) -> PoolResult<TxHash> {
         match tx {
             TransactionValidationOutcome::Valid {
@@ -438,15 +497,34 @@ where
                     }
                 };
 
+                // conditional transactions are never propagated, peers would include them without
+                // checking the conditions
+                let propagate = propagate && conditional.is_none();
+
                 let tx = ValidPoolTransaction {
                     transaction,
                     transaction_id,
                     propagate,
-                    timestamp: Instant::now(),
+                    timestamp,
                     origin,
+                    conditional,
+                };
+
+                let recorded = if self.is_recording() {
+                    RecordedTransaction::new(&tx, maybe_sidecar.as_ref())
+                } else {
+                    None
                 };
 
-                let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
+                let added = {
+                    let mut pool = self.pool.write();
+                    let added = pool.add_transaction(tx, balance, state_nonce)?;
+                    // recorded while the pool is locked, so that updates are recorded in order
+                    if let Some(recorded) = recorded {
+                        self.record(RecordedEvent::Transaction(recorded));
+                    }
+                    added
+                };
                 let hash = *added.hash();
 
                 // transaction was successfully inserted into the pool
@@ -481,12 +559,12 @@ where
             }
             TransactionValidationOutcome::Invalid(tx, err) => {
                 let mut listener = self.event_listener.write();
-                listener.discarded(tx.hash());
+                listener.rejected(tx.hash());
                 Err(PoolError::new(*tx.hash(), err))
             }
             TransactionValidationOutcome::Error(tx_hash, err) => {
                 let mut listener = self.event_listener.write();
-                listener.discarded(&tx_hash);
+                listener.rejected(&tx_hash);
                 Err(PoolError::other(tx_hash, err))
             }
         }
@@ -512,9 +590,50 @@ where
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
     ) -> Vec<PoolResult<TxHash>> {
-        let mut added =
-            transactions.into_iter().map(|tx| self.add_transaction(origin, tx)).collect::<Vec<_>>();
+        let added = transactions
+            .into_iter()
+            .map(|tx| self.add_transaction(origin, tx, Instant::now(), None))
+            .collect::<Vec<_>>();
+        self.enforce_size_limits(added)
+    }
+
+    /// Adds a transaction that may only be included in a block if the given conditions hold.
+    pub(crate) fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        tx: TransactionValidationOutcome<T::Transaction>,
+        conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let added = self.add_transaction(origin, tx, Instant::now(), Some(Box::new(conditional)));
+        let mut results = self.enforce_size_limits(vec![added]);
+        results.pop().expect("result length is the same as the input")
+    }
 
+    /// Adds all transactions in the iterator to the pool with the time they were originally
+    /// submitted at and their conditions, returning a list of results.
+    pub(crate) fn add_transactions_with_timestamps(
+        &self,
+        transactions: impl IntoIterator<
+            Item = (
+                TransactionOrigin,
+                Instant,
+                TransactionValidationOutcome<T::Transaction>,
+                Option<TransactionConditional>,
+            ),
+        >,
+    ) -> Vec<PoolResult<TxHash>> {
+        let added = transactions
+            .into_iter()
+            .map(|(origin, timestamp, tx, conditional)| {
+                self.add_transaction(origin, tx, timestamp, conditional.map(Box::new))
+            })
+            .collect::<Vec<_>>();
+        self.enforce_size_limits(added)
+    }
+
+    /// Discards the worst transactions if the pool exceeds its limits after the given transactions
+    /// were added, and adjusts their results accordingly.
+    fn enforce_size_limits(&self, mut added: Vec<PoolResult<TxHash>>) -> Vec<PoolResult<TxHash>> {
         // If at least one transaction was added successfully, then we enforce the pool size limits.
         let discarded =
             if added.iter().any(Result::is_ok) { self.discard_worst() } else { Default::default() };
@@ -632,11 +751,13 @@ where
             AddedTransaction::Pending(tx) => {
                 let AddedPendingTransaction { transaction, promoted, discarded, replaced } = tx;
 
+                listener.track(transaction);
                 listener.pending(transaction.hash(), replaced.clone());
                 promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
                 discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
             }
             AddedTransaction::Parked { transaction, replaced, .. } => {
+                listener.track(transaction);
                 listener.queued(transaction.hash());
                 if let Some(replaced) = replaced {
                     listener.replaced(replaced.clone(), *transaction.hash());
@@ -652,12 +773,29 @@ where
 
     /// Returns an iterator that yields transactions that are ready to be included in the block with
     /// the given base fee and optional blob fee attributes.
+    ///
+    /// If the attributes contain the block's number and timestamp, conditional transactions whose
+    /// bounds are violated by the block are skipped.
+    ///
+    /// The `known_accounts` conditions depend on the state of the block's parent, which the pool
+    /// doesn't have access to, so they must be checked by the caller, see
+    /// [ValidPoolTransaction::matches_known_accounts].
     pub(crate) fn best_transactions_with_attributes(
         &self,
         best_transactions_attributes: BestTransactionsAttributes,
     ) -> Box<dyn crate::traits::BestTransactions<Item = Arc<ValidPoolTransaction<T::Transaction>>>>
     {
-        self.get_pool_data().best_transactions_with_attributes(best_transactions_attributes)
+        let best =
+            self.get_pool_data().best_transactions_with_attributes(best_transactions_attributes);
+        let (Some(number), Some(timestamp)) =
+            (best_transactions_attributes.block_number, best_transactions_attributes.timestamp)
+        else {
+            return best
+        };
+        Box::new(crate::traits::BestTransactions::filter(best, move |tx| {
+            tx.conditional()
+                .map_or(true, |conditional| conditional.is_within_block_bounds(number, timestamp))
+        }))
     }
 
     /// Returns all transactions from the pending sub-pool
@@ -670,6 +808,13 @@ where
         self.get_pool_data().queued_transactions()
     }
 
+    /// Returns all transactions in the pool that were submitted with conditions.
+    pub(crate) fn conditional_transactions(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.get_pool_data().conditional_transactions()
+    }
+
     /// Returns all transactions in the pool
     pub(crate) fn all_transactions(&self) -> AllPoolTransactions<T::Transaction> {
         let pool = self.get_pool_data();
@@ -696,6 +841,65 @@ where
         removed
     }
 
+    /// Removes and returns all transactions of the given sender.
+    pub(crate) fn remove_transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        // senders without an identifier have no transactions in the pool
+        let Some(sender_id) = self.identifiers.read().sender_id(&sender) else { return Vec::new() };
+        let removed = self.pool.write().remove_transactions_by_sender(sender_id);
+        self.on_removed(&removed);
+        removed
+    }
+
+    /// Removes and returns all transactions in the given sub-pool, including their descendants.
+    pub(crate) fn remove_transactions_by_subpool(
+        &self,
+        subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let removed = self.pool.write().remove_transactions_by_subpool(subpool);
+        self.on_removed(&removed);
+        removed
+    }
+
+    /// Notifies listeners about the removed transactions and deletes their blobs.
+    fn on_removed(&self, removed: &[Arc<ValidPoolTransaction<T::Transaction>>]) {
+        if removed.is_empty() {
+            return
+        }
+
+        {
+            let mut listener = self.event_listener.write();
+            removed.iter().for_each(|tx| listener.discarded(tx.hash()));
+        }
+
+        self.delete_discarded_blobs(removed.iter());
+    }
+
+    /// Returns the state of the transaction with the given hash, if it's in the pool.
+    pub(crate) fn transaction_state(&self, tx_hash: TxHash) -> Option<TxStateInfo> {
+        self.get_pool_data().transaction_state(&tx_hash)
+    }
+
+    /// Evicts and returns all transactions that exceeded their configured lifetime.
+    pub(crate) fn evict_stale_transactions(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let evicted = self.pool.write().remove_stale_transactions(Instant::now());
+        if evicted.is_empty() {
+            return evicted
+        }
+
+        {
+            let mut listener = self.event_listener.write();
+            evicted.iter().for_each(|tx| listener.evicted(tx.hash()));
+        }
+
+        self.delete_discarded_blobs(evicted.iter());
+        evicted
+    }
+
     /// Removes and returns all transactions that are present in the pool.
     pub(crate) fn retain_unknown<A>(&self, announcement: &mut A)
     where
@@ -804,6 +1008,20 @@ where
         let _ = self.blob_store.delete_all(txs);
     }
 
+    /// Returns the hashes of all sidecars in the blob store that don't belong to a transaction in
+    /// the pool.
+    pub(crate) fn orphaned_blobs(&self) -> Vec<TxHash> {
+        let hashes = match self.blob_store.hashes() {
+            Ok(hashes) => hashes,
+            Err(err) => {
+                warn!(target: "txpool", %err, "failed to list blob store sidecars");
+                return Vec::new()
+            }
+        };
+        let pool = self.get_pool_data();
+        hashes.into_iter().filter(|hash| !pool.contains(hash)).collect()
+    }
+
     /// Cleans up the blob store
     pub(crate) fn cleanup_blobs(&self) {
         let stat = self.blob_store.cleanup();
@@ -1135,10 +1353,13 @@ mod tests {
         blobstore::{BlobStore, InMemoryBlobStore},
         test_utils::{MockTransaction, TestPoolBuilder},
         validate::ValidTransaction,
-        BlockInfo, PoolConfig, SubPoolLimit, TransactionOrigin, TransactionValidationOutcome, U256,
+        BestTransactionsAttributes, BlockInfo, FullTransactionEvent, GetPooledTransactionLimit,
+        PoolConfig, SubPoolLimit, TransactionConditional, TransactionEvent,
+        TransactionListenerKind, TransactionOrigin, TransactionValidationOutcome, U256,
     };
-    use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar};
-    use std::{fs, path::PathBuf};
+    use futures_util::{FutureExt, StreamExt};
+    use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar, InvalidTransactionError};
+    use std::{fs, path::PathBuf, time::Instant};
 
     #[test]
     fn test_discard_blobs_on_blob_tx_eviction() {
@@ -1203,6 +1424,8 @@ mod tests {
                         },
                         propagate: true,
                     },
+                    Instant::now(),
+                    None,
                 )
                 .unwrap();
 
@@ -1219,4 +1442,211 @@ mod tests {
         // Assert that the pool's blob store matches the expected blob store.
         assert_eq!(*test_pool.blob_store(), blob_store);
     }
+
+    #[test]
+    fn best_transactions_skip_violated_conditionals() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let valid = |transaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(1_000),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(transaction),
+            propagate: true,
+        };
+
+        let conditional =
+            TransactionConditional { block_number_max: Some(10), ..Default::default() };
+        let conditional_hash = test_pool
+            .add_conditional_transaction(
+                TransactionOrigin::Local,
+                valid(MockTransaction::eip1559()),
+                conditional,
+            )
+            .unwrap();
+        test_pool
+            .add_transaction(
+                TransactionOrigin::External,
+                valid(MockTransaction::eip1559()),
+                Instant::now(),
+                None,
+            )
+            .unwrap();
+
+        let conditional_txs = test_pool.conditional_transactions();
+        assert_eq!(conditional_txs.len(), 1);
+        assert_eq!(*conditional_txs[0].hash(), conditional_hash);
+
+        let attributes =
+            BestTransactionsAttributes::base_fee(test_pool.block_info().pending_basefee);
+        assert_eq!(test_pool.best_transactions_with_attributes(attributes).count(), 2);
+        assert_eq!(
+            test_pool.best_transactions_with_attributes(attributes.with_block(10, 0)).count(),
+            2
+        );
+
+        let best = test_pool
+            .best_transactions_with_attributes(attributes.with_block(11, 0))
+            .collect::<Vec<_>>();
+        assert_eq!(best.len(), 1);
+        assert_ne!(*best[0].hash(), conditional_hash);
+    }
+
+    #[test]
+    fn private_transactions_are_not_served() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let valid = |transaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(1_000),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(transaction),
+            propagate: false,
+        };
+
+        let private_hash = test_pool
+            .add_transaction(
+                TransactionOrigin::Private,
+                valid(MockTransaction::eip1559()),
+                Instant::now(),
+                None,
+            )
+            .unwrap();
+        let local_hash = test_pool
+            .add_transaction(
+                TransactionOrigin::Local,
+                valid(MockTransaction::eip1559()),
+                Instant::now(),
+                None,
+            )
+            .unwrap();
+
+        let elements = test_pool.get_pooled_transaction_elements(
+            vec![private_hash, local_hash],
+            GetPooledTransactionLimit::None,
+        );
+        assert_eq!(elements.len(), 1);
+        assert_eq!(*elements[0].hash(), local_hash);
+        assert!(test_pool.get_pooled_transaction_element(private_hash).is_none());
+        assert!(test_pool.get_pooled_transaction_element(local_hash).is_some());
+
+        // private transactions are still included in blocks
+        let attributes =
+            BestTransactionsAttributes::base_fee(test_pool.block_info().pending_basefee);
+        assert_eq!(test_pool.best_transactions_with_attributes(attributes).count(), 2);
+    }
+
+    #[test]
+    fn private_transactions_are_not_broadcast() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let valid = |transaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(1_000),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(transaction),
+            propagate: true,
+        };
+        let mut all_events =
+            test_pool.add_all_transactions_event_listener(TransactionListenerKind::All);
+        let mut propagate_only_events =
+            test_pool.add_all_transactions_event_listener(TransactionListenerKind::PropagateOnly);
+
+        let tx = MockTransaction::eip1559();
+        let local_hash = test_pool
+            .add_transaction(TransactionOrigin::Local, valid(tx.clone()), Instant::now(), None)
+            .unwrap();
+        // replace the local transaction with a private one and drop that
+        let replacement = tx.inc_price_by(tx.get_gas_price()).rng_hash();
+        let private_hash = test_pool
+            .add_transaction(TransactionOrigin::Private, valid(replacement), Instant::now(), None)
+            .unwrap();
+        test_pool.remove_transactions(vec![private_hash]);
+
+        let all_events =
+            std::iter::from_fn(|| all_events.events.try_recv().ok()).collect::<Vec<_>>();
+        assert_eq!(all_events.len(), 4);
+        assert!(all_events.iter().any(|event| matches!(
+            event,
+            FullTransactionEvent::Replaced { replaced_by, .. } if *replaced_by == private_hash
+        )));
+
+        // the private transaction is never revealed, not even as the replacement
+        let propagate_only_events =
+            std::iter::from_fn(|| propagate_only_events.events.try_recv().ok()).collect::<Vec<_>>();
+        assert_eq!(propagate_only_events.len(), 2);
+        assert!(matches!(
+            propagate_only_events[0],
+            FullTransactionEvent::Pending(hash) | FullTransactionEvent::Queued(hash)
+                if hash == local_hash
+        ));
+        assert!(matches!(
+            propagate_only_events[1],
+            FullTransactionEvent::Discarded(hash) if hash == local_hash
+        ));
+    }
+
+    #[test]
+    fn conditional_transactions_are_not_propagated() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let valid = |transaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(1_000),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(transaction),
+            propagate: true,
+        };
+        let mut pending = test_pool.add_pending_listener(TransactionListenerKind::PropagateOnly);
+
+        let conditional =
+            TransactionConditional { block_number_max: Some(10), ..Default::default() };
+        let conditional_hash = test_pool
+            .add_conditional_transaction(
+                TransactionOrigin::Local,
+                valid(MockTransaction::eip1559()),
+                conditional,
+            )
+            .unwrap();
+        let local_hash = test_pool
+            .add_transaction(
+                TransactionOrigin::Local,
+                valid(MockTransaction::eip1559()),
+                Instant::now(),
+                None,
+            )
+            .unwrap();
+
+        // only the unconditional transaction is announced
+        assert_eq!(pending.try_recv(), Ok(local_hash));
+        assert!(pending.try_recv().is_err());
+        let pooled = test_pool.pooled_transactions();
+        assert_eq!(pooled.len(), 1);
+        assert_eq!(*pooled[0].hash(), local_hash);
+        assert_eq!(test_pool.pooled_transactions_hashes(), vec![local_hash]);
+
+        // and served to peers
+        let elements = test_pool.get_pooled_transaction_elements(
+            vec![conditional_hash, local_hash],
+            GetPooledTransactionLimit::None,
+        );
+        assert_eq!(elements.len(), 1);
+        assert_eq!(*elements[0].hash(), local_hash);
+    }
+
+    #[test]
+    fn rejected_transactions_are_not_discarded() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let mut all_events = test_pool.add_unbounded_all_transactions_event_listener();
+
+        let tx = MockTransaction::eip1559();
+        let mut events = test_pool.event_listener.write().subscribe(*tx.get_hash());
+        let result = test_pool.add_transaction(
+            TransactionOrigin::External,
+            TransactionValidationOutcome::Invalid(
+                tx,
+                InvalidTransactionError::NonceNotConsistent.into(),
+            ),
+            Instant::now(),
+            None,
+        );
+        assert!(result.is_err());
+
+        // only the listeners of the transaction learn about the rejection, the transaction was
+        // never in the pool
+        assert_eq!(events.next().now_or_never(), Some(Some(TransactionEvent::Discarded)));
+        assert!(all_events.try_recv().is_err());
+    }
 }
@@ -6,6 +6,7 @@ use crate::{
     },
     Priority, SubPoolLimit, TransactionOrdering, ValidPoolTransaction,
 };
+use reth_primitives::constants::eip4844::BLOB_TX_MIN_BLOB_GASPRICE;
 use std::{
     cmp::Ordering,
     collections::{BTreeMap, BTreeSet},
@@ -32,6 +33,8 @@ pub struct PendingPool<T: TransactionOrdering> {
     ///
     /// This way we can determine when transactions were submitted to the pool.
     submission_id: u64,
+    /// The blob fee of the pending block, which the ordering may take into account.
+    blob_fee: u128,
     /// _All_ Transactions that are currently inside the pool grouped by their identifier.
     by_id: BTreeMap<TransactionId, PendingTransaction<T>>,
     /// _All_ transactions sorted by priority
@@ -64,6 +67,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         Self {
             ordering,
             submission_id: 0,
+            blob_fee: BLOB_TX_MIN_BLOB_GASPRICE,
             by_id: Default::default(),
             all: Default::default(),
             independent_transactions: Default::default(),
@@ -144,7 +148,8 @@ impl<T: TransactionOrdering> PendingPool<T> {
         for tx in unlocked {
             submission_id += 1;
             debug_assert!(!best.all.contains_key(tx.id()), "transaction already included");
-            let priority = self.ordering.priority(&tx.transaction, base_fee);
+            let priority =
+                self.ordering.priority_with_blob_fee(&tx.transaction, base_fee, self.blob_fee);
             let tx_id = *tx.id();
             let transaction = PendingTransaction { submission_id, transaction: tx, priority };
             if best.ancestor(&tx_id).is_none() {
@@ -163,7 +168,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         self.by_id.values().map(|tx| tx.transaction.clone())
     }
 
-    /// Updates the pool with the new blob fee. Removes
+    /// Updates the pool with the new blob fee. Reorders transactions by new priorities. Removes
     /// from the subpool all transactions and their dependents that no longer satisfy the given
     /// blob fee (`tx.max_blob_fee < blob_fee`).
     ///
@@ -174,14 +179,17 @@ impl<T: TransactionOrdering> PendingPool<T> {
     /// Removed transactions that no longer satisfy the blob fee.
     pub(crate) fn update_blob_fee(
         &mut self,
+        base_fee: u64,
         blob_fee: u128,
     ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.blob_fee = blob_fee;
+
         // Create a collection for removed transactions.
         let mut removed = Vec::new();
 
         // Drain and iterate over all transactions.
         let mut transactions_iter = self.clear_transactions().into_iter().peekable();
-        while let Some((id, tx)) = transactions_iter.next() {
+        while let Some((id, mut tx)) = transactions_iter.next() {
             if tx.transaction.max_fee_per_blob_gas() < Some(blob_fee) {
                 // Add this tx to the removed collection since it no longer satisfies the blob fee
                 // condition. Decrease the total pool size.
@@ -196,6 +204,13 @@ impl<T: TransactionOrdering> PendingPool<T> {
                     transactions_iter.next();
                 }
             } else {
+                // Re-insert the transaction with new priority.
+                tx.priority = self.ordering.priority_with_blob_fee(
+                    &tx.transaction.transaction,
+                    base_fee,
+                    blob_fee,
+                );
+
                 self.size_of += tx.transaction.size();
                 self.update_independents_and_highest_nonces(&tx, &id);
                 self.all.insert(tx.clone());
@@ -240,7 +255,11 @@ impl<T: TransactionOrdering> PendingPool<T> {
                 }
             } else {
                 // Re-insert the transaction with new priority.
-                tx.priority = self.ordering.priority(&tx.transaction.transaction, base_fee);
+                tx.priority = self.ordering.priority_with_blob_fee(
+                    &tx.transaction.transaction,
+                    base_fee,
+                    self.blob_fee,
+                );
 
                 self.size_of += tx.transaction.size();
                 self.update_independents_and_highest_nonces(&tx, &id);
@@ -302,7 +321,8 @@ impl<T: TransactionOrdering> PendingPool<T> {
         let tx_id = *tx.id();
 
         let submission_id = self.next_id();
-        let priority = self.ordering.priority(&tx.transaction, base_fee);
+        let priority =
+            self.ordering.priority_with_blob_fee(&tx.transaction, base_fee, self.blob_fee);
         let tx = PendingTransaction { submission_id, transaction: tx, priority };
 
         self.update_independents_and_highest_nonces(&tx, &tx_id);
@@ -139,6 +139,47 @@ impl From<TxState> for SubPool {
     }
 }
 
+/// The state of a transaction in the pool, which determines the [SubPool] it resides in.
+///
+/// Each flag mirrors a bit of the pool's internal transaction state: a transaction is parked if any
+/// of the flags required by the pending sub-pool is unset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxStateInfo {
+    /// The sub-pool that currently contains the transaction.
+    pub subpool: SubPool,
+    /// Whether all ancestor transactions of the sender are pending.
+    pub no_parked_ancestors: bool,
+    /// Whether there are no nonce gaps between the on chain nonce and the transaction.
+    pub no_nonce_gaps: bool,
+    /// Whether the sender's balance covers the cumulative cost of the transaction and all prior
+    /// transactions.
+    pub enough_balance: bool,
+    /// Whether the gas limit of the transaction doesn't exceed the block's gas limit.
+    pub not_too_much_gas: bool,
+    /// Whether the `maxFeePerGas` of the transaction meets the base fee of the pending block.
+    pub enough_fee_cap_block: bool,
+    /// Whether the `maxFeePerBlobGas` of the transaction meets the blob fee of the pending block.
+    pub enough_blob_fee_cap_block: bool,
+    /// Whether the transaction is an EIP-4844 blob transaction.
+    pub blob_transaction: bool,
+}
+
+impl TxStateInfo {
+    /// Creates the state info of a transaction with the given state in the given sub-pool.
+    pub(crate) const fn new(state: TxState, subpool: SubPool) -> Self {
+        Self {
+            subpool,
+            no_parked_ancestors: state.contains(TxState::NO_PARKED_ANCESTORS),
+            no_nonce_gaps: state.contains(TxState::NO_NONCE_GAPS),
+            enough_balance: state.contains(TxState::ENOUGH_BALANCE),
+            not_too_much_gas: state.contains(TxState::NOT_TOO_MUCH_GAS),
+            enough_fee_cap_block: state.contains(TxState::ENOUGH_FEE_CAP_BLOCK),
+            enough_blob_fee_cap_block: state.contains(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK),
+            blob_transaction: state.contains(TxState::BLOB_TRANSACTION),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -10,7 +10,7 @@ use crate::{
         blob::BlobTransactions,
         parked::{BasefeeOrd, ParkedPool, QueuedOrd},
         pending::PendingPool,
-        state::{SubPool, TxState},
+        state::{SubPool, TxState, TxStateInfo},
         update::{Destination, PoolUpdate},
         AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome,
     },
@@ -33,6 +33,7 @@ use std::{
     fmt,
     ops::Bound::{Excluded, Unbounded},
     sync::Arc,
+    time::Instant,
 };
 use tracing::trace;
 
@@ -145,43 +146,39 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// Updates the tracked blob fee
     fn update_blob_fee(&mut self, mut pending_blob_fee: u128, base_fee_update: Ordering) {
         std::mem::swap(&mut self.all_transactions.pending_fees.blob_fee, &mut pending_blob_fee);
-        match (self.all_transactions.pending_fees.blob_fee.cmp(&pending_blob_fee), base_fee_update)
-        {
-            (Ordering::Equal, Ordering::Equal) | (Ordering::Equal, Ordering::Greater) => {
-                // fee unchanged, nothing to update
+        let blob_fee_update = self.all_transactions.pending_fees.blob_fee.cmp(&pending_blob_fee);
+        if blob_fee_update != Ordering::Equal {
+            // changed blob fee: recheck pending pool and remove all that are no longer valid, this
+            // also reorders the pending pool for orderings that take the blob fee into account
+            let removed = self.pending_pool.update_blob_fee(
+                self.all_transactions.pending_fees.base_fee,
+                self.all_transactions.pending_fees.blob_fee,
+            );
+            for tx in removed {
+                let to = {
+                    let tx = self.all_transactions.txs.get_mut(tx.id()).expect("tx exists in set");
+
+                    // the blob fee is too high now, unset the blob fee cap block flag
+                    tx.state.remove(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK);
+                    tx.subpool = tx.state.into();
+                    tx.subpool
+                };
+                self.add_transaction_to_subpool(to, tx);
             }
-            (Ordering::Greater, Ordering::Equal) | (Ordering::Greater, Ordering::Greater) => {
-                // increased blob fee: recheck pending pool and remove all that are no longer valid
-                let removed =
-                    self.pending_pool.update_blob_fee(self.all_transactions.pending_fees.blob_fee);
-                for tx in removed {
-                    let to = {
-                        let tx =
-                            self.all_transactions.txs.get_mut(tx.id()).expect("tx exists in set");
+        }
 
-                        // the blob fee is too high now, unset the blob fee cap block flag
-                        tx.state.remove(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK);
-                        tx.subpool = tx.state.into();
-                        tx.subpool
-                    };
-                    self.add_transaction_to_subpool(to, tx);
-                }
-            }
-            (Ordering::Less, _) | (_, Ordering::Less) => {
-                // decreased blob/base fee: recheck blob pool and promote all that are now valid
-                let removed =
-                    self.blob_pool.enforce_pending_fees(&self.all_transactions.pending_fees);
-                for tx in removed {
-                    let to = {
-                        let tx =
-                            self.all_transactions.txs.get_mut(tx.id()).expect("tx exists in set");
-                        tx.state.insert(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK);
-                        tx.state.insert(TxState::ENOUGH_FEE_CAP_BLOCK);
-                        tx.subpool = tx.state.into();
-                        tx.subpool
-                    };
-                    self.add_transaction_to_subpool(to, tx);
-                }
+        if blob_fee_update == Ordering::Less || base_fee_update == Ordering::Less {
+            // decreased blob/base fee: recheck blob pool and promote all that are now valid
+            let removed = self.blob_pool.enforce_pending_fees(&self.all_transactions.pending_fees);
+            for tx in removed {
+                let to = {
+                    let tx = self.all_transactions.txs.get_mut(tx.id()).expect("tx exists in set");
+                    tx.state.insert(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK);
+                    tx.state.insert(TxState::ENOUGH_FEE_CAP_BLOCK);
+                    tx.subpool = tx.state.into();
+                    tx.subpool
+                };
+                self.add_transaction_to_subpool(to, tx);
             }
         }
     }
@@ -328,6 +325,17 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.basefee_pool.all().chain(self.queued_pool.all()).collect()
     }
 
+    /// Returns all transactions in the pool that were submitted with conditions.
+    pub(crate) fn conditional_transactions(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions
+            .conditional
+            .iter()
+            .filter_map(|hash| self.all_transactions.by_hash.get(hash).cloned())
+            .collect()
+    }
+
     /// Returns queued and pending transactions for the specified sender
     pub fn queued_and_pending_txs_by_sender(
         &self,
@@ -355,7 +363,8 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// Returns `true` if the pool is over its configured limits.
     #[inline]
     pub(crate) fn is_exceeded(&self) -> bool {
-        self.config.is_exceeded(self.size())
+        self.config.is_exceeded(self.size()) ||
+            self.blob_pool.exceeds_blob_limit(&self.config.blob_pool)
     }
 
     /// Returns the transaction for the given hash.
@@ -420,7 +429,10 @@ impl<T: TransactionOrdering> TxPool<T> {
             }
         }
 
-        let UpdateOutcome { promoted, discarded } = self.update_accounts(changed_senders);
+        let UpdateOutcome { promoted, mut discarded } = self.update_accounts(changed_senders);
+
+        // evict blob transactions that can't pay the blob fee in the foreseeable future
+        self.discard_unviable_blob_transactions(&mut discarded);
 
         self.metrics.performed_state_updates.increment(1);
 
@@ -621,6 +633,84 @@ impl<T: TransactionOrdering> TxPool<T> {
         txs
     }
 
+    /// Removes all transactions that exceeded their configured lifetime at the given time.
+    ///
+    /// Descendants of a stale transaction are removed as well, since they can't be executed
+    /// without it.
+    pub(crate) fn remove_stale_transactions(
+        &mut self,
+        now: Instant,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let lifetimes = self.config.transaction_lifetimes;
+        if lifetimes.is_unlimited() {
+            return Vec::new()
+        }
+
+        let mut stale = Vec::new();
+        let mut stale_sender = None;
+        // transactions are ordered by sender and nonce
+        for (id, tx) in &self.all_transactions.txs {
+            if stale_sender == Some(id.sender) {
+                stale.push(*id);
+                continue
+            }
+            let is_local = self
+                .config
+                .local_transactions_config
+                .is_local(tx.transaction.origin, tx.transaction.sender());
+            let Some(lifetime) = lifetimes.max_lifetime(tx.subpool, is_local) else { continue };
+            if now.saturating_duration_since(tx.transaction.timestamp) > lifetime {
+                stale.push(*id);
+                stale_sender = Some(id.sender);
+            }
+        }
+
+        let removed = stale.iter().filter_map(|id| self.remove_transaction(id)).collect();
+        self.update_size_metrics();
+        removed
+    }
+
+    /// Removes and returns all transactions of the given sender.
+    pub(crate) fn remove_transactions_by_sender(
+        &mut self,
+        sender: SenderId,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let ids = self.all_transactions.txs_iter(sender).map(|(id, _)| *id).collect::<Vec<_>>();
+        let removed = ids.iter().filter_map(|id| self.remove_transaction(id)).collect();
+        self.update_size_metrics();
+        removed
+    }
+
+    /// Removes and returns all transactions in the given sub-pool.
+    ///
+    /// Descendants of a removed transaction are removed as well, since they can't be executed
+    /// without it.
+    pub(crate) fn remove_transactions_by_subpool(
+        &mut self,
+        subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let mut ids = Vec::new();
+        let mut removed_sender = None;
+        // transactions are ordered by sender and nonce
+        for (id, tx) in &self.all_transactions.txs {
+            if removed_sender == Some(id.sender) || tx.subpool == subpool {
+                ids.push(*id);
+                removed_sender = Some(id.sender);
+            }
+        }
+
+        let removed = ids.iter().filter_map(|id| self.remove_transaction(id)).collect();
+        self.update_size_metrics();
+        removed
+    }
+
+    /// Returns the state of the transaction with the given hash, if it's in the pool.
+    pub(crate) fn transaction_state(&self, tx_hash: &TxHash) -> Option<TxStateInfo> {
+        let id = self.all_transactions.by_hash.get(tx_hash)?.id();
+        let tx = self.all_transactions.get(id)?;
+        Some(TxStateInfo::new(tx.state, tx.subpool))
+    }
+
     /// Remove the transaction from the __entire__ pool.
     ///
     /// This includes the total set of transaction and the subpool it currently resides in.
@@ -814,9 +904,62 @@ impl<T: TransactionOrdering> TxPool<T> {
             ]
         );
 
+        // enforce the blob specific limits of the blob pool
+        self.discard_unviable_blob_transactions(&mut removed);
+        if self.blob_pool.exceeds_blob_limit(&self.config.blob_pool) {
+            let removed_from_subpool = self.blob_pool.truncate_blobs(&self.config.blob_pool);
+            trace!(
+                target: "txpool",
+                "removed {} transactions from blob_pool, limit: {:?}, curr blobs: {}",
+                removed_from_subpool.len(),
+                self.config.blob_pool,
+                self.blob_pool.blob_count(),
+            );
+            self.discard_subpool_transactions(removed_from_subpool, &mut removed);
+        }
+
         removed
     }
 
+    /// Removes all blob transactions from the blob pool whose `max_fee_per_blob_gas` can't satisfy
+    /// the blob fee within the configured
+    /// [fee_eviction_horizon](crate::BlobPoolConfig::fee_eviction_horizon), starting at the
+    /// current pending blob fee.
+    ///
+    /// The transactions and all their descendants are removed from the entire pool and added to
+    /// `removed`.
+    fn discard_unviable_blob_transactions(
+        &mut self,
+        removed: &mut Vec<Arc<ValidPoolTransaction<T::Transaction>>>,
+    ) {
+        let Some(horizon) = self.config.blob_pool.fee_eviction_horizon else { return };
+        let unviable =
+            self.blob_pool.remove_unviable(self.all_transactions.pending_fees.blob_fee, horizon);
+        if !unviable.is_empty() {
+            trace!(target: "txpool", "removed {} unviable transactions from blob_pool", unviable.len());
+            self.discard_subpool_transactions(unviable, removed);
+        }
+    }
+
+    /// Removes the given transactions, which were already removed from their sub-pool, and all
+    /// their descendants from the entire pool.
+    fn discard_subpool_transactions(
+        &mut self,
+        transactions: Vec<Arc<ValidPoolTransaction<T::Transaction>>>,
+        removed: &mut Vec<Arc<ValidPoolTransaction<T::Transaction>>>,
+    ) {
+        // the transactions may descend from each other, so they're all removed from the total set
+        // before their descendants are removed
+        let ids = transactions.iter().map(|tx| *tx.id()).collect::<Vec<_>>();
+        for tx in transactions {
+            self.all_transactions.remove_transaction(tx.id());
+            removed.push(tx);
+        }
+        for id in ids {
+            self.remove_descendants(&id, removed);
+        }
+    }
+
     /// Number of transactions in the entire pool
     pub(crate) fn len(&self) -> usize {
         self.all_transactions.len()
@@ -902,6 +1045,8 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
     by_hash: HashMap<TxHash, Arc<ValidPoolTransaction<T>>>,
     /// _All_ transaction in the pool sorted by their sender and nonce pair.
     txs: BTreeMap<TransactionId, PoolInternalTransaction<T>>,
+    /// Hashes of the transactions that were submitted with conditions.
+    conditional: HashSet<TxHash>,
     /// Tracks the number of transactions by sender that are currently in the pool.
     tx_counter: FnvHashMap<SenderId, usize>,
     /// The current block number the pool keeps track of.
@@ -1241,6 +1386,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
     ) -> Option<(Arc<ValidPoolTransaction<T>>, SubPool)> {
         let tx = self.by_hash.remove(tx_hash)?;
         let internal = self.txs.remove(&tx.transaction_id)?;
+        self.conditional.remove(tx_hash);
         // decrement the counter for the sender.
         self.tx_decr(tx.sender_id());
         self.update_size_metrics();
@@ -1257,6 +1403,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
         id: &TransactionId,
     ) -> Option<(Arc<ValidPoolTransaction<T>>, SubPool)> {
         let internal = self.txs.remove(id)?;
+        self.conditional.remove(internal.transaction.hash());
 
         // decrement the counter for the sender.
         self.tx_decr(internal.transaction.sender_id());
@@ -1387,7 +1534,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
         maybe_replacement: &ValidPoolTransaction<T>,
         price_bumps: &PriceBumpConfig,
     ) -> bool {
-        let price_bump = price_bumps.price_bump(existing_transaction.tx_type());
+        let price_bump = existing_transaction.transaction.replacement_price_bump(price_bumps);
 
         if maybe_replacement.max_fee_per_gas() <=
             existing_transaction.max_fee_per_gas() * (100 + price_bump) / 100
@@ -1531,6 +1678,9 @@ impl<T: PoolTransaction> AllTransactions<T> {
             Entry::Vacant(entry) => {
                 // Insert the transaction in both maps
                 self.by_hash.insert(*pool_tx.transaction.hash(), pool_tx.transaction.clone());
+                if pool_tx.transaction.conditional.is_some() {
+                    self.conditional.insert(*pool_tx.transaction.hash());
+                }
                 entry.insert(pool_tx);
             }
             Entry::Occupied(mut entry) => {
@@ -1550,6 +1700,10 @@ impl<T: PoolTransaction> AllTransactions<T> {
                 let new_transaction = pool_tx.transaction.clone();
                 let replaced = entry.insert(pool_tx);
                 self.by_hash.remove(replaced.transaction.hash());
+                self.conditional.remove(replaced.transaction.hash());
+                if new_transaction.conditional.is_some() {
+                    self.conditional.insert(new_hash);
+                }
                 self.by_hash.insert(new_hash, new_transaction);
                 // also remove the hash
                 replaced_tx = Some((replaced.transaction, replaced.subpool));
@@ -1660,6 +1814,10 @@ impl<T: PoolTransaction> AllTransactions<T> {
     #[cfg(any(test, feature = "test-utils"))]
     pub(crate) fn assert_invariants(&self) {
         assert_eq!(self.by_hash.len(), self.txs.len(), "by_hash.len() != txs.len()");
+        assert!(
+            self.conditional.iter().all(|hash| self.by_hash.contains_key(hash)),
+            "conditional transactions must be in the pool"
+        );
     }
 }
 
@@ -1681,6 +1839,7 @@ impl<T: PoolTransaction> Default for AllTransactions<T> {
             block_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             by_hash: Default::default(),
             txs: Default::default(),
+            conditional: Default::default(),
             tx_counter: Default::default(),
             last_seen_block_number: Default::default(),
             last_seen_block_hash: Default::default(),
@@ -1854,8 +2013,9 @@ mod tests {
     use crate::{
         test_utils::{MockOrdering, MockTransaction, MockTransactionFactory, MockTransactionSet},
         traits::TransactionOrigin,
-        SubPoolLimit,
+        BlobPoolConfig, SubPoolLimit, TransactionLifetimes,
     };
+    use std::time::Duration;
 
     #[test]
     fn test_insert_blob() {
@@ -2847,6 +3007,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn discard_blobs_over_blob_limit() {
+        let mut f = MockTransactionFactory::default();
+        let blob_pool = BlobPoolConfig { max_blobs: 10, ..Default::default() };
+        let mut pool =
+            TxPool::new(MockOrdering::default(), PoolConfig { blob_pool, ..Default::default() });
+        pool.all_transactions.pending_fees.blob_fee = 10000;
+
+        for _ in 0..20 {
+            let tx = MockTransaction::eip4844().inc_price_by(100).with_blob_fee(100);
+            pool.add_transaction(f.validated(tx), U256::from(1_000), 0).unwrap();
+        }
+        assert!(pool.is_exceeded());
+
+        let removed = pool.discard_worst();
+        assert_eq!(removed.len(), 10);
+        assert_eq!(pool.blob_pool.blob_count(), 10);
+        assert!(!pool.is_exceeded());
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_unviable_blob_txs() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        pool.all_transactions.pending_fees.blob_fee = 10000;
+
+        let sender = address!("000000000000000000000000000000000000000a");
+        let txs = MockTransactionSet::dependent(sender, 0, 2, TxType::Eip4844)
+            .into_iter()
+            .map(|tx| tx.with_blob_fee(100))
+            .collect::<Vec<_>>();
+        for tx in txs {
+            pool.add_transaction(f.validated(tx), U256::from(1_000), 0).unwrap();
+        }
+        assert_eq!(pool.blob_pool.len(), 2);
+
+        // the blob fee can't drop from 1M to 100 within the default horizon
+        let mut block_info = pool.block_info();
+        block_info.pending_blob_fee = Some(1_000_000);
+        let outcome = pool.on_canonical_state_change(block_info, vec![], HashMap::new());
+        assert_eq!(outcome.discarded.len(), 2);
+        assert!(pool.is_empty());
+        pool.assert_invariants();
+    }
+
     #[test]
     fn account_updates_nonce_gap() {
         let on_chain_balance = U256::from(10_000);
@@ -2981,4 +3187,78 @@ mod tests {
             vec![1, 2, 3]
         );
     }
+
+    #[test]
+    fn remove_stale_transactions() {
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            transaction_lifetimes: TransactionLifetimes {
+                pending: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pool = TxPool::new(MockOrdering::default(), config);
+        let now = Instant::now() + Duration::from_secs(120);
+
+        // the first transaction of the sender is stale, which also evicts its descendant
+        let tx = MockTransaction::eip1559();
+        let stale = f.validated(tx.clone());
+        pool.add_transaction(stale.clone(), U256::MAX, 0).unwrap();
+        let mut descendant = f.validated(tx.next());
+        descendant.timestamp = now;
+        pool.add_transaction(descendant.clone(), U256::MAX, 0).unwrap();
+
+        let mut fresh = f.validated(MockTransaction::eip1559());
+        fresh.timestamp = now;
+        pool.add_transaction(fresh.clone(), U256::MAX, 0).unwrap();
+        assert_eq!(pool.pending_pool.len(), 3);
+
+        let removed = pool.remove_stale_transactions(now);
+        assert_eq!(
+            removed.iter().map(|tx| *tx.hash()).collect::<HashSet<_>>(),
+            HashSet::from([*stale.hash(), *descendant.hash()])
+        );
+        assert_eq!(pool.pending_pool.len(), 1);
+        assert!(pool.contains(fresh.hash()));
+    }
+
+    #[test]
+    fn remove_transactions_by_subpool_and_sender() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559();
+        let pending = f.validated(tx.clone());
+        pool.add_transaction(pending.clone(), U256::MAX, 0).unwrap();
+        let descendant = f.validated(tx.next());
+        pool.add_transaction(descendant.clone(), U256::MAX, 0).unwrap();
+
+        // nonce gapped transaction of another sender
+        let queued = f.validated(MockTransaction::eip1559().with_nonce(5));
+        pool.add_transaction(queued.clone(), U256::MAX, 0).unwrap();
+        assert_eq!(pool.pending_pool.len(), 2);
+        assert_eq!(pool.queued_pool.len(), 1);
+
+        let state = pool.transaction_state(pending.hash()).unwrap();
+        assert_eq!(state.subpool, SubPool::Pending);
+        assert!(state.no_nonce_gaps);
+        let state = pool.transaction_state(queued.hash()).unwrap();
+        assert_eq!(state.subpool, SubPool::Queued);
+        assert!(!state.no_nonce_gaps);
+        assert!(state.enough_balance);
+
+        let removed = pool.remove_transactions_by_subpool(SubPool::Pending);
+        assert_eq!(
+            removed.iter().map(|tx| *tx.hash()).collect::<HashSet<_>>(),
+            HashSet::from([*pending.hash(), *descendant.hash()])
+        );
+        assert!(pool.pending_pool.is_empty());
+        assert!(pool.transaction_state(pending.hash()).is_none());
+
+        let removed = pool.remove_transactions_by_sender(queued.sender_id());
+        assert_eq!(removed.len(), 1);
+        assert_eq!(*removed[0].hash(), *queued.hash());
+        assert!(pool.is_empty());
+    }
 }
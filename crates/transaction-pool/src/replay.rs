@@ -0,0 +1,801 @@
+//! Recording of transaction pool activity and replaying it against a fresh pool.
+//!
+//! The recording file consists of a sequence of RLP encoded [`RecordedEvent`]s: every transaction
+//! that was added to the pool and every canonical block update, in the order in which they were
+//! applied to the pool.
+//! A recording is captured by [`record_pool_task`] and can be replayed against a pool with a
+//! different [`PoolConfig`](crate::PoolConfig) via [`replay`], which uses a [`ReplayValidator`] in
+//! place of the state backed validator and reports how the pool behaved over time.
+
+use crate::{
+    error::InvalidPoolTransactionError,
+    journal::{origin_from_u8, origin_to_u8},
+    traits::{CanonicalStateUpdate, ChangedAccount, TransactionPoolExt},
+    BlobStore, FullTransactionEvent, Pool, PoolSize, PoolTransaction, TransactionOrdering,
+    TransactionOrigin, TransactionPool, TransactionValidationOutcome, TransactionValidator,
+    ValidPoolTransaction, ValidTransaction,
+};
+use alloy_rlp::{BufMut, Decodable, Encodable, Header};
+use parking_lot::RwLock;
+use reth_primitives::{
+    fs::FsPathError, Address, BlobTransaction, BlobTransactionSidecar,
+    FromRecoveredPooledTransaction, IntoRecoveredTransaction, InvalidTransactionError,
+    PooledTransactionsElement, SealedBlock, TxHash, U256,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::Write,
+    marker::PhantomData,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, info, trace, warn};
+
+/// Identifier of a recorded transaction.
+const TRANSACTION_EVENT: u8 = 0;
+/// Identifier of a recorded block update.
+const BLOCK_EVENT: u8 = 1;
+
+/// Errors that can occur while reading or writing a recording.
+#[derive(Debug, thiserror::Error)]
+pub enum PoolRecordingError {
+    /// Error accessing the recording file.
+    #[error("failed to access transaction pool recording: {0}")]
+    Io(#[from] std::io::Error),
+    /// Error reading the recording file.
+    #[error("failed to read transaction pool recording: {0}")]
+    FsPath(#[from] FsPathError),
+}
+
+/// A transaction that was added to the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedTransaction {
+    /// Unix timestamp in milliseconds at which the transaction was recorded.
+    pub recorded_at: u64,
+    /// Where the transaction originated from.
+    pub origin: TransactionOrigin,
+    /// The transaction, including the blob sidecar of blob transactions.
+    pub transaction: PooledTransactionsElement,
+}
+
+impl RecordedTransaction {
+    /// Creates a new record of the transaction that is added to the pool, with the blob sidecar of
+    /// blob transactions.
+    ///
+    /// Returns `None` if the transaction is a blob transaction and the sidecar is missing.
+    pub(crate) fn new<T: PoolTransaction>(
+        transaction: &ValidPoolTransaction<T>,
+        sidecar: Option<&BlobTransactionSidecar>,
+    ) -> Option<Self> {
+        let signed = transaction.to_recovered_transaction().into_signed();
+        let element = match sidecar {
+            Some(sidecar) => PooledTransactionsElement::BlobTransaction(
+                BlobTransaction::try_from_signed(signed, sidecar.clone()).ok()?,
+            ),
+            None => PooledTransactionsElement::try_from(signed).ok()?,
+        };
+        Some(Self { recorded_at: unix_millis(), origin: transaction.origin, transaction: element })
+    }
+}
+
+/// A canonical block update of the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedBlock {
+    /// Unix timestamp in milliseconds at which the update was recorded.
+    pub recorded_at: u64,
+    /// Header of the new tip block.
+    pub header: reth_primitives::Header,
+    /// EIP-1559 base fee of the next block.
+    pub pending_block_base_fee: u64,
+    /// EIP-4844 blob fee of the next block, if Cancun is active.
+    pub pending_block_blob_fee: Option<u128>,
+    /// All accounts changed by the update.
+    pub changed_accounts: Vec<ChangedAccount>,
+    /// All transactions mined by the update.
+    pub mined_transactions: Vec<TxHash>,
+}
+
+impl RecordedBlock {
+    /// Creates a new record of the canonical state update that is applied to the pool.
+    pub(crate) fn new(update: &CanonicalStateUpdate<'_>) -> Self {
+        Self {
+            recorded_at: unix_millis(),
+            header: update.new_tip.header.header().clone(),
+            pending_block_base_fee: update.pending_block_base_fee,
+            pending_block_blob_fee: update.pending_block_blob_fee,
+            changed_accounts: update.changed_accounts.clone(),
+            mined_transactions: update.mined_transactions.clone(),
+        }
+    }
+}
+
+/// An entry of a recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordedEvent {
+    /// A transaction was added to the pool.
+    Transaction(RecordedTransaction),
+    /// The canonical chain advanced.
+    Block(RecordedBlock),
+}
+
+impl RecordedEvent {
+    /// Returns the unix timestamp in milliseconds at which the event was recorded.
+    pub const fn recorded_at(&self) -> u64 {
+        match self {
+            RecordedEvent::Transaction(tx) => tx.recorded_at,
+            RecordedEvent::Block(block) => block.recorded_at,
+        }
+    }
+
+    fn payload_length(&self) -> usize {
+        match self {
+            RecordedEvent::Transaction(tx) => {
+                TRANSACTION_EVENT.length() +
+                    tx.recorded_at.length() +
+                    origin_to_u8(tx.origin).length() +
+                    tx.transaction.length()
+            }
+            RecordedEvent::Block(block) => {
+                BLOCK_EVENT.length() +
+                    block.recorded_at.length() +
+                    block.header.length() +
+                    block.pending_block_base_fee.length() +
+                    block.pending_block_blob_fee.unwrap_or_default().length() +
+                    block.changed_accounts.length() +
+                    block.mined_transactions.length()
+            }
+        }
+    }
+}
+
+impl Encodable for RecordedEvent {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        match self {
+            RecordedEvent::Transaction(tx) => {
+                TRANSACTION_EVENT.encode(out);
+                tx.recorded_at.encode(out);
+                origin_to_u8(tx.origin).encode(out);
+                tx.transaction.encode(out);
+            }
+            RecordedEvent::Block(block) => {
+                BLOCK_EVENT.encode(out);
+                block.recorded_at.encode(out);
+                block.header.encode(out);
+                block.pending_block_base_fee.encode(out);
+                // the blob fee is never zero once Cancun is active
+                block.pending_block_blob_fee.unwrap_or_default().encode(out);
+                block.changed_accounts.encode(out);
+                block.mined_transactions.encode(out);
+            }
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for RecordedEvent {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let event = match u8::decode(buf)? {
+            TRANSACTION_EVENT => RecordedEvent::Transaction(RecordedTransaction {
+                recorded_at: u64::decode(buf)?,
+                origin: origin_from_u8(u8::decode(buf)?)?,
+                transaction: PooledTransactionsElement::decode(buf)?,
+            }),
+            BLOCK_EVENT => RecordedEvent::Block(RecordedBlock {
+                recorded_at: u64::decode(buf)?,
+                header: reth_primitives::Header::decode(buf)?,
+                pending_block_base_fee: u64::decode(buf)?,
+                pending_block_blob_fee: Some(u128::decode(buf)?).filter(|fee| *fee != 0),
+                changed_accounts: Vec::<ChangedAccount>::decode(buf)?,
+                mined_transactions: Vec::<TxHash>::decode(buf)?,
+            }),
+            _ => return Err(alloy_rlp::Error::Custom("unknown recorded event")),
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+        Ok(event)
+    }
+}
+
+impl Encodable for ChangedAccount {
+    fn encode(&self, out: &mut dyn BufMut) {
+        let payload_length = self.address.length() + self.nonce.length() + self.balance.length();
+        Header { list: true, payload_length }.encode(out);
+        self.address.encode(out);
+        self.nonce.encode(out);
+        self.balance.encode(out);
+    }
+}
+
+impl Decodable for ChangedAccount {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        Ok(Self {
+            address: Address::decode(buf)?,
+            nonce: u64::decode(buf)?,
+            balance: U256::decode(buf)?,
+        })
+    }
+}
+
+/// Returns the current unix timestamp in milliseconds.
+fn unix_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// A recording file of the transaction pool.
+#[derive(Debug)]
+pub struct PoolRecording {
+    /// Path to the recording file.
+    path: PathBuf,
+    /// The recording file opened for appending, opened on the first append.
+    file: Option<File>,
+}
+
+impl PoolRecording {
+    /// Creates a new recording at the given path.
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path, file: None }
+    }
+
+    /// Reads all recorded events, in the order they were recorded.
+    ///
+    /// A corrupted tail, for example of an append that was interrupted by a crash, is skipped.
+    pub fn read(&self) -> Result<Vec<RecordedEvent>, PoolRecordingError> {
+        let data = reth_primitives::fs::read(&self.path)?;
+        let mut buf = data.as_slice();
+
+        let mut events = Vec::new();
+        while !buf.is_empty() {
+            match RecordedEvent::decode(&mut buf) {
+                Ok(event) => events.push(event),
+                Err(err) => {
+                    warn!(target: "txpool", %err, recording=?self.path, remaining=buf.len(), "Skipping corrupted transaction pool recording entries");
+                    break
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    /// Appends the event to the recording.
+    pub fn append(&mut self, event: &RecordedEvent) -> Result<(), PoolRecordingError> {
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => {
+                if let Some(parent) = self.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+                self.file.insert(file)
+            }
+        };
+        let mut buf = Vec::with_capacity(event.length());
+        event.encode(&mut buf);
+        file.write_all(&buf)?;
+        Ok(())
+    }
+}
+
+/// Appends the events to the recording on a blocking thread.
+async fn append_events(mut recording: PoolRecording, events: Vec<RecordedEvent>) -> PoolRecording {
+    let path = recording.path.clone();
+    tokio::task::spawn_blocking(move || {
+        for event in &events {
+            trace!(target: "txpool", recorded_at = event.recorded_at(), "Appending transaction pool recording entry");
+            if let Err(err) = recording.append(event) {
+                warn!(target: "txpool", %err, recording=?recording.path, "Failed to append to transaction pool recording");
+            }
+        }
+        recording
+    })
+    .await
+    .unwrap_or_else(|err| {
+        warn!(target: "txpool", %err, recording=?path, "Failed to append to transaction pool recording");
+        PoolRecording::new(path)
+    })
+}
+
+/// Task which records all transactions added to the pool and all canonical block updates in a
+/// [`PoolRecording`] at the given path.
+///
+/// The updates are recorded in the order in which they were applied to the pool, reorgs are
+/// recorded as an update to the tip of the new chain.
+pub async fn record_pool_task<V, T, S>(
+    shutdown: reth_tasks::shutdown::GracefulShutdown,
+    pool: Pool<V, T, S>,
+    path: PathBuf,
+) where
+    V: TransactionValidator,
+    T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
+    S: BlobStore,
+{
+    let mut recording = PoolRecording::new(path);
+    // unbounded, so that no updates are missed
+    let mut events = pool.inner().add_recording_listener();
+    info!(target: "txpool", recording=?recording.path, "Recording transaction pool activity");
+
+    let mut shutdown = std::pin::pin!(shutdown);
+
+    let graceful_guard = loop {
+        let event = tokio::select! {
+            guard = &mut shutdown => break guard,
+            Some(event) = events.recv() => event,
+        };
+
+        let mut batch = vec![event];
+        while let Ok(event) = events.try_recv() {
+            batch.push(event);
+        }
+        recording = append_events(recording, batch).await;
+    };
+
+    // record the updates that were queued before the shutdown
+    let mut batch = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        batch.push(event);
+    }
+    append_events(recording, batch).await;
+
+    drop(graceful_guard)
+}
+
+/// Settings of the [`ReplayValidator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayValidatorConfig {
+    /// Balance of senders whose balance isn't known from a recorded block update.
+    pub default_balance: U256,
+    /// Max gas limit of transactions, if any.
+    pub block_gas_limit: Option<u64>,
+}
+
+impl Default for ReplayValidatorConfig {
+    fn default() -> Self {
+        Self { default_balance: U256::MAX, block_gas_limit: None }
+    }
+}
+
+/// A [`TransactionValidator`] for replaying recordings, which doesn't have access to the state.
+///
+/// The state of senders is tracked from the recorded block updates. The on chain nonce of a
+/// sender that hasn't been updated yet is assumed to be the nonce of its first transaction.
+#[derive(Debug)]
+pub struct ReplayValidator<T> {
+    /// The settings of the validator.
+    config: ReplayValidatorConfig,
+    /// Nonce and balance of all known senders.
+    accounts: Arc<RwLock<HashMap<Address, (u64, U256)>>>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> ReplayValidator<T> {
+    /// Creates a new validator with the given settings.
+    pub fn new(config: ReplayValidatorConfig) -> Self {
+        Self { config, accounts: Default::default(), _marker: Default::default() }
+    }
+
+    /// Updates the tracked state of the given accounts.
+    pub fn update_accounts(&self, accounts: &[ChangedAccount]) {
+        let mut tracked = self.accounts.write();
+        for account in accounts {
+            tracked.insert(account.address, (account.nonce, account.balance));
+        }
+    }
+}
+
+impl<T> Default for ReplayValidator<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T> Clone for ReplayValidator<T> {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config,
+            accounts: Arc::clone(&self.accounts),
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<T: PoolTransaction> TransactionValidator for ReplayValidator<T> {
+    type Transaction = T;
+
+    async fn validate_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        if let Some(block_gas_limit) = self.config.block_gas_limit {
+            let gas_limit = transaction.gas_limit();
+            if gas_limit > block_gas_limit {
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::ExceedsGasLimit(gas_limit, block_gas_limit),
+                )
+            }
+        }
+
+        let (state_nonce, balance) = *self
+            .accounts
+            .write()
+            .entry(transaction.sender())
+            .or_insert((transaction.nonce(), self.config.default_balance));
+        if transaction.nonce() < state_nonce {
+            return TransactionValidationOutcome::Invalid(
+                transaction,
+                InvalidTransactionError::NonceNotConsistent.into(),
+            )
+        }
+
+        TransactionValidationOutcome::Valid {
+            balance,
+            state_nonce,
+            transaction: ValidTransaction::Valid(transaction),
+            propagate: !origin.is_private(),
+        }
+    }
+}
+
+/// The state of the pool after a replayed block update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayBlockReport {
+    /// Number of the block.
+    pub number: u64,
+    /// Size of the pool after the update.
+    pub size: PoolSize,
+    /// Number of transactions promoted to the pending sub-pool since the previous block.
+    pub promoted: usize,
+    /// Number of transactions discarded because of the pool limits since the previous block.
+    pub discarded: usize,
+    /// Number of transactions evicted because of their lifetime since the previous block.
+    pub evicted: usize,
+    /// Number of best transactions that fit into the next block.
+    pub best_transactions: usize,
+    /// Time it took to yield the best transactions.
+    pub best_transactions_elapsed: Duration,
+}
+
+impl ReplayBlockReport {
+    /// Returns the number of best transactions yielded per second.
+    pub fn best_transactions_per_second(&self) -> f64 {
+        self.best_transactions as f64 / self.best_transactions_elapsed.as_secs_f64().max(1e-9)
+    }
+}
+
+/// Outcome of a replayed recording.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// The state of the pool after each block update.
+    pub blocks: Vec<ReplayBlockReport>,
+    /// Number of transactions that were added to the pool.
+    pub inserted: usize,
+    /// Number of transactions that were rejected by the pool.
+    pub rejected: usize,
+    /// Number of transactions promoted to the pending sub-pool.
+    pub promoted: usize,
+    /// Number of transactions discarded because of the pool limits.
+    pub discarded: usize,
+    /// Number of transactions evicted because of their lifetime.
+    pub evicted: usize,
+    /// Number of transactions replaced by another transaction.
+    pub replaced: usize,
+    /// Number of transactions mined by the block updates.
+    pub mined: usize,
+    /// Time it took to replay the recording.
+    pub elapsed: Duration,
+}
+
+/// Counts the pool events since the last block update.
+#[derive(Debug, Default)]
+struct EventCounter {
+    /// Transactions that are currently parked in a sub-pool other than pending.
+    parked: HashSet<TxHash>,
+    promoted: usize,
+    discarded: usize,
+    evicted: usize,
+}
+
+impl EventCounter {
+    fn on_event<T: PoolTransaction>(
+        &mut self,
+        event: FullTransactionEvent<T>,
+        report: &mut ReplayReport,
+    ) {
+        match event {
+            FullTransactionEvent::Pending(hash) => {
+                if self.parked.remove(&hash) {
+                    self.promoted += 1;
+                    report.promoted += 1;
+                }
+            }
+            FullTransactionEvent::Queued(hash) => {
+                self.parked.insert(hash);
+            }
+            FullTransactionEvent::Mined { tx_hash, .. } => {
+                self.parked.remove(&tx_hash);
+                report.mined += 1;
+            }
+            FullTransactionEvent::Replaced { transaction, .. } => {
+                self.parked.remove(transaction.hash());
+                report.replaced += 1;
+            }
+            FullTransactionEvent::Discarded(hash) => {
+                self.parked.remove(&hash);
+                self.discarded += 1;
+                report.discarded += 1;
+            }
+            FullTransactionEvent::Evicted(hash) => {
+                self.parked.remove(&hash);
+                self.evicted += 1;
+                report.evicted += 1;
+            }
+            FullTransactionEvent::Invalid(hash) => {
+                self.parked.remove(&hash);
+            }
+            FullTransactionEvent::Propagated(_) => {}
+        }
+    }
+}
+
+/// Replays the recorded events against the pool and reports how the pool behaved.
+///
+/// The events are replayed as fast as possible, so transaction lifetimes are only enforced
+/// relative to the time of the replay.
+pub async fn replay<T, O, S>(
+    pool: &Pool<ReplayValidator<T>, O, S>,
+    events: impl IntoIterator<Item = RecordedEvent>,
+) -> ReplayReport
+where
+    T: PoolTransaction,
+    O: TransactionOrdering<Transaction = T>,
+    S: BlobStore,
+{
+    let started = Instant::now();
+    let mut report = ReplayReport::default();
+    // unbounded, so that no events are missed between the block updates
+    let mut pool_events = pool.inner().add_unbounded_all_transactions_event_listener();
+    let mut counter = EventCounter::default();
+
+    for event in events {
+        match event {
+            RecordedEvent::Transaction(tx) => {
+                let Ok(recovered) = tx.transaction.try_into_ecrecovered() else {
+                    report.rejected += 1;
+                    continue
+                };
+                let transaction = T::from_recovered_pooled_transaction(recovered);
+                match pool.add_transaction(tx.origin, transaction).await {
+                    Ok(_) => report.inserted += 1,
+                    Err(_) => report.rejected += 1,
+                }
+            }
+            RecordedEvent::Block(block) => {
+                pool.inner().validator().update_accounts(&block.changed_accounts);
+
+                let gas_limit = block.header.gas_limit;
+                let tip = SealedBlock::new(block.header.seal_slow(), Default::default());
+                pool.on_canonical_state_change(CanonicalStateUpdate {
+                    new_tip: &tip,
+                    pending_block_base_fee: block.pending_block_base_fee,
+                    pending_block_blob_fee: block.pending_block_blob_fee,
+                    changed_accounts: block.changed_accounts,
+                    mined_transactions: block.mined_transactions,
+                });
+                pool.evict_stale_transactions();
+
+                let best_started = Instant::now();
+                let mut best_transactions = 0;
+                let mut gas_used = 0u64;
+                for tx in pool.best_transactions() {
+                    if gas_used + tx.gas_limit() > gas_limit {
+                        continue
+                    }
+                    gas_used += tx.gas_limit();
+                    best_transactions += 1;
+                }
+                let best_transactions_elapsed = best_started.elapsed();
+
+                while let Ok(event) = pool_events.try_recv() {
+                    counter.on_event(event, &mut report);
+                }
+
+                let block_report = ReplayBlockReport {
+                    number: tip.number,
+                    size: pool.pool_size(),
+                    promoted: std::mem::take(&mut counter.promoted),
+                    discarded: std::mem::take(&mut counter.discarded),
+                    evicted: std::mem::take(&mut counter.evicted),
+                    best_transactions,
+                    best_transactions_elapsed,
+                };
+                debug!(target: "txpool", ?block_report, "Replayed block update");
+                report.blocks.push(block_report);
+            }
+        }
+    }
+
+    while let Ok(event) = pool_events.try_recv() {
+        counter.on_event(event, &mut report);
+    }
+    report.elapsed = started.elapsed();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blobstore::InMemoryBlobStore, test_utils::TransactionGenerator, CoinbaseTipOrdering,
+        EthPooledTransaction,
+    };
+    use reth_primitives::B256;
+    use reth_tasks::TaskManager;
+
+    /// Returns a recorded transaction of a new sender.
+    fn transaction_event(gen: &mut TransactionGenerator<rand::rngs::ThreadRng>) -> RecordedEvent {
+        let transaction = gen.transaction().signer(B256::random()).into_eip1559();
+        let transaction = PooledTransactionsElement::try_from(transaction).unwrap();
+        RecordedEvent::Transaction(RecordedTransaction {
+            recorded_at: unix_millis(),
+            origin: TransactionOrigin::External,
+            transaction,
+        })
+    }
+
+    /// Returns a pool transaction of a new sender.
+    fn transaction(gen: &mut TransactionGenerator<rand::rngs::ThreadRng>) -> EthPooledTransaction {
+        let RecordedEvent::Transaction(tx) = transaction_event(gen) else { unreachable!() };
+        EthPooledTransaction::from_recovered_pooled_transaction(
+            tx.transaction.try_into_ecrecovered().unwrap(),
+        )
+    }
+
+    fn block_event(number: u64, mined_transactions: Vec<TxHash>) -> RecordedEvent {
+        RecordedEvent::Block(RecordedBlock {
+            recorded_at: unix_millis(),
+            header: reth_primitives::Header { number, gas_limit: 30_000_000, ..Default::default() },
+            pending_block_base_fee: 1,
+            pending_block_blob_fee: None,
+            changed_accounts: vec![ChangedAccount {
+                address: Address::with_last_byte(1),
+                nonce: 1,
+                balance: U256::from(100),
+            }],
+            mined_transactions,
+        })
+    }
+
+    #[test]
+    fn recording_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recording = PoolRecording::new(dir.path().join("recording.rlp"));
+
+        let mut gen = TransactionGenerator::new(rand::thread_rng());
+        let events =
+            vec![transaction_event(&mut gen), block_event(1, vec![B256::with_last_byte(2)])];
+        for event in &events {
+            recording.append(event).unwrap();
+        }
+
+        assert_eq!(recording.read().unwrap(), events);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replay_recording() {
+        let mut gen = TransactionGenerator::new(rand::thread_rng());
+        let events = (0..10).map(|_| transaction_event(&mut gen)).collect::<Vec<_>>();
+        let mined = match &events[0] {
+            RecordedEvent::Transaction(tx) => *tx.transaction.hash(),
+            _ => unreachable!(),
+        };
+        let events = events.into_iter().chain([block_event(1, vec![mined])]);
+
+        let pool = Pool::new(
+            ReplayValidator::<EthPooledTransaction>::default(),
+            CoinbaseTipOrdering::default(),
+            InMemoryBlobStore::default(),
+            Default::default(),
+        );
+        let report = replay(&pool, events).await;
+
+        assert_eq!(report.inserted, 10);
+        assert_eq!(report.rejected, 0);
+        assert_eq!(report.blocks.len(), 1);
+        let block = report.blocks[0];
+        assert_eq!(block.number, 1);
+        assert_eq!(block.size.total, 9);
+        assert_eq!(block.best_transactions, 9);
+        assert!(!pool.contains(&mined));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn records_updates_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.rlp");
+        let pool = Pool::new(
+            ReplayValidator::<EthPooledTransaction>::default(),
+            CoinbaseTipOrdering::default(),
+            InMemoryBlobStore::default(),
+            Default::default(),
+        );
+
+        let manager = TaskManager::new(tokio::runtime::Handle::current());
+        manager.executor().spawn_critical_with_graceful_shutdown_signal("recorder", |shutdown| {
+            record_pool_task(shutdown, pool.clone(), path.clone())
+        });
+        while !pool.inner().is_recording() {
+            tokio::task::yield_now().await;
+        }
+
+        // a burst that exceeds the capacity of the bounded listeners
+        let mut gen = TransactionGenerator::new(rand::thread_rng());
+        let burst = (0..1500).map(|_| transaction(&mut gen)).collect::<Vec<_>>();
+        let hashes = burst.iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        let outcome = pool.add_transactions(TransactionOrigin::External, burst).await;
+        assert!(outcome.iter().all(Result::is_ok));
+
+        let tip = SealedBlock::new(
+            reth_primitives::Header { number: 1, gas_limit: 30_000_000, ..Default::default() }
+                .seal_slow(),
+            Default::default(),
+        );
+        pool.on_canonical_state_change(CanonicalStateUpdate {
+            new_tip: &tip,
+            pending_block_base_fee: 1,
+            pending_block_blob_fee: None,
+            changed_accounts: Vec::new(),
+            mined_transactions: vec![hashes[0]],
+        });
+
+        let last = transaction(&mut gen);
+        let last_hash = pool.add_transaction(TransactionOrigin::Local, last).await.unwrap();
+
+        manager.graceful_shutdown();
+
+        let events = PoolRecording::new(path).read().unwrap();
+        assert_eq!(events.len(), hashes.len() + 2);
+        for (event, hash) in events.iter().zip(&hashes) {
+            match event {
+                RecordedEvent::Transaction(tx) => assert_eq!(tx.transaction.hash(), hash),
+                _ => panic!("expected a transaction, got {event:?}"),
+            }
+        }
+        match &events[hashes.len()] {
+            RecordedEvent::Block(block) => {
+                assert_eq!(block.header.number, 1);
+                assert_eq!(block.mined_transactions, vec![hashes[0]]);
+            }
+            event => panic!("expected a block update, got {event:?}"),
+        }
+        match &events[hashes.len() + 1] {
+            RecordedEvent::Transaction(tx) => {
+                assert_eq!(*tx.transaction.hash(), last_hash);
+                assert_eq!(tx.origin, TransactionOrigin::Local);
+            }
+            event => panic!("expected a transaction, got {event:?}"),
+        }
+    }
+}
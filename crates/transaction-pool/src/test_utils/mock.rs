@@ -634,6 +634,14 @@ impl PoolTransaction for MockTransaction {
         }
     }
 
+    /// Returns the number of blobs, mock blob transactions have a single blob.
+    fn blob_count(&self) -> usize {
+        match self {
+            MockTransaction::Eip4844 { .. } => 1,
+            _ => 0,
+        }
+    }
+
     /// Calculates the effective tip per gas given a base fee.
     fn effective_tip_per_gas(&self, base_fee: u64) -> Option<u128> {
         // Convert base_fee to u128 for precision in calculations
@@ -1082,6 +1090,7 @@ impl MockTransactionFactory {
             transaction,
             timestamp: Instant::now(),
             origin,
+            conditional: None,
         }
     }
 
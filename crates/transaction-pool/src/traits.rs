@@ -2,20 +2,23 @@
 
 use crate::{
     blobstore::BlobStoreError,
-    error::PoolResult,
-    pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
-    validate::ValidPoolTransaction,
-    AllTransactionsEvents,
+    error::{InvalidPoolTransactionError, PoolResult},
+    pool::{
+        state::{SubPool, TxStateInfo},
+        BestTransactionFilter, TransactionEvents,
+    },
+    validate::{ForkTracker, ValidPoolTransaction},
+    AllTransactionsEvents, PriceBumpConfig, TransactionConditional,
 };
 use futures_util::{ready, Stream};
 use reth_eth_wire::HandleMempoolData;
 use reth_primitives::{
     kzg::KzgSettings, transaction::TryFromRecoveredTransactionError, AccessList, Address,
-    BlobTransactionSidecar, BlobTransactionValidationError, FromRecoveredPooledTransaction,
-    IntoRecoveredTransaction, PeerId, PooledTransactionsElement,
-    PooledTransactionsElementEcRecovered, SealedBlock, Transaction, TransactionKind,
-    TransactionSignedEcRecovered, TryFromRecoveredTransaction, TxEip4844, TxHash, B256,
-    EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, U256,
+    BlobTransactionSidecar, BlobTransactionValidationError, ChainSpec,
+    FromRecoveredPooledTransaction, IntoRecoveredTransaction, InvalidTransactionError, PeerId,
+    PooledTransactionsElement, PooledTransactionsElementEcRecovered, SealedBlock, Transaction,
+    TransactionKind, TransactionSignedEcRecovered, TryFromRecoveredTransaction, TxEip4844, TxHash,
+    B256, EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, U256,
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -95,6 +98,19 @@ pub trait TransactionPool: Send + Sync + Clone {
         transaction: Self::Transaction,
     ) -> impl Future<Output = PoolResult<TxHash>> + Send;
 
+    /// Adds an _unvalidated_ transaction into the pool that may only be included in a block if the
+    /// given conditions hold.
+    ///
+    /// The transaction is removed from the pool once its conditions can no longer be satisfied.
+    ///
+    /// Consumer: RPC
+    fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> impl Future<Output = PoolResult<TxHash>> + Send;
+
     /// Adds the given _unvalidated_ transaction into the pool.
     ///
     /// Returns a list of results.
@@ -112,7 +128,20 @@ pub trait TransactionPool: Send + Sync + Clone {
     fn transaction_event_listener(&self, tx_hash: TxHash) -> Option<TransactionEvents>;
 
     /// Returns a new transaction change event stream for _all_ transactions in the pool.
-    fn all_transactions_event_listener(&self) -> AllTransactionsEvents<Self::Transaction>;
+    fn all_transactions_event_listener(&self) -> AllTransactionsEvents<Self::Transaction> {
+        self.all_transactions_event_listener_for(TransactionListenerKind::All)
+    }
+
+    /// Returns a new transaction change event stream for _all_ transactions in the pool depending
+    /// on the given [TransactionListenerKind] argument.
+    ///
+    /// Note: [TransactionListenerKind::PropagateOnly] listeners __only__ receive events of
+    /// transactions that are allowed to be propagated, and a replacement that isn't is reported as
+    /// [FullTransactionEvent::Discarded](crate::FullTransactionEvent::Discarded).
+    fn all_transactions_event_listener_for(
+        &self,
+        kind: TransactionListenerKind,
+    ) -> AllTransactionsEvents<Self::Transaction>;
 
     /// Returns a new Stream that yields transactions hashes for new __pending__ transactions
     /// inserted into the pool that are allowed to be propagated.
@@ -218,6 +247,8 @@ pub trait TransactionPool: Send + Sync + Clone {
     ///
     /// If the transaction is a blob transaction, the sidecar will be included.
     ///
+    /// Transactions submitted as [TransactionOrigin::Private] are never returned.
+    ///
     /// Consumer: P2P
     fn get_pooled_transaction_elements(
         &self,
@@ -232,6 +263,9 @@ pub trait TransactionPool: Send + Sync + Clone {
     ///
     /// If the transaction is a blob transaction, the sidecar will be included.
     ///
+    /// Transactions submitted as [TransactionOrigin::Private] or with conditions are never
+    /// returned.
+    ///
     /// Consumer: P2P
     fn get_pooled_transaction_element(&self, tx_hash: TxHash) -> Option<PooledTransactionsElement>;
 
@@ -287,6 +321,11 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Consumer: RPC
     fn all_transactions(&self) -> AllPoolTransactions<Self::Transaction>;
 
+    /// Returns all transactions in the pool that were submitted with conditions.
+    ///
+    /// Consumer: Maintenance
+    fn conditional_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Removes all transactions corresponding to the given hashes.
     ///
     /// Also removes all _dependent_ transactions.
@@ -297,6 +336,24 @@ pub trait TransactionPool: Send + Sync + Clone {
         hashes: Vec<TxHash>,
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Removes all transactions of the given sender.
+    ///
+    /// Consumer: RPC
+    fn remove_transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
+    /// Removes all transactions in the given [SubPool].
+    ///
+    /// Also removes all _dependent_ transactions.
+    ///
+    /// Consumer: RPC
+    fn remove_transactions_by_subpool(
+        &self,
+        subpool: SubPool,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Retains only those hashes that are unknown to the pool.
     /// In other words, removes all transactions from the given set that are currently present in
     /// the pool. Returns hashes already known to the pool.
@@ -306,6 +363,14 @@ pub trait TransactionPool: Send + Sync + Clone {
     where
         A: HandleMempoolData;
 
+    /// Returns the state of the transaction for the given hash, which explains why it resides in
+    /// its [SubPool].
+    ///
+    /// Returns `None` if the transaction is not in the pool.
+    ///
+    /// Consumer: RPC
+    fn transaction_state(&self, tx_hash: TxHash) -> Option<TxStateInfo>;
+
     /// Returns if the transaction for the given hash is already included in this pool.
     fn contains(&self, tx_hash: &TxHash) -> bool {
         self.get(tx_hash).is_some()
@@ -411,6 +476,12 @@ pub trait TransactionPoolExt: TransactionPool {
     /// Updates the accounts in the pool
     fn update_accounts(&self, accounts: Vec<ChangedAccount>);
 
+    /// Evicts all transactions that exceeded their configured lifetime, see
+    /// [TransactionLifetimes](crate::TransactionLifetimes), and returns them.
+    ///
+    /// Descendants of evicted transactions are evicted as well.
+    fn evict_stale_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Deletes the blob sidecar for the given transaction from the blob store
     fn delete_blob(&self, tx: B256);
 
@@ -419,6 +490,13 @@ pub trait TransactionPoolExt: TransactionPool {
 
     /// Maintenance function to cleanup blobs that are no longer needed.
     fn cleanup_blobs(&self);
+
+    /// Returns the hashes of all blob sidecars in the blob store that don't belong to a transaction
+    /// in the pool.
+    ///
+    /// This includes the sidecars of mined blob transactions that are kept until their block is
+    /// finalized.
+    fn orphaned_blobs(&self) -> Vec<B256>;
 }
 
 /// Determines what kind of new transactions should be emitted by a stream of transactions.
@@ -711,6 +789,24 @@ pub trait BestTransactions: Iterator + Send {
     }
 }
 
+impl<T: BestTransactions + ?Sized> BestTransactions for Box<T> {
+    fn mark_invalid(&mut self, tx: &Self::Item) {
+        (**self).mark_invalid(tx)
+    }
+
+    fn no_updates(&mut self) {
+        (**self).no_updates()
+    }
+
+    fn skip_blobs(&mut self) {
+        (**self).skip_blobs()
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        (**self).set_skip_blobs(skip_blobs)
+    }
+}
+
 /// A no-op implementation that yields no transactions.
 impl<T> BestTransactions for std::iter::Empty<T> {
     fn mark_invalid(&mut self, _tx: &T) {}
@@ -729,6 +825,13 @@ pub struct BestTransactionsAttributes {
     pub basefee: u64,
     /// The blob fee attribute for best transactions.
     pub blob_fee: Option<u64>,
+    /// The number of the block the transactions are included in.
+    ///
+    /// Together with the timestamp, this is used to skip conditional transactions whose bounds
+    /// are violated by the block.
+    pub block_number: Option<u64>,
+    /// The timestamp of the block the transactions are included in.
+    pub timestamp: Option<u64>,
 }
 
 // === impl BestTransactionsAttributes ===
@@ -736,7 +839,7 @@ pub struct BestTransactionsAttributes {
 impl BestTransactionsAttributes {
     /// Creates a new `BestTransactionsAttributes` with the given basefee and blob fee.
     pub const fn new(basefee: u64, blob_fee: Option<u64>) -> Self {
-        Self { basefee, blob_fee }
+        Self { basefee, blob_fee, block_number: None, timestamp: None }
     }
 
     /// Creates a new `BestTransactionsAttributes` with the given basefee.
@@ -749,6 +852,13 @@ impl BestTransactionsAttributes {
         self.blob_fee = Some(blob_fee);
         self
     }
+
+    /// Sets the number and timestamp of the block the transactions are included in.
+    pub const fn with_block(mut self, number: u64, timestamp: u64) -> Self {
+        self.block_number = Some(number);
+        self.timestamp = Some(timestamp);
+        self
+    }
 }
 
 /// Trait for transaction types used inside the pool
@@ -801,6 +911,11 @@ pub trait PoolTransaction:
     /// This will return `None` for non-EIP4844 transactions
     fn max_fee_per_blob_gas(&self) -> Option<u128>;
 
+    /// Returns the number of blobs of an EIP-4844 transaction.
+    ///
+    /// This will return `0` for non-EIP4844 transactions
+    fn blob_count(&self) -> usize;
+
     /// Returns the effective tip for this transaction.
     ///
     /// For EIP-1559 transactions: `min(max_fee_per_gas - base_fee, max_priority_fee_per_gas)`.
@@ -840,6 +955,22 @@ pub trait PoolTransaction:
         self.tx_type() == EIP4844_TX_TYPE_ID
     }
 
+    /// Returns the price bump (in %) a replacement of this transaction must pay.
+    ///
+    /// By default this is determined by the pool's [PriceBumpConfig], transaction types with
+    /// their own replacement rules can override this.
+    fn replacement_price_bump(&self, price_bumps: &PriceBumpConfig) -> u128 {
+        price_bumps.price_bump(self.tx_type())
+    }
+
+    /// Returns true if the full transaction can be broadcast to peers.
+    ///
+    /// Transactions that aren't broadcastable in full, like EIP-4844 transactions, are only
+    /// announced by hash and must be requested by the peers.
+    fn is_broadcastable_in_full(&self) -> bool {
+        !self.is_eip4844()
+    }
+
     /// Returns the length of the rlp encoded transaction object
     ///
     /// Note: Implementations should cache this value.
@@ -855,11 +986,6 @@ pub trait EthPoolTransaction: PoolTransaction {
     /// Extracts the blob sidecar from the transaction.
     fn take_blob(&mut self) -> EthBlobTransactionSidecar;
 
-    /// Returns the number of blobs this transaction has.
-    fn blob_count(&self) -> usize {
-        self.as_eip4844().map(|tx| tx.blob_versioned_hashes.len()).unwrap_or_default()
-    }
-
     /// Returns the transaction as EIP-4844 transaction if it is one.
     fn as_eip4844(&self) -> Option<&TxEip4844>;
 
@@ -869,6 +995,23 @@ pub trait EthPoolTransaction: PoolTransaction {
         blob: &BlobTransactionSidecar,
         settings: &KzgSettings,
     ) -> Result<(), BlobTransactionValidationError>;
+
+    /// Performs the intrinsic validation of a transaction of an additional type that was
+    /// registered with the validator, see
+    /// [with_additional_tx_type](crate::validate::EthTransactionValidatorBuilder::with_additional_tx_type).
+    ///
+    /// This replaces the intrinsic gas check of the built-in transaction types, all other checks
+    /// of the validator still apply. The [ForkTracker] reflects the forks that are active at the
+    /// current tip.
+    ///
+    /// By default additional transaction types are not supported.
+    fn validate_additional_type(
+        &self,
+        _chain_spec: &ChainSpec,
+        _fork_tracker: &ForkTracker,
+    ) -> Result<(), InvalidPoolTransactionError> {
+        Err(InvalidTransactionError::TxTypeNotSupported.into())
+    }
 }
 
 /// The default [PoolTransaction] for the [Pool](crate::Pool) for Ethereum.
@@ -1042,6 +1185,10 @@ impl PoolTransaction for EthPooledTransaction {
         self.transaction.max_fee_per_blob_gas()
     }
 
+    fn blob_count(&self) -> usize {
+        self.transaction.as_eip4844().map(|tx| tx.blob_versioned_hashes.len()).unwrap_or_default()
+    }
+
     /// Returns the effective tip for this transaction.
     ///
     /// For EIP-1559 transactions: `min(max_fee_per_gas - base_fee, max_priority_fee_per_gas)`.
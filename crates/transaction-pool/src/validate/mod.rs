@@ -1,6 +1,7 @@
 //! Transaction validation abstractions.
 
 use crate::{
+    conditional::TransactionConditional,
     error::InvalidPoolTransactionError,
     identifier::{SenderId, TransactionId},
     traits::{PoolTransaction, TransactionOrigin},
@@ -9,6 +10,7 @@ use reth_primitives::{
     Address, BlobTransactionSidecar, IntoRecoveredTransaction, SealedBlock,
     TransactionSignedEcRecovered, TxHash, B256, U256,
 };
+use reth_provider::StateProvider;
 use std::{fmt, future::Future, time::Instant};
 
 mod constants;
@@ -225,6 +227,9 @@ pub struct ValidPoolTransaction<T: PoolTransaction> {
     pub timestamp: Instant,
     /// Where this transaction originated from.
     pub origin: TransactionOrigin,
+    /// Conditions that must hold for this transaction to be included in a block, if it was
+    /// submitted conditionally.
+    pub conditional: Option<Box<TransactionConditional>>,
 }
 
 // === impl ValidPoolTransaction ===
@@ -323,6 +328,33 @@ impl<T: PoolTransaction> ValidPoolTransaction<T> {
         self.transaction.is_eip4844()
     }
 
+    /// Returns the conditions of the transaction, if it was submitted conditionally.
+    pub fn conditional(&self) -> Option<&TransactionConditional> {
+        self.conditional.as_deref()
+    }
+
+    /// Whether the transaction may be shared with peers.
+    ///
+    /// Private transactions and transactions submitted with conditions are only included in blocks
+    /// built by this node, because peers would include them without checking the conditions.
+    pub fn is_shareable(&self) -> bool {
+        !self.origin.is_private() && self.conditional.is_none()
+    }
+
+    /// Returns true if the `known_accounts` conditions of the transaction, if any, hold for the
+    /// given state, which is expected to be the state of the including block's parent.
+    ///
+    /// Conditions that can't be verified because of a provider error are treated as violated.
+    pub fn matches_known_accounts<S>(&self, state: &S) -> bool
+    where
+        S: StateProvider + ?Sized,
+    {
+        self.conditional().map_or(true, |conditional| {
+            conditional.known_accounts.is_empty() ||
+                conditional.matches_state(state).unwrap_or(false)
+        })
+    }
+
     /// The heap allocated size of this transaction.
     pub(crate) fn size(&self) -> usize {
         self.transaction.size()
@@ -354,6 +386,7 @@ impl<T: PoolTransaction + Clone> Clone for ValidPoolTransaction<T> {
             propagate: self.propagate,
             timestamp: self.timestamp,
             origin: self.origin,
+            conditional: self.conditional.clone(),
         }
     }
 }
@@ -11,7 +11,9 @@ use reth_tasks::pool::BlockingTaskPool;
 use reth_trie::{
     hashed_cursor::HashedPostStateCursorFactory, HashedPostState, HashedStorage, StateRoot,
 };
-use reth_trie_parallel::{async_root::AsyncStateRoot, parallel_root::ParallelStateRoot};
+use reth_trie_parallel::{
+    async_root::AsyncStateRoot, parallel_root::ParallelStateRoot, tx_root::TxParallelStateRoot,
+};
 use std::collections::HashMap;
 
 pub fn calculate_state_root(c: &mut Criterion) {
@@ -24,6 +26,13 @@ pub fn calculate_state_root(c: &mut Criterion) {
     for size in [1_000, 3_000, 5_000, 10_000] {
         let (db_state, updated_state) = generate_test_data(size);
         let provider_factory = create_test_provider_factory();
+        // hashed state without tries, so that the full root is computed from scratch
+        let rebuild_provider_factory = create_test_provider_factory();
+        {
+            let provider_rw = rebuild_provider_factory.provider_rw().unwrap();
+            HashedStateChanges(db_state.clone()).write_to_db(provider_rw.tx_ref()).unwrap();
+            provider_rw.commit().unwrap();
+        }
         {
             let provider_rw = provider_factory.provider_rw().unwrap();
             HashedStateChanges(db_state).write_to_db(provider_rw.tx_ref()).unwrap();
@@ -71,6 +80,26 @@ pub fn calculate_state_root(c: &mut Criterion) {
                 |calculator| calculator.incremental_root(),
             );
         });
+
+        // full root
+        group.bench_function(BenchmarkId::new("sync full root", size), |b| {
+            b.iter_with_setup(
+                || rebuild_provider_factory.provider().unwrap(),
+                |provider| StateRoot::from_tx(provider.tx_ref()).root().unwrap(),
+            )
+        });
+
+        // parallel full root on a database transaction
+        group.bench_function(BenchmarkId::new("tx parallel full root", size), |b| {
+            b.iter_with_setup(
+                || rebuild_provider_factory.provider().unwrap(),
+                |provider| {
+                    TxParallelStateRoot::from_tx(provider.tx_ref(), &rebuild_provider_factory)
+                        .root()
+                        .unwrap()
+                },
+            )
+        });
     }
 }
 
@@ -21,6 +21,10 @@ pub mod async_root;
 #[cfg(feature = "parallel")]
 pub mod parallel_root;
 
+/// Implementation of parallel state root computation on a database transaction.
+#[cfg(feature = "parallel")]
+pub mod tx_root;
+
 /// Parallel state root metrics.
 #[cfg(feature = "metrics")]
 pub mod metrics;
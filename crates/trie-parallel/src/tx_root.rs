@@ -0,0 +1,427 @@
+use crate::{parallel_root::ParallelStateRootError, stats::ParallelTrieTracker};
+use alloy_rlp::{BufMut, Encodable};
+use rayon::prelude::*;
+use reth_db::{database::Database, transaction::DbTx};
+use reth_primitives::{
+    trie::{HashBuilder, Nibbles, TrieAccount},
+    BlockNumber, B256,
+};
+use reth_provider::{DatabaseProviderFactory, ProviderError};
+use reth_trie::{
+    node_iter::{AccountNode, AccountNodeIter},
+    prefix_set::{PrefixSetLoader, TriePrefixSets},
+    trie_cursor::{noop::NoopTrieCursorFactory, TrieCursorFactory},
+    updates::TrieUpdates,
+    walker::TrieWalker,
+    IntermediateStateRootState, StateRootProgress, StorageRoot,
+};
+use std::{marker::PhantomData, ops::RangeInclusive};
+use tracing::*;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::ParallelStateRootMetrics;
+
+/// The default number of account leaves for which storage roots are computed in parallel.
+pub const DEFAULT_STORAGE_ROOT_BATCH_SIZE: usize = 1_000;
+
+/// Parallel state root calculator for the state trie of a database transaction.
+///
+/// The calculator walks the state trie of the transaction like [StateRoot](reth_trie::StateRoot),
+/// but collects the account leaves in batches and computes the storage roots of every batch in
+/// parallel. Like [ParallelStateRoot](crate::parallel_root::ParallelStateRoot), every worker
+/// computes storage roots on its own read-only transaction, so the storage roots are computed on
+/// the committed state. The hashed storages and storage tries must not have uncommitted changes in
+/// the transaction, unless the storage tries are rebuilt from scratch, see
+/// [TxParallelStateRoot::with_rebuilt_storage_tries].
+///
+/// Intermediate progress is only returned at batch boundaries.
+#[derive(Debug)]
+pub struct TxParallelStateRoot<'a, TX, DB, Provider> {
+    /// The transaction to read the account trie and hashed accounts from.
+    tx: &'a TX,
+    /// The factory of read-only transactions to compute storage roots on.
+    provider: &'a Provider,
+    /// A set of prefix sets that have changes.
+    prefix_sets: TriePrefixSets,
+    /// Previous intermediate state.
+    previous_state: Option<IntermediateStateRootState>,
+    /// The number of updates after which the intermediate progress should be returned.
+    threshold: u64,
+    /// The number of account leaves for which storage roots are computed in parallel.
+    batch_size: usize,
+    /// Whether stored storage trie nodes are ignored, because the storage tries are rebuilt.
+    rebuilt_storage_tries: bool,
+    /// Parallel state root metrics.
+    #[cfg(feature = "metrics")]
+    metrics: ParallelStateRootMetrics,
+    _database: PhantomData<DB>,
+}
+
+impl<'a, TX, DB, Provider> TxParallelStateRoot<'a, TX, DB, Provider>
+where
+    TX: DbTx,
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB> + Sync,
+{
+    /// Create a new calculator for the entire state.
+    pub fn from_tx(tx: &'a TX, provider: &'a Provider) -> Self {
+        Self {
+            tx,
+            provider,
+            prefix_sets: TriePrefixSets::default(),
+            previous_state: None,
+            threshold: 100_000,
+            batch_size: DEFAULT_STORAGE_ROOT_BATCH_SIZE,
+            rebuilt_storage_tries: false,
+            #[cfg(feature = "metrics")]
+            metrics: ParallelStateRootMetrics::default(),
+            _database: PhantomData,
+        }
+    }
+
+    /// Create a new calculator with the account and storage prefixes that changed in the given
+    /// block range loaded.
+    pub fn incremental_root_calculator(
+        tx: &'a TX,
+        provider: &'a Provider,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, ParallelStateRootError> {
+        let loaded_prefix_sets =
+            PrefixSetLoader::new(tx).load(range).map_err(ProviderError::Database)?;
+        Ok(Self::from_tx(tx, provider).with_prefix_sets(loaded_prefix_sets))
+    }
+
+    /// Set the prefix sets.
+    pub fn with_prefix_sets(mut self, prefix_sets: TriePrefixSets) -> Self {
+        self.prefix_sets = prefix_sets;
+        self
+    }
+
+    /// Set the threshold.
+    pub fn with_threshold(mut self, threshold: u64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Set the threshold to maximum value so that intermediate progress is not returned.
+    pub fn with_no_threshold(mut self) -> Self {
+        self.threshold = u64::MAX;
+        self
+    }
+
+    /// Set the previously recorded intermediate state.
+    pub fn with_intermediate_state(mut self, state: Option<IntermediateStateRootState>) -> Self {
+        self.previous_state = state;
+        self
+    }
+
+    /// Set the number of account leaves for which storage roots are computed in parallel.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Compute the storage roots from the hashed storages only, ignoring the committed storage
+    /// tries.
+    ///
+    /// This is required if the storage tries have been cleared in the transaction, but the changes
+    /// are not committed yet.
+    pub fn with_rebuilt_storage_tries(mut self) -> Self {
+        self.rebuilt_storage_tries = true;
+        self
+    }
+
+    /// Calculate the state root with updates in parallel.
+    ///
+    /// Ignores the threshold.
+    pub fn root_with_updates(self) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
+        match self.with_no_threshold().calculate(true)? {
+            StateRootProgress::Complete(root, _, updates) => Ok((root, updates)),
+            StateRootProgress::Progress(..) => unreachable!(), // unreachable threshold
+        }
+    }
+
+    /// Calculate the state root in parallel.
+    pub fn root(self) -> Result<B256, ParallelStateRootError> {
+        match self.calculate(false)? {
+            StateRootProgress::Complete(root, _, _) => Ok(root),
+            StateRootProgress::Progress(..) => unreachable!(), // update retention is disabled
+        }
+    }
+
+    /// Calculate the state root in parallel, collecting the updates and returning intermediate
+    /// progress once the number of updates exceeds the threshold.
+    pub fn root_with_progress(self) -> Result<StateRootProgress, ParallelStateRootError> {
+        self.calculate(true)
+    }
+
+    fn calculate(self, retain_updates: bool) -> Result<StateRootProgress, ParallelStateRootError> {
+        trace!(target: "trie::parallel_state_root", "calculating state root");
+        let mut tracker = ParallelTrieTracker::default();
+        let mut trie_updates = TrieUpdates::default();
+
+        let trie_cursor = self.tx.account_trie_cursor().map_err(ProviderError::Database)?;
+
+        let (mut hash_builder, mut account_node_iter) = match self.previous_state {
+            Some(state) => {
+                let hash_builder = state.hash_builder.with_updates(retain_updates);
+                let walker = TrieWalker::from_stack(
+                    trie_cursor,
+                    state.walker_stack,
+                    self.prefix_sets.account_prefix_set,
+                )
+                .with_updates(retain_updates);
+                let node_iter = AccountNodeIter::from_factory(walker, self.tx)
+                    .map_err(ProviderError::Database)?
+                    .with_last_account_key(state.last_account_key);
+                (hash_builder, node_iter)
+            }
+            None => {
+                let hash_builder = HashBuilder::default().with_updates(retain_updates);
+                let walker = TrieWalker::new(trie_cursor, self.prefix_sets.account_prefix_set)
+                    .with_updates(retain_updates);
+                let node_iter = AccountNodeIter::from_factory(walker, self.tx)
+                    .map_err(ProviderError::Database)?;
+                (hash_builder, node_iter)
+            }
+        };
+
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut hashed_entries_walked = 0;
+        let mut precomputed_storage_roots = 0;
+        let mut last_account_key = None;
+
+        let mut batch = Vec::with_capacity(self.batch_size);
+        let mut batch_leaves = 0;
+        let provider = self.provider;
+        let storage_prefix_sets = &self.prefix_sets.storage_prefix_sets;
+        let rebuilt_storage_tries = self.rebuilt_storage_tries;
+        #[cfg(feature = "metrics")]
+        let storage_trie_metrics = &self.metrics.storage_trie;
+        loop {
+            let next = account_node_iter.try_next().map_err(ProviderError::Database)?;
+            let exhausted = next.is_none();
+            if let Some(node) = next {
+                if matches!(node, AccountNode::Leaf(..)) {
+                    batch_leaves += 1;
+                }
+                batch.push(node);
+                if batch_leaves < self.batch_size {
+                    continue
+                }
+            }
+
+            // Calculate storage roots of the batch in parallel, every worker on its own read-only
+            // transaction.
+            trace!(target: "trie::parallel_state_root", len = batch_leaves, "calculating storage roots");
+            let storage_roots = batch
+                .par_iter()
+                .map_init(
+                    || provider.database_provider_ro(),
+                    |provider_ro, node| {
+                        let AccountNode::Leaf(hashed_address, _) = node else { return Ok(None) };
+                        let provider_ro = provider_ro.as_ref().map_err(Clone::clone)?;
+                        let storage_root = StorageRoot::new_hashed(
+                            provider_ro.tx_ref(),
+                            provider_ro.tx_ref(),
+                            *hashed_address,
+                            #[cfg(feature = "metrics")]
+                            storage_trie_metrics.clone(),
+                        )
+                        .with_prefix_set(
+                            storage_prefix_sets.get(hashed_address).cloned().unwrap_or_default(),
+                        );
+                        let result = if rebuilt_storage_tries {
+                            storage_root
+                                .with_trie_cursor_factory(NoopTrieCursorFactory::default())
+                                .calculate(retain_updates)
+                        } else {
+                            storage_root.calculate(retain_updates)
+                        };
+                        Ok(Some(result?))
+                    },
+                )
+                .collect::<Result<Vec<_>, ParallelStateRootError>>()?;
+            precomputed_storage_roots += batch_leaves as u64;
+            batch_leaves = 0;
+
+            for (node, storage_root) in batch.drain(..).zip(storage_roots) {
+                match node {
+                    AccountNode::Branch(node) => {
+                        tracker.inc_branch();
+                        hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                    }
+                    AccountNode::Leaf(hashed_address, account) => {
+                        tracker.inc_leaf();
+                        hashed_entries_walked += 1;
+
+                        let (storage_root, storage_slots_walked, updates) =
+                            storage_root.expect("storage root is calculated for every leaf");
+                        if retain_updates {
+                            hashed_entries_walked += storage_slots_walked;
+                            trie_updates.extend(updates);
+                        }
+
+                        account_rlp.clear();
+                        let account = TrieAccount::from((account, storage_root));
+                        account.encode(&mut account_rlp as &mut dyn BufMut);
+                        hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                        last_account_key = Some(hashed_address);
+                    }
+                }
+            }
+
+            if exhausted {
+                break
+            }
+
+            // Decide if we need to return intermediate progress. A full batch always ends with a
+            // leaf, so the walker is positioned right after the last account key.
+            let total_updates_len = trie_updates.len() +
+                account_node_iter.walker.updates_len() +
+                hash_builder.updates_len();
+            if retain_updates && total_updates_len as u64 >= self.threshold {
+                if let Some(last_account_key) = last_account_key {
+                    let (walker_stack, walker_updates) = account_node_iter.walker.split();
+                    let (hash_builder, hash_builder_updates) = hash_builder.split();
+
+                    let state =
+                        IntermediateStateRootState { hash_builder, walker_stack, last_account_key };
+
+                    trie_updates.extend(walker_updates);
+                    trie_updates.extend_with_account_updates(hash_builder_updates);
+
+                    return Ok(StateRootProgress::Progress(
+                        Box::new(state),
+                        hashed_entries_walked,
+                        trie_updates,
+                    ))
+                }
+            }
+        }
+
+        let root = hash_builder.root();
+
+        trie_updates.finalize_state_updates(
+            account_node_iter.walker,
+            hash_builder,
+            self.prefix_sets.destroyed_accounts,
+        );
+
+        tracker.set_precomputed_storage_roots(precomputed_storage_roots);
+        let stats = tracker.finish();
+
+        #[cfg(feature = "metrics")]
+        self.metrics.record_state_trie(stats);
+
+        trace!(
+            target: "trie::parallel_state_root",
+            %root,
+            duration = ?stats.duration(),
+            branches_added = stats.branches_added(),
+            leaves_added = stats.leaves_added(),
+            precomputed_storage_roots = stats.precomputed_storage_roots(),
+            "calculated state root"
+        );
+
+        Ok(StateRootProgress::Complete(root, hashed_entries_walked, trie_updates))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use reth_db::{tables, transaction::DbTxMut};
+    use reth_primitives::{Account, Address, StorageEntry, U256};
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
+    use reth_trie::{test_utils, StateRoot};
+    use std::collections::HashMap;
+
+    fn random_state() -> HashMap<Address, (Account, HashMap<B256, U256>)> {
+        let mut rng = rand::thread_rng();
+        (0..100)
+            .map(|_| {
+                let address = Address::random();
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let mut storage = HashMap::<B256, U256>::default();
+                if rng.gen_bool(0.7) {
+                    for _ in 0..100 {
+                        storage.insert(
+                            B256::from(U256::from(rng.gen::<u64>())),
+                            U256::from(rng.gen::<u64>()),
+                        );
+                    }
+                }
+                (address, (account, storage))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn random_tx_parallel_root() {
+        let factory = create_test_provider_factory();
+        let state = random_state();
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .insert_account_for_hashing(
+                state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+            )
+            .unwrap();
+        provider_rw
+            .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                (
+                    *address,
+                    storage.iter().map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                )
+            }))
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let expected = test_utils::state_root(state);
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        assert_eq!(
+            TxParallelStateRoot::from_tx(tx, &factory).with_batch_size(7).root().unwrap(),
+            expected
+        );
+
+        // intermediate progress yields the same root and updates as the serial calculation
+        let mut intermediate_state = None;
+        let (root, updates) = loop {
+            let progress = TxParallelStateRoot::from_tx(tx, &factory)
+                .with_threshold(10)
+                .with_batch_size(3)
+                .with_intermediate_state(intermediate_state.take())
+                .root_with_progress()
+                .unwrap();
+            match progress {
+                StateRootProgress::Progress(state, _, updates) => {
+                    updates.flush(tx).unwrap();
+                    intermediate_state = Some(*state);
+                }
+                StateRootProgress::Complete(root, _, updates) => break (root, updates),
+            }
+        };
+        updates.flush(tx).unwrap();
+        assert_eq!(root, expected);
+        assert_eq!(StateRoot::from_tx(tx).root().unwrap(), expected);
+        provider_rw.commit().unwrap();
+
+        // uncommitted cleared storage tries are rebuilt from the hashed storages
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        tx.clear::<tables::AccountsTrie>().unwrap();
+        tx.clear::<tables::StoragesTrie>().unwrap();
+        let (root, updates) = TxParallelStateRoot::from_tx(tx, &factory)
+            .with_batch_size(5)
+            .with_rebuilt_storage_tries()
+            .root_with_updates()
+            .unwrap();
+        updates.flush(tx).unwrap();
+        assert_eq!(root, expected);
+        assert_eq!(StateRoot::from_tx(tx).root().unwrap(), expected);
+    }
+}
@@ -12,8 +12,9 @@ use reth_primitives::{
     constants::EMPTY_ROOT_HASH,
     keccak256,
     trie::{AccountProof, HashBuilder, Nibbles, StorageProof, TrieAccount},
-    Address, B256,
+    Address, Bytes, B256,
 };
+use std::collections::BTreeMap;
 
 /// A struct for generating merkle proofs.
 ///
@@ -97,6 +98,114 @@ where
         Ok(account_proof)
     }
 
+    /// Generate the nodes of the account trie that prove the inclusion or exclusion of the given
+    /// hashed addresses.
+    ///
+    /// The nodes of all targets are deduplicated and ordered by their path.
+    pub fn account_multiproof(&self, targets: &[B256]) -> Result<Vec<Bytes>, StateRootError> {
+        let paths = targets.iter().map(Nibbles::unpack).collect();
+        Ok(self.account_trie_nodes(paths)?.into_values().collect())
+    }
+
+    /// Retrieve the nodes of the account trie that lie on the given paths, keyed by the path of
+    /// the node.
+    ///
+    /// The node at a path is included in the result if the path points to the beginning of a
+    /// node.
+    pub fn account_trie_nodes(
+        &self,
+        paths: Vec<Nibbles>,
+    ) -> Result<BTreeMap<Nibbles, Bytes>, StateRootError> {
+        let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let trie_cursor =
+            DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+
+        let prefix_set = PrefixSetMut::from(paths.clone()).freeze();
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(paths);
+
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut account_node_iter = AccountNodeIter::new(walker, hashed_account_cursor);
+        while let Some(account_node) = account_node_iter.try_next()? {
+            match account_node {
+                AccountNode::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                AccountNode::Leaf(hashed_address, account) => {
+                    let storage_root = self.storage_root(hashed_address)?;
+
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        Ok(hash_builder.take_proofs())
+    }
+
+    /// Compute the storage root and generate the nodes of the storage trie that prove the
+    /// inclusion or exclusion of the given hashed slots.
+    ///
+    /// The nodes of all targets are deduplicated and ordered by their path.
+    pub fn storage_multiproof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> Result<(B256, Vec<Bytes>), StorageRootError> {
+        let paths = targets.iter().map(Nibbles::unpack).collect();
+        let (storage_root, nodes) = self.storage_trie_nodes(hashed_address, paths)?;
+        Ok((storage_root, nodes.into_values().collect()))
+    }
+
+    /// Compute the storage root and retrieve the nodes of the storage trie that lie on the given
+    /// paths, keyed by the path of the node.
+    pub fn storage_trie_nodes(
+        &self,
+        hashed_address: B256,
+        paths: Vec<Nibbles>,
+    ) -> Result<(B256, BTreeMap<Nibbles, Bytes>), StorageRootError> {
+        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
+
+        // short circuit on empty storage
+        if hashed_storage_cursor.is_storage_empty(hashed_address)? {
+            return Ok((EMPTY_ROOT_HASH, BTreeMap::new()))
+        }
+
+        let prefix_set = PrefixSetMut::from(paths.clone()).freeze();
+        let trie_cursor = DatabaseStorageTrieCursor::new(
+            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
+            hashed_address,
+        );
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(paths);
+        let mut storage_node_iter =
+            StorageNodeIter::new(walker, hashed_storage_cursor, hashed_address);
+        while let Some(node) = storage_node_iter.try_next()? {
+            match node {
+                StorageNode::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                StorageNode::Leaf(hashed_slot, value) => {
+                    hash_builder.add_leaf(
+                        Nibbles::unpack(hashed_slot),
+                        alloy_rlp::encode_fixed_size(&value).as_ref(),
+                    );
+                }
+            }
+        }
+
+        let root = hash_builder.root();
+
+        Ok((root, hash_builder.take_proofs()))
+    }
+
     /// Compute storage root.
     pub fn storage_root(&self, hashed_address: B256) -> Result<B256, StorageRootError> {
         let (storage_root, _) = self.storage_root_with_proofs(hashed_address, &[])?;
@@ -291,6 +400,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn testspec_multiproof() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let provider = factory.provider().unwrap();
+        let proof = Proof::new(provider.tx_ref());
+
+        let targets = [
+            Address::from_str("0x2031f89b3ea8014eb51a78c316e42af3e0d7695f").unwrap(),
+            Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap(),
+        ];
+        let mut expected = Vec::new();
+        for target in targets {
+            for node in proof.account_proof(target, &[]).unwrap().proof {
+                if !expected.contains(&node) {
+                    expected.push(node);
+                }
+            }
+        }
+
+        let multiproof = proof.account_multiproof(&targets.map(keccak256)).unwrap();
+        assert_eq!(multiproof.len(), expected.len());
+        for node in &expected {
+            assert!(multiproof.contains(node), "missing proof node {node}");
+        }
+
+        // the node at the empty path is the root node
+        let root = StateRoot::from_tx(provider.tx_ref()).root().unwrap();
+        let nodes = proof.account_trie_nodes(vec![Nibbles::default()]).unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(keccak256(&nodes[&Nibbles::default()]), root);
+    }
+
     #[test]
     fn testspec_empty_storage_proof() {
         // Create test database and insert genesis accounts.